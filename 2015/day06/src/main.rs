@@ -2,14 +2,10 @@
 extern crate lazy_static;
 extern crate regex;
 
-#[derive(Debug, PartialEq)]
-struct Range((usize, usize), (usize, usize));
+use interval::Cuboid;
 
-impl Range {
-    fn contains(&self, (x,y): (usize,usize)) -> bool {
-        (x >= self.0.0 && x <= self.1.0) && (y >= self.0.1 && y <= self.1.1)
-    }
-}
+/// A rectangle of lights, with both corners included.
+type Range = Cuboid<usize, 2>;
 
 #[derive(Debug, PartialEq)]
 enum Action {
@@ -32,19 +28,12 @@ fn parse_action(s: &str) -> Rule {
     }
     let m = RE.captures(s).unwrap();
 
-    let range = Range(
-        (
-            m[2].parse::<usize>().unwrap(),
-            m[3].parse::<usize>().unwrap(),
-        ),
-        (
-            m[4].parse::<usize>().unwrap(),
-            m[5].parse::<usize>().unwrap(),
-        ),
+    let range = Range::inclusive(
+        [m[2].parse().unwrap(), m[3].parse().unwrap()],
+        [m[4].parse().unwrap(), m[5].parse().unwrap()],
     );
 
-    assert!(range.0.0 <= range.1.0);
-    assert!(range.0.1 <= range.1.1);
+    assert!(!range.is_empty());
 
     let action = match &m[1] {
         "turn on" => Action::TurnOn,
@@ -53,46 +42,52 @@ fn parse_action(s: &str) -> Rule {
         _ => panic!("Invalid"),
     };
 
-    return Rule{ action: action, range: range };
+    Rule { action, range }
 }
 
-fn compute_lights_lit(rules: &Vec<Rule>, size: usize) -> usize {
-    let mut lit_count = 0;
-    for x in 0..(size) {
-        for y in 0..(size) {
-            let lit = rules.iter()
-                .filter(|r| r.range.contains((x,y)))
-                .fold(false, |state, rule| match rule.action {
-                    Action::TurnOff => false,
-                    Action::TurnOn => true,
-                    Action::Toggle => !state,
-                });
-            if lit {
-                lit_count += 1;
-            }
-        }
+/// Applies the rules to a `size` by `size` grid of lights that start at 0,
+/// keeping the grid as disjoint rectangles of lights in the same state, and
+/// sums up the final states.
+fn apply_rules(rules: &[Rule], size: usize, apply: impl Fn(&Action, u64) -> u64) -> u64 {
+    let mut regions = vec![(Range::inclusive([0, 0], [size - 1, size - 1]), 0)];
+    for rule in rules {
+        regions = regions
+            .into_iter()
+            .flat_map(|(region, state)| match region.intersection(&rule.range) {
+                None => vec![(region, state)],
+                Some(overlap) => region
+                    .subtract(&rule.range)
+                    .into_iter()
+                    .map(|rest| (rest, state))
+                    .chain([(overlap, apply(&rule.action, state))])
+                    .collect(),
+            })
+            .collect();
     }
-    lit_count
+    regions
+        .iter()
+        .map(|(region, state)| region.volume() * state)
+        .sum()
 }
 
-fn compute_lights_brightness(rules: &Vec<Rule>, size: usize) -> u64 {
-    let mut total_bri: u64 = 0;
-    for x in 0..(size) {
-        for y in 0..(size) {
-            total_bri += rules.iter()
-                .filter(|r| r.range.contains((x,y)))
-                .fold(0, |state, rule| match rule.action {
-                    Action::TurnOff => if state > 0 { state - 1 } else { 0 },
-                    Action::TurnOn => state + 1,
-                    Action::Toggle => state + 2
-                });
-        }
-    }
-    total_bri
+fn compute_lights_lit(rules: &[Rule], size: usize) -> u64 {
+    apply_rules(rules, size, |action, state| match action {
+        Action::TurnOff => 0,
+        Action::TurnOn => 1,
+        Action::Toggle => 1 - state,
+    })
+}
+
+fn compute_lights_brightness(rules: &[Rule], size: usize) -> u64 {
+    apply_rules(rules, size, |action, state| match action {
+        Action::TurnOff => state.saturating_sub(1),
+        Action::TurnOn => state + 1,
+        Action::Toggle => state + 2,
+    })
 }
 
 fn main() {
-    let rules: Vec<Rule> = include_str!("../in.txt").split('\n').map(|line|parse_action(line)).collect();
+    let rules: Vec<Rule> = include_str!("../in.txt").split('\n').map(parse_action).collect();
     println!("Part 1: LIT {}", compute_lights_lit(&rules, 1000));
     println!("Part 2: BRI {}", compute_lights_brightness(&rules, 1000));
 }
@@ -105,26 +100,29 @@ mod tests {
     fn test_parse_action() {
         assert_eq!(
             parse_action("turn on 0,0 through 999,999"),
-            Rule{action:Action::TurnOn,range:Range((0, 0), (999, 999))}
+            Rule{action:Action::TurnOn,range:Range::inclusive([0, 0], [999, 999])}
         );
         assert_eq!(
             parse_action("turn on 1,2 through 5,6"),
-            Rule{action:Action::TurnOn,range:Range((1, 2), (5, 6))}
+            Rule{action:Action::TurnOn,range:Range::inclusive([1, 2], [5, 6])}
         );
         assert_eq!(
             parse_action("toggle 1,2 through 5,6"),
-            Rule{action:Action::Toggle,range:Range((1, 2), (5, 6))}
+            Rule{action:Action::Toggle,range:Range::inclusive([1, 2], [5, 6])}
         );
     }
 
     #[test]
-    fn range_contains() {
-        assert_eq!(Range((0,0), (1,1)).contains((0,0)), true);
-        assert_eq!(Range((0,0), (1,1)).contains((1,0)), true);
-        assert_eq!(Range((0,0), (1,1)).contains((1,1)), true);
-        assert_eq!(Range((0,0), (1,1)).contains((0,1)), true);
-        assert_eq!(Range((0,0), (1,1)).contains((2,0)), false);
-        assert_eq!(Range((0,0), (1,1)).contains((2,2)), false);
-        assert_eq!(Range((0,0), (1,1)).contains((0,2)), false);
+    fn test_compute_lights() {
+        let rules: Vec<Rule> = [
+            "turn on 0,0 through 999,999",
+            "toggle 0,0 through 999,0",
+            "turn off 499,499 through 500,500",
+        ]
+        .iter()
+        .map(|line| parse_action(line))
+        .collect();
+        assert_eq!(compute_lights_lit(&rules, 1000), 1_000_000 - 1000 - 4);
+        assert_eq!(compute_lights_brightness(&rules, 1000), 1_000_000 + 2000 - 4);
     }
 }
@@ -1,30 +1,8 @@
 #[macro_use]
 extern crate lazy_static;
 extern crate regex;
-use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
-enum Value {
-    Scalar(u16),
-    Ref(String),
-}
-
-#[derive(Debug, PartialEq)]
-enum Gate {
-    Value(Value),
-    Lshift(Value, Value),
-    Rshift(Value, Value),
-    Not(Value),
-    And(Value, Value),
-    Or(Value, Value),
-}
-
-fn parse_value(valuestr: &str) -> Value {
-    match valuestr.parse::<u16>() {
-        Ok(v) => Value::Scalar(v),
-        Err(_) => Value::Ref(String::from(valuestr)),
-    }
-}
+use circuit::{CircuitBuilder, Gate, LogicOp, Operand, Simulation};
 
 fn parse_gate(gatestr: &str) -> Gate {
     lazy_static! {
@@ -35,23 +13,15 @@ fn parse_gate(gatestr: &str) -> Gate {
     }
 
     if let Some(m) = BIN_EXPR_RE.captures(gatestr) {
-        let left = parse_value(&m[1]);
-        let right = parse_value(&m[3]);
-        return match &m[2] {
-            "AND" => Gate::And(left, right),
-            "OR" => Gate::Or(left, right),
-            "LSHIFT" => Gate::Lshift(left, right),
-            "RSHIFT" => Gate::Rshift(left, right),
-            _ => panic!("Invalid binary expr"),
-        };
+        return Gate::Logic(m[2].parse().unwrap(), vec![m[1].into(), m[3].into()]);
     }
 
     if let Some(m) = UNARY_EXPR_RE.captures(gatestr) {
-        return Gate::Not(parse_value(&m[1]));
+        return Gate::Logic(LogicOp::Not, vec![m[1].into()]);
     }
 
     if let Some(m) = VAL_EXPR_RE.captures(gatestr) {
-        return Gate::Value(parse_value(&m[1]));
+        return Gate::Logic(LogicOp::Copy, vec![m[1].into()]);
     }
 
     panic!("Invalid expr");
@@ -62,77 +32,60 @@ fn parse_line(line: &str) -> (Gate, String) {
         static ref RE: regex::Regex = regex::Regex::new(r"(.+?) -> (\w+)").unwrap();
     }
     let m = RE.captures(line).unwrap();
-    return (parse_gate(&m[1]), String::from(&m[2]));
+    (parse_gate(&m[1]), String::from(&m[2]))
 }
 
-fn resolve(value: &Value, wires: &HashMap<String, Gate>, memo: &mut HashMap<String, u16>) -> u16 {
-    println!("RESOLVE {:?}", value);
-    match value {
-        Value::Scalar(val) => *val,
-        Value::Ref(name) => {
-            if let Some(v) = memo.get(name) {
-                *v
-            } else {
-                let v = eval(wires.get(name).unwrap(), wires, memo);
-                memo.insert(name.clone(), v);
-                v
-            }
-        }
+fn parse_wires(input: &str) -> CircuitBuilder {
+    let mut wires = CircuitBuilder::new();
+    for line in input.lines() {
+        let (gate, wire) = parse_line(line);
+        wires.gate(&wire, gate, &[]);
     }
+    wires
 }
 
-fn eval(gate: &Gate, wires: &HashMap<String, Gate>, memo: &mut HashMap<String, u16>) -> u16 {
-    println!("EVAL {:?}", gate);
-    let v = match gate {
-        Gate::Value(v) => resolve(v, wires, memo),
-        Gate::And(left, right) => resolve(left, wires, memo) & resolve(right, wires, memo),
-        Gate::Or(left, right) => resolve(left, wires, memo) | resolve(right, wires, memo),
-        Gate::Lshift(left, right) => resolve(left, wires, memo) << resolve(right, wires, memo),
-        Gate::Rshift(left, right) => resolve(left, wires, memo) >> resolve(right, wires, memo),
-        Gate::Not(value) => resolve(value, wires, memo) ^ 0xFFFF,
-    };
-    println!("EVAL {:?} -> {}", gate, v);
-    return v;
+fn signal(wires: &CircuitBuilder, wire: &str) -> u16 {
+    let circuit = wires.build().unwrap();
+    let mut sim = Simulation::new(&circuit);
+    sim.power_on(|_| {});
+    sim.value(circuit.id(wire).unwrap()).unwrap()
 }
 
 fn main() {
-    let mut wires = HashMap::new();
-
-    for line in include_str!("../in.txt").split('\n') {
-        let (gate, wire) = parse_line(line);
-        wires.insert(wire, gate);
-    }
-
-    // step 2
-    wires.insert("b".to_string(), Gate::Value(Value::Scalar(956)));
-
-    println!("{:?}", &wires);
-    let mut memo = HashMap::new();
-    println!("{:?}", eval(wires.get("a").unwrap(), &wires, &mut memo));
+    let mut wires = parse_wires(include_str!("../in.txt"));
+    let a = signal(&wires, "a");
+    println!("Part 1: {}", a);
+
+    wires.gate(
+        "b",
+        Gate::Logic(LogicOp::Copy, vec![Operand::Const(a)]),
+        &[],
+    );
+    println!("Part 2: {}", signal(&wires, "a"));
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_value() {
-        assert_eq!(parse_value("123"), Value::Scalar(123),);
-        assert_eq!(parse_value("a"), Value::Ref(String::from("a")),);
-        assert_eq!(parse_value("li"), Value::Ref(String::from("li")),);
-    }
-
     #[test]
     fn test_parse_gate() {
         assert_eq!(
             parse_gate("1 AND 2"),
-            Gate::And(Value::Scalar(1), Value::Scalar(2)),
+            Gate::Logic(LogicOp::And, vec![Operand::Const(1), Operand::Const(2)]),
         );
         assert_eq!(
             parse_gate("a LSHIFT b"),
-            Gate::Lshift(Value::Ref("a".to_string()), Value::Ref("b".to_string())),
+            Gate::Logic(LogicOp::Shl, vec!["a".into(), "b".into()]),
+        );
+        assert_eq!(
+            parse_gate("NOT 1"),
+            Gate::Logic(LogicOp::Not, vec![Operand::Const(1)])
+        );
+        assert_eq!(
+            parse_gate("li"),
+            Gate::Logic(LogicOp::Copy, vec![Operand::Wire("li".into())])
         );
-        assert_eq!(parse_gate("NOT 1"), Gate::Not(Value::Scalar(1)),);
     }
 
     #[test]
@@ -140,9 +93,31 @@ mod tests {
         assert_eq!(
             parse_line("as RSHIFT 3 -> au"),
             (
-                Gate::Rshift(Value::Ref("as".to_string()), Value::Scalar(3)),
+                Gate::Logic(LogicOp::Shr, vec!["as".into(), "3".into()]),
                 "au".to_string()
             ),
         );
     }
+
+    #[test]
+    fn test_signals() {
+        let wires = parse_wires(include_str!("../test.txt"));
+        let expected = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ];
+        for (wire, expected) in expected {
+            assert_eq!(signal(&wires, wire), expected, "{}", wire);
+        }
+        assert_eq!(
+            signal(&parse_wires("65535 -> x\nx LSHIFT 9 -> z"), "z"),
+            65024
+        );
+    }
 }
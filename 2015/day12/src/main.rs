@@ -1,37 +1,28 @@
-use serde_json::Value;
+use nested::Value;
 
-fn numeric_value(val: &Value) -> i64 {
+/// The sum of all numbers, leaving out every map that `skip` holds for.
+fn sum(val: &Value, skip: &impl Fn(&[(String, Value)]) -> bool) -> i64 {
     match val {
-        Value::Null | Value::Bool(_) | Value::String(_) => 0,
-        Value::Number(n) => n.as_i64().unwrap(),
-        Value::Array(v) => v.iter().map(numeric_value).sum(),
-        Value::Object(v) => v.values().map(numeric_value).sum(),
+        Value::Int(n) => *n,
+        Value::Map(entries) if skip(entries) => 0,
+        _ => val
+            .children()
+            .into_iter()
+            .map(|child| sum(child, skip))
+            .sum(),
     }
 }
 
 fn part1(s: &str) -> i64 {
-    let json = serde_json::from_str(s).unwrap();
-    numeric_value(&json)
-}
-
-fn numeric_value_without_red(val: &Value) -> i64 {
-    match val {
-        Value::Null | Value::Bool(_) | Value::String(_) => 0,
-        Value::Number(n) => n.as_i64().unwrap(),
-        Value::Array(v) => v.iter().map(numeric_value_without_red).sum(),
-        Value::Object(v) => {
-            if v.values().any(|v| v == &Value::String("red".to_string())) {
-                0
-            } else {
-                v.values().map(numeric_value_without_red).sum()
-            }
-        }
-    }
+    let json = s.parse().unwrap();
+    sum(&json, &|_| false)
 }
 
 fn part2(s: &str) -> i64 {
-    let json = serde_json::from_str(s).unwrap();
-    numeric_value_without_red(&json)
+    let json = s.parse().unwrap();
+    sum(&json, &|entries| {
+        entries.iter().any(|(_, v)| v.as_str() == Some("red"))
+    })
 }
 
 fn main() {
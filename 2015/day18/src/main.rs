@@ -1,110 +1,52 @@
-use std::collections::HashSet;
-use std::str::FromStr;
+use grid::Grid;
 
-#[derive(Debug)]
-struct Grid {
-    size: usize,
-    on: HashSet<(usize, usize)>,
+fn parse(s: &str) -> Grid<bool> {
+    Grid::try_parse(s, |ch| match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .unwrap()
 }
 
-impl FromStr for Grid {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<&str>>();
-        let size = lines.len();
-        let mut on = HashSet::new();
-        for (x, line) in lines.iter().enumerate() {
-            for (y, ch) in line.chars().enumerate() {
-                match ch {
-                    '#' => {
-                        on.insert((x, y));
-                    }
-                    '.' => {
-                        // off
-                    }
-                    _ => {
-                        return Err(());
-                    }
-                }
-            }
-        }
-
-        Ok(Grid { on: on, size: size })
+fn light_corners(grid: &mut Grid<bool>) {
+    let (w, h) = (grid.width(), grid.height());
+    for pos in [(0, 0), (0, w - 1), (h - 1, 0), (h - 1, w - 1)] {
+        grid[pos] = true;
     }
 }
 
-impl Grid {
-    #[allow(dead_code)]
-    fn print(&self) {
-        for x in 0..self.size {
-            println!(
-                "{}",
-                (0..self.size)
-                    .map(|y| if self.on.contains(&(x, y)) { '#' } else { '.' })
-                    .collect::<String>()
-            );
-        }
-    }
-
-    fn light_corners(&mut self) {
-        self.on.insert((0, 0));
-        self.on.insert((0, self.size - 1));
-        self.on.insert((self.size - 1, 0));
-        self.on.insert((self.size - 1, self.size - 1));
-    }
-
-    fn next(&self) -> Grid {
-        let size = self.size;
-        let mut next = vec![];
-
-        for x in 0..size {
-            for y in 0..size {
-                let neighbors = (-1..=1)
-                    .flat_map(|dx| (-1..=1).map(move |dy| (x as isize + dx, y as isize + dy)))
-                    .filter(|(x, y)| *x >= 0 && *y >= 0 && *x < size as isize && *y < size as isize)
-                    .map(|(x, y)| (x as usize, y as usize))
-                    .filter(|(nx, ny)| (nx, ny) != (&x, &y))
-                    .filter(|(x, y)| self.on.contains(&(*x, *y)))
-                    .count();
-
-                let on = if self.on.contains(&(x, y)) {
-                    neighbors == 2 || neighbors == 3
-                } else {
-                    neighbors == 3
-                };
-
-                if on {
-                    next.push((x, y));
-                }
-            }
-        }
-        Grid {
-            on: next.iter().cloned().collect::<HashSet<(usize, usize)>>(),
-            size: size,
+fn next(grid: &Grid<bool>) -> Grid<bool> {
+    Grid::from_fn(grid.width(), grid.height(), |pos| {
+        let neighbors = grid.neighbours8(pos).filter(|&p| grid[p]).count();
+        if grid[pos] {
+            neighbors == 2 || neighbors == 3
+        } else {
+            neighbors == 3
         }
-    }
+    })
+}
 
-    fn on_count(&self) -> usize {
-        self.on.len()
-    }
+fn on_count(grid: &Grid<bool>) -> usize {
+    grid.values().filter(|&&on| on).count()
 }
 
 fn part1(s: &str, steps: usize) -> usize {
-    let mut grid: Grid = s.parse().unwrap();
+    let mut grid = parse(s);
     for _ in 0..steps {
-        grid = grid.next();
+        grid = next(&grid);
     }
-    grid.on_count()
+    on_count(&grid)
 }
 
 fn part2(s: &str, steps: usize) -> usize {
-    let mut grid: Grid = s.parse().unwrap();
-    grid.light_corners();
+    let mut grid = parse(s);
+    light_corners(&mut grid);
     for _ in 0..steps {
-        grid = grid.next();
-        grid.light_corners();
+        grid = next(&grid);
+        light_corners(&mut grid);
     }
-    grid.on_count()
+    on_count(&grid)
 }
 
 fn main() {
@@ -1,24 +1,39 @@
+use numtheory::modpow;
+
 const FIRST_CODE: u64 = 20151125;
 const FACTOR: u64 = 252533;
 const MOD: u64 = 33554393;
-const INPUT_ROW: (usize, usize) = (2947, 3029);
+const INPUT_ROW: (u64, u64) = (2947, 3029);
 
-fn compute_code() -> u64 {
-    let mut cur: u64 = FIRST_CODE;
-    let mut k = 0;
-    loop {
-        for col in 0..(k) {
-            let row = k - col - 1;
-            if (row + 1, col + 1) == INPUT_ROW {
-                return cur;
-            }
-            cur = (cur * FACTOR) % MOD;
-        }
-        k += 1;
-    }
-    panic!("nah");
+/// The code at `row` and `col`, both counted from 1. The codes are filled in
+/// diagonal by diagonal, each one `FACTOR` times the one before, so the code
+/// is `FIRST_CODE * FACTOR ^ n` for its position `n` in that order.
+fn compute_code((row, col): (u64, u64)) -> u64 {
+    let diagonal = row + col - 1;
+    let n = diagonal * (diagonal - 1) / 2 + col - 1;
+    FIRST_CODE * modpow(FACTOR, n, MOD) % MOD
 }
 
 fn main() {
-    dbg!(compute_code());
+    println!("Part 1: {}", compute_code(INPUT_ROW));
+}
+
+#[test]
+fn test_compute_code() {
+    let table: Vec<Vec<u64>> = include_str!("../test.txt")
+        .lines()
+        .skip(2)
+        .map(|line| {
+            let (_, codes) = line.split_once('|').unwrap();
+            codes
+                .split_whitespace()
+                .map(|c| c.parse().unwrap())
+                .collect()
+        })
+        .collect();
+    for (row, codes) in table.iter().enumerate() {
+        for (col, &code) in codes.iter().enumerate() {
+            assert_eq!(compute_code((row as u64 + 1, col as u64 + 1)), code);
+        }
+    }
 }
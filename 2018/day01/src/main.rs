@@ -1,5 +1,5 @@
 // Problem: https://adventofcode.com/2018/day/1
-use std::collections::HashSet;
+use cycle::first_repeat;
 
 fn part1(input: &str) -> i32 {
     input.lines().map(|l| l.parse::<i32>().unwrap()).sum()
@@ -7,18 +7,12 @@ fn part1(input: &str) -> i32 {
 
 fn part2(input: &str) -> i32 {
     let numbers: Vec<i32> = input.lines().map(|l| l.parse::<i32>().unwrap()).collect();
-    let mut freq: i32 = 0;
-    let mut seen = HashSet::new();
-
-    for n in numbers.iter().cloned().cycle() {
-        if seen.contains(&freq) {
-            return freq;
-        }
-        seen.insert(freq);
-        freq += n;
-    }
-
-    unreachable!()
+    let frequencies = numbers.iter().cycle().scan(0, |freq, n| {
+        let current = *freq;
+        *freq += n;
+        Some(current)
+    });
+    first_repeat(frequencies).unwrap().value
 }
 
 fn main() {
@@ -1,14 +1,7 @@
-#![allow(
-    dead_code,
-    unused_variables,
-    unreachable_code,
-    clippy::diverging_sub_expression
-)]
-
 // Problem: https://adventofcode.com/2018/day/7
 
+use graph::{schedule, toposort_by_key, Graph, NodeId};
 use sscanf::sscanf;
-use std::collections::{HashMap, HashSet};
 
 type Input = Vec<(char, char)>;
 
@@ -27,41 +20,26 @@ fn parse_input(input: &str) -> Input {
 
 // ------------------------------------------
 
-fn part1(input: &Input) -> String {
-    let mut all = HashSet::new();
-    let mut deps = HashMap::new();
+/// The steps, with an edge from every step to the steps waiting for it.
+fn build_graph(input: &Input) -> Graph {
+    let mut graph = Graph::directed();
     for (a, b) in input.iter() {
-        deps.entry(*b).or_insert(vec![]).push(*a);
-        all.insert(*a);
-        all.insert(*b);
+        graph.add_edge(&a.to_string(), &b.to_string());
     }
+    graph
+}
 
-    let start = all.iter().find(|v| !deps.contains_key(v)).unwrap();
-    let mut remaining = all.clone();
-    let mut result: Vec<char> = vec![*start];
-    let mut resolved = HashSet::new();
-    resolved.insert(*start);
-    remaining.remove(start);
-
-    while !remaining.is_empty() {
-        let next = remaining
-            .iter()
-            .filter(|c| {
-                if let Some(d) = deps.get(c) {
-                    d.iter().all(|o| resolved.contains(o))
-                } else {
-                    true
-                }
-            })
-            .cloned()
-            .min()
-            .unwrap();
+fn letter(graph: &Graph, id: NodeId) -> char {
+    graph.name(id).chars().next().unwrap()
+}
 
-        remaining.remove(&next);
-        resolved.insert(next);
-        result.push(next);
-    }
-    result.iter().cloned().collect()
+fn part1(input: &Input) -> String {
+    let graph = build_graph(input);
+    toposort_by_key(&graph, |id| letter(&graph, id))
+        .unwrap()
+        .into_iter()
+        .map(|id| letter(&graph, id))
+        .collect()
 }
 
 #[test]
@@ -72,53 +50,12 @@ fn test_part1() {
 
 // ------------------------------------------
 
-fn part2(input: &Input, worker_count: usize, base_duration: usize) -> usize {
-    let mut all = HashSet::new();
-    let mut deps = HashMap::new();
-    for (a, b) in input.iter() {
-        deps.entry(*b).or_insert(vec![]).push(*a);
-        all.insert(*a);
-        all.insert(*b);
-    }
-
-    let mut resolved = HashSet::new();
-    let mut workers: Vec<(char, usize)> = vec![];
-    let mut seconds = 0;
-    loop {
-        if workers.is_empty() && resolved.len() == all.len() {
-            break;
-        }
-        while workers.len() < worker_count {
-            if let Some(next) = all
-                .iter()
-                .filter(|c| !resolved.contains(*c) && !workers.iter().any(|(x, _)| *c == x))
-                .filter(|c| {
-                    if let Some(d) = deps.get(c) {
-                        d.iter().all(|o| resolved.contains(o))
-                    } else {
-                        true
-                    }
-                })
-                .cloned()
-                .min()
-            {
-                workers.push((next, next as usize - 'A' as usize + 1 + base_duration));
-            } else {
-                break;
-            }
-        }
-        for entry in workers.iter_mut() {
-            entry.1 -= 1;
-        }
-        for i in (0..workers.len()).rev() {
-            if workers[i].1 == 0 {
-                resolved.insert(workers[i].0);
-                workers.remove(i);
-            }
-        }
-        seconds += 1;
-    }
-    seconds
+fn part2(input: &Input, worker_count: usize, base_duration: u64) -> u64 {
+    let graph = build_graph(input);
+    let duration = |id| (letter(&graph, id) as u8 - b'A' + 1) as u64 + base_duration;
+    schedule(&graph, worker_count, duration, |id| letter(&graph, id))
+        .unwrap()
+        .finish
 }
 
 #[test]
@@ -1,8 +1,10 @@
+use vis::{Cell, Color, Frame, Player, Visualise};
+
 fn is_immediate_mode(instruction: isize, n: u32) -> bool {
     ((instruction) / (10 * (10_isize).pow(n))) % 10 == 1
 }
 
-fn read_param(v: &Vec<isize>, pos: usize, n: u32, instruction: isize) -> isize {
+fn read_param(v: &[isize], pos: usize, n: u32, instruction: isize) -> isize {
     let idx: usize = pos + n as usize;
     if is_immediate_mode(instruction, n) {
         v[idx]
@@ -11,141 +13,177 @@ fn read_param(v: &Vec<isize>, pos: usize, n: u32, instruction: isize) -> isize {
     }
 }
 
-const DEBUG: bool = false;
+/// Number of values an instruction takes up, itself included.
+fn instruction_len(instruction: isize) -> usize {
+    match instruction % 100 {
+        1 | 2 | 7 | 8 => 4,
+        3 | 4 => 2,
+        5 | 6 => 3,
+        _ => 1,
+    }
+}
+
+/// A program being run one instruction at a time.
+struct Computer {
+    memory: Vec<isize>,
+    pos: usize,
+    input: isize,
+    last_output: isize,
+    /// What the last instruction did.
+    trace: String,
+}
+
+impl Computer {
+    fn new(memory: Vec<isize>, input: isize) -> Self {
+        Self {
+            memory,
+            pos: 0,
+            input,
+            last_output: -1,
+            trace: String::new(),
+        }
+    }
 
-fn execute(cur: &mut Vec<isize>, input: isize) -> isize {
-    let mut pos = 0;
-    let len = cur.len();
-    let mut last_output = -1;
-    while pos < len {
+    /// Executes the next instruction, returning whether the program goes on.
+    fn step(&mut self) -> bool {
+        if self.pos >= self.memory.len() {
+            return false;
+        }
+        let cur = &mut self.memory;
+        let pos = self.pos;
         let instruction = cur[pos];
         match instruction % 100 {
             1 => {
-                let a = read_param(&cur, pos, 1, instruction);
-                let b = read_param(&cur, pos, 2, instruction);
+                let a = read_param(cur, pos, 1, instruction);
+                let b = read_param(cur, pos, 2, instruction);
                 let r = cur[pos + 3];
-                if DEBUG {
-                    println!("ADD {} + {} -> cur[{}] = {}", a, b, r, a + b);
-                }
+                self.trace = format!("ADD {} + {} -> cur[{}] = {}", a, b, r, a + b);
                 cur[r as usize] = a + b;
-                pos += 4;
+                self.pos += 4;
             }
             2 => {
-                let a = read_param(&cur, pos, 1, instruction);
-                let b = read_param(&cur, pos, 2, instruction);
+                let a = read_param(cur, pos, 1, instruction);
+                let b = read_param(cur, pos, 2, instruction);
                 let r = cur[pos + 3];
-                if DEBUG {
-                    println!("MUL {} * {} -> cur[{}] = {}", a, b, r, a * b);
-                }
+                self.trace = format!("MUL {} * {} -> cur[{}] = {}", a, b, r, a * b);
                 cur[r as usize] = a * b;
-                pos += 4;
+                self.pos += 4;
             }
             3 => {
                 let v = cur[pos + 1] as usize;
-                if DEBUG {
-                    println!("INPUT cur[{}] = {}", v, input);
-                }
-                cur[v as usize] = input;
-                pos += 2;
+                self.trace = format!("INPUT cur[{}] = {}", v, self.input);
+                cur[v] = self.input;
+                self.pos += 2;
             }
             4 => {
-                let v = read_param(&cur, pos, 1, instruction);
-                if DEBUG {
-                    println!("OUTPUT {}", v);
-                }
-                last_output = v;
-                pos += 2;
+                let v = read_param(cur, pos, 1, instruction);
+                self.trace = format!("OUTPUT {}", v);
+                self.last_output = v;
+                self.pos += 2;
             }
             5 => {
-                let a = read_param(&cur, pos, 1, instruction);
-                if DEBUG {
-                    println!("JMPIF {} > 0", a);
-                }
+                let a = read_param(cur, pos, 1, instruction);
+                self.trace = format!("JMPIF {} > 0", a);
                 if a > 0 {
-                    let b = read_param(&cur, pos, 2, instruction);
-                    pos = b as usize;
-                    if DEBUG {
-                        dbg!(pos);
-                    }
+                    self.pos = read_param(cur, pos, 2, instruction) as usize;
                 } else {
-                    pos += 3;
+                    self.pos += 3;
                 }
             }
             6 => {
-                let a = read_param(&cur, pos, 1, instruction);
-                if DEBUG {
-                    println!("JMPIF {} == 0", a);
-                }
+                let a = read_param(cur, pos, 1, instruction);
+                self.trace = format!("JMPIF {} == 0", a);
                 if a == 0 {
-                    let b = read_param(&cur, pos, 2, instruction);
-                    pos = b as usize;
-                    if DEBUG {
-                        dbg!(pos);
-                    }
+                    self.pos = read_param(cur, pos, 2, instruction) as usize;
                 } else {
-                    pos += 3;
+                    self.pos += 3;
                 }
             }
             7 => {
-                let a = read_param(&cur, pos, 1, instruction);
-                let b = read_param(&cur, pos, 2, instruction);
+                let a = read_param(cur, pos, 1, instruction);
+                let b = read_param(cur, pos, 2, instruction);
                 let c = cur[pos + 3];
-                if DEBUG {
-                    println!(
-                        "LT {} < {} -> cur[{}] = {}",
-                        a,
-                        b,
-                        c,
-                        if a < b { 1 } else { 0 }
-                    );
-                }
-                cur[c as usize] = if a < b { 1 } else { 0 };
-                pos += 4;
+                let result = (a < b) as isize;
+                self.trace = format!("LT {} < {} -> cur[{}] = {}", a, b, c, result);
+                cur[c as usize] = result;
+                self.pos += 4;
             }
             8 => {
-                let a = read_param(&cur, pos, 1, instruction);
-                let b = read_param(&cur, pos, 2, instruction);
+                let a = read_param(cur, pos, 1, instruction);
+                let b = read_param(cur, pos, 2, instruction);
                 let c = cur[pos + 3];
-                if DEBUG {
-                    println!(
-                        "EQ {} == {} -> cur[{}] = {}",
-                        a,
-                        b,
-                        c,
-                        if a == b { 1 } else { 0 }
-                    );
-                }
-                cur[c as usize] = if a == b { 1 } else { 0 };
-                pos += 4;
+                let result = (a == b) as isize;
+                self.trace = format!("EQ {} == {} -> cur[{}] = {}", a, b, c, result);
+                cur[c as usize] = result;
+                self.pos += 4;
             }
             99 => {
-                if DEBUG {
-                    println!("TERMINATE");
-                }
-                break;
+                self.trace = "TERMINATE".to_string();
+                return false;
             }
             _ => {
                 panic!("UNKNOWN OP {} at {}", cur[pos], pos);
             }
         }
+        self.pos < self.memory.len()
+    }
+}
+
+/// Width of a memory cell in the frame, with a space before the value.
+const CELL_WIDTH: usize = 7;
+const CELLS_PER_ROW: usize = 10;
+
+impl Visualise for Computer {
+    /// The memory, ten values to a row, with the next instruction picked
+    /// out and what the last one did below.
+    fn frame(&self) -> Frame {
+        let rows = self.memory.len().div_ceil(CELLS_PER_ROW);
+        let mut frame = Frame::new(CELLS_PER_ROW * CELL_WIDTH, rows);
+        let next = self.memory.get(self.pos).map(|&i| instruction_len(i));
+        let current = self.pos..self.pos + next.unwrap_or(0);
+        for (i, value) in self.memory.iter().enumerate() {
+            let (x, y) = ((i % CELLS_PER_ROW) * CELL_WIDTH, i / CELLS_PER_ROW);
+            let text = format!("{:>width$}", value, width = CELL_WIDTH);
+            for (dx, ch) in text.chars().skip(text.len() - CELL_WIDTH).enumerate() {
+                let cell = match current.contains(&i) {
+                    true => Cell::colored(ch, Color::Yellow),
+                    false => Cell::plain(ch),
+                };
+                frame.set(x + dx, y, cell);
+            }
+        }
+        let (x, y) = (self.pos % CELLS_PER_ROW, self.pos / CELLS_PER_ROW);
+        frame
+            .with_caption(format!("{}\nlast output {}", self.trace, self.last_output))
+            .focus_on((x * CELL_WIDTH) as i64, y as i64)
+    }
+}
+
+/// Runs the program on `input` and returns its last output. With
+/// `VISUALISE` set the memory is shown after every instruction.
+fn execute(program: Vec<isize>, input: isize) -> isize {
+    let mut computer = Computer::new(program, input);
+    match Player::from_env() {
+        Some(mut player) => player.play(&mut computer, Computer::step).unwrap(),
+        None => while computer.step() {},
     }
-    last_output
+    computer.last_output
 }
 
 fn part1(input: &str) -> isize {
-    let mut program = input
+    let program = input
         .split(',')
         .map(|s| s.parse::<isize>().unwrap())
         .collect::<Vec<_>>();
-    execute(&mut program, 1)
+    execute(program, 1)
 }
 
 fn part2(prog: &str, input: isize) -> isize {
-    let mut program = prog
+    let program = prog
         .split(',')
         .map(|s| s.parse::<isize>().unwrap())
         .collect::<Vec<_>>();
-    execute(&mut program, input)
+    execute(program, input)
 }
 
 fn main() {
@@ -155,15 +193,15 @@ fn main() {
 
 #[test]
 fn test_immediate_mode_flag() {
-    assert_eq!(is_immediate_mode(1002, 1), false);
-    assert_eq!(is_immediate_mode(1002, 2), true);
-    assert_eq!(is_immediate_mode(1002, 3), false);
-    assert_eq!(is_immediate_mode(11002, 3), true);
-    assert_eq!(is_immediate_mode(11002, 2), true);
-    assert_eq!(is_immediate_mode(11002, 1), false);
-    assert_eq!(is_immediate_mode(102, 1), true);
-    assert_eq!(is_immediate_mode(102, 2), false);
-    assert_eq!(is_immediate_mode(102, 3), false);
+    assert!(!is_immediate_mode(1002, 1));
+    assert!(is_immediate_mode(1002, 2));
+    assert!(!is_immediate_mode(1002, 3));
+    assert!(is_immediate_mode(11002, 3));
+    assert!(is_immediate_mode(11002, 2));
+    assert!(!is_immediate_mode(11002, 1));
+    assert!(is_immediate_mode(102, 1));
+    assert!(!is_immediate_mode(102, 2));
+    assert!(!is_immediate_mode(102, 3));
 }
 
 #[test]
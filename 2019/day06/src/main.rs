@@ -1,50 +1,27 @@
-use std::collections::HashMap;
+use graph::{Graph, Lca};
 
-fn part1(input: &str) -> usize {
-    let parent_map = input
-        .split('\n')
-        .map(|line| {
-            let child_parent = line.split(')').collect::<Vec<_>>();
-            (child_parent[1], child_parent[0])
-        })
-        .collect::<HashMap<_, _>>();
-    let mut count = 0;
-    for parent in parent_map.keys() {
-        let mut cur_parent = parent;
-        while let Some(parent) = parent_map.get(cur_parent) {
-            count += 1;
-            cur_parent = parent;
-        }
+/// The orbits as a tree rooted at the center of mass.
+fn parse_orbits(input: &str) -> (Graph, Lca) {
+    let mut graph = Graph::directed();
+    for line in input.lines() {
+        let (center, orbiter) = line.split_once(')').unwrap();
+        graph.add_edge(center, orbiter);
     }
-    count
+    let lca = Lca::new(&graph, graph.id("COM").unwrap());
+    (graph, lca)
 }
 
-fn path_to_root(name: &str, parent_map: &HashMap<&str, &str>) -> Vec<String> {
-    let mut result = vec![];
-    let mut cur = name;
-    while let Some(next) = parent_map.get(cur) {
-        result.push(String::from(next.clone()));
-        cur = next;
-    }
-    result
+fn part1(input: &str) -> usize {
+    let (graph, lca) = parse_orbits(input);
+    graph.nodes().map(|node| lca.depth(node).unwrap()).sum()
 }
 
 fn part2(input: &str) -> usize {
-    let parent_map = input
-        .split('\n')
-        .map(|line| {
-            let edge_data = line.split(')').collect::<Vec<_>>();
-            (edge_data[1], edge_data[0])
-        })
-        .collect::<HashMap<_, _>>();
-    let you_path = path_to_root("YOU", &parent_map);
-    let san_path = path_to_root("SAN", &parent_map);
-    let ch = you_path
-        .iter()
-        .find(|c| san_path.contains(&c))
-        .clone()
-        .unwrap();
-    you_path.iter().position(|c| c == ch).unwrap() + san_path.iter().position(|c| c == ch).unwrap()
+    let (graph, lca) = parse_orbits(input);
+    let you = graph.id("YOU").unwrap();
+    let san = graph.id("SAN").unwrap();
+    // transfers are between the objects YOU and SAN orbit
+    lca.distance(you, san).unwrap() - 2
 }
 
 fn main() {
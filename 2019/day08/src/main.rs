@@ -1,3 +1,4 @@
+use ocr::Image;
 use std::str::Chars;
 
 fn part1(input: &str, cols: usize, rows: usize) -> usize {
@@ -33,7 +34,8 @@ fn pixel(layers: &Vec<Chars>, i: usize) -> char {
     '2'
 }
 
-fn part2(input: &str, cols: usize, rows: usize) -> String {
+/// Stacks the layers and draws the visible pixels, `#` for white ones.
+fn decode_image(input: &str, cols: usize, rows: usize) -> String {
     let len: usize = input.len();
     let mut row: usize = 0;
     let mut layers: Vec<Chars> = vec![];
@@ -65,12 +67,23 @@ fn part2(input: &str, cols: usize, rows: usize) -> String {
     enc
 }
 
+fn part2(input: &str, cols: usize, rows: usize) -> String {
+    Image::parse(&decode_image(input, cols, rows))
+        .recognize()
+        .unwrap()
+}
+
 fn main() {
     println!("Part 1: {}", part1(include_str!("in.txt"), 25, 6));
-    println!("Part 2:\n{}\n", part2(include_str!("in.txt"), 25, 6));
+    println!("Part 2: {}", part2(include_str!("in.txt"), 25, 6));
+}
+
+#[test]
+fn test_decode_image() {
+    assert_eq!(decode_image("0222112222120000", 2, 2), " #\n# ".to_string());
 }
 
 #[test]
 fn test_part2() {
-    assert_eq!(part2("0222112222120000", 2, 2), " #\n# ".to_string());
+    assert_eq!(part2(include_str!("in.txt"), 25, 6), "LEJKC");
 }
@@ -1,7 +1,9 @@
+use cycle::brent;
 use lazy_static::lazy_static;
-use num::Integer;
+use numtheory::lcm_all;
 use regex::Regex;
-use std::{collections::HashSet, ops::Add};
+use std::ops::Add;
+use vis::{Cell, Color, Frame, Player, Visualise};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct Voxel {
@@ -35,7 +37,7 @@ impl PartialEq for Moon {
 impl Eq for Moon {}
 
 fn voxel(x: i32, y: i32, z: i32) -> Voxel {
-    Voxel { x: x, y: y, z: z }
+    Voxel { x, y, z }
 }
 
 fn parse_moon_locations(input: &str) -> Vec<Voxel> {
@@ -67,7 +69,7 @@ fn gravity_impact(val1: i32, val2: i32) -> i32 {
     }
 }
 
-fn apply_gravity(moon: &mut Moon, all_moons: &Vec<Moon>) {
+fn apply_gravity(moon: &mut Moon, all_moons: &[Moon]) {
     for other in all_moons.iter() {
         if other != moon {
             moon.velocity = moon.velocity
@@ -92,20 +94,55 @@ fn voxel_to_string(voxel: Voxel) -> String {
     format!("<x={:3}, y={:3}, z={:3}>", voxel.x, voxel.y, voxel.z)
 }
 
-fn print_moons(moons: &Vec<Moon>) {
-    for moon in moons.iter() {
-        println!(
-            "pos={}, vel={}",
-            voxel_to_string(moon.location),
-            voxel_to_string(moon.velocity)
-        );
+/// The moons moving step by step, seen from above.
+struct Orbits {
+    moons: Vec<Moon>,
+    steps: usize,
+}
+
+impl Orbits {
+    fn step(&mut self) {
+        let all_moons = self.moons.clone();
+        for moon in self.moons.iter_mut() {
+            apply_gravity(moon, &all_moons);
+        }
+        for moon in self.moons.iter_mut() {
+            apply_velocity(moon);
+        }
+        self.steps += 1;
+    }
+
+    fn total_energy(&self) -> usize {
+        self.moons
+            .iter()
+            .map(|moon| energy(moon.location) * energy(moon.velocity))
+            .sum()
     }
 }
 
-const DEBUG: bool = false;
+impl Visualise for Orbits {
+    /// The moons in the x-y plane, lettered in input order, with their
+    /// positions and velocities below.
+    fn frame(&self) -> Frame {
+        let moons = self.moons.iter().map(|moon| {
+            let letter = (b'A' + moon.id as u8) as char;
+            let at = (moon.location.x as i64, moon.location.y as i64);
+            (at, Cell::colored(letter, Color::Yellow))
+        });
+        let mut caption = format!("After step {}", self.steps);
+        for moon in &self.moons {
+            caption += &format!(
+                "\npos={}, vel={}",
+                voxel_to_string(moon.location),
+                voxel_to_string(moon.velocity)
+            );
+        }
+        Frame::from_points(moons).with_caption(caption)
+    }
+}
 
 fn part1(input: &str, iterations: usize) -> usize {
-    let mut moons = parse_moon_locations(input)
+    let moons = parse_moon_locations(input)
         .iter()
         .enumerate()
         .map(|(number, loc)| Moon {
@@ -114,55 +151,39 @@ fn part1(input: &str, iterations: usize) -> usize {
             velocity: voxel(0, 0, 0),
         })
         .collect::<Vec<_>>();
-    if DEBUG {
-        print_moons(&moons);
-        println!();
+    let mut orbits = Orbits { moons, steps: 0 };
+    match Player::from_env() {
+        Some(mut player) => player
+            .play(&mut orbits, |orbits| {
+                orbits.step();
+                orbits.steps < iterations
+            })
+            .unwrap(),
+        None => (0..iterations).for_each(|_| orbits.step()),
     }
+    orbits.total_energy()
+}
 
-    for step in 0..iterations {
-        let all_moons = moons.clone();
-        for moon in moons.iter_mut() {
-            apply_gravity(moon, &all_moons);
-        }
-        for moon in moons.iter_mut() {
-            apply_velocity(moon);
-        }
-
-        if DEBUG {
-            println!("After step {}", step + 1);
-            print_moons(&moons);
-            println!();
-        }
-    }
+/// Position and velocity of every moon along one axis.
+type Axis = Vec<(i32, i32)>;
 
-    let mut total_energy = 0;
-    for moon in moons.iter() {
-        let pot = energy(moon.location);
-        let kin = energy(moon.velocity);
-        total_energy += pot * kin;
-    }
-    total_energy
-}
-
-fn find_rep(axis: Vec<(i32, i32)>) -> usize {
-    let mut seen = HashSet::<Vec<(i32, i32)>>::new();
-    let mut cur = axis;
-    loop {
-        seen.insert(cur.clone());
-        for i in 0..cur.len() {
-            for j in 0..cur.len() {
-                if i != j {
-                    cur[i].1 += gravity_impact(cur[i].0, cur[j].0);
-                }
+/// Advances the positions and velocities of all moons along one axis by one
+/// step. The axes do not influence each other.
+fn step_axis(axis: &Axis) -> Axis {
+    let mut next = axis.clone();
+    for (i, (pos, vel)) in next.iter_mut().enumerate() {
+        for (j, (other, _)) in axis.iter().enumerate() {
+            if i != j {
+                *vel += gravity_impact(*pos, *other);
             }
         }
-        for i in 0..cur.len() {
-            cur[i].0 += cur[i].1;
-        }
-        if seen.contains(&cur) {
-            return seen.len();
-        }
+        *pos += *vel;
     }
+    next
+}
+
+fn find_rep(axis: Axis) -> usize {
+    brent(axis, step_axis).length
 }
 
 fn part2(input: &str) -> usize {
@@ -193,7 +214,7 @@ fn part2(input: &str) -> usize {
     let y_reps = find_rep(y_axis);
     let z_reps = find_rep(z_axis);
 
-    x_reps.lcm(&y_reps.lcm(&z_reps))
+    lcm_all([x_reps, y_reps, z_reps])
 }
 
 fn main() {
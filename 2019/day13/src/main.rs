@@ -1,4 +1,3 @@
-mod intcode;
 use intcode::{parse_program, IntcodeComputer};
 use std::collections::HashSet;
 
@@ -7,11 +6,7 @@ fn part1(input: &str) -> usize {
     let mut computer = IntcodeComputer::create(program, vec![]);
     let mut block_tiles = HashSet::<(i64, i64)>::new();
 
-    loop {
-        let x = match computer.run_until_next_output() {
-            Some(v) => v,
-            None => break,
-        };
+    while let Some(x) = computer.run_until_next_output() {
         let y = computer.run_until_next_output().unwrap();
         let tile = computer.run_until_next_output().unwrap();
 
@@ -34,11 +29,7 @@ fn part2(input: &str) -> i64 {
     let mut ball: i64 = 0;
     let mut paddle: i64 = 0;
 
-    loop {
-        let x = match computer.run_until_next_output() {
-            Some(v) => v,
-            None => break,
-        };
+    while let Some(x) = computer.run_until_next_output() {
         let y = computer.run_until_next_output().unwrap();
         let tile = computer.run_until_next_output().unwrap();
 
@@ -1,7 +1,6 @@
-mod intcode;
 use intcode::IntcodeComputer;
-use itertools::Itertools;
 use std::{collections::HashMap, slice::Iter};
+use vis::{Cell, Color, Frame, Player, Visualise};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Square {
@@ -24,14 +23,6 @@ impl Direction {
         static DIRS: [Direction; 4] = [North, East, South, West];
         DIRS.iter()
     }
-    fn opposite(&self) -> Direction {
-        match &self {
-            North => South,
-            South => North,
-            East => West,
-            West => East,
-        }
-    }
 }
 
 fn move_coords((x, y): (i64, i64), dir: Direction) -> (i64, i64) {
@@ -70,47 +61,82 @@ fn step(computer: &mut IntcodeComputer, dir: Direction) -> Status {
     Status::parse(computer.run_until_next_output().unwrap())
 }
 
-#[allow(unused)]
-fn print_map(map: &HashMap<(i64, i64), Square>) {
-    let (x0, x1) = map.keys().map(|(x, _)| x).minmax().into_option().unwrap();
-    let (y0, y1) = map.keys().map(|(_, y)| y).minmax().into_option().unwrap();
-    for y in *y0..=*y1 {
-        for x in *x0..=*x1 {
-            print!(
-                "{}",
-                match map.get(&(x, y)) {
-                    None => ' ',
-                    Some(&Square::Start) => 'X',
-                    Some(&Square::Empty) => '.',
-                    Some(&Square::Wall) => '█',
-                    Some(&Square::OxygenSystem) => 'O',
-                }
-            )
-        }
-        println!("");
-    }
+/// Maps the maze depth first, keeping a copy of the droid for every square on
+/// the path back to the start.
+struct Explorer {
+    map: HashMap<(i64, i64), Square>,
+    /// The droid at each square of the current path, with the index of the
+    /// next direction to try from there.
+    stack: Vec<(IntcodeComputer, (i64, i64), usize)>,
 }
 
-fn crawl(
-    computer: &mut IntcodeComputer,
-    coords: (i64, i64),
-    map: &mut HashMap<(i64, i64), Square>,
-) {
-    for dir in Direction::iter() {
-        let target_coords = move_coords(coords, *dir);
-        if map.contains_key(&target_coords) {
-            continue;
+impl Explorer {
+    fn new(computer: IntcodeComputer) -> Self {
+        let mut map = HashMap::new();
+        map.insert((0, 0), Square::Start);
+        Self {
+            map,
+            stack: vec![(computer, (0, 0), 0)],
         }
-        match step(computer, *dir) {
-            Status::HitWall => {
-                map.insert(target_coords, Square::Wall);
+    }
+
+    /// Tries to move the droid into the next unexplored square, or backtracks
+    /// if there is none around it. Returns `false` once the whole maze is
+    /// mapped.
+    fn step(&mut self) -> bool {
+        while let Some((computer, coords, next)) = self.stack.last_mut() {
+            let Some(dir) = Direction::iter().nth(*next) else {
+                self.stack.pop();
+                return true;
+            };
+            *next += 1;
+            let target_coords = move_coords(*coords, *dir);
+            if self.map.contains_key(&target_coords) {
+                continue;
             }
-            Status::Moved(square) => {
-                map.insert(target_coords, square);
-                let mut fork = computer.clone();
-                crawl(&mut fork, target_coords, map);
-                step(computer, dir.opposite());
+            let mut fork = computer.clone();
+            match step(&mut fork, *dir) {
+                Status::HitWall => {
+                    self.map.insert(target_coords, Square::Wall);
+                }
+                Status::Moved(square) => {
+                    self.map.insert(target_coords, square);
+                    self.stack.push((fork, target_coords, 0));
+                }
             }
+            return true;
+        }
+        false
+    }
+
+    fn explore(mut self) -> HashMap<(i64, i64), Square> {
+        match Player::from_env() {
+            Some(mut player) => player.play(&mut self, Explorer::step).unwrap(),
+            None => while self.step() {},
+        }
+        self.map
+    }
+}
+
+impl Visualise for Explorer {
+    fn frame(&self) -> Frame {
+        // north is towards larger y, so flip it to point up on screen
+        let squares = self.map.iter().map(|(&(x, y), square)| {
+            let cell = match square {
+                Square::Start => Cell::colored('X', Color::Cyan),
+                Square::Empty => Cell::plain('.'),
+                Square::Wall => Cell::plain('█'),
+                Square::OxygenSystem => Cell::colored('O', Color::Blue),
+            };
+            ((x, -y), cell)
+        });
+        let droid = self.stack.last().map(|(_, (x, y), _)| (*x, -y));
+        let droid_cell = droid.map(|p| (p, Cell::colored('D', Color::Red)));
+        let frame = Frame::from_points(squares.chain(droid_cell))
+            .with_caption(format!("{} squares mapped", self.map.len()));
+        match droid {
+            Some((x, y)) => frame.focus_on(x, y),
+            None => frame,
         }
     }
 }
@@ -139,10 +165,7 @@ fn solve_maze(
 }
 
 fn part1(input: &str) -> usize {
-    let mut computer = IntcodeComputer::from_str(input);
-    let mut map: HashMap<(i64, i64), Square> = HashMap::new();
-    map.insert((0, 0), Square::Start);
-    crawl(&mut computer, (0, 0), &mut map);
+    let map = Explorer::new(IntcodeComputer::from_str(input)).explore();
     solve_maze((0, 0), None, &map).unwrap()
 }
 
@@ -163,10 +186,7 @@ fn oxygen_spread_time(coords: (i64, i64), map: &mut HashMap<(i64, i64), Square>)
 }
 
 fn part2(input: &str) -> usize {
-    let mut computer = IntcodeComputer::from_str(input);
-    let mut map: HashMap<(i64, i64), Square> = HashMap::new();
-    map.insert((0, 0), Square::Start);
-    crawl(&mut computer, (0, 0), &mut map);
+    let mut map = Explorer::new(IntcodeComputer::from_str(input)).explore();
     let (start_coords, _) = map
         .iter()
         .find(|(_k, v)| v == &&Square::OxygenSystem)
@@ -1,93 +1,61 @@
-use std::collections::HashSet;
-
-#[derive(Debug, PartialEq, Clone)]
-enum Instruction {
-    Acc(isize),
-    Jmp(isize),
-    Nop(isize),
+use vm::{repair, Arg, Exit, Flow, InstructionSet, LoopCheck, Machine, Program, State};
+
+fn console() -> InstructionSet {
+    InstructionSet::new(&["acc"])
+        .op("acc", &[Arg::Imm], 1, |state, args| {
+            state.regs[0] += args[0].imm();
+            Flow::Next
+        })
+        .op("jmp", &[Arg::Imm], 1, |_, args| Flow::Jump(args[0].imm()))
+        .op("nop", &[Arg::Imm], 1, |_, _| Flow::Next)
 }
 
-use Instruction::*;
-
-fn parse_line(s: &str) -> Instruction {
-    let parts = s.splitn(2, " ").collect::<Vec<&str>>();
-    let n = (&parts[1]).parse::<isize>().unwrap();
-    match parts[0] {
-        "acc" => Acc(n),
-        "jmp" => Jmp(n),
-        "nop" => Nop(n),
-        _ => panic!("invalid op"),
-    }
+/// The accumulator just before any instruction runs a second time.
+fn part1(isa: &InstructionSet, program: &Program) -> i64 {
+    let mut machine = Machine::new(isa, program);
+    assert_eq!(machine.run_until_loop(LoopCheck::Pc), Exit::Loop);
+    machine.state.regs[0]
 }
 
-fn exec(ops: Vec<Instruction>) -> (isize, bool) {
-    let mut accum: isize = 0;
-    let mut seen = HashSet::new();
-    let mut i: usize = 0;
-    loop {
-        if seen.contains(&i) {
-            // infinite loop detected
-            break;
-        }
-        seen.insert(i);
-
-        let inst = ops.get(i);
-        match inst {
-            Some(Nop(_)) => {
-                i += 1;
-            }
-            Some(Acc(n)) => {
-                accum += n;
-                i += 1;
-            }
-            Some(Jmp(n)) => {
-                let t: isize = i as isize + n;
-                i = t.clone() as usize;
-            }
-            _ => {
-                if i == ops.len() {
-                    return (accum, true);
-                }
-                panic!("invalid instruction pointer");
-            }
-        };
-    }
-
-    return (accum, false);
+/// The accumulator after the program terminates with exactly one `jmp`
+/// swapped for a `nop` or the other way round.
+fn part2(isa: &InstructionSet, program: &Program) -> i64 {
+    let (jmp, nop) = (isa.opcode("jmp"), isa.opcode("nop"));
+    let init = State::new(1);
+    let (_, state) = repair(
+        isa,
+        program,
+        &init,
+        LoopCheck::Pc,
+        |instruction| match instruction.op {
+            op if op == jmp => Some(instruction.with_op(nop)),
+            op if op == nop => Some(instruction.with_op(jmp)),
+            _ => None,
+        },
+    )
+    .expect("no single patch terminates");
+    state.regs[0]
 }
 
 fn main() {
-    let instructions = include_str!("../in.txt")
-        .split('\n')
-        .map(|s| parse_line(s))
-        .collect::<Vec<Instruction>>();
+    let isa = console();
+    let program = isa.parse(include_str!("../in.txt")).unwrap();
+    println!("Part 1: {}", part1(&isa, &program));
+    println!("Part 2: {}", part2(&isa, &program));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let (result, _) = exec(instructions.clone());
-    println!("Part 1: {}", result);
+    const EXAMPLE: &str =
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
 
-    for i in 0..(instructions.len()) {
-        match instructions.get(i) {
-            Some(Jmp(n)) => {
-                let mut cloned = instructions.clone();
-                cloned[i] = Nop(*n);
-                let (accum, completed) = exec(cloned);
-                if completed {
-                    println!("Part 2: {}", accum);
-                    return;
-                }
-            }
-            Some(Nop(n)) => {
-                let mut cloned = instructions.clone();
-                cloned[i] = Jmp(*n);
-                let (accum, completed) = exec(cloned);
-                if completed {
-                    println!("Part 2: {}", accum);
-                    return;
-                }
-            }
-            _ => {
-                // ignore
-            }
-        }
+    #[test]
+    fn test_example() {
+        let isa = console();
+        let program = isa.parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&isa, &program), 5);
+        assert_eq!(part2(&isa, &program), 8);
     }
 }
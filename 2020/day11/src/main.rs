@@ -1,5 +1,5 @@
-#[macro_use]
-extern crate lazy_static;
+use geometry::Direction;
+use grid::{offset, Grid, Pos};
 
 #[derive(Debug, PartialEq, Clone)]
 enum SeatState {
@@ -10,182 +10,101 @@ enum SeatState {
 
 use SeatState::*;
 
-lazy_static! {
-    static ref DIRECTIONS: Vec<(isize, isize)> = vec![
-        (-1, -1),
-        (-1, 0),
-        (-1, 1),
-        (0, -1),
-        (0, 1),
-        (1, -1),
-        (1, 0),
-        (1, 1),
-    ];
-}
-
 #[derive(Debug)]
 struct SeatMap {
-    pub seats: Vec<SeatState>,
-    pub next_seats: Vec<SeatState>,
-    pub row_count: usize,
-    pub row_len: usize,
+    pub seats: Grid<SeatState>,
 }
 
 impl SeatMap {
     pub fn new(s: &str) -> SeatMap {
-        let input = s.split('\n').collect::<Vec<_>>();
-        let row_len = &input[0].len();
-        let row_count = &input.len();
-        let seats = input
-            .iter()
-            .flat_map(|row| {
-                row.chars().map(|c| match c {
-                    'L' => Empty,
-                    '.' => Floor,
-                    '#' => Occupied,
-                    _ => panic!(),
-                })
-            })
-            .collect::<Vec<SeatState>>();
-        SeatMap {
-            seats: seats,
-            next_seats: Vec::with_capacity(row_count * row_len),
-            row_count: *row_count,
-            row_len: *row_len,
-        }
+        let seats = Grid::parse(s, |c| match c {
+            'L' => Empty,
+            '.' => Floor,
+            '#' => Occupied,
+            _ => panic!(),
+        });
+        SeatMap { seats }
     }
 
-    pub fn get(&self, (row, col): (usize, usize)) -> &SeatState {
-        self.seats.get(self.row_len * row + col).unwrap()
+    pub fn neighbors(&self, pos: Pos) -> Vec<&SeatState> {
+        self.seats.neighbours8(pos).map(|p| &self.seats[p]).collect()
     }
 
-    pub fn neighbors(&self, (row, col): (usize, usize)) -> Vec<&SeatState> {
-        let res = DIRECTIONS
+    pub fn visible_seats(&self, pos: Pos) -> Vec<SeatState> {
+        Direction::ALL
             .iter()
-            .filter(|(dx, dy)| {
-                row as isize + dx >= 0
-                    && row as isize + dx < self.row_count as isize
-                    && col as isize + dy >= 0
-                    && col as isize + dy < self.row_len as isize
+            .filter_map(|&dir| {
+                self.seats
+                    .ray(pos, offset(dir))
+                    .map(|(_, state)| state)
+                    .find(|&state| *state != Floor)
+                    .cloned()
             })
-            .map(|(dx, dy)| self.get(((row as isize + dx) as usize, (col as isize + dy) as usize)))
-            .collect::<Vec<&SeatState>>();
-        res
+            .collect::<Vec<SeatState>>()
     }
 
-    pub fn visible_seat(
-        &self,
-        (row, col): (usize, usize),
-        (dx, dy): (isize, isize),
-    ) -> Option<SeatState> {
-        let mut cur_row = row as isize;
-        let mut cur_col = col as isize;
-        loop {
-            cur_row = cur_row + dx;
-            cur_col = cur_col + dy;
-            if cur_row >= 0
-                && cur_col >= 0
-                && cur_row < self.row_count as isize
-                && cur_col < self.row_len as isize
-            {
-                match self.get((cur_row as usize, cur_col as usize)) {
-                    Occupied => {
-                        return Some(Occupied);
+    fn calc_next(&self) -> Grid<SeatState> {
+        Grid::from_fn(self.seats.width(), self.seats.height(), |pos| {
+            match &self.seats[pos] {
+                Floor => Floor,
+                Empty => {
+                    if self
+                        .neighbors(pos)
+                        .iter()
+                        .all(|s| **s == Empty || **s == Floor)
+                    {
+                        Occupied
+                    } else {
+                        Empty
                     }
-                    Empty => {
-                        return Some(Empty);
+                }
+                Occupied => {
+                    if self
+                        .neighbors(pos)
+                        .iter()
+                        .filter(|s| ***s == Occupied)
+                        .count()
+                        >= 4
+                    {
+                        Empty
+                    } else {
+                        Occupied
                     }
-                    Floor => {
-                        // noop
-                    }
-                };
-            } else {
-                return None;
+                }
             }
-        }
+        })
     }
 
-    pub fn visible_seats(&self, pos: (usize, usize)) -> Vec<SeatState> {
-        DIRECTIONS
-            .iter()
-            .filter_map(|dir| self.visible_seat(pos, *dir))
-            .collect::<Vec<SeatState>>()
-    }
-
-    fn calc_next(&self) -> Vec<SeatState> {
-        (0..self.row_count)
-            .flat_map(|row| {
-                (0..self.row_len).map(move |col| {
-                    let pos = (row, col);
-                    let cur = self.get(pos);
-                    match cur {
-                        Floor => Floor,
-                        Empty => {
-                            if self
-                                .neighbors(pos)
-                                .iter()
-                                .all(|s| **s == Empty || **s == Floor)
-                            {
-                                Occupied
-                            } else {
-                                Empty
-                            }
-                        }
-                        Occupied => {
-                            if self
-                                .neighbors(pos)
-                                .iter()
-                                .filter(|s| ***s == Occupied)
-                                .count()
-                                >= 4
-                            {
-                                Empty
-                            } else {
-                                Occupied
-                            }
-                        }
+    fn calc_next2(&self) -> Grid<SeatState> {
+        Grid::from_fn(self.seats.width(), self.seats.height(), |pos| {
+            match &self.seats[pos] {
+                Floor => Floor,
+                Empty => {
+                    if self
+                        .visible_seats(pos)
+                        .iter()
+                        .all(|s| *s == Empty || *s == Floor)
+                    {
+                        Occupied
+                    } else {
+                        Empty
                     }
-                })
-            })
-            .collect::<Vec<SeatState>>()
-    }
-
-    fn calc_next2(&self) -> Vec<SeatState> {
-        (0..self.row_count)
-            .flat_map(|row| {
-                (0..self.row_len).map(move |col| {
-                    let pos = (row, col);
-                    let cur = self.get(pos);
-                    match cur {
-                        Floor => Floor,
-                        Empty => {
-                            if self
-                                .visible_seats(pos)
-                                .iter()
-                                .all(|s| *s == Empty || *s == Floor)
-                            {
-                                Occupied
-                            } else {
-                                Empty
-                            }
-                        }
-                        Occupied => {
-                            if self
-                                .visible_seats(pos)
-                                .iter()
-                                .filter(|s| **s == Occupied)
-                                .count()
-                                >= 5
-                            {
-                                Empty
-                            } else {
-                                Occupied
-                            }
-                        }
+                }
+                Occupied => {
+                    if self
+                        .visible_seats(pos)
+                        .iter()
+                        .filter(|s| **s == Occupied)
+                        .count()
+                        >= 5
+                    {
+                        Empty
+                    } else {
+                        Occupied
                     }
-                })
-            })
-            .collect::<Vec<SeatState>>()
+                }
+            }
+        })
     }
 
     pub fn apply_rules(&mut self) -> bool {
@@ -209,25 +128,19 @@ impl SeatMap {
     }
 
     pub fn occupied_count(&self) -> usize {
-        (0..self.row_count)
-            .flat_map(|row| (0..self.row_len).map(move |col| self.get((row, col))))
-            .filter(|s| **s == Occupied)
-            .count()
+        self.seats.values().filter(|s| **s == Occupied).count()
     }
 
     #[allow(dead_code)]
     pub fn print(&self) {
-        for row in 0..self.row_count {
-            let s: String = (0..self.row_len)
-                .map(|col| self.get((row, col)))
-                .map(|state| match state {
-                    Occupied => '#',
-                    Empty => 'L',
-                    Floor => '.',
-                })
-                .collect();
-            println!("{}", s);
-        }
+        println!(
+            "{}",
+            self.seats.render(|state| match state {
+                Occupied => '#',
+                Empty => 'L',
+                Floor => '.',
+            })
+        );
     }
 }
 
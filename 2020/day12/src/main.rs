@@ -1,83 +1,48 @@
-#[derive(Debug, Clone)]
-enum Dir {
-    North,
-    South,
-    East,
-    West,
-}
+use geometry::{Direction, Point2, Turn};
 
-#[derive(Debug, Clone)]
-enum TurnDir {
-    Left,
-    Right,
-}
+type Pos = Point2<isize>;
 
 #[derive(Debug, Clone)]
 enum Instruction {
-    Move(Dir, usize),
-    Turn(TurnDir, usize),
-    Forward(usize),
+    Move(Direction, isize),
+    Turn(Turn, usize),
+    Forward(isize),
 }
 
-#[derive(Debug, Clone)]
-struct Pos(isize, isize);
-
 fn parse_instruction(s: &str) -> Instruction {
     let n = s[1..].parse::<usize>().unwrap();
     match s.chars().next().unwrap() {
-        'N' => Instruction::Move(Dir::North, n),
-        'S' => Instruction::Move(Dir::South, n),
-        'E' => Instruction::Move(Dir::East, n),
-        'W' => Instruction::Move(Dir::West, n),
-        'L' => Instruction::Turn(TurnDir::Left, n),
-        'R' => Instruction::Turn(TurnDir::Right, n),
-        'F' => Instruction::Forward(n),
-        _ => panic!(format!("invalid instruction {:?}", s)),
+        'L' => Instruction::Turn(Turn::Left, n),
+        'R' => Instruction::Turn(Turn::Right, n),
+        'F' => Instruction::Forward(n as isize),
+        c => match Direction::from_char(c) {
+            Some(dir) => Instruction::Move(dir, n as isize),
+            None => panic!("invalid instruction {:?}", s),
+        },
     }
 }
 
-fn deg_to_right_turn_count(deg: usize, turn: TurnDir) -> usize {
-    use TurnDir::*;
-    (match turn {
-        Left => 360 - (deg % 360),
-        Right => deg % 360,
-    }) / 90
+fn turn_count(deg: usize) -> usize {
+    (deg % 360) / 90
 }
 
-fn turn_right_once(dir: Dir) -> Dir {
-    use Dir::*;
-    match dir {
-        North => East,
-        East => South,
-        South => West,
-        West => North,
-    }
-}
-
-fn turn(dir: Dir, deg: usize, turn: TurnDir) -> Dir {
-    (0..(deg_to_right_turn_count(deg, turn))).fold(dir, |cur, _| turn_right_once(cur))
+fn turn(dir: Direction, deg: usize, turn: Turn) -> Direction {
+    (0..turn_count(deg)).fold(dir, |cur, _| cur.turn(turn))
 }
 
-fn rotate_waypoint(waypoint: &Pos, deg: usize, turn: TurnDir) -> Pos {
-    (0..(deg_to_right_turn_count(deg, turn))).fold(waypoint.clone(), |Pos(x, y), _| Pos(y * -1, x))
-}
-
-fn apply_dir(pos: &Pos, dir: &Dir, amount: isize) -> Pos {
-    use Dir::*;
-    match dir {
-        North => Pos(pos.0 + amount, pos.1),
-        East => Pos(pos.0, pos.1 + amount),
-        South => Pos(pos.0 - amount, pos.1),
-        West => Pos(pos.0, pos.1 - amount),
-    }
+fn rotate_waypoint(waypoint: Pos, deg: usize, turn: Turn) -> Pos {
+    (0..turn_count(deg)).fold(waypoint, |cur, _| match turn {
+        Turn::Left => cur.rotate_left(),
+        Turn::Right => cur.rotate_right(),
+    })
 }
 
-fn apply_instruction(pos: &Pos, cur_dir: &Dir, inst: &Instruction) -> (Pos, Dir) {
+fn apply_instruction(pos: Pos, cur_dir: Direction, inst: &Instruction) -> (Pos, Direction) {
     use Instruction::*;
     match inst {
-        Move(move_dir, n) => (apply_dir(&pos, move_dir, *n as isize), cur_dir.clone()),
-        Forward(n) => (apply_dir(&pos, cur_dir, *n as isize), cur_dir.clone()),
-        Turn(turn_dir, deg) => (pos.clone(), turn(cur_dir.clone(), *deg, turn_dir.clone())),
+        Move(move_dir, n) => (pos + move_dir.unit_cartesian() * *n, cur_dir),
+        Forward(n) => (pos + cur_dir.unit_cartesian() * *n, cur_dir),
+        Turn(turn_dir, deg) => (pos, turn(cur_dir, *deg, *turn_dir)),
     }
 }
 
@@ -91,41 +56,38 @@ impl ShipState {
         use Instruction::*;
         match inst {
             Move(dir, n) => {
-                self.waypoint = apply_dir(&self.waypoint, dir, *n as isize);
+                self.waypoint += dir.unit_cartesian() * *n;
             }
             Forward(n) => {
-                let Pos(dx, dy) = self.waypoint;
-                self.pos = (0..(*n)).fold(self.pos.clone(), |Pos(x, y), _| Pos(x + dx, y + dy));
+                self.pos += self.waypoint * *n;
             }
             Turn(turn_dir, deg) => {
-                self.waypoint = rotate_waypoint(&self.waypoint, *deg, turn_dir.clone());
+                self.waypoint = rotate_waypoint(self.waypoint, *deg, *turn_dir);
             }
         }
     }
 }
 
-fn part1(inputs: &Vec<Instruction>) -> isize {
+fn part1(inputs: &[Instruction]) -> isize {
     let (final_pos, _) = inputs
         .iter()
-        .fold((Pos(0, 0), Dir::East), |(pos, dir), inst| {
-            apply_instruction(&pos, &dir, inst)
+        .fold((Pos::ORIGIN, Direction::East), |(pos, dir), inst| {
+            apply_instruction(pos, dir, inst)
         });
 
-    let Pos(x, y) = final_pos;
-    x.abs() + y.abs()
+    final_pos.manhattan(Pos::ORIGIN)
 }
 
-fn part2(inputs: &Vec<Instruction>) -> isize {
+fn part2(inputs: &[Instruction]) -> isize {
     let mut ship = ShipState {
-        pos: Pos(0, 0),
-        waypoint: Pos(1, 10),
+        pos: Pos::ORIGIN,
+        waypoint: Pos::new(10, 1),
     };
 
     for inst in inputs {
-        &ship.apply_instruction(inst);
+        ship.apply_instruction(inst);
     }
-    let Pos(x, y) = ship.pos;
-    x.abs() + y.abs()
+    ship.pos.manhattan(Pos::ORIGIN)
 }
 
 fn main() {
@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::slice::Iter;
+use vis::{Export, Frame, Palette};
 
 fn parse_id(s: &str) -> usize {
     s["Tile ".len()..(s.len() - 1)].parse().unwrap()
@@ -42,24 +43,20 @@ impl Bitmap {
 
     fn rotate_right(&self) -> Bitmap {
         let size = self.size();
-        let mut rotated = vec![vec![false; size]; size];
-        for i in 0..size {
-            for j in 0..size {
-                rotated[i][j] = self.0[size - j - 1][i];
-            }
-        }
-        Bitmap(rotated)
+        Bitmap(
+            (0..size)
+                .map(|i| (0..size).map(|j| self.0[size - j - 1][i]).collect())
+                .collect(),
+        )
     }
 
     fn flip(&self) -> Bitmap {
         let size = self.size();
-        let mut flipped = vec![vec![false; size]; size];
-        for i in 0..size {
-            for j in 0..size {
-                flipped[i][j] = self.0[j][i];
-            }
-        }
-        Bitmap(flipped)
+        Bitmap(
+            (0..size)
+                .map(|i| (0..size).map(|j| self.0[j][i]).collect())
+                .collect(),
+        )
     }
 
     fn count(&self) -> usize {
@@ -108,8 +105,8 @@ impl Tile {
             }
         }
         Tile {
-            id: parse_id(lines.get(0).unwrap()),
-            bitmap: bitmap,
+            id: parse_id(lines.first().unwrap()),
+            bitmap,
         }
     }
 
@@ -132,7 +129,7 @@ impl Tile {
     fn edge(&self, edge: Edge) -> String {
         let size = self.bitmap.size();
         match edge {
-            Edge::N => bits_to_string(self.bitmap.0[0].iter().cloned().collect::<Vec<bool>>()),
+            Edge::N => bits_to_string(self.bitmap.0[0].to_vec()),
             Edge::E => bits_to_string(
                 self.bitmap
                     .0
@@ -140,12 +137,7 @@ impl Tile {
                     .map(|row| row[size - 1])
                     .collect::<Vec<bool>>(),
             ),
-            Edge::S => bits_to_string(
-                self.bitmap.0[size - 1]
-                    .iter()
-                    .cloned()
-                    .collect::<Vec<bool>>(),
-            ),
+            Edge::S => bits_to_string(self.bitmap.0[size - 1].to_vec()),
             Edge::W => bits_to_string(
                 self.bitmap
                     .0
@@ -211,7 +203,7 @@ impl RotatedTile {
 
         RotatedTile {
             tile: if flip { rotated.flip() } else { rotated },
-            rotation: rotation,
+            rotation,
             flipped: flip,
         }
     }
@@ -236,7 +228,7 @@ fn rev(s: String) -> String {
 }
 
 fn solve(s: &str) -> (usize, usize) {
-    let tiles = s.split("\n\n").map(|s| Tile::new(s)).collect::<Vec<Tile>>();
+    let tiles = s.split("\n\n").map(Tile::new).collect::<Vec<Tile>>();
     let count: usize = tiles.len();
     let mut map: HashMap<String, Vec<Tile>> = HashMap::new();
     for t in tiles.clone() {
@@ -249,13 +241,13 @@ fn solve(s: &str) -> (usize, usize) {
     }
     let size = (count as f64).sqrt() as usize;
     fn find_match(
-        cur: &Vec<RotatedTile>,
+        cur: &[RotatedTile],
         tiles: &Vec<Tile>,
         edges: &HashMap<String, Vec<Tile>>,
         size: usize,
     ) -> Option<Vec<RotatedTile>> {
         if cur.len() == size * size {
-            return Some(cur.clone());
+            return Some(cur.to_vec());
         }
         let pos = cur.len();
         let row = pos / size;
@@ -270,7 +262,7 @@ fn solve(s: &str) -> (usize, usize) {
             let top_tile = cur.get(pos - size).unwrap();
             top = Some(top_tile.edge(Edge::S));
         }
-        let candidates = if top == None && left == None {
+        let candidates = if top.is_none() && left.is_none() {
             tiles.clone()
         } else {
             let a: HashSet<Tile> = match top.clone() {
@@ -278,25 +270,25 @@ fn solve(s: &str) -> (usize, usize) {
                 Some(top) => edges.get(&top).unwrap().clone(),
             }
             .iter()
-            .map(|t| t.clone())
+            .cloned()
             .collect::<HashSet<Tile>>();
             let b: HashSet<Tile> = match left.clone() {
                 None => tiles.clone(),
                 Some(edge) => edges.get(&edge).unwrap().clone(),
             }
             .iter()
-            .map(|t| t.clone())
+            .cloned()
             .collect::<HashSet<Tile>>();
             let s = a
                 .intersection(&b)
                 .filter(|t| !cur.iter().any(|o| t.id == o.tile.id))
-                .map(|t| t.clone())
+                .cloned()
                 .collect::<Vec<Tile>>();
             s
         };
         for c in candidates {
             for e in Edge::iter() {
-                for flipped in vec![true, false] {
+                for flipped in [true, false] {
                     let tile = RotatedTile::new(c.clone(), e.clone(), flipped);
                     if let Some(ref l) = left {
                         let left_edge = tile.edge(Edge::W);
@@ -311,7 +303,7 @@ fn solve(s: &str) -> (usize, usize) {
                         }
                     }
 
-                    let mut next = cur.clone();
+                    let mut next = cur.to_vec();
                     next.push(tile);
                     if let Some(res) = find_match(&next, tiles, edges, size) {
                         return Some(res);
@@ -320,17 +312,17 @@ fn solve(s: &str) -> (usize, usize) {
             }
         }
 
-        return None;
+        None
     }
 
-    let arrangement = find_match(&vec![], &tiles, &map, size).unwrap();
-    let corners = vec![
-        arrangement.get(0).unwrap().tile.id,
+    let arrangement = find_match(&[], &tiles, &map, size).unwrap();
+    let corners = [
+        arrangement.first().unwrap().tile.id,
         arrangement.get(size - 1).unwrap().tile.id,
         arrangement.get(size * size - size).unwrap().tile.id,
         arrangement.get(size * size - 1).unwrap().tile.id,
     ];
-    let part1 = corners.iter().fold(1, |a, b| a * b);
+    let part1 = corners.iter().product::<usize>();
 
     let mut bitmap = Bitmap::new(size * 8);
     for x in 0..size {
@@ -370,7 +362,7 @@ fn solve(s: &str) -> (usize, usize) {
                     }
                 }
             }
-            if found_at.len() > 0 {
+            if !found_at.is_empty() {
                 break 'outer;
             }
             bm = bm.flip();
@@ -378,6 +370,10 @@ fn solve(s: &str) -> (usize, usize) {
         bm = bm.rotate_right();
     }
 
+    if let Some(export) = Export::from_env() {
+        export_image(&export, &bm, &found_at, &seemonster).unwrap();
+    }
+
     for (x, y) in found_at {
         for (sx, sy) in seemonster.iter() {
             bm.set(x + sx, y + sy, false);
@@ -389,6 +385,32 @@ fn solve(s: &str) -> (usize, usize) {
     (part1, part2)
 }
 
+/// Saves the assembled image with the sea monsters picked out.
+fn export_image(
+    export: &Export,
+    bm: &Bitmap,
+    found_at: &[(usize, usize)],
+    seemonster: &[(usize, usize)],
+) -> std::io::Result<()> {
+    let size = bm.size();
+    let mut frame = Frame::new(size, size);
+    for x in 0..size {
+        for y in 0..size {
+            frame.set(y, x, if bm.get(x, y) { '#' } else { '.' });
+        }
+    }
+    for (x, y) in found_at {
+        for (sx, sy) in seemonster {
+            frame.set(y + sy, x + sx, 'O');
+        }
+    }
+    let palette = Palette::new([10, 40, 90], [0, 0, 0])
+        .with('.', [10, 40, 90])
+        .with('#', [70, 130, 200])
+        .with('O', [90, 220, 120]);
+    export.save(&frame, &palette)
+}
+
 fn main() {
     let (part1, part2) = solve(include_str!("in.txt"));
     println!("Part 1: {}", part1);
@@ -404,10 +426,10 @@ fn test_example() {
 fn test_tile() {
     let tiles = include_str!("test.txt")
         .split("\n\n")
-        .map(|s| Tile::new(s))
+        .map(Tile::new)
         .collect::<Vec<Tile>>();
 
-    let tile = tiles.get(0).unwrap();
+    let tile = tiles.first().unwrap();
     assert_eq!(tile.id, 2311);
     assert_eq!(tile.edge(Edge::N), "..##.#..#.");
     assert_eq!(tile.edge(Edge::S), "..###..###");
@@ -1,127 +1,52 @@
+use geometry::{Hex, HexDir};
 use std::collections::HashSet;
 
-type Tile = (isize, isize);
-
-#[derive(Debug, Clone)]
-enum Dir {
-    East,
-    West,
-    SouthEast,
-    SouthWest,
-    NorthWest,
-    NorthEast,
-}
-
-fn parse_directions(line: &str) -> Vec<Dir> {
-    let mut result = vec![];
-    let mut it = line.chars();
-    loop {
-        match it.next() {
-            Some(c) => {
-                let dir = match c {
-                    'e' => Dir::East,
-                    'w' => Dir::West,
-                    's' => match it.next().unwrap() {
-                        'w' => Dir::SouthWest,
-                        'e' => Dir::SouthEast,
-                        _ => panic!(),
-                    },
-                    'n' => match it.next().unwrap() {
-                        'w' => Dir::NorthWest,
-                        'e' => Dir::NorthEast,
-                        _ => panic!(),
-                    },
-                    _ => panic!(),
-                };
-                result.push(dir);
-            }
-            None => break,
-        }
-    }
-    result
+fn parse_tile(line: &str) -> Hex {
+    Hex::ORIGIN.walk(&HexDir::parse_path(line).expect("invalid path"))
 }
 
-fn move_dir(pos: Tile, dir: Dir) -> Tile {
-    use Dir::*;
-    let (x, y) = pos;
-    match dir {
-        East => (x + 1, y),
-        West => (x - 1, y),
-        SouthEast => (x, y + 1),
-        SouthWest => (x - 1, y + 1),
-        NorthEast => (x + 1, y - 1),
-        NorthWest => (x, y - 1),
-    }
-}
-
-fn move_path(pos: Tile, dirs: Vec<Dir>) -> Tile {
-    dirs.iter().fold(pos, |pos, dir| move_dir(pos, dir.clone()))
+fn part1(input: &str) -> usize {
+    initial_black(input).len()
 }
 
-fn part1(input: &str) -> usize {
-    let mut black: HashSet<Tile> = HashSet::new();
-    for line in input.lines() {
-        let dirs = parse_directions(line);
-        let pos = move_path((0, 0), dirs);
-        if black.contains(&pos) {
-            &black.remove(&pos);
-        } else {
-            &black.insert(pos);
+fn initial_black(input: &str) -> HashSet<Hex> {
+    let mut black = HashSet::new();
+    for pos in input.lines().map(parse_tile) {
+        if !black.remove(&pos) {
+            black.insert(pos);
         }
     }
-    black.len()
-}
-
-fn neighbors(pos: Tile) -> Vec<Tile> {
-    use Dir::*;
-    vec![East, West, SouthEast, SouthWest, NorthWest, NorthEast]
-        .iter()
-        .map(|dir| move_dir(pos, dir.clone()))
-        .collect::<Vec<Tile>>()
+    black
 }
 
 fn part2(input: &str) -> usize {
-    let mut black: HashSet<Tile> = HashSet::new();
-    for line in input.lines() {
-        let pos = move_path((0, 0), parse_directions(line));
-        if black.contains(&pos) {
-            &black.remove(&pos);
-        } else {
-            &black.insert(pos);
-        }
-    }
+    let mut black = initial_black(input);
 
     for _ in 0..100 {
         let flip_to_white = black
             .iter()
-            .cloned()
+            .copied()
             .filter(|tile| {
-                let black_neighbors = neighbors(tile.clone())
+                let black_neighbors = tile
+                    .neighbours()
                     .iter()
-                    .filter(|n| *&black.contains(n))
+                    .filter(|n| black.contains(n))
                     .count();
 
                 black_neighbors == 0 || black_neighbors > 2
             })
-            .collect::<Vec<Tile>>();
+            .collect::<Vec<Hex>>();
         let flip_to_black = black
             .iter()
-            .cloned()
-            .flat_map(|b| neighbors(b))
+            .flat_map(|b| b.neighbours())
             .filter(|p| !black.contains(p))
-            .filter(|p| {
-                neighbors(p.clone())
-                    .iter()
-                    .filter(|n| black.contains(n))
-                    .count()
-                    == 2
-            })
-            .collect::<Vec<Tile>>();
+            .filter(|p| p.neighbours().iter().filter(|n| black.contains(n)).count() == 2)
+            .collect::<Vec<Hex>>();
         for t in flip_to_white.iter() {
             black.remove(t);
         }
         for t in flip_to_black.iter() {
-            black.insert(t.clone());
+            black.insert(*t);
         }
     }
     black.len()
@@ -134,7 +59,7 @@ fn main() {
 
 #[test]
 fn test_move_path() {
-    assert_eq!(move_path((0, 0), parse_directions("nwwswee")), (0, 0));
+    assert_eq!(parse_tile("nwwswee"), Hex::ORIGIN);
 }
 
 #[test]
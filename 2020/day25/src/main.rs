@@ -1,39 +1,21 @@
-const DIVIDER: usize = 20201227;
-const SUBJECT_NUMBER: usize = 7;
+use numtheory::{discrete_log, modpow};
 
-fn transform(subject_number: usize, loop_size: usize) -> usize {
-    let mut value = 1;
-    for _ in 0..loop_size {
-        value = (value * subject_number) % DIVIDER;
-    }
-    value
+const DIVIDER: u64 = 20201227;
+const SUBJECT_NUMBER: u64 = 7;
+
+fn transform(subject_number: u64, loop_size: u64) -> u64 {
+    modpow(subject_number, loop_size, DIVIDER)
 }
 
-fn part1(card_pub: usize, door_pub: usize) -> usize {
-    let mut card_pk: Option<usize> = None;
-    let mut door_pk: Option<usize> = None;
-    let mut value = 1;
-    let mut loop_size = 1;
-    loop {
-        value = (value * SUBJECT_NUMBER) % DIVIDER;
-        if value == card_pub {
-            card_pk = Some(loop_size);
-            break;
-        }
-        if value == door_pub {
-            door_pk = Some(loop_size);
-            break;
-        }
-        loop_size += 1;
-    }
+/// The loop size is the discrete logarithm of the public key to the base of
+/// the subject number, which baby-step giant-step finds in about
+/// `sqrt(DIVIDER)` steps.
+fn loop_size(public_key: u64) -> u64 {
+    discrete_log(SUBJECT_NUMBER, public_key, DIVIDER).expect("not a public key")
+}
 
-    if let Some(door_pk) = door_pk {
-        transform(card_pub, door_pk)
-    } else if let Some(card_pk) = card_pk {
-        transform(door_pub, card_pk)
-    } else {
-        panic!();
-    }
+fn part1(card_pub: u64, door_pub: u64) -> u64 {
+    transform(door_pub, loop_size(card_pub))
 }
 
 fn main() {
@@ -42,5 +24,7 @@ fn main() {
 
 #[test]
 fn test_part1() {
+    assert_eq!(loop_size(5764801), 8);
+    assert_eq!(loop_size(17807724), 11);
     assert_eq!(part1(5764801, 17807724), 14897079)
 }
@@ -1,82 +1,92 @@
-use std::collections::{HashMap, HashSet};
+use bitset::{BitSet, Memo};
+use graph::{Graph, NodeId};
 
-fn parse_graph(input: &str) -> HashMap<String, Vec<String>> {
-    let graph = input
-        .lines()
-        .map(|line| {
-            let (left, right) = line.split_once('-').unwrap();
-            (left.to_string(), right.to_string())
-        })
-        .collect::<Vec<_>>();
-    let all_nodes = graph
-        .iter()
-        .flat_map(|(a, b)| vec![a.clone(), b.clone()])
-        .collect::<Vec<String>>();
-    all_nodes
-        .iter()
-        .map(|e| {
-            (
-                e.clone(),
-                graph
-                    .iter()
-                    .filter_map(|(start, end)| {
-                        if start == e {
-                            Some(end.clone())
-                        } else if end == e {
-                            Some(start.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<String>>(),
-            )
-        })
-        .collect()
+fn parse_graph(input: &str) -> Graph {
+    let mut graph = Graph::undirected();
+    for line in input.lines() {
+        let (left, right) = line.split_once('-').unwrap();
+        graph.add_edge(left, right);
+    }
+    assert!(graph.len() <= 64, "visited caves are kept in a u64 bitset");
+    graph
 }
 
-fn is_small_cave(s: &String) -> bool {
+fn is_small_cave(s: &str) -> bool {
     s.chars().next().unwrap().is_lowercase()
 }
 
-fn walk(
-    graph: &HashMap<String, Vec<String>>,
-    el: String,
-    cur_path: &HashSet<String>,
-    small_extra: bool,
-) -> usize {
-    let mut small_extra = small_extra;
-    if is_small_cave(&el) {
-        if cur_path.contains(&el) {
-            if small_extra && el != "end" && el != "start" {
+struct Caves {
+    graph: Graph,
+    small: Vec<bool>,
+    start: NodeId,
+    end: NodeId,
+}
+
+impl Caves {
+    fn new(input: &str) -> Self {
+        let graph = parse_graph(input);
+        let small = graph
+            .nodes()
+            .map(|n| is_small_cave(graph.name(n)))
+            .collect();
+        let start = graph.id("start").unwrap();
+        let end = graph.id("end").unwrap();
+        Caves {
+            graph,
+            small,
+            start,
+            end,
+        }
+    }
+
+    /// Number of paths to the end from `cave`, where `visited` holds the small
+    /// caves on the current path.
+    fn walk(
+        &self,
+        cave: NodeId,
+        visited: BitSet,
+        small_extra: bool,
+        memo: &mut Memo<usize>,
+    ) -> usize {
+        let mut small_extra = small_extra;
+        if visited.contains(cave) {
+            if small_extra && cave != self.start {
                 small_extra = false;
             } else {
                 return 0;
             }
         }
-    }
-    let mut cur_path = cur_path.clone();
-    cur_path.insert(el.clone());
-    let mut paths = 0;
-    if let Some(routes) = graph.get(&el) {
-        for route in routes {
-            if *route == "end".to_string() {
-                paths += 1;
-            } else {
-                paths += walk(graph, route.clone(), &cur_path.clone(), small_extra);
-            }
+        if let Some(paths) = memo.get(cave, visited, small_extra as u32) {
+            return paths;
         }
+        let next_visited = if self.small[cave] {
+            visited.with(cave)
+        } else {
+            visited
+        };
+        let paths = self
+            .graph
+            .neighbours(cave)
+            .map(|next| {
+                if next == self.end {
+                    1
+                } else {
+                    self.walk(next, next_visited, small_extra, memo)
+                }
+            })
+            .sum();
+        memo.insert(cave, visited, small_extra as u32, paths)
     }
-    paths
 }
 
 fn part1(input: &str) -> usize {
-    let graph = parse_graph(input);
-    walk(&graph, "start".to_string(), &HashSet::new(), false)
+    let caves = Caves::new(input);
+    caves.walk(caves.start, BitSet::EMPTY, false, &mut Memo::new())
 }
 
 fn part2(input: &str) -> usize {
-    let graph = parse_graph(input);
-    walk(&graph, "start".to_string(), &HashSet::new(), true)
+    let caves = Caves::new(input);
+    caves.walk(caves.start, BitSet::EMPTY, true, &mut Memo::new())
 }
 
 fn main() {
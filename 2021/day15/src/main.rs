@@ -1,23 +1,41 @@
-use pathfinding::prelude::dijkstra;
+use search::{astar, SearchProblem};
+
+struct Cave<'a> {
+    risk: &'a Vec<Vec<usize>>,
+}
+
+impl SearchProblem for Cave<'_> {
+    type State = (usize, usize);
+    type Cost = usize;
+
+    fn start(&self) -> Self::State {
+        (0, 0)
+    }
+
+    fn successors(&self, &(row, col): &Self::State) -> Vec<(Self::State, usize)> {
+        let dirs: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, 1), (0, -1)];
+        dirs.iter()
+            .map(|(dr, dc)| (row as isize + dr, col as isize + dc))
+            .filter(|&(r, c)| r >= 0 && c >= 0)
+            .map(|(r, c)| (r as usize, c as usize))
+            .filter(|&(r, c)| r < self.risk.len() && c < self.risk[0].len())
+            .map(|(r, c)| ((r, c), self.risk[r][c]))
+            .collect()
+    }
+
+    fn is_goal(&self, &(row, col): &Self::State) -> bool {
+        row == self.risk.len() - 1 && col == self.risk[0].len() - 1
+    }
+
+    /// Every step costs at least 1, so the manhattan distance to the goal
+    /// never overestimates.
+    fn heuristic(&self, &(row, col): &Self::State) -> usize {
+        (self.risk.len() - 1 - row) + (self.risk[0].len() - 1 - col)
+    }
+}
 
 fn smallest_risk(grid: &Vec<Vec<usize>>) -> usize {
-    let row_count = grid.len();
-    let col_count = grid[0].len();
-    let dirs: Vec<(isize, isize)> = vec![(-1, 0), (1, 0), (0, 1), (0, -1)];
-    let result = dijkstra(
-        &(0, 0),
-        |&(x, y)| {
-            dirs.iter()
-                .map(move |(dx, dy)| (x as isize + dx, y as isize + dy))
-                .filter(|&(x, y)| x >= 0 && y >= 0)
-                .map(|(x, y)| (x as usize, y as usize))
-                .filter(|&(x, y)| x < row_count && y < col_count)
-                .map(|(x, y)| ((x, y), grid[x][y]))
-        },
-        |&(row, col)| row == row_count - 1 && col == col_count - 1,
-    );
-    let (_, result) = result.unwrap();
-    result
+    astar(&Cave { risk: grid }).cost().unwrap()
 }
 
 fn part1(input: &str) -> usize {
@@ -1,66 +1,234 @@
-#[derive(Debug)]
-pub struct Bits {
-    idx: usize,
-    data: Vec<bool>,
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitsError {
+    InvalidHex(char),
+    /// A read ran past the end of the data or of the enclosing packet,
+    /// at this bit offset.
+    UnexpectedEnd(usize),
+    /// Sub-packets that overrun the length their operator gives them.
+    LengthMismatch(usize),
+    /// Bits that are not zero after the outermost packet.
+    TrailingData(usize),
+    #[allow(dead_code)]
+    VersionTooLarge(u8),
+    /// More sub-packets or bits than the length field can count.
+    #[allow(dead_code)]
+    TooLong(usize),
+    /// A literal, starting at this bit offset, with more than 64 bits.
+    LiteralTooLarge(usize),
+    /// An operator with a number of sub-packets it is not defined for.
+    Arity(&'static str, usize),
 }
 
-impl Bits {
-    pub fn new(data: Vec<bool>) -> Self {
-        Bits { idx: 0, data }
-    }
-    pub fn from_hex_str(hex_str: &str) -> Self {
-        Self::new(
-            hex_str
-                .chars()
-                .map(|c| c.to_digit(16).unwrap())
-                .map(|c| format!("{:04b}", c))
-                .collect::<String>()
-                .chars()
-                .map(|c| match c.to_digit(2).unwrap() {
-                    0 => false,
-                    1 => true,
-                    _ => unreachable!(),
-                })
-                .collect(),
-        )
-    }
-    pub fn take(&mut self, n: usize) -> &[bool] {
-        if n > self.len() {
-            panic!(
-                "take({}) out of bounds with len={} remaining",
-                n,
-                self.len()
-            );
+impl fmt::Display for BitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitsError::InvalidHex(c) => write!(f, "invalid hex digit {:?}", c),
+            BitsError::UnexpectedEnd(at) => write!(f, "unexpected end at bit {}", at),
+            BitsError::LengthMismatch(at) => {
+                write!(f, "sub-packets overrun their length at bit {}", at)
+            }
+            BitsError::TrailingData(at) => write!(f, "trailing data at bit {}", at),
+            BitsError::VersionTooLarge(v) => write!(f, "version {} does not fit 3 bits", v),
+            BitsError::TooLong(n) => write!(f, "{} does not fit the length field", n),
+            BitsError::LiteralTooLarge(at) => {
+                write!(f, "literal at bit {} does not fit 64 bits", at)
+            }
+            BitsError::Arity(op, n) => write!(f, "{} cannot take {} sub-packets", op, n),
         }
-        let start = self.idx;
-        self.idx += n;
-        &self.data[start..(start + n)]
     }
-    pub fn len(&self) -> usize {
-        self.data.len() - self.idx
+}
+
+impl std::error::Error for BitsError {}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, BitsError> {
+    let digits = hex
+        .trim()
+        .chars()
+        .map(|c| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or(BitsError::InvalidHex(c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // an odd digit out fills the high half of the last byte
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect())
+}
+
+#[allow(dead_code)]
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Reads bits most significant first out of bytes, up to an end that can
+/// be narrowed for the contents of a packet.
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader {
+            bytes,
+            pos: 0,
+            end: bytes.len() * 8,
+        }
     }
-    pub fn empty(&self) -> bool {
-        let len = self.len();
-        len <= 8 && (0..len).all(|i| self.data[self.idx + i] == false)
+
+    /// The offset of the next bit from the start of the data.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, BitsError> {
+        if self.pos >= self.end {
+            return Err(BitsError::UnexpectedEnd(self.pos));
+        }
+        let bit = self.bytes[self.pos / 8] >> (7 - self.pos % 8) & 1;
+        self.pos += 1;
+        Ok(bit == 1)
+    }
+
+    /// The next `n` bits as a number, `n` at most 64.
+    pub fn read(&mut self, n: usize) -> Result<u64, BitsError> {
+        assert!(n <= 64, "cannot read {} bits into a u64", n);
+        if n > self.remaining() {
+            return Err(BitsError::UnexpectedEnd(self.end));
+        }
+        let mut value = 0;
+        for _ in 0..n {
+            value = value << 1 | self.read_bit()? as u64;
+        }
+        Ok(value)
+    }
+
+    /// A reader of the next `n` bits, which this one skips.
+    pub fn take(&mut self, n: usize) -> Result<BitReader<'a>, BitsError> {
+        if n > self.remaining() {
+            return Err(BitsError::UnexpectedEnd(self.end));
+        }
+        let sub = BitReader {
+            bytes: self.bytes,
+            pos: self.pos,
+            end: self.pos + n,
+        };
+        self.pos += n;
+        Ok(sub)
     }
-}
 
-#[test]
-fn test_bits_empty() {
-    assert_eq!(Bits::new(vec![]).empty(), true);
-    assert_eq!(Bits::new(vec![false, false, false]).empty(), true);
-    assert_eq!(
-        Bits::new(vec![false, false, false, false, false, false, false]).empty(),
+    /// Whether all that is left is zeros, as padding is.
+    pub fn only_zeros(&self) -> bool {
+        let mut rest = self.clone();
+        while let Ok(bit) = rest.read_bit() {
+            if bit {
+                return false;
+            }
+        }
         true
-    );
-    let mut bits = Bits::from_hex_str("C0FF88");
-    bits.take(21);
-    assert_eq!(bits.empty(), true);
+    }
 }
 
-#[test]
-fn test_bits_take() {
-    let mut bits = Bits::new(vec![true, true, true, false, false, false]);
-    assert_eq!(bits.take(3), vec![true, true, true]);
-    assert_eq!(bits.take(3), vec![false, false, false]);
+/// Writes bits most significant first, the counterpart of [`BitReader`].
+/// The solution only decodes, so the encoder is used by the tests alone.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+#[allow(dead_code)]
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of bits written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            self.bytes[self.len / 8] |= 1 << (7 - self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// The low `n` bits of `value`.
+    pub fn write(&mut self, value: u64, n: usize) {
+        for i in (0..n).rev() {
+            self.write_bit(value >> i & 1 == 1);
+        }
+    }
+
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut reader = BitReader {
+            bytes: &other.bytes,
+            pos: 0,
+            end: other.len,
+        };
+        while let Ok(bit) = reader.read_bit() {
+            self.write_bit(bit);
+        }
+    }
+
+    /// The bits written, padded with zeros to whole bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let bytes = from_hex("D2FE28").unwrap();
+        let mut bits = BitReader::new(&bytes);
+        assert_eq!(bits.read(3), Ok(6));
+        assert_eq!(bits.read(3), Ok(4));
+        assert_eq!(bits.read_bit(), Ok(true));
+        let mut group = bits.take(4).unwrap();
+        assert_eq!(group.read(4), Ok(0b0111));
+        assert!(group.is_empty());
+        assert_eq!(group.read_bit(), Err(BitsError::UnexpectedEnd(11)));
+        assert_eq!(bits.position(), 11);
+        assert_eq!(bits.read(10), Ok(0b1111000101));
+        assert!(bits.only_zeros());
+        assert_eq!(bits.read(4), Err(BitsError::UnexpectedEnd(24)));
+        assert_eq!(from_hex("0G"), Err(BitsError::InvalidHex('G')));
+    }
+
+    #[test]
+    fn test_write() {
+        let mut bits = BitWriter::new();
+        bits.write(6, 3);
+        bits.write(4, 3);
+        let mut group = BitWriter::new();
+        group.write(0b10111, 5);
+        bits.append(&group);
+        assert_eq!(bits.len(), 11);
+        bits.write(0b1111000101, 10);
+        assert_eq!(to_hex(&bits.into_bytes()), "D2FE28");
+    }
 }
@@ -0,0 +1,423 @@
+use std::fmt;
+
+use crate::bits::{from_hex, to_hex, BitReader, BitWriter, BitsError};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OperatorType {
+    Sum,
+    Product,
+    Min,
+    Max,
+    Gt,
+    Lt,
+    Eq,
+}
+
+const LITERAL: u64 = 4;
+
+impl OperatorType {
+    fn from_id(type_id: u64) -> Self {
+        use OperatorType::*;
+        match type_id {
+            0 => Sum,
+            1 => Product,
+            2 => Min,
+            3 => Max,
+            5 => Gt,
+            6 => Lt,
+            7 => Eq,
+            _ => unreachable!("type id {} is not an operator", type_id),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn id(self) -> u64 {
+        use OperatorType::*;
+        match self {
+            Sum => 0,
+            Product => 1,
+            Min => 2,
+            Max => 3,
+            Gt => 5,
+            Lt => 6,
+            Eq => 7,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        use OperatorType::*;
+        match self {
+            Sum => "sum",
+            Product => "prod",
+            Min => "min",
+            Max => "max",
+            Gt => "gt",
+            Lt => "lt",
+            Eq => "eq",
+        }
+    }
+
+    /// Comparisons take exactly two sub-packets, everything else at least
+    /// one.
+    fn check_arity(self, count: usize) -> Result<(), BitsError> {
+        use OperatorType::*;
+        let valid = match self {
+            Sum | Product | Min | Max => count >= 1,
+            Gt | Lt | Eq => count == 2,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(BitsError::Arity(self.name(), count))
+        }
+    }
+}
+
+/// How an operator packet gives the extent of its sub-packets.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LengthType {
+    /// Length type 0: the total number of bits, in 15 bits.
+    Bits,
+    /// Length type 1: the number of sub-packets, in 11 bits.
+    Count,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Packet {
+    Operator {
+        version: u8,
+        op_type: OperatorType,
+        length_type: LengthType,
+        sub_packets: Vec<Packet>,
+    },
+    LiteralValue {
+        version: u8,
+        value: u64,
+    },
+}
+
+impl Packet {
+    /// Decodes the hex transmission of one packet, which may only be
+    /// followed by zeros.
+    pub fn from_hex(hex: &str) -> Result<Self, BitsError> {
+        let bytes = from_hex(hex)?;
+        let mut bits = BitReader::new(&bytes);
+        let packet = Self::read(&mut bits)?;
+        if !bits.only_zeros() {
+            return Err(BitsError::TrailingData(bits.position()));
+        }
+        Ok(packet)
+    }
+
+    pub fn read(bits: &mut BitReader) -> Result<Self, BitsError> {
+        let version = bits.read(3)? as u8;
+        let type_id = bits.read(3)?;
+        if type_id == LITERAL {
+            return Ok(Packet::LiteralValue {
+                version,
+                value: Self::read_literal_value(bits)?,
+            });
+        }
+        let mut sub_packets = vec![];
+        let length_type = if bits.read_bit()? {
+            for _ in 0..bits.read(11)? {
+                sub_packets.push(Self::read(bits)?);
+            }
+            LengthType::Count
+        } else {
+            let length = bits.read(15)? as usize;
+            let mut content = bits.take(length)?;
+            while !content.is_empty() {
+                let packet = Self::read(&mut content).map_err(|err| match err {
+                    BitsError::UnexpectedEnd(at) => BitsError::LengthMismatch(at),
+                    err => err,
+                })?;
+                sub_packets.push(packet);
+            }
+            LengthType::Bits
+        };
+        let op_type = OperatorType::from_id(type_id);
+        op_type.check_arity(sub_packets.len())?;
+        Ok(Packet::Operator {
+            version,
+            op_type,
+            length_type,
+            sub_packets,
+        })
+    }
+
+    fn read_literal_value(bits: &mut BitReader) -> Result<u64, BitsError> {
+        let start = bits.position();
+        let mut value: u64 = 0;
+        loop {
+            let more = bits.read_bit()?;
+            if value >> 60 != 0 {
+                return Err(BitsError::LiteralTooLarge(start));
+            }
+            value = value << 4 | bits.read(4)?;
+            if !more {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Encodes the packet in as few 4-bit groups as its value takes, padded
+    /// to whole bytes.
+    #[allow(dead_code)]
+    pub fn to_hex(&self) -> Result<String, BitsError> {
+        let mut bits = BitWriter::new();
+        self.write(&mut bits)?;
+        Ok(to_hex(&bits.into_bytes()))
+    }
+
+    #[allow(dead_code)]
+    pub fn write(&self, bits: &mut BitWriter) -> Result<(), BitsError> {
+        let version = match *self {
+            Packet::Operator { version, .. } | Packet::LiteralValue { version, .. } => version,
+        };
+        if version > 7 {
+            return Err(BitsError::VersionTooLarge(version));
+        }
+        bits.write(version as u64, 3);
+        match self {
+            &Packet::LiteralValue { value, .. } => {
+                bits.write(LITERAL, 3);
+                let groups = (64 - value.leading_zeros() as usize).div_ceil(4).max(1);
+                for i in (0..groups).rev() {
+                    bits.write_bit(i > 0);
+                    bits.write(value >> (4 * i), 4);
+                }
+            }
+            Packet::Operator {
+                op_type,
+                length_type,
+                sub_packets,
+                ..
+            } => {
+                op_type.check_arity(sub_packets.len())?;
+                bits.write(op_type.id(), 3);
+                let mut content = BitWriter::new();
+                for packet in sub_packets {
+                    packet.write(&mut content)?;
+                }
+                let (length, width) = match length_type {
+                    LengthType::Bits => (content.len(), 15),
+                    LengthType::Count => (sub_packets.len(), 11),
+                };
+                if length >= 1 << width {
+                    return Err(BitsError::TooLong(length));
+                }
+                bits.write_bit(*length_type == LengthType::Count);
+                bits.write(length as u64, width);
+                bits.append(&content);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn version_sum(&self) -> u64 {
+        match self {
+            &Packet::LiteralValue { version, .. } => version as u64,
+            Packet::Operator {
+                version,
+                sub_packets,
+                ..
+            } => *version as u64 + sub_packets.iter().map(Packet::version_sum).sum::<u64>(),
+        }
+    }
+
+    /// Panics on an operator with the wrong number of sub-packets, which
+    /// decoding rejects.
+    pub fn value(&self) -> u64 {
+        use OperatorType::*;
+        let (op_type, sub_packets) = match self {
+            &Packet::LiteralValue { value, .. } => return value,
+            Packet::Operator {
+                op_type,
+                sub_packets,
+                ..
+            } => (op_type, sub_packets),
+        };
+        let mut values = sub_packets.iter().map(Packet::value);
+        match op_type {
+            Sum => values.sum(),
+            Product => values.product(),
+            Min => values.min().unwrap(),
+            Max => values.max().unwrap(),
+            Gt | Lt | Eq => {
+                let (first, second) = (values.next().unwrap(), values.next().unwrap());
+                let holds = match op_type {
+                    Gt => first > second,
+                    Lt => first < second,
+                    _ => first == second,
+                };
+                holds as u64
+            }
+        }
+    }
+}
+
+/// The packet as the expression it stands for, like `sum(prod(3, 4), 5)`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::LiteralValue { value, .. } => write!(f, "{}", value),
+            Packet::Operator {
+                op_type,
+                sub_packets,
+                ..
+            } => {
+                write!(f, "{}(", op_type.name())?;
+                for (i, packet) in sub_packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", packet)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_read_packet() {
+        assert_eq!(
+            Packet::from_hex("D2FE28"),
+            Ok(Packet::LiteralValue {
+                version: 6,
+                value: 2021
+            })
+        );
+        assert_eq!(
+            Packet::from_hex("38006F45291200"),
+            Ok(Packet::Operator {
+                version: 1,
+                op_type: OperatorType::Lt,
+                length_type: LengthType::Bits,
+                sub_packets: vec![
+                    Packet::LiteralValue {
+                        version: 6,
+                        value: 10,
+                    },
+                    Packet::LiteralValue {
+                        version: 2,
+                        value: 20,
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Packet::from_hex("D2FE"), Err(BitsError::UnexpectedEnd(16)));
+        assert_eq!(Packet::from_hex("D2FE29"), Err(BitsError::TrailingData(21)));
+        // the second literal of 38006F45291200 with its length cut by one
+        assert_eq!(
+            Packet::from_hex("38006B45291200"),
+            Err(BitsError::LengthMismatch(48))
+        );
+        let packet = Packet::LiteralValue {
+            version: 8,
+            value: 0,
+        };
+        assert_eq!(packet.to_hex(), Err(BitsError::VersionTooLarge(8)));
+        // a literal of seventeen groups, the first of them 1
+        let mut bits = BitWriter::new();
+        bits.write(LITERAL, 6);
+        for i in 0..17 {
+            bits.write_bit(i < 16);
+            bits.write((i == 0) as u64, 4);
+        }
+        assert_eq!(
+            Packet::from_hex(&to_hex(&bits.into_bytes())),
+            Err(BitsError::LiteralTooLarge(6))
+        );
+        // eq(1) and a sum of nothing
+        assert_eq!(Packet::from_hex("3E004408"), Err(BitsError::Arity("eq", 1)));
+        assert_eq!(Packet::from_hex("020000"), Err(BitsError::Arity("sum", 0)));
+        let packet = Packet::Operator {
+            version: 0,
+            op_type: OperatorType::Lt,
+            length_type: LengthType::Count,
+            sub_packets: vec![],
+        };
+        assert_eq!(packet.to_hex(), Err(BitsError::Arity("lt", 0)));
+    }
+
+    #[test]
+    fn test_display() {
+        let packet = Packet::from_hex("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(packet.to_string(), "eq(sum(1, 3), prod(2, 2))");
+    }
+
+    #[test]
+    fn test_examples_round_trip() {
+        for hex in [
+            "8A004A801A8002F478",
+            "620080001611562C8802118E34",
+            "C0015000016115A2E0802F182340",
+            "A0016C880162017C3686B18A3D4780",
+            "C200B40A82",
+            "04005AC33890",
+            "880086C3E88112",
+            "CE00C43D881120",
+            "D8005AC2A8F0",
+            "F600BC2D8F",
+            "9C005AC2F8F0",
+            "9C0141080250320F1802104A08",
+            include_str!("in.txt"),
+        ] {
+            assert_eq!(Packet::from_hex(hex).unwrap().to_hex().unwrap(), hex.trim());
+        }
+    }
+
+    fn packet() -> impl Strategy<Value = Packet> {
+        let literal = (0..8u8, any::<u64>())
+            .prop_map(|(version, value)| Packet::LiteralValue { version, value });
+        literal.prop_recursive(4, 32, 5, |inner| {
+            let reduction = (
+                prop_oneof![
+                    Just(OperatorType::Sum),
+                    Just(OperatorType::Product),
+                    Just(OperatorType::Min),
+                    Just(OperatorType::Max),
+                ],
+                prop::collection::vec(inner.clone(), 1..5),
+            );
+            let comparison = (
+                prop_oneof![
+                    Just(OperatorType::Gt),
+                    Just(OperatorType::Lt),
+                    Just(OperatorType::Eq),
+                ],
+                prop::collection::vec(inner, 2),
+            );
+            let length_type = prop_oneof![Just(LengthType::Bits), Just(LengthType::Count)];
+            (0..8u8, prop_oneof![reduction, comparison], length_type).prop_map(
+                |(version, (op_type, sub_packets), length_type)| Packet::Operator {
+                    version,
+                    op_type,
+                    length_type,
+                    sub_packets,
+                },
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn encoding_round_trips(packet in packet()) {
+            let hex = packet.to_hex().unwrap();
+            let decoded = Packet::from_hex(&hex).unwrap();
+            prop_assert_eq!(&decoded, &packet);
+            prop_assert_eq!(decoded.to_hex().unwrap(), hex);
+        }
+    }
+}
@@ -1,93 +1,56 @@
-use itertools::Itertools;
-use std::collections::HashSet;
+use grid::SparseGrid;
+use vis::{Cell, Color, Frame, Player, Visualise};
 
-struct Image {
-    pub points: HashSet<(i64, i64)>,
-    pub inverted: bool,
-}
-impl Image {
-    fn new(inverted: bool) -> Self {
-        Image {
-            points: HashSet::new(),
-            inverted,
-        }
-    }
-    fn is_lit(&self, pos: &(i64, i64)) -> bool {
-        self.points.contains(pos) != self.inverted
-    }
-    fn set_lit(&mut self, pos: (i64, i64), lit: bool) {
-        if lit != self.inverted {
-            self.points.insert(pos);
-        }
-    }
-}
-
-fn full_range(image: &Image) -> (i64, i64, i64, i64) {
-    let (min_x, max_x) = image
-        .points
-        .iter()
-        .map(|(x, _)| *x)
-        .minmax()
-        .into_option()
-        .unwrap();
-    let (min_y, max_y) = image
-        .points
-        .iter()
-        .map(|(_, y)| *y)
-        .minmax()
-        .into_option()
-        .unwrap();
-    (min_x, max_x, min_y, max_y)
-}
+/// Lit pixels of an infinite image. Pixels outside of the bounds all share
+/// the default value, which can flip between lit and dark on each step.
+type Image = SparseGrid<(i64, i64), bool>;
 
-fn is_next_pixel_lit(x: i64, y: i64, image: &Image, algo: &[bool]) -> bool {
-    let s = ((-1)..=1)
-        .map(|dx| {
-            ((-1)..=1)
-                .map(|dy| match image.is_lit(&(x + dx, y + dy)) {
-                    true => '1',
-                    false => '0',
-                })
-                .collect::<String>()
-        })
-        .collect::<String>();
-    let idx = u32::from_str_radix(&s, 2).unwrap() as usize;
+fn is_next_pixel_lit((x, y): (i64, i64), image: &Image, algo: &[bool]) -> bool {
+    let idx = ((-1)..=1)
+        .flat_map(|dx| ((-1)..=1).map(move |dy| (x + dx, y + dy)))
+        .fold(0, |idx, p| idx << 1 | *image.get(p) as usize);
     algo[idx]
 }
 
-fn enhance(image: &Image, algo: &[bool], invert: bool) -> Image {
-    let mut result = Image::new(invert);
-    let (min_x, max_x, min_y, max_y) = full_range(image);
-    for x in (min_x - 1)..=(max_x + 1) {
-        for y in (min_y - 1)..=(max_y + 1) {
-            result.set_lit((x, y), is_next_pixel_lit(x, y, image, algo));
-        }
+fn enhance(image: &Image, algo: &[bool]) -> Image {
+    let background = algo[if *image.default_value() { 511 } else { 0 }];
+    let mut result = Image::new(background);
+    for p in image.points_in_bounds(1) {
+        result.insert(p, is_next_pixel_lit(p, image, algo));
     }
     result
 }
 
-fn rasterize(image: &Image, padding: i64) -> String {
-    let (min_x, max_x, min_y, max_y) = full_range(image);
-    (min_x - padding..=max_x + padding)
-        .map(|x| {
-            format!(
-                "{}\n",
-                (min_y - padding..=max_y + padding)
-                    .map(|y| match image.is_lit(&(x, y)) {
-                        true => '#',
-                        false => '.',
-                    })
-                    .collect::<String>()
-            )
-        })
-        .collect::<String>()
+fn lit_count(image: &Image) -> usize {
+    assert!(!image.default_value(), "infinitely many pixels are lit");
+    image.iter().filter(|(_, lit)| **lit).count()
 }
 
-const PRINT_ENABLED: bool = false;
+/// The image being enhanced step by step.
+struct Enhancement {
+    image: Image,
+    algo: Vec<bool>,
+    steps: usize,
+}
+
+impl Enhancement {
+    fn step(&mut self) {
+        self.image = enhance(&self.image, &self.algo);
+        self.steps += 1;
+    }
+}
 
-fn print(image: &Image) {
-    if PRINT_ENABLED {
-        println!("{}", rasterize(image, 5));
+impl Visualise for Enhancement {
+    fn frame(&self) -> Frame {
+        // points are (row, column), the frame wants (x, y)
+        let pixels = self.image.points_in_bounds(0).map(|(row, col)| {
+            let cell = match self.image.get((row, col)) {
+                true => Cell::colored('#', Color::White),
+                false => Cell::plain('.'),
+            };
+            ((col, row), cell)
+        });
+        Frame::from_points(pixels).with_caption(format!("step {}", self.steps))
     }
 }
 
@@ -103,28 +66,30 @@ fn solve(input: &str) -> (usize, usize) {
         })
         .collect();
     assert_eq!(algo.len(), 512);
-    let grid = grid
-        .split('\n')
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
     let mut image = Image::new(false);
-    for x in 0..grid.len() {
-        for y in 0..grid[0].len() {
-            image.set_lit((x as i64, y as i64), grid[x][y] == '#');
+    for (x, line) in grid.split('\n').enumerate() {
+        for (y, ch) in line.chars().enumerate() {
+            image.insert((x as i64, y as i64), ch == '#');
         }
     }
-    let should_invert = algo[0];
+    let mut enhancement = Enhancement {
+        image,
+        algo,
+        steps: 0,
+    };
     let mut p1 = 0;
-    for step in 0..50 {
-        let invert = should_invert && step % 2 == 0;
-        print(&image);
-        image = enhance(&image, &algo, invert);
-        if step == 1 {
-            p1 = image.points.len();
+    let mut step = |enhancement: &mut Enhancement| {
+        enhancement.step();
+        if enhancement.steps == 2 {
+            p1 = lit_count(&enhancement.image);
         }
+        enhancement.steps < 50
+    };
+    match Player::from_env() {
+        Some(mut player) => player.play(&mut enhancement, step).unwrap(),
+        None => while step(&mut enhancement) {},
     }
-    print(&image);
-    (p1, image.points.len())
+    (p1, lit_count(&enhancement.image))
 }
 
 fn main() {
@@ -1,4 +1,4 @@
-use itertools::Itertools;
+use interval::Cuboid;
 
 #[derive(Debug, PartialEq, Clone, Copy, Eq)]
 enum State {
@@ -7,133 +7,53 @@ enum State {
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
-struct Box {
-    x: (i32, i32),
-    y: (i32, i32),
-    z: (i32, i32),
+struct Step {
+    cuboid: Cuboid<i32, 3>,
     state: State,
 }
-impl Box {
+impl Step {
     fn from_str(input: &str) -> Self {
-        let (state, x1, x2, y1, y2, z1, z2): (&str, i32, i32, i32, i32, i32, i32) =
-            serde_scan::scan!("{} x={}..{},y={}..{},z={}..{}" <- input).unwrap();
-        Self::new(
-            (x1, x2 + 1),
-            (y1, y2 + 1),
-            (z1, z2 + 1),
-            match state {
+        let (state, x1, x2, y1, y2, z1, z2) =
+            sscanf::sscanf!(input, "{str} x={i32}..{i32},y={i32}..{i32},z={i32}..{i32}").unwrap();
+        Self {
+            cuboid: Cuboid::inclusive([x1, y1, z1], [x2, y2, z2]),
+            state: match state {
                 "on" => State::On,
                 "off" => State::Off,
                 _ => panic!(),
             },
-        )
-    }
-    fn new(x: (i32, i32), y: (i32, i32), z: (i32, i32), state: State) -> Self {
-        assert!(x.0 <= x.1 && y.0 <= y.1 && z.0 <= z.1);
-        Self { x, y, z, state }
-    }
-    fn contains(&self, other: &Box) -> bool {
-        self.x.0 <= other.x.0
-            && self.x.1 >= other.x.1
-            && self.y.0 <= other.y.0
-            && self.y.1 >= other.y.1
-            && self.z.0 <= other.z.0
-            && self.z.1 >= other.z.1
-    }
-    fn intersects(&self, other: &Box) -> bool {
-        self.x.1 > other.x.0
-            && self.x.0 < other.x.1
-            && self.y.1 > other.y.0
-            && self.y.0 < other.y.1
-            && self.z.1 > other.z.0
-            && self.z.0 < other.z.1
-    }
-    fn size(&self) -> usize {
-        (self.x.1 - self.x.0) as usize
-            * (self.y.1 - self.y.0) as usize
-            * (self.z.1 - self.z.0) as usize
-    }
-}
-
-fn cap_at(b: &Box, cap: &Box) -> Option<Box> {
-    if b.intersects(cap) {
-        Some(Box::new(
-            (i32::max(cap.x.0, b.x.0), i32::min(cap.x.1, b.x.1)),
-            (i32::max(cap.y.0, b.y.0), i32::min(cap.y.1, b.y.1)),
-            (i32::max(cap.z.0, b.z.0), i32::min(cap.z.1, b.z.1)),
-            b.state,
-        ))
-    } else {
-        None
+        }
     }
 }
 
-fn split_existing(existing: &Box, new_box: &Box) -> Vec<Box> {
-    if !new_box.intersects(existing) {
-        return vec![existing.clone()];
-    }
-    if new_box.contains(existing) {
-        return vec![];
-    }
-    fn axis_split_points((p1, p2): (i32, i32), (a, b): (i32, i32)) -> Vec<(i32, i32)> {
-        let mut result = vec![p1];
-        if a > p1 && a < p2 {
-            result.push(a);
-        }
-        if b > p1 && b < p2 {
-            result.push(b);
-        }
-        result.push(p2);
-        result
+/// Number of cubes that are on after the steps, as disjoint cuboids that the
+/// next step is cut out of.
+fn reboot(steps: impl Iterator<Item = Step>) -> u64 {
+    let mut cuboids: Vec<Cuboid<i32, 3>> = vec![];
+    for step in steps {
+        cuboids = cuboids
             .iter()
-            .tuple_windows()
-            .map(|(&a, &b)| (a, b))
-            .collect()
-    }
-    let mut result = vec![];
-    for (x1, x2) in axis_split_points(existing.x, new_box.x) {
-        for (y1, y2) in axis_split_points(existing.y, new_box.y) {
-            for (z1, z2) in axis_split_points(existing.z, new_box.z) {
-                let piece = Box::new((x1, x2), (y1, y2), (z1, z2), State::On);
-                if !new_box.contains(&piece) {
-                    result.push(piece);
-                }
-            }
+            .flat_map(|existing| existing.subtract(&step.cuboid))
+            .collect();
+        if step.state == State::On {
+            cuboids.push(step.cuboid);
         }
     }
-    result
+    cuboids.iter().map(Cuboid::volume).sum()
 }
 
-fn part1(input: &str) -> usize {
-    let init = Box::new((-50, 51), (-50, 51), (-50, 51), State::On);
-    let mut boxes: Vec<Box> = vec![];
-    for line in input.lines() {
-        if let Some(new_box) = cap_at(&Box::from_str(line), &init) {
-            boxes = boxes
-                .iter()
-                .flat_map(|existing| split_existing(existing, &new_box))
-                .collect();
-            if new_box.state == State::On {
-                boxes.push(new_box);
-            }
-        }
-    }
-    boxes.iter().map(|b| b.size()).sum()
+fn part1(input: &str) -> u64 {
+    let init = Cuboid::inclusive([-50; 3], [50; 3]);
+    reboot(input.lines().map(Step::from_str).filter_map(|step| {
+        Some(Step {
+            cuboid: step.cuboid.intersection(&init)?,
+            ..step
+        })
+    }))
 }
 
-fn part2(input: &str) -> usize {
-    let mut boxes: Vec<Box> = vec![];
-    for line in input.lines() {
-        let new_box = Box::from_str(line);
-        boxes = boxes
-            .iter()
-            .flat_map(|existing| split_existing(existing, &new_box))
-            .collect();
-        if new_box.state == State::On {
-            boxes.push(new_box);
-        }
-    }
-    boxes.iter().map(|b| b.size()).sum()
+fn part2(input: &str) -> u64 {
+    reboot(input.lines().map(Step::from_str))
 }
 
 fn main() {
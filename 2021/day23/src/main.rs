@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use search::{dijkstra, SearchProblem};
+use vis::{Cell, Color, Frame, Player, Visualise};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Amphipod {
@@ -39,13 +40,14 @@ fn parse_amphipod(c: char) -> Amphipod {
 }
 
 fn parse_side_rooms(input: &str) -> SideRooms {
-    let (a1, b1, c1, d1, a2, b2, c2, d2): (char, char, char, char, char, char, char, char) =
-        serde_scan::scan!(r"#############
-#...........#
-###{}#{}#{}#{}###
-  #{}#{}#{}#{}#
-  #########" <- input)
-        .unwrap();
+    let letters: Vec<char> = input
+        .lines()
+        .skip(2)
+        .flat_map(|line| line.chars().filter(|c| c.is_ascii_uppercase()))
+        .collect();
+    let [a1, b1, c1, d1, a2, b2, c2, d2] = letters[..] else {
+        panic!("expected 8 amphipods, found {:?}", letters);
+    };
 
     [
         [
@@ -75,66 +77,76 @@ fn parse_side_rooms(input: &str) -> SideRooms {
     ]
 }
 
-#[allow(unused)]
-fn dbg_amphipod(a: Amphipod) -> char {
-    match a {
-        Amber => 'A',
-        Bronze => 'B',
-        Copper => 'C',
-        Desert => 'D',
+impl Amphipod {
+    fn letter(self) -> char {
+        match self {
+            Amber => 'A',
+            Bronze => 'B',
+            Copper => 'C',
+            Desert => 'D',
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Amber => Color::Yellow,
+            Bronze => Color::DarkYellow,
+            Copper => Color::Red,
+            Desert => Color::Green,
+        }
     }
 }
 
-#[allow(unused)]
-fn dbg_opt_amphipod(a: Option<Amphipod>) -> char {
-    match a {
-        None => '.',
-        Some(a) => dbg_amphipod(a),
+/// The burrow as the puzzle draws it, with every side room four deep.
+const BURROW: [&str; 7] = [
+    "#############",
+    "#...........#",
+    "###.#.#.#.###",
+    "  #.#.#.#.#  ",
+    "  #.#.#.#.#  ",
+    "  #.#.#.#.#  ",
+    "  #########  ",
+];
+
+impl Visualise for State {
+    fn frame(&self) -> Frame {
+        let mut frame = Frame::new(BURROW[0].len(), BURROW.len());
+        for (y, row) in BURROW.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                frame.set(x, y, ch);
+            }
+        }
+        let mut put = |x: usize, y: usize, a: &Option<Amphipod>| {
+            if let Some(a) = a {
+                frame.set(x, y, Cell::colored(a.letter(), a.color()));
+            }
+        };
+        for (i, a) in self.hallway.iter().enumerate() {
+            put(i + 1, 1, a);
+        }
+        for (room, side_room) in self.side_rooms.iter().enumerate() {
+            for (slot, a) in side_room.iter().enumerate() {
+                put(3 + 2 * room, 2 + slot, a);
+            }
+        }
+        frame
     }
 }
 
-#[allow(unused)]
-fn dbg_state(hallway: &Hallway, side_rooms: &SideRooms) {
-    let hallway = hallway
-        .iter()
-        .map(|p| match p {
-            None => '.',
-            Some(a) => dbg_amphipod(*a),
-        })
-        .collect::<String>();
-    let (sr1, sr2, sr3, sr4) = (
-        &side_rooms[0],
-        &side_rooms[1],
-        &side_rooms[2],
-        &side_rooms[3],
-    );
-    println!(
-        r"#############
-#{}#
-###{}#{}#{}#{}###
-  #{}#{}#{}#{}#
-  #{}#{}#{}#{}#
-  #{}#{}#{}#{}#
-  #########",
-        hallway,
-        dbg_opt_amphipod(sr1[0]),
-        dbg_opt_amphipod(sr2[0]),
-        dbg_opt_amphipod(sr3[0]),
-        dbg_opt_amphipod(sr4[0]),
-        dbg_opt_amphipod(sr1[1]),
-        dbg_opt_amphipod(sr2[1]),
-        dbg_opt_amphipod(sr3[1]),
-        dbg_opt_amphipod(sr4[1]),
-        dbg_opt_amphipod(sr1[2]),
-        dbg_opt_amphipod(sr2[2]),
-        dbg_opt_amphipod(sr3[2]),
-        dbg_opt_amphipod(sr4[2]),
-        dbg_opt_amphipod(sr1[3]),
-        dbg_opt_amphipod(sr2[3]),
-        dbg_opt_amphipod(sr3[3]),
-        dbg_opt_amphipod(sr4[3]),
-    );
-    println!();
+/// Steps through the states of a solution.
+struct Replay {
+    path: Vec<State>,
+    at: usize,
+}
+
+impl Visualise for Replay {
+    fn frame(&self) -> Frame {
+        self.path[self.at].frame().with_caption(format!(
+            "move {} of {}",
+            self.at,
+            self.path.len() - 1
+        ))
+    }
 }
 
 fn side_room_to_hallway(side_room_idx: usize) -> usize {
@@ -178,7 +190,7 @@ fn move_energy(a: Amphipod) -> usize {
 fn is_unobstructed(hallway: &Hallway, pos: Option<usize>, a: usize, b: usize) -> bool {
     let from = usize::min(a, b);
     let to = usize::max(a, b);
-    (from..=to).all(|i| Some(i) == pos || hallway[i] == None)
+    (from..=to).all(|i| Some(i) == pos || hallway[i].is_none())
 }
 
 fn hallway_distance(a: usize, b: usize) -> usize {
@@ -190,33 +202,28 @@ fn can_move_to_hallway(idx: usize) -> bool {
 }
 
 fn possible_move_from_hallway(hallway_idx: usize, state: &State) -> Option<Move> {
-    match state.hallway[hallway_idx] {
-        Some(hallway_a) => {
-            let new_home = home_for(hallway_a);
-            let new_home_hallway_idx = side_room_to_hallway(new_home);
-            if is_unobstructed(
-                &state.hallway,
-                Some(hallway_idx),
-                hallway_idx,
-                new_home_hallway_idx,
-            ) {
-                if state.side_rooms[new_home]
-                    .iter()
-                    .all(|v| v == &None || v == &Some(hallway_a))
-                {
-                    let new_home_top = find_open_slot(&state.side_rooms[new_home]);
-                    return Some(Move(
-                        hallway_a,
-                        Pos::Hallway(hallway_idx),
-                        Pos::SideRoom(new_home, new_home_top),
-                        (move_inout_cost(new_home_top)
-                            + hallway_distance(hallway_idx, new_home_hallway_idx))
-                            * move_energy(hallway_a),
-                    ));
-                }
-            }
+    if let Some(hallway_a) = state.hallway[hallway_idx] {
+        let new_home = home_for(hallway_a);
+        let new_home_hallway_idx = side_room_to_hallway(new_home);
+        if is_unobstructed(
+            &state.hallway,
+            Some(hallway_idx),
+            hallway_idx,
+            new_home_hallway_idx,
+        ) && state.side_rooms[new_home]
+            .iter()
+            .all(|v| v.is_none() || v == &Some(hallway_a))
+        {
+            let new_home_top = find_open_slot(&state.side_rooms[new_home]);
+            return Some(Move(
+                hallway_a,
+                Pos::Hallway(hallway_idx),
+                Pos::SideRoom(new_home, new_home_top),
+                (move_inout_cost(new_home_top)
+                    + hallway_distance(hallway_idx, new_home_hallway_idx))
+                    * move_energy(hallway_a),
+            ));
         }
-        None => {}
     }
     None
 }
@@ -231,7 +238,7 @@ fn find_top(side_room: &SideRoom) -> Option<(usize, Amphipod)> {
 
 fn find_open_slot(side_room: &SideRoom) -> usize {
     for i in (0..4).rev() {
-        if side_room[i] == None {
+        if side_room[i].is_none() {
             return i;
         }
     }
@@ -258,46 +265,44 @@ fn possible_moves_from_side_room(side_room_idx: usize, state: &State) -> Option<
                 side_room_to_hallway(side_room_idx),
                 side_room_to_hallway(new_home),
             )
-        {
-            if state.side_rooms[new_home]
+            && state.side_rooms[new_home]
                 .iter()
-                .all(|v| v == &None || v == &Some(top_a))
+                .all(|v| v.is_none() || v == &Some(top_a))
+        {
+            let new_home_slot = find_open_slot(&state.side_rooms[new_home]);
+            result.push(Move(
+                top_a,
+                cur_pos.clone(),
+                Pos::SideRoom(new_home, new_home_slot),
+                (move_inout_cost(top_slot)
+                    + move_inout_cost(new_home_slot)
+                    + hallway_distance(
+                        side_room_to_hallway(side_room_idx),
+                        side_room_to_hallway(new_home),
+                    ))
+                    * move_energy(top_a),
+            ))
+        }
+        for hw_idx in 0..11 {
+            if can_move_to_hallway(hw_idx)
+                && is_unobstructed(
+                    &state.hallway,
+                    None,
+                    side_room_to_hallway(side_room_idx),
+                    hw_idx,
+                )
             {
-                let new_home_slot = find_open_slot(&state.side_rooms[new_home]);
                 result.push(Move(
                     top_a,
                     cur_pos.clone(),
-                    Pos::SideRoom(new_home, new_home_slot),
+                    Pos::Hallway(hw_idx),
                     (move_inout_cost(top_slot)
-                        + move_inout_cost(new_home_slot)
-                        + hallway_distance(
-                            side_room_to_hallway(side_room_idx),
-                            side_room_to_hallway(new_home),
-                        ))
+                        + hallway_distance(side_room_to_hallway(side_room_idx), hw_idx))
                         * move_energy(top_a),
                 ))
             }
         }
-        for hw_idx in 0..11 {
-            if can_move_to_hallway(hw_idx) {
-                if is_unobstructed(
-                    &state.hallway,
-                    None,
-                    side_room_to_hallway(side_room_idx),
-                    hw_idx,
-                ) {
-                    result.push(Move(
-                        top_a,
-                        cur_pos.clone(),
-                        Pos::Hallway(hw_idx),
-                        (move_inout_cost(top_slot)
-                            + hallway_distance(side_room_to_hallway(side_room_idx), hw_idx))
-                            * move_energy(top_a),
-                    ))
-                }
-            }
-        }
-        if result.len() > 0 {
+        if !result.is_empty() {
             return Some(result);
         }
     }
@@ -310,7 +315,7 @@ fn all_possible_moves(state: &State) -> Vec<Move> {
         .iter()
         .enumerate()
         .filter_map(|(sr_idx, _)| possible_moves_from_side_room(sr_idx, state))
-        .flat_map(|v| v)
+        .flatten()
         .chain(
             state
                 .hallway
@@ -322,25 +327,25 @@ fn all_possible_moves(state: &State) -> Vec<Move> {
 }
 
 fn apply_move(m: &Move, state: &State) -> State {
-    let mut hallway = state.hallway.clone();
-    let mut side_rooms = state.side_rooms.clone();
+    let mut hallway = state.hallway;
+    let mut side_rooms = state.side_rooms;
     let Move(a, from, to, _cost) = m;
-    match from {
-        &Pos::SideRoom(idx, slot) => {
+    match *from {
+        Pos::SideRoom(idx, slot) => {
             assert_eq!(&side_rooms[idx][slot].unwrap(), a);
             side_rooms[idx][slot] = None;
         }
-        &Pos::Hallway(idx) => {
+        Pos::Hallway(idx) => {
             assert_eq!(&hallway[idx].unwrap(), a);
             hallway[idx] = None;
         }
     }
-    match to {
-        &Pos::SideRoom(idx, slot) => {
+    match *to {
+        Pos::SideRoom(idx, slot) => {
             assert_eq!(side_rooms[idx][slot], None);
             side_rooms[idx][slot] = Some(*a);
         }
-        &Pos::Hallway(idx) => {
+        Pos::Hallway(idx) => {
             assert_eq!(hallway[idx], None);
             hallway[idx] = Some(*a);
         }
@@ -358,25 +363,51 @@ fn is_complete(state: &State) -> bool {
     })
 }
 
-fn make_moves(state: &State, memo: &mut HashMap<State, Option<usize>>) -> Option<usize> {
-    if is_complete(state) {
-        return Some(0);
-    }
-    if let Some(memoized_result) = memo.get(state) {
-        return *memoized_result;
+struct Burrow {
+    start: State,
+}
+
+impl SearchProblem for Burrow {
+    type State = State;
+    type Cost = usize;
+
+    fn start(&self) -> State {
+        self.start.clone()
     }
-    let result = {
+
+    fn successors(&self, state: &State) -> Vec<(State, usize)> {
         all_possible_moves(state)
             .iter()
-            .filter_map(|m| {
+            .map(|m| {
                 let Move(_, _, _, move_cost) = m;
-                let new_state = apply_move(m, state);
-                make_moves(&new_state, memo).map(|sub_cost| move_cost + sub_cost)
+                (apply_move(m, state), *move_cost)
             })
-            .min()
-    };
-    memo.insert(state.clone(), result);
-    result
+            .collect()
+    }
+
+    fn is_goal(&self, state: &State) -> bool {
+        is_complete(state)
+    }
+}
+
+/// The energy of the cheapest solution, which is played back if
+/// `VISUALISE` is set.
+fn least_energy(start: State) -> usize {
+    let solution = dijkstra(&Burrow { start }).solution.unwrap();
+    if let Some(mut player) = Player::from_env() {
+        let mut replay = Replay {
+            path: solution.path,
+            at: 0,
+        };
+        player
+            .play(&mut replay, |replay| {
+                let last = replay.path.len() - 1;
+                replay.at = (replay.at + 1).min(last);
+                replay.at < last
+            })
+            .unwrap();
+    }
+    solution.cost
 }
 
 fn part1(input: &str) -> usize {
@@ -386,7 +417,7 @@ fn part1(input: &str) -> usize {
         hallway,
         side_rooms,
     };
-    make_moves(&state, &mut HashMap::new()).unwrap()
+    least_energy(state)
 }
 
 fn part2(input: &str) -> usize {
@@ -408,7 +439,7 @@ fn part2(input: &str) -> usize {
         hallway,
         side_rooms,
     };
-    make_moves(&state, &mut HashMap::new()).unwrap()
+    least_energy(state)
 }
 
 fn main() {
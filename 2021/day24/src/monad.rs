@@ -0,0 +1,365 @@
+use std::fmt;
+
+use vm::{Instruction, InstructionSet, Operand};
+
+/// What the analyser knows about a register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Sym {
+    Const(i64),
+    /// Input digit `index`, from 1 to 9, plus `offset`.
+    Digit {
+        index: usize,
+        offset: i64,
+    },
+    /// A base-26 number whose digits are the entries, the last one least
+    /// significant. Every entry is a `Const` or `Digit` in `0..26`.
+    Stack(Vec<Sym>),
+}
+
+use Sym::*;
+
+impl Sym {
+    /// The smallest and largest value, for anything but a stack.
+    fn range(&self) -> Option<(i64, i64)> {
+        match *self {
+            Const(c) => Some((c, c)),
+            Digit { offset, .. } => Some((1 + offset, 9 + offset)),
+            Stack(_) => None,
+        }
+    }
+
+    /// The value as base-26 digits, if it is known to be a stack or to fit
+    /// in one entry.
+    fn into_stack(self) -> Option<Vec<Sym>> {
+        match self {
+            Const(0) => Some(vec![]),
+            Stack(entries) => Some(entries),
+            value => match value.range() {
+                Some((lo, hi)) if lo >= 0 && hi < 26 => Some(vec![value]),
+                _ => None,
+            },
+        }
+    }
+
+    fn from_stack(entries: Vec<Sym>) -> Sym {
+        if entries.is_empty() {
+            Const(0)
+        } else {
+            Stack(entries)
+        }
+    }
+}
+
+/// `d[later] = d[earlier] + offset`, where `earlier` pushed its digit onto
+/// `z` and `later` had to match it to pop it again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    pub earlier: usize,
+    pub later: usize,
+    pub offset: i64,
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.offset < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "d[{}] = d[{}] {} {}",
+            self.later,
+            self.earlier,
+            sign,
+            self.offset.abs()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalysisError {
+    /// The instruction at this line does not fit the push/pop pattern.
+    Unsupported { line: usize, instruction: String },
+    /// `z` is not 0 for any input.
+    NonZero,
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::Unsupported { line, instruction } => {
+                write!(f, "line {}: cannot analyse `{}`", line, instruction)
+            }
+            AnalysisError::NonZero => write!(f, "z never ends up 0"),
+        }
+    }
+}
+
+impl std::error::Error for AnalysisError {}
+
+/// The digit constraints under which a MONAD program accepts a model
+/// number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub digits: usize,
+    pub constraints: Vec<Constraint>,
+}
+
+/// Executes the program symbolically, with every `inp` reading a fresh
+/// digit.
+///
+/// MONAD keeps `z` as a stack of base-26 entries. Each chunk either pushes
+/// its digit plus a constant, or compares its digit with the top entry plus
+/// a constant and pops it, pushing its own digit only if they differ. Since
+/// there are as many pops as pushes, `z` can only end up 0 if every
+/// comparison that can hold does, which is taken as a constraint between
+/// the two digits.
+pub fn analyse(isa: &InstructionSet, program: &[Instruction]) -> Result<Analysis, AnalysisError> {
+    let mut regs = vec![Const(0); isa.registers().len()];
+    let mut analysis = Analysis {
+        digits: 0,
+        constraints: vec![],
+    };
+    for (i, instruction) in program.iter().enumerate() {
+        let unsupported = || AnalysisError::Unsupported {
+            line: i + 1,
+            instruction: isa.display(instruction).to_string(),
+        };
+        let args = instruction.args();
+        let Operand::Reg(target) = args[0] else {
+            return Err(unsupported());
+        };
+        let value = match isa.get(instruction.op).name {
+            "inp" => {
+                analysis.digits += 1;
+                Some(Digit {
+                    index: analysis.digits - 1,
+                    offset: 0,
+                })
+            }
+            name => {
+                let a = regs[target].clone();
+                let b = match args[1] {
+                    Operand::Reg(r) => regs[r].clone(),
+                    Operand::Imm(n) => Const(n),
+                };
+                match name {
+                    "add" => add(a, b),
+                    "mul" => mul(a, b),
+                    "div" => div(a, b),
+                    "mod" => rem(a, b),
+                    "eql" => eql(a, b, &mut analysis.constraints),
+                    _ => None,
+                }
+            }
+        };
+        regs[target] = value.ok_or_else(unsupported)?;
+    }
+    if regs[3] != Const(0) {
+        return Err(AnalysisError::NonZero);
+    }
+    Ok(analysis)
+}
+
+fn add(a: Sym, b: Sym) -> Option<Sym> {
+    match (a, b) {
+        (Const(a), Const(b)) => Some(Const(a + b)),
+        (Digit { index, offset }, Const(c)) | (Const(c), Digit { index, offset }) => Some(Digit {
+            index,
+            offset: offset + c,
+        }),
+        (a, Const(0)) | (Const(0), a) => Some(a),
+        // adding the digit into the entry that `mul z 26` just made room for
+        (Stack(mut entries), b) => {
+            let top = add(entries.pop()?, b)?;
+            let (lo, hi) = top.range()?;
+            (lo >= 0 && hi < 26).then(|| {
+                entries.push(top);
+                Stack(entries)
+            })
+        }
+        _ => None,
+    }
+}
+
+fn mul(a: Sym, b: Sym) -> Option<Sym> {
+    match (a, b) {
+        (Const(a), Const(b)) => Some(Const(a * b)),
+        (_, Const(0)) | (Const(0), _) => Some(Const(0)),
+        (a, Const(1)) | (Const(1), a) => Some(a),
+        (a, Const(26)) => {
+            let mut entries = a.into_stack()?;
+            if !entries.is_empty() {
+                entries.push(Const(0));
+            }
+            Some(Sym::from_stack(entries))
+        }
+        _ => None,
+    }
+}
+
+fn div(a: Sym, b: Sym) -> Option<Sym> {
+    match (a, b) {
+        (Const(a), Const(b)) if b != 0 => Some(Const(a / b)),
+        (a, Const(1)) => Some(a),
+        (a, Const(26)) => {
+            let mut entries = a.into_stack()?;
+            entries.pop();
+            Some(Sym::from_stack(entries))
+        }
+        _ => None,
+    }
+}
+
+fn rem(a: Sym, b: Sym) -> Option<Sym> {
+    match (a, b) {
+        (Const(a), Const(b)) if a >= 0 && b > 0 => Some(Const(a % b)),
+        (a, Const(26)) => Some(a.into_stack()?.pop().unwrap_or(Const(0))),
+        _ => None,
+    }
+}
+
+fn eql(a: Sym, b: Sym, constraints: &mut Vec<Constraint>) -> Option<Sym> {
+    let ((alo, ahi), (blo, bhi)) = (a.range()?, b.range()?);
+    if ahi < blo || bhi < alo {
+        return Some(Const(0));
+    }
+    match (a, b) {
+        (Const(a), Const(b)) => Some(Const((a == b) as i64)),
+        (
+            Digit {
+                index: i,
+                offset: oi,
+            },
+            Digit {
+                index: j,
+                offset: oj,
+            },
+        ) if i != j => {
+            let (earlier, later, offset) = if i < j {
+                (i, j, oi - oj)
+            } else {
+                (j, i, oj - oi)
+            };
+            constraints.push(Constraint {
+                earlier,
+                later,
+                offset,
+            });
+            Some(Const(1))
+        }
+        _ => None,
+    }
+}
+
+impl Analysis {
+    /// The largest or smallest model number that satisfies every constraint,
+    /// if there is one.
+    pub fn model_number(&self, largest: bool) -> Option<Vec<i64>> {
+        let mut digits = vec![if largest { 9 } else { 1 }; self.digits];
+        for c in &self.constraints {
+            let earlier = if largest {
+                9.min(9 - c.offset)
+            } else {
+                1.max(1 - c.offset)
+            };
+            if !(1..=9).contains(&earlier) || !(1..=9).contains(&(earlier + c.offset)) {
+                return None;
+            }
+            digits[c.earlier] = earlier;
+            digits[c.later] = earlier + c.offset;
+        }
+        Some(digits)
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (largest, smallest) = (self.model_number(true), self.model_number(false));
+        for c in &self.constraints {
+            write!(f, "{}", c)?;
+            if let (Some(largest), Some(smallest)) = (&largest, &smallest) {
+                write!(
+                    f,
+                    "    largest {}{}, smallest {}{}",
+                    largest[c.earlier], largest[c.later], smallest[c.earlier], smallest[c.later]
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alu;
+
+    /// A push chunk for the first digit and a pop chunk for the second.
+    const PAIR: &str = "\
+inp w
+mul x 0
+add x z
+mod x 26
+div z 1
+add x 11
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 3
+mul y x
+add z y
+inp w
+mul x 0
+add x z
+mod x 26
+div z 26
+add x -5
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 8
+mul y x
+add z y
+";
+
+    #[test]
+    fn test_pair() {
+        let isa = alu();
+        let analysis = analyse(&isa, &isa.parse(PAIR).unwrap()).unwrap();
+        let constraint = Constraint {
+            earlier: 0,
+            later: 1,
+            offset: -2,
+        };
+        assert_eq!(analysis.constraints, [constraint]);
+        assert_eq!(constraint.to_string(), "d[1] = d[0] - 2");
+        assert_eq!(analysis.model_number(true), Some(vec![9, 7]));
+        assert_eq!(analysis.model_number(false), Some(vec![3, 1]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let isa = alu();
+        let program = isa.parse("inp w\nmul w w\n").unwrap();
+        assert_eq!(
+            analyse(&isa, &program),
+            Err(AnalysisError::Unsupported {
+                line: 2,
+                instruction: "mul w w".to_string()
+            })
+        );
+        let program = isa.parse("inp z\n").unwrap();
+        assert_eq!(analyse(&isa, &program), Err(AnalysisError::NonZero));
+    }
+}
@@ -1,28 +1,25 @@
-fn parse_pair(s: &str) -> (u32, u32) {
+use interval::Interval;
+
+fn parse_pair(s: &str) -> Interval<u32> {
     let (l, r) = s.split_once('-').unwrap();
-    (l.parse().unwrap(), r.parse().unwrap())
+    Interval::inclusive(l.parse().unwrap(), r.parse().unwrap())
+}
+
+fn parse_input(input: &str) -> impl Iterator<Item = (Interval<u32>, Interval<u32>)> + '_ {
+    input.lines().map(|line| {
+        let (p1, p2) = line.split_once(',').unwrap();
+        (parse_pair(p1), parse_pair(p2))
+    })
 }
 
 fn part1(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| {
-            let (p1, p2) = line.split_once(',').unwrap();
-            (parse_pair(p1), parse_pair(p2))
-        })
-        .filter(|((x1, y1), (x2, y2))| (x1 <= x2 && y1 >= y2) || (x2 <= x1 && y2 >= y1))
+    parse_input(input)
+        .filter(|(a, b)| a.contains_interval(b) || b.contains_interval(a))
         .count()
 }
 
 fn part2(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| {
-            let (p1, p2) = line.split_once(',').unwrap();
-            (parse_pair(p1), parse_pair(p2))
-        })
-        .filter(|((x1, y1), (x2, y2))| std::cmp::max(x1, x2) <= std::cmp::min(y1, y2))
-        .count()
+    parse_input(input).filter(|(a, b)| a.overlaps(b)).count()
 }
 
 fn main() {
@@ -1,35 +1,14 @@
 // Problem: https://adventofcode.com/2022/day/9
 
-use std::{collections::HashSet, fmt::Debug, ops::Add};
+use geometry::{Direction, Point2};
+use std::collections::HashSet;
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
-struct Pos(i32, i32);
-
-impl Debug for Pos {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({},{})", self.0, self.1)
-    }
-}
-
-impl Add<Pos> for Pos {
-    type Output = Pos;
-    fn add(self, rhs: Pos) -> Self::Output {
-        Pos(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
+type Pos = Point2<i32>;
 
 fn dir(c: char) -> Pos {
-    match c {
-        'D' => Pos(0, -1),
-        'U' => Pos(0, 1),
-        'R' => Pos(1, 0),
-        'L' => Pos(-1, 0),
-        _ => panic!("Invalid direction"),
-    }
-}
-
-fn distance(p1: Pos, p2: Pos) -> i32 {
-    std::cmp::max((p1.0 - p2.0).abs(), (p1.1 - p2.1).abs())
+    Direction::from_char(c)
+        .expect("Invalid direction")
+        .unit_cartesian()
 }
 
 fn part1(input: &str) -> usize {
@@ -40,16 +19,16 @@ fn part1(input: &str) -> usize {
         .map(|(l, r)| (l.chars().next().unwrap(), r.parse::<i32>().unwrap()))
         .collect();
 
-    let mut head = Pos(0, 0);
-    let mut tail = Pos(0, 0);
+    let mut head = Pos::ORIGIN;
+    let mut tail = Pos::ORIGIN;
     let mut seen = HashSet::new();
     seen.insert(tail);
 
     for (d, dist) in instructions {
         for _ in 0..dist {
             let last = head;
-            head = head + dir(d);
-            if distance(head, tail) > 1 {
+            head += dir(d);
+            if head.chebyshev(tail) > 1 {
                 tail = last;
                 seen.insert(tail);
             }
@@ -59,14 +38,6 @@ fn part1(input: &str) -> usize {
     seen.len()
 }
 
-fn follow_dist(x: i32) -> i32 {
-    match x {
-        x if x > 1 => 1,
-        x if x < -1 => -1,
-        _ => 0,
-    }
-}
-
 fn part2(input: &str) -> usize {
     let instructions: Vec<(char, i32)> = input
         .trim()
@@ -75,20 +46,17 @@ fn part2(input: &str) -> usize {
         .map(|(l, r)| (l.chars().next().unwrap(), r.parse::<i32>().unwrap()))
         .collect();
 
-    let mut rope = [Pos(0, 0); 10];
+    let mut rope = [Pos::ORIGIN; 10];
     let mut seen = HashSet::new();
     seen.insert(rope[9]);
 
     for (d, dist) in instructions {
         for _ in 0..dist {
-            rope[0] = rope[0] + dir(d);
+            rope[0] += dir(d);
             let mut last = rope[0];
             rope.iter_mut().skip(1).for_each(|cur| {
-                if distance(*cur, last) > 1 {
-                    *cur = Pos(
-                        last.0 + follow_dist(cur.0 - last.0),
-                        last.1 + follow_dist(cur.1 - last.1),
-                    );
+                if cur.chebyshev(last) > 1 {
+                    *cur += (last - *cur).signum();
                 }
                 last = *cur;
             });
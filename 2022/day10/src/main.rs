@@ -1,59 +1,48 @@
 // Problem: https://adventofcode.com/2022/day/10
 
-use std::cell::Cell;
+use ocr::Image;
+use vm::{Arg, Exit, Flow, InstructionSet, Machine, State};
+
+fn cpu() -> InstructionSet {
+    InstructionSet::new(&["x"])
+        .op("noop", &[], 1, |_, _| Flow::Next)
+        .op("addx", &[Arg::Imm], 2, |state, args| {
+            state.regs[0] += args[0].imm();
+            Flow::Next
+        })
+}
+
+/// Runs the program with `x` starting at 1, calling `during` with the cycle
+/// number and `x` during every cycle.
+fn run(input: &str, mut during: impl FnMut(usize, i32)) {
+    let isa = cpu();
+    let program = isa.parse(input).unwrap();
+    let mut state = State::new(1);
+    state.regs[0] = 1;
+    let mut machine = Machine::with_state(&isa, &program, state);
+    let exit = machine.run_with(|state| during(state.cycle as usize, state.regs[0] as i32));
+    assert_eq!(exit, Exit::Halted);
+}
 
 fn part1(input: &str) -> usize {
-    let mut cycle = 0;
-    let x: Cell<i32> = Cell::new(1);
     let mut result = 0;
-
-    let mut inc = || {
-        cycle += 1;
+    run(input, |cycle, x| {
         if (cycle as i32 - 20) % 40 == 0 {
-            result += x.get() as usize * cycle;
+            result += x as usize * cycle;
         }
-    };
-
-    for inst in input.trim().lines() {
-        if inst == "noop" {
-            inc();
-        } else {
-            inc();
-            let (_, amount) = inst.split_once(' ').unwrap();
-            let amount: i32 = amount.parse().unwrap();
-            inc();
-            x.replace(x.get() + amount);
-        }
-    }
-
+    });
     result
 }
 
-fn part2(input: &str) -> String {
-    let mut cycle = 0;
-    let x: Cell<i32> = Cell::new(1);
+/// The CRT screen after running the program, `#` for lit pixels.
+fn draw_crt(input: &str) -> String {
     let mut crt = vec![vec![' '; 40]; 6];
-
-    let mut inc = || {
-        let x = x.get();
-        let row = cycle / 40;
-        let pos = cycle % 40;
+    run(input, |cycle, x| {
+        let row = (cycle - 1) / 40;
+        let pos = (cycle - 1) % 40;
         let is_sprite_drawn = ((x - 1)..=(x + 1)).contains(&(pos as i32));
         crt[row][pos] = if is_sprite_drawn { '#' } else { '.' };
-        cycle += 1;
-    };
-
-    for inst in input.trim().lines() {
-        if inst == "noop" {
-            inc();
-        } else {
-            inc();
-            let (_, amount) = inst.split_once(' ').unwrap();
-            let amount: i32 = amount.parse().unwrap();
-            inc();
-            x.replace(x.get() + amount);
-        }
-    }
+    });
 
     crt.iter()
         .map(|line| line.iter().collect::<String>())
@@ -61,9 +50,13 @@ fn part2(input: &str) -> String {
         .join("\n")
 }
 
+fn part2(input: &str) -> String {
+    Image::parse(&draw_crt(input)).recognize().unwrap()
+}
+
 fn main() {
     println!("Part 1: {:?}", part1(include_str!("input.txt")));
-    println!("Part 2:\n{}", part2(include_str!("input.txt")));
+    println!("Part 2: {:?}", part2(include_str!("input.txt")));
 }
 
 #[test]
@@ -72,9 +65,9 @@ fn test_part1() {
 }
 
 #[test]
-fn test_part2() {
+fn test_draw_crt() {
     assert_eq!(
-        part2(include_str!("test.txt")),
+        draw_crt(include_str!("test.txt")),
         r"##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
 ####....####....####....####....####....
@@ -83,3 +76,8 @@ fn test_part2() {
 #######.......#######.......#######....."
     );
 }
+
+#[test]
+fn test_part2() {
+    assert_eq!(part2(include_str!("input.txt")), "RKAZAJBR");
+}
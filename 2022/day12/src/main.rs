@@ -1,27 +1,16 @@
 // Problem: https://adventofcode.com/2022/day/12
 
-use itertools::Itertools;
-use std::{
-    collections::{HashSet, VecDeque},
-    ops::Add,
+use geometry::Point2;
+use search::{
+    astar, beam_search, bfs, bidirectional_bfs, dijkstra, ReversibleProblem, SearchProblem,
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Pos(i32, i32);
+type Pos = Point2<i32>;
 
-impl Add<Pos> for Pos {
-    type Output = Pos;
-    fn add(self, rhs: Pos) -> Self::Output {
-        Pos(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-fn find(grid: &Vec<Vec<u8>>, val: u8) -> Pos {
-    for row in 0..grid.len() {
-        for col in 0..grid[0].len() {
-            if grid[row][col] == val {
-                return Pos(row as i32, col as i32);
-            }
+fn find(grid: &[Vec<u8>], val: u8) -> Pos {
+    for (row, line) in grid.iter().enumerate() {
+        if let Some(col) = line.iter().position(|&v| v == val) {
+            return Pos::from_row_col(row as i32, col as i32);
         }
     }
     panic!();
@@ -45,8 +34,8 @@ impl Grid {
             .collect();
         let start = find(&grid, b'S');
         let dest = find(&grid, b'E');
-        grid[start.0 as usize][start.1 as usize] = b'a';
-        grid[dest.0 as usize][dest.1 as usize] = b'z';
+        grid[start.y as usize][start.x as usize] = b'a';
+        grid[dest.y as usize][dest.x as usize] = b'z';
         let rows = grid.len() as i32;
         let cols = grid[0].len() as i32;
         Self {
@@ -58,58 +47,132 @@ impl Grid {
         }
     }
     fn get(&self, pos: Pos) -> u8 {
-        self.grid[pos.0 as usize][pos.1 as usize]
+        let (row, col) = pos.to_row_col();
+        self.grid[row as usize][col as usize]
     }
     fn is_valid(&self, pos: Pos) -> bool {
-        pos.0 >= 0 && pos.1 >= 0 && pos.0 < self.rows && pos.1 < self.cols
+        let (row, col) = pos.to_row_col();
+        row >= 0 && col >= 0 && row < self.rows && col < self.cols
+    }
+}
+
+/// Climbs from `start` up to the best signal at `grid.dest`, or with
+/// `descend` set walks down from the best signal to any square of height `a`.
+struct Hike<'a> {
+    grid: &'a Grid,
+    start: Pos,
+    descend: bool,
+}
+
+impl Hike<'_> {
+    fn can_climb(&self, from: Pos, to: Pos) -> bool {
+        self.grid.get(to) <= self.grid.get(from) + 1
     }
-    fn iter(&self) -> impl Iterator<Item = Pos> {
-        (0..self.rows)
-            .cartesian_product(0..self.cols)
-            .map(|(r, c)| Pos(r as i32, c as i32))
+
+    fn neighbours(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbours4()
+            .into_iter()
+            .filter(|next| self.grid.is_valid(*next))
     }
 }
 
-fn find_path(start: Pos, grid: &Grid) -> Option<usize> {
-    let mut seen = HashSet::<Pos>::new();
-    let mut queue = VecDeque::<(Pos, usize)>::new();
-    queue.push_back((start, 0));
-    while !queue.is_empty() {
-        let (pos, cur_len) = queue.pop_front().unwrap();
-        if !seen.contains(&pos) {
-            if pos == grid.dest {
-                return Some(cur_len);
-            }
-            seen.insert(pos);
-            for dir in &[Pos(1, 0), Pos(-1, 0), Pos(0, 1), Pos(0, -1)] {
-                let next = pos + *dir;
-                if grid.is_valid(next) && grid.get(next) <= grid.get(pos) + 1 {
-                    queue.push_back((next, cur_len + 1));
+impl SearchProblem for Hike<'_> {
+    type State = Pos;
+    type Cost = usize;
+
+    fn start(&self) -> Pos {
+        self.start
+    }
+
+    fn successors(&self, &pos: &Pos) -> Vec<(Pos, usize)> {
+        self.neighbours(pos)
+            .filter(|&next| {
+                if self.descend {
+                    self.can_climb(next, pos)
+                } else {
+                    self.can_climb(pos, next)
                 }
-            }
+            })
+            .map(|next| (next, 1))
+            .collect()
+    }
+
+    fn is_goal(&self, &pos: &Pos) -> bool {
+        if self.descend {
+            self.grid.get(pos) == b'a'
+        } else {
+            pos == self.grid.dest
+        }
+    }
+
+    fn heuristic(&self, pos: &Pos) -> usize {
+        if self.descend {
+            0
+        } else {
+            pos.manhattan(self.grid.dest) as usize
         }
     }
-    None
+}
+
+impl ReversibleProblem for Hike<'_> {
+    fn goal(&self) -> Pos {
+        assert!(!self.descend, "a descent has no single goal");
+        self.grid.dest
+    }
+
+    fn predecessors(&self, &pos: &Pos) -> Vec<(Pos, usize)> {
+        self.neighbours(pos)
+            .filter(|&prev| self.can_climb(prev, pos))
+            .map(|prev| (prev, 1))
+            .collect()
+    }
 }
 
 fn part1(input: &str) -> usize {
     let grid = Grid::parse(input);
-    find_path(grid.start, &grid).unwrap()
+    let hike = Hike {
+        grid: &grid,
+        start: grid.start,
+        descend: false,
+    };
+    bidirectional_bfs(&hike).steps().unwrap()
 }
 
 fn part2(input: &str) -> usize {
     let grid = Grid::parse(input);
-    let low_height = b'a';
-    grid.iter()
-        .filter(|pos| grid.get(*pos) == low_height)
-        .filter_map(|pos| find_path(pos, &grid))
-        .min()
-        .unwrap()
+    let hike = Hike {
+        grid: &grid,
+        start: grid.dest,
+        descend: true,
+    };
+    bfs(&hike).steps().unwrap()
+}
+
+/// Prints how much work each search strategy needs for part 1.
+fn compare_strategies(input: &str) {
+    let grid = Grid::parse(input);
+    let hike = Hike {
+        grid: &grid,
+        start: grid.start,
+        descend: false,
+    };
+    for (name, result) in [
+        ("bfs", bfs(&hike)),
+        ("bidirectional bfs", bidirectional_bfs(&hike)),
+        ("dijkstra", dijkstra(&hike)),
+        ("a*", astar(&hike)),
+        ("beam search (100)", beam_search(&hike, 100)),
+    ] {
+        println!("{:>18}: {:?} steps, {}", name, result.steps(), result.stats);
+    }
 }
 
 fn main() {
     println!("Part 1: {}", part1(include_str!("input.txt")));
     println!("Part 2: {}", part2(include_str!("input.txt")));
+    if std::env::var("SEARCH_STATS").is_ok() {
+        compare_strategies(include_str!("input.txt"));
+    }
 }
 
 #[test]
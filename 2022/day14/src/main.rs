@@ -1,5 +1,8 @@
+use grid::SparseGrid;
 use itertools::Itertools;
-use std::{collections::HashMap, ops::RangeInclusive};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use vis::{Animation, Cell, Color, Export, Frame, Palette, Player, Raster, Visualise};
 
 // Problem: https://adventofcode.com/2022/day/14
 
@@ -17,60 +20,36 @@ fn right(p: Point) -> Point {
     (p.0 + 1, p.1)
 }
 
-trait Grid {
-    fn has(&self, p: Point) -> bool;
-    fn get(&self, p: Point) -> Option<char>;
-    fn range(&self) -> (Point, Point);
-    fn set(&mut self, p: Point, ch: char);
+/// Rocks and settled sand, optionally resting on an infinitely wide floor.
+struct Cave {
+    cells: SparseGrid<Point, char>,
+    floor: Option<i32>,
 }
 
-fn print_grid(grid: &impl Grid) {
-    let ((min_x, min_y), (max_x, max_y)) = grid.range();
+impl Cave {
+    fn has(&self, p: Point) -> bool {
+        self.floor == Some(p.1) || self.cells.contains(p)
+    }
 
-    println!();
-    for y in std::cmp::min(0, min_y)..=max_y {
-        for x in min_x..=max_x {
-            match grid.get((x, y)) {
-                Some(ch) => print!("{}", ch),
-                None => print!("."),
-            }
-        }
-        println!();
+    fn lowest_rock(&self) -> i32 {
+        self.cells.bounds().unwrap().1 .1
     }
-    println!();
 }
 
-struct Grid1(HashMap<Point, char>);
-impl Grid for Grid1 {
-    fn has(&self, p: Point) -> bool {
-        self.0.contains_key(&p)
-    }
-    fn set(&mut self, p: Point, ch: char) {
-        self.0.insert(p, ch);
-    }
-    fn range(&self) -> (Point, Point) {
-        let (&min_x, &max_x) = self
-            .0
-            .keys()
-            .map(|(x, _)| x)
-            .minmax()
-            .into_option()
-            .unwrap();
-        let (&min_y, &max_y) = self
-            .0
-            .keys()
-            .map(|(_, y)| y)
-            .minmax()
-            .into_option()
-            .unwrap();
-        ((min_x, min_y), (max_x, max_y))
-    }
-    fn get(&self, p: Point) -> Option<char> {
-        self.0.get(&p).copied()
+impl Visualise for Cave {
+    fn frame(&self) -> Frame {
+        let grains = self.cells.iter().filter(|(_, &ch)| ch == 'o').count();
+        Frame::from_sparse(&self.cells, |ch| match ch {
+            Some('#') => Cell::colored('█', Color::DarkGrey),
+            Some('o') => Cell::colored('o', Color::Yellow),
+            _ => Cell::plain(' '),
+        })
+        .with_caption(format!("{} grains of sand", grains))
+        .focus_on(500, 0)
     }
 }
 
-fn draw_line(grid: &mut impl Grid, (x0, y0): Point, (x1, y1): Point) {
+fn draw_line(rocks: &mut SparseGrid<Point, char>, (x0, y0): Point, (x1, y1): Point) {
     fn range(a: i32, b: i32) -> RangeInclusive<i32> {
         if a > b {
             b..=a
@@ -81,18 +60,18 @@ fn draw_line(grid: &mut impl Grid, (x0, y0): Point, (x1, y1): Point) {
 
     if x0 == x1 {
         for y in range(y0, y1) {
-            grid.set((x0, y), '#');
+            rocks.insert((x0, y), '#');
         }
     } else if y0 == y1 {
         for x in range(x0, x1) {
-            grid.set((x, y0), '#');
+            rocks.insert((x, y0), '#');
         }
     } else {
         panic!("{},{} -> {},{}", x0, y0, x1, y1);
     }
 }
 
-fn build_grid(input: &str) -> Grid1 {
+fn parse_cave(input: &str) -> Cave {
     let structures: Vec<Vec<Point>> = input
         .trim()
         .lines()
@@ -107,16 +86,16 @@ fn build_grid(input: &str) -> Grid1 {
                 .collect()
         })
         .collect();
-    let mut grid = Grid1(HashMap::new());
+    let mut cells = SparseGrid::new('.');
     for structure in structures {
         for i in 1..structure.len() {
-            draw_line(&mut grid, structure[i - 1], structure[i]);
+            draw_line(&mut cells, structure[i - 1], structure[i]);
         }
     }
-    grid
+    Cave { cells, floor: None }
 }
 
-fn drop_sand(sand: Point, grid: &mut impl Grid, abyss: i32) -> bool {
+fn drop_sand(sand: Point, grid: &mut Cave, abyss: i32) -> bool {
     if grid.has(sand) {
         return false;
     }
@@ -144,87 +123,66 @@ fn drop_sand(sand: Point, grid: &mut impl Grid, abyss: i32) -> bool {
         }
     }
 
-    grid.set(cur, 'o');
+    grid.cells.insert(cur, 'o');
     true
 }
 
-fn part1(input: &str) -> usize {
-    let print_enabled = std::env::var("PRINT_GRID").is_ok();
+const GIF_GRAINS_PER_FRAME: usize = 100;
+
+fn palette() -> Palette {
+    Palette::new([20, 24, 40], [0, 0, 0])
+        .with('█', [120, 110, 100])
+        .with('o', [230, 190, 90])
+}
+
+/// Drops sand until it stops settling, animating every grain if `VISUALISE`
+/// is set and printing the final cave if `PRINT_GRID` is.
+fn fill(cave: &mut Cave, abyss: i32, mut animation: Option<&mut Animation>) -> usize {
     let sand_origin = (500, 0);
-    let mut grid = build_grid(input);
-    let abyss = grid.range().1 .1 + 1;
     let mut count = 0;
-    loop {
-        if drop_sand(sand_origin, &mut grid, abyss) {
-            count += 1;
-        } else {
-            break;
+    let mut step = |cave: &mut Cave| {
+        let settled = drop_sand(sand_origin, cave, abyss);
+        count += settled as usize;
+        if let Some(animation) = animation.as_deref_mut() {
+            if !settled || count % GIF_GRAINS_PER_FRAME == 0 {
+                animation.push(Raster::from_frame(&cave.frame(), &palette()));
+            }
         }
+        settled
+    };
+    match Player::from_env() {
+        Some(mut player) => player.play(cave, step).unwrap(),
+        None => while step(cave) {},
     }
-    if print_enabled {
-        print_grid(&grid);
+    if std::env::var("PRINT_GRID").is_ok() {
+        println!("\n{}", cave.frame());
     }
     count
 }
 
-struct Grid2 {
-    grid: HashMap<Point, char>,
-    floor: i32,
-}
-impl Grid for Grid2 {
-    fn has(&self, p: Point) -> bool {
-        p.1 == self.floor || self.grid.contains_key(&p)
-    }
-    fn set(&mut self, p: Point, ch: char) {
-        self.grid.insert(p, ch);
-    }
-    fn range(&self) -> (Point, Point) {
-        let (&min_x, &max_x) = self
-            .grid
-            .keys()
-            .map(|(x, _)| x)
-            .minmax()
-            .into_option()
-            .unwrap();
-        let (&min_y, _) = self
-            .grid
-            .keys()
-            .map(|(_, y)| y)
-            .minmax()
-            .into_option()
-            .unwrap();
-        ((min_x, min_y), (max_x, self.floor))
-    }
-    fn get(&self, p: Point) -> Option<char> {
-        self.grid.get(&p).copied()
-    }
-}
-impl Grid2 {
-    fn from(grid: Grid1) -> Self {
-        let ((_, _), (_, max_y)) = grid.range();
-        Self {
-            grid: grid.0,
-            floor: max_y + 2,
-        }
-    }
+fn part1(input: &str) -> usize {
+    let mut grid = parse_cave(input);
+    let abyss = grid.lowest_rock() + 1;
+    fill(&mut grid, abyss, None)
 }
 
 fn part2(input: &str) -> usize {
-    let print_enabled = std::env::var("PRINT_GRID").is_ok();
-    let sand_origin = (500, 0);
-    let mut grid = Grid2::from(build_grid(input));
-    let mut count = 0;
-    loop {
-        if drop_sand(sand_origin, &mut grid, i32::MAX) {
-            count += 1;
-        } else {
-            break;
-        }
-    }
-    if print_enabled {
-        print_grid(&grid);
+    let mut grid = parse_cave(input);
+    grid.floor = Some(grid.lowest_rock() + 2);
+    let Some(export) = Export::from_env() else {
+        return fill(&mut grid, i32::MAX, None);
+    };
+    // EXPORT=sand.png saves the final pile, EXPORT=sand.gif records it growing
+    if export.is_animation() {
+        let mut animation = Animation::new(Duration::from_millis(40), palette().background);
+        let count = fill(&mut grid, i32::MAX, Some(&mut animation));
+        export.save_animation(&animation).unwrap();
+        count
+    } else {
+        let count = fill(&mut grid, i32::MAX, None);
+        export.save(&grid.frame(), &palette()).unwrap();
+        count
     }
-    count
 }
 
 fn main() {
@@ -1,6 +1,6 @@
 // Problem: https://adventofcode.com/2022/day/15
 
-use std::collections::HashSet;
+use interval::{Interval, IntervalSet};
 
 type Point = (i64, i64);
 
@@ -8,8 +8,8 @@ fn manhattan_distance((x0, y0): Point, (x1, y1): Point) -> i64 {
     (x1 - x0).abs() + (y1 - y0).abs()
 }
 
-fn part1(input: &str, target_y: i64) -> usize {
-    let input = input
+fn parse_input(input: &str) -> Vec<(Point, Point)> {
+    input
         .trim()
         .lines()
         .map(|line| {
@@ -20,83 +20,37 @@ fn part1(input: &str, target_y: i64) -> usize {
             .unwrap();
             ((sx, sy), (bx, by))
         })
-        .collect::<Vec<_>>();
-
-    let mut x_no_beacons = HashSet::<i64>::new();
-    let mut x_beacons = HashSet::<i64>::new();
-
-    for (sensor, beacon) in input {
-        let dist = manhattan_distance(sensor, beacon);
-
-        if beacon.1 == target_y {
-            x_beacons.insert(beacon.0);
-            x_no_beacons.remove(&beacon.0);
-        }
+        .collect()
+}
 
-        let dist_to_y = (sensor.1 - target_y).abs();
+/// The x coordinates on row `y` that are closer to a sensor than its beacon.
+fn coverage(input: &[(Point, Point)], y: i64) -> IntervalSet<i64> {
+    input
+        .iter()
+        .filter_map(|&(sensor, beacon)| {
+            let reach = manhattan_distance(sensor, beacon) - (sensor.1 - y).abs();
+            (reach >= 0).then(|| Interval::inclusive(sensor.0 - reach, sensor.0 + reach))
+        })
+        .collect()
+}
 
-        if dist >= dist_to_y {
-            let mut dx = 0;
-            while manhattan_distance(sensor, (sensor.0 + dx, target_y)) <= dist {
-                let a = sensor.0 + dx;
-                let b = sensor.0 - dx;
-                if !x_beacons.contains(&a) {
-                    x_no_beacons.insert(a);
-                }
-                if !x_beacons.contains(&b) {
-                    x_no_beacons.insert(b);
-                }
-                dx += 1;
-            }
+fn part1(input: &str, target_y: i64) -> u64 {
+    let input = parse_input(input);
+    let mut no_beacons = coverage(&input, target_y);
+    for &(_, (bx, by)) in &input {
+        if by == target_y {
+            no_beacons.remove(Interval::inclusive(bx, bx));
         }
     }
-
-    x_no_beacons.len()
+    no_beacons.len()
 }
 
 fn part2(input: &str, min_xy: i64, max_xy: i64) -> i64 {
-    let input = input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (sx, sy, bx, by) = sscanf::scanf!(
-                line,
-                "Sensor at x={i64}, y={i64}: closest beacon is at x={i64}, y={i64}"
-            )
-            .unwrap();
-            ((sx, sy), (bx, by))
-        })
-        .collect::<Vec<_>>();
-
-    let sensors: Vec<(Point, i64)> = input
-        .iter()
-        .map(|&(s, b)| (s, manhattan_distance(s, b)))
-        .collect();
-
-    let is_outside_all = |(x, y): Point| {
-        x >= min_xy
-            && x <= max_xy
-            && y >= min_xy
-            && y <= max_xy
-            && sensors
-                .iter()
-                .all(|&(s, d)| manhattan_distance(s, (x, y)) > d)
-    };
-
-    for (sensor, dist) in sensors.clone() {
-        let (x, y) = sensor;
-        for dy in 0..=(dist + 1) {
-            let dx = (dist + 1) - dy;
-            for p in [
-                (x + dx, y + dy),
-                (x + dx, y - dy),
-                (x - dx, y + dy),
-                (x - dx, y - dy),
-            ] {
-                if is_outside_all(p) {
-                    return (p.0 * 4000000) + p.1;
-                }
-            }
+    let input = parse_input(input);
+    let bounds = IntervalSet::from(Interval::inclusive(min_xy, max_xy));
+    for y in min_xy..=max_xy {
+        if let Some(x) = bounds.difference(&coverage(&input, y)).min() {
+            return x * 4000000 + y;
         }
     }
     unreachable!();
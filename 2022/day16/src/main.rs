@@ -1,6 +1,5 @@
-use petgraph::algo::floyd_warshall;
-use petgraph::{Directed, Graph};
-use std::collections::{HashMap, VecDeque};
+use bitset::{subset_maxima, BitSet, Interner};
+use graph::{all_pairs_bfs, Graph};
 
 fn parse_input(input: &str) -> Vec<(String, usize, Vec<String>)> {
     input
@@ -28,145 +27,101 @@ fn parse_input(input: &str) -> Vec<(String, usize, Vec<String>)> {
         .collect()
 }
 
-fn calc_distances(input: &[(String, usize, Vec<String>)]) -> HashMap<(String, String), usize> {
-    let mut graph: Graph<String, (), Directed> = Graph::new();
-    let graph_nodes = input
-        .iter()
-        .map(|(name, _, _)| (name.clone(), graph.add_node(name.clone())))
-        .collect::<HashMap<_, _>>();
-    for (id, _, next) in input.iter() {
-        for dest in next.iter() {
-            graph.add_edge(graph_nodes[id], graph_nodes[dest], ());
-        }
-    }
-    floyd_warshall(&graph, |_| 1)
-        .unwrap()
-        .iter()
-        .map(|((from, to), cost)| {
-            (
-                (
-                    graph.node_weight(*from).unwrap().clone(),
-                    graph.node_weight(*to).unwrap().clone(),
-                ),
-                *cost as usize,
-            )
-        })
-        .collect()
-}
-
-fn extend_vec<T: Clone>(vec: &Vec<T>, el: T) -> Vec<T> {
-    let mut result = Vec::with_capacity(vec.len() + 1);
-    for it in vec {
-        result.push(it.clone());
-    };
-    result.push(el);
-    result
+/// The valves worth opening, numbered from 0, and the start valve after them.
+struct Valves {
+    rates: Vec<usize>,
+    /// Minutes it takes to walk between any two of the valves.
+    distances: Vec<Vec<usize>>,
+    start: usize,
 }
 
-fn part1(input: &str) -> usize {
-    let input = parse_input(input);
-    let distances = calc_distances(&input);
-    let nodes_with_rate = input
-        .iter()
-        .filter(|(_, r, _)| r > &0)
-        .map(|(origin, rate, _)| (origin.clone(), *rate))
-        .collect::<HashMap<String, usize>>();
+impl Valves {
+    fn new(input: &str) -> Self {
+        let input = parse_input(input);
+        let graph = Graph::from_adjacency(
+            true,
+            input
+                .iter()
+                .map(|(id, _, next)| (id.as_str(), next.iter().map(String::as_str))),
+        );
+        let all_distances = all_pairs_bfs(&graph);
+        let mut valves: Interner<&str> = input
+            .iter()
+            .filter(|(_, rate, _)| *rate > 0)
+            .map(|(id, _, _)| id.as_str())
+            .collect();
+        let rates = input
+            .iter()
+            .filter(|(_, rate, _)| *rate > 0)
+            .map(|(_, rate, _)| *rate)
+            .collect();
+        let start = valves.intern("AA");
+        let ids: Vec<_> = valves
+            .iter()
+            .map(|(_, name)| graph.id(name).unwrap())
+            .collect();
+        let distances = ids
+            .iter()
+            .map(|&from| {
+                ids.iter()
+                    .map(|&to| all_distances.get(from, to).unwrap() as usize)
+                    .collect()
+            })
+            .collect();
+        Valves {
+            rates,
+            distances,
+            start,
+        }
+    }
 
-    let minutes = 30;
-    let mut best = 0;
-    let mut queue = VecDeque::new();
-    let start = "AA".to_string();
-    queue.push_back((&start, 0, vec![], 0));
+    /// The most pressure that can be released within `minutes` by opening
+    /// exactly the valves in a set, indexed by the set's mask.
+    fn best_per_set(&self, minutes: usize) -> Vec<usize> {
+        let mut best = vec![0; 1 << self.rates.len()];
+        self.explore(self.start, BitSet::EMPTY, minutes, 0, &mut best);
+        best
+    }
 
-    while let Some((id, minute, open, released)) = queue.pop_front() {
-        if released > best {
-            best = released;
-        }
-        for next in nodes_with_rate.keys() {
-            if !open.contains(&next) {
-                let dist = distances.get(&(id.clone(), (*next).clone())).unwrap();
-                if minute + dist < minutes {
-                    let rate = nodes_with_rate.get(next).unwrap();
-                    let released = released + rate * (minutes - dist - minute - 1);
-                    queue.push_back((next, minute + dist + 1, extend_vec(&open, next), released));
-                }
+    fn explore(
+        &self,
+        at: usize,
+        open: BitSet,
+        minutes: usize,
+        released: usize,
+        best: &mut [usize],
+    ) {
+        let slot = &mut best[open.0 as usize];
+        *slot = (*slot).max(released);
+        for next in (BitSet::full(self.rates.len()) - open).iter() {
+            // walking there and opening it
+            let cost = self.distances[at][next] + 1;
+            if cost < minutes {
+                let minutes = minutes - cost;
+                let released = released + self.rates[next] * minutes;
+                self.explore(next, open.with(next), minutes, released, best);
             }
         }
     }
+}
 
-    best
+fn part1(input: &str) -> usize {
+    let valves = Valves::new(input);
+    valves.best_per_set(30).into_iter().max().unwrap()
 }
 
 fn part2(input: &str) -> usize {
-    let input = parse_input(input);
-    let distances = calc_distances(&input);
-    let nodes_with_rate = input
-        .iter()
-        .filter(|(_, r, _)| r > &0)
-        .map(|(origin, rate, _)| (origin.clone(), *rate))
-        .collect::<HashMap<String, usize>>();
-
-    let minutes = 26;
-    let mut best = 0;
-    let mut queue = VecDeque::new();
-    let start = "AA".to_string();
-    queue.push_back((&start, &start, 0, 0, vec![], 0));
-    let mut best_cache = HashMap::new();
-
-    while let Some((a, b, a_minute, b_minute, open, released)) = queue.pop_front() {
-        if released > best {
-            best = released;
-        }
-
-        let k = (a, b, a_minute, b_minute);
-        if let Some(&prev_best) = best_cache.get(&k) {
-            if prev_best >= released {
-                continue;
-            }
-        }
-        best_cache.insert(k, released);
-
-        let [a_next, b_next] = [(a, a_minute), (b, b_minute)].map(|(node, min)| {
-            nodes_with_rate
-                .keys()
-                .filter(|d| {
-                    !open.contains(d)
-                        && distances.get(&(node.clone(), (*d).clone())).unwrap() < &(minutes - min)
-                })
-                .collect::<Vec<_>>()
-        });
-
-        for &a_next in a_next.iter() {
-            let a_rate = nodes_with_rate.get(a_next).unwrap();
-            let a_dist = distances.get(&(a.clone(), (*a_next).clone())).unwrap();
-            let a_newly_released = a_rate * (minutes - a_dist - a_minute - 1);
-            queue.push_back((
-                a_next,
-                b,
-                a_minute + a_dist + 1,
-                b_minute,
-                extend_vec(&open, a_next),
-                released + a_newly_released,
-            ));
-        }
-
-        for &b_next in b_next.iter() {
-            let b_rate = nodes_with_rate.get(b_next).unwrap();
-            let b_dist = distances.get(&(b.clone(), (*b_next).clone())).unwrap();
-            let b_newly_released = b_rate * (minutes - b_dist - b_minute - 1);
-
-            queue.push_back((
-                a,
-                b_next,
-                a_minute,
-                b_minute + b_dist + 1,
-                extend_vec(&open, b_next),
-                released + b_newly_released,
-            ));
-        }
-    }
-
-    best
+    let valves = Valves::new(input);
+    let n = valves.rates.len();
+    let mut best = valves.best_per_set(26);
+    // the elephant and I open disjoint sets, and the best for the valves
+    // left to the elephant may come from any of their subsets
+    subset_maxima(&mut best, n);
+    let all = BitSet::<u64>::full(n);
+    all.subsets()
+        .map(|mine| best[mine.0 as usize] + best[(all - mine).0 as usize])
+        .max()
+        .unwrap()
 }
 
 fn main() {
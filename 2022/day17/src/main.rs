@@ -1,38 +1,13 @@
 // Problem: https://adventofcode.com/2022/day/17
 
+use cycle::{extrapolate, find_delta_cycle};
+use geometry::{Dir4, Point2};
 use itertools::Itertools;
-use std::ops::Add;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
-struct Point {
-    x: i64,
-    y: i64,
-}
-
-impl Add<Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Self::Output {
-        point(self.x + rhs.x, self.y + rhs.y)
-    }
-}
-impl Add<Point> for &Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Self::Output {
-        point(self.x + rhs.x, self.y + rhs.y)
-    }
-}
-
-fn point(x: i64, y: i64) -> Point {
-    Point { x, y }
-}
-
-impl From<(i64, i64)> for Point {
-    fn from((x, y): (i64, i64)) -> Self {
-        point(x, y)
-    }
-}
+/// A position in the chamber, with `y` growing upwards from the floor.
+type Point = Point2<i64>;
 
-const SHAPE_ORIGIN: Point = Point { x: 0, y: 0 };
+const SHAPE_ORIGIN: Point = Point::ORIGIN;
 
 #[derive(Debug, Clone)]
 struct Shape(Vec<Point>, Point);
@@ -56,15 +31,14 @@ impl From<Vec<(i64, i64)>> for Shape {
 }
 
 fn shapes() -> Vec<Shape> {
-    vec![
+    [
         vec![(0, 0), (1, 0), (2, 0), (3, 0)],
         vec![(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
         vec![(2, 0), (2, 1), (2, 2), (1, 0), (0, 0)],
         vec![(0, 0), (0, 1), (0, 2), (0, 3)],
         vec![(0, 0), (1, 0), (0, 1), (1, 1)],
     ]
-    .iter()
-    .cloned()
+    .into_iter()
     .map(|v| v.into())
     .collect()
 }
@@ -77,40 +51,27 @@ impl Rock {
     fn new(shape: Shape, top: i64) -> Self {
         Rock {
             shape,
-            offset: point(2, top + 4),
+            offset: Point::new(2, top + 4),
         }
     }
-    fn shift_left(&mut self) {
-        if self.offset.x > 0 {
-            self.offset.x -= 1;
+    /// Moves one step east or west, unless that leaves the chamber.
+    fn shift(&mut self, dir: Dir4) {
+        let next = self.offset + dir.unit_cartesian();
+        if next.x >= 0 && next.x + self.shape.width() <= 7 {
+            self.offset = next;
         }
     }
-    fn shift_right(&mut self) {
-        let width = self.shape.width();
-        if self.offset.x + width < 7 {
-            self.offset.x += 1;
-        }
-    }
-    fn shift(&mut self, dir: &Jet) {
-        match dir {
-            Jet::Left => self.shift_left(),
-            Jet::Right => self.shift_right(),
-        };
-    }
-    fn undo_shift(&mut self, dir: &Jet) {
-        match dir {
-            Jet::Left => self.shift_right(),
-            Jet::Right => self.shift_left(),
-        };
+    fn undo_shift(&mut self, dir: Dir4) {
+        self.shift(dir.opposite());
     }
     fn down(&mut self) {
-        self.offset = self.offset + point(0, -1);
+        self.offset += Dir4::South.unit_cartesian();
     }
     fn up(&mut self) {
-        self.offset = self.offset + point(0, 1);
+        self.offset += Dir4::North.unit_cartesian();
     }
     fn serialize_points(&self, offset: Option<Point>) -> Vec<Point> {
-        let off = self.offset + offset.unwrap_or_else(|| point(0, 0));
+        let off = self.offset + offset.unwrap_or(Point::ORIGIN);
         self.shape.0.clone().iter().map(|p| *p + off).collect()
     }
     fn collides(&self, rested: &Rested) -> bool {
@@ -120,11 +81,6 @@ impl Rock {
     }
 }
 
-enum Jet {
-    Left,
-    Right,
-}
-
 struct Rested(Vec<u8>);
 impl Rested {
     fn set(&mut self, p: &Point) {
@@ -154,13 +110,13 @@ impl Rested {
 
 fn drop_shape(
     shape: &Shape,
-    jets: &mut impl Iterator<Item = Jet>,
+    jets: &mut impl Iterator<Item = Dir4>,
     rested: &Rested,
     top: i64,
 ) -> Vec<Point> {
     let rock = &mut Rock::new(shape.clone(), top);
     loop {
-        let dir = &jets.next().unwrap();
+        let dir = jets.next().unwrap();
         rock.shift(dir);
         if rock.collides(rested) {
             rock.undo_shift(dir);
@@ -174,121 +130,46 @@ fn drop_shape(
     rock.serialize_points(None)
 }
 
-fn part1(input: &str) -> usize {
+/// The height of the tower after each of the first `drops` rocks, starting
+/// with the empty floor.
+fn tower_heights(input: &str, drops: usize) -> Vec<i64> {
     let mut jets = input
         .trim()
         .chars()
-        .map(|c| match c {
-            '>' => Jet::Right,
-            '<' => Jet::Left,
-            _ => panic!(),
-        })
+        .map(|c| Dir4::from_char(c).unwrap())
         .cycle();
     let shapes = shapes();
 
     let mut top: i64 = 0;
     let mut rested = Rested(vec![]);
+    let mut heights = vec![top];
 
-    for (i, shape) in shapes.iter().cycle().enumerate() {
-        if i >= 2022 {
-            break;
-        }
+    for shape in shapes.iter().cycle().take(drops) {
         let points = drop_shape(shape, &mut jets, &rested, top);
         for point in points {
             top = std::cmp::max(point.y, top);
             rested.set(&point);
         }
+        heights.push(top);
     }
 
-    top as usize
+    heights
 }
 
-fn is_rep(values: &Vec<u8>, rep_len: usize) -> bool {
-    let len = values.len();
-    let l1 = len - 1;
-    let l2 = len - 1 - rep_len;
-    (0..rep_len).all(|o| values[l1 - o] == values[l2 - o])
+fn part1(input: &str) -> usize {
+    tower_heights(input, 2022)[2022] as usize
 }
 
 fn part2(input: &str) -> i64 {
-    let mut jets = input
-        .trim()
-        .chars()
-        .map(|c| match c {
-            '>' => Jet::Right,
-            '<' => Jet::Left,
-            _ => panic!(),
-        })
-        .cycle();
-    let shapes = shapes();
-    let mut shapes = shapes.iter().cycle();
-
-    let mut top: i64 = 0;
-    let mut rested = Rested(vec![]);
-    let mut dropped = 0;
-    let rep;
-
-    'repfinder: loop {
-        for _ in 0..10_000 {
-            let points = drop_shape(shapes.next().unwrap(), &mut jets, &rested, top);
-            for p in points {
-                rested.set(&p);
-                if p.y > top {
-                    top = p.y;
-                }
-            }
-        }
-        dropped += 10_000;
-
-        let mut len = 5;
-
-        loop {
-            if is_rep(&rested.0, len) {
-                rep = len;
-                break 'repfinder;
-            }
-            len += 5;
-            if len >= rested.0.len() / 2 {
-                break;
-            }
-        }
-    }
-
-    let idx = rested.0.len() - rep;
-    let seg: Vec<u8> = Vec::from(&rested.0[idx..]);
-
-    let mut rep_drops = 0;
+    let target = 1000000000000;
+    let mut drops = 10_000;
     loop {
-        rep_drops += 1;
-        let points = drop_shape(shapes.next().unwrap(), &mut jets, &rested, top);
-        for p in points {
-            rested.set(&p);
-            if p.y > top {
-                top = p.y;
-            }
-        }
-
-        if rested.0.len() - idx == 2 * rep {
-            assert_eq!(Vec::from(&rested.0[(idx + rep)..]), seg);
-            break;
+        let heights = tower_heights(input, drops);
+        if let Some(cycle) = find_delta_cycle(&heights) {
+            return extrapolate(&heights, cycle, target);
         }
+        drops *= 2;
     }
-
-    let target: i64 = 1000000000000;
-    let skipped = (target - dropped - rep_drops) / rep_drops;
-
-    let remaining = (target - dropped) % rep_drops;
-    for _ in 0..remaining {
-        let points = drop_shape(shapes.next().unwrap(), &mut jets, &rested, top);
-        for p in points {
-            rested.set(&p);
-            if p.y > top {
-                top = p.y;
-            }
-        }
-    }
-
-    top + skipped * rep as i64
 }
 
 fn main() {
@@ -1,7 +1,7 @@
 // Problem: https://adventofcode.com/2022/day/19
 
 use rayon::prelude::*;
-use std::collections::{HashSet, VecDeque};
+use search::{astar, SearchProblem};
 
 #[derive(Debug, Clone)]
 struct Blueprint {
@@ -68,74 +68,73 @@ fn run_robots(state: &mut State) {
     state.minute += 1;
 }
 
-fn can_eleminate_early(state: &State, max: usize, time: usize) -> bool {
-    let mut making = state.geode_collectors;
-    let mut geodes = state.geode;
-    for _ in state.minute..=time {
-        geodes += making;
-        making += 1;
-    }
-    geodes < max
+/// Collecting geodes until `time` runs out. Every minute costs `time` minus
+/// the number of geodes collected in it, so the cheapest path to the end is
+/// the one that collects the most geodes.
+struct Factory<'a> {
+    blueprint: &'a Blueprint,
+    time: usize,
+    max_ore_prod: usize,
 }
 
-fn max_obsidian_output(blueprint: &Blueprint, time: usize) -> usize {
-    let max_ore_prod = *vec![
-        blueprint.ore_robot_cost_ore,
-        blueprint.clay_robot_cost_ore,
-        blueprint.obsidian_robot_cost_ore,
-        blueprint.geode_robot_cost_ore,
-    ]
-    .iter()
-    .max()
-    .unwrap();
-    let max_clay_prod = blueprint.obsidian_robot_cost_clay;
-    let max_obsidian_prod = blueprint.geode_robot_cost_obsidian;
-    let mut queue: VecDeque<State> = vec![State {
-        minute: 0,
-        ore: 0,
-        clay: 0,
-        obsidian: 0,
-        geode: 0,
-        ore_collectors: 1,
-        clay_collectors: 0,
-        obidian_collectors: 0,
-        geode_collectors: 0,
-    }]
-    .into();
-    let mut seen = HashSet::new();
-    let mut max = 0;
-
-    while !queue.is_empty() {
-        let state = queue.pop_front().unwrap();
-
-        if state.minute == time {
-            if state.geode > max {
-                max = state.geode;
-            }
-            continue;
+impl Factory<'_> {
+    fn new(blueprint: &Blueprint, time: usize) -> Factory<'_> {
+        let max_ore_prod = [
+            blueprint.ore_robot_cost_ore,
+            blueprint.clay_robot_cost_ore,
+            blueprint.obsidian_robot_cost_ore,
+            blueprint.geode_robot_cost_ore,
+        ]
+        .into_iter()
+        .max()
+        .unwrap();
+        Factory {
+            blueprint,
+            time,
+            max_ore_prod,
         }
+    }
 
-        if seen.contains(&state) {
-            continue;
-        }
-        seen.insert(state);
+    /// Geodes collected in the remaining minutes if a new geode collector is
+    /// built in each of them.
+    fn max_additional_geodes(&self, state: &State) -> usize {
+        let remaining = self.time - state.minute;
+        remaining * state.geode_collectors + remaining * remaining.saturating_sub(1) / 2
+    }
 
-        if can_eleminate_early(&state, max, time) {
-            continue;
+    fn cost_of(&self, from: &State, to: &State) -> usize {
+        (to.minute - from.minute) * self.time - (to.geode - from.geode)
+    }
+}
+
+impl SearchProblem for Factory<'_> {
+    type State = State;
+    type Cost = usize;
+
+    fn start(&self) -> State {
+        State {
+            minute: 0,
+            ore: 0,
+            clay: 0,
+            obsidian: 0,
+            geode: 0,
+            ore_collectors: 1,
+            clay_collectors: 0,
+            obidian_collectors: 0,
+            geode_collectors: 0,
         }
+    }
+
+    fn successors(&self, state: &State) -> Vec<(State, usize)> {
+        let blueprint = self.blueprint;
+        let state = *state;
+        let mut next = vec![];
 
         if can_make_geode_collector_every_minute(&state, blueprint) {
-            let mut making = state.geode_collectors;
-            let mut geodes = state.geode;
-
-            for _ in state.minute..time {
-                geodes += making;
-                making += 1;
-            }
-            if geodes > max {
-                max = geodes;
-            }
-            continue;
+            let mut end = state;
+            end.geode += self.max_additional_geodes(&state);
+            end.minute = self.time;
+            return vec![(end, self.cost_of(&state, &end))];
         }
 
         if state.obsidian >= blueprint.geode_robot_cost_obsidian
@@ -146,9 +145,9 @@ fn max_obsidian_output(blueprint: &Blueprint, time: usize) -> usize {
             state.obsidian -= blueprint.geode_robot_cost_obsidian;
             run_robots(&mut state);
             state.geode_collectors += 1;
-            queue.push_back(state);
+            next.push(state);
         }
-        if state.obidian_collectors < max_obsidian_prod
+        if state.obidian_collectors < blueprint.geode_robot_cost_obsidian
             && state.clay >= blueprint.obsidian_robot_cost_clay
             && state.ore >= blueprint.obsidian_robot_cost_ore
         {
@@ -157,28 +156,48 @@ fn max_obsidian_output(blueprint: &Blueprint, time: usize) -> usize {
             state.clay -= blueprint.obsidian_robot_cost_clay;
             run_robots(&mut state);
             state.obidian_collectors += 1;
-            queue.push_back(state);
+            next.push(state);
         }
-        if state.clay_collectors < max_clay_prod && state.ore >= blueprint.clay_robot_cost_ore {
+        if state.clay_collectors < blueprint.obsidian_robot_cost_clay
+            && state.ore >= blueprint.clay_robot_cost_ore
+        {
             let mut state = state;
             state.ore -= blueprint.clay_robot_cost_ore;
             run_robots(&mut state);
             state.clay_collectors += 1;
-            queue.push_back(state);
+            next.push(state);
         }
-        if state.ore_collectors < max_ore_prod && state.ore >= blueprint.ore_robot_cost_ore {
+        if state.ore_collectors < self.max_ore_prod && state.ore >= blueprint.ore_robot_cost_ore {
             let mut state = state;
             state.ore -= blueprint.ore_robot_cost_ore;
             run_robots(&mut state);
             state.ore_collectors += 1;
-            queue.push_back(state);
+            next.push(state);
         }
 
-        let mut state = state;
-        run_robots(&mut state);
-        queue.push_back(state);
+        let mut waiting = state;
+        run_robots(&mut waiting);
+        next.push(waiting);
+
+        next.into_iter()
+            .map(|to| (to, self.cost_of(&state, &to)))
+            .collect()
+    }
+
+    fn is_goal(&self, state: &State) -> bool {
+        state.minute == self.time
     }
-    max
+
+    /// Assumes a geode collector can be built in every remaining minute,
+    /// which is never worse than reality.
+    fn heuristic(&self, state: &State) -> usize {
+        (self.time - state.minute) * self.time - self.max_additional_geodes(state)
+    }
+}
+
+fn max_obsidian_output(blueprint: &Blueprint, time: usize) -> usize {
+    let factory = Factory::new(blueprint, time);
+    astar(&factory).solution.unwrap().goal().geode
 }
 
 fn part1(input: &str) -> usize {
@@ -1,52 +1,15 @@
 // Problem: https://adventofcode.com/2022/day/22
+use geometry::{Dir4, Point2, Turn};
 use itertools::Itertools;
 use std::collections::HashMap;
-use Direction::*;
+use vis::{Export, Frame, Palette};
+use Dir4::*;
 
-type Point = (i32, i32);
+/// A tile of the board, with `y` counting rows down from the top.
+type Point = Point2<i32>;
 
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Up => Right,
-            Right => Down,
-            Down => Left,
-            Left => Up,
-        }
-    }
-    fn turn_left(&self) -> Self {
-        match self {
-            Up => Left,
-            Right => Up,
-            Down => Right,
-            Left => Down,
-        }
-    }
-    fn turn_around(&self) -> Self {
-        match self {
-            Up => Down,
-            Right => Left,
-            Down => Up,
-            Left => Right,
-        }
-    }
-    fn go(&self, &(row, col): &Point) -> Point {
-        match self {
-            Up => (row - 1, col),
-            Right => (row, col + 1),
-            Down => (row + 1, col),
-            Left => (row, col - 1),
-        }
-    }
-}
+/// Which face of the cube net a tile is on, as (row, col) in face widths.
+type Segment = (i32, i32);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Space {
@@ -66,10 +29,10 @@ impl Space {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 struct State {
     pos: Point,
-    facing: Direction,
+    facing: Dir4,
 }
 
 fn parse_board(input: &str) -> Board {
@@ -77,19 +40,16 @@ fn parse_board(input: &str) -> Board {
 
     for (row, line) in input.lines().enumerate() {
         for (col, ch) in line.chars().enumerate() {
-            map.insert((row as i32, col as i32), Space::from_char(ch));
+            map.insert(
+                Point::from_row_col(row as i32, col as i32),
+                Space::from_char(ch),
+            );
         }
     }
 
     Board(map)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-enum Turn {
-    Right,
-    Left,
-}
-
 #[derive(Debug, Clone)]
 enum Instruction {
     Go(usize),
@@ -142,9 +102,8 @@ impl Board {
     fn row_bounds(&self, row: i32) -> (i32, i32) {
         self.0
             .iter()
-            .filter(|(&(r, _), &v)| row == r && v != Space::Empty)
-            .map(|((_, col), _)| col)
-            .cloned()
+            .filter(|(p, &v)| p.y == row && v != Space::Empty)
+            .map(|(p, _)| p.x)
             .minmax()
             .into_option()
             .unwrap()
@@ -152,38 +111,41 @@ impl Board {
     fn col_bounds(&self, col: i32) -> (i32, i32) {
         self.0
             .iter()
-            .filter(|(&(_, c), &v)| col == c && v != Space::Empty)
-            .map(|((row, _), _)| row)
-            .cloned()
+            .filter(|(p, &v)| p.x == col && v != Space::Empty)
+            .map(|(p, _)| p.y)
             .minmax()
             .into_option()
             .unwrap()
     }
 }
 
-type WrapFn = fn(Point, Direction, &Board, i32) -> (Point, Direction);
+type WrapFn = fn(Point, Dir4, &Board, i32) -> (Point, Dir4);
 
-fn wrap((row, col): Point, dir: Direction, board: &Board, _: i32) -> (Point, Direction) {
+fn wrap(pos: Point, dir: Dir4, board: &Board, _: i32) -> (Point, Dir4) {
+    let (row, col) = pos.to_row_col();
     let next = match dir {
-        Up => (board.col_bounds(col).1, col),
-        Right => (row, board.row_bounds(row).0),
-        Down => (board.col_bounds(col).0, col),
-        Left => (row, board.row_bounds(row).1),
+        North => Point::from_row_col(board.col_bounds(col).1, col),
+        East => Point::from_row_col(row, board.row_bounds(row).0),
+        South => Point::from_row_col(board.col_bounds(col).0, col),
+        West => Point::from_row_col(row, board.row_bounds(row).1),
     };
 
     (next, dir)
 }
 
+/// Follows the instructions, returning every state on the way with the
+/// final one last.
 fn make_moves(
     board: &Board,
     instructions: Vec<Instruction>,
     wrap_around: WrapFn,
     sqs: i32,
-) -> State {
+) -> Vec<State> {
     let mut state = State {
-        pos: (0, board.row_bounds(0).0),
-        facing: Direction::Right,
+        pos: Point::from_row_col(0, board.row_bounds(0).0),
+        facing: East,
     };
+    let mut trail = vec![state];
 
     for instruction in instructions {
         match instruction {
@@ -191,7 +153,7 @@ fn make_moves(
                 for _ in 0..distance {
                     let pos = state.pos;
                     let facing = state.facing;
-                    let next_pos = facing.go(&pos);
+                    let next_pos = pos + facing.unit_screen();
                     match board.get(next_pos) {
                         Space::Open => {
                             state = State {
@@ -218,34 +180,28 @@ fn make_moves(
                             }
                         }
                     }
+                    trail.push(state);
                 }
             }
-            Instruction::Turn(turn) => match turn {
-                Turn::Right => {
-                    state = State {
-                        pos: state.pos,
-                        facing: state.facing.turn_right(),
-                    }
-                }
-                Turn::Left => {
-                    state = State {
-                        pos: state.pos,
-                        facing: state.facing.turn_left(),
-                    }
-                }
-            },
+            Instruction::Turn(turn) => {
+                state = State {
+                    pos: state.pos,
+                    facing: state.facing.turn(turn),
+                };
+                trail.push(state);
+            }
         }
     }
-    state
+    trail
 }
 
 fn compute_password(state: State) -> i32 {
-    let (row, col) = state.pos;
+    let (row, col) = state.pos.to_row_col();
     let face_value: i32 = match state.facing {
-        Up => 3,
-        Right => 0,
-        Down => 1,
-        Left => 2,
+        North => 3,
+        East => 0,
+        South => 1,
+        West => 2,
     };
     (row + 1) * 1000 + (col + 1) * 4 + face_value
 }
@@ -255,8 +211,8 @@ fn part1(input: &str) -> i32 {
     let board = parse_board(board);
     let instructions = parse_instructions(instructions.trim());
 
-    let state = make_moves(&board, instructions, wrap, 0);
-    compute_password(state)
+    let trail = make_moves(&board, instructions, wrap, 0);
+    compute_password(*trail.last().unwrap())
 }
 
 #[test]
@@ -264,60 +220,62 @@ fn test_part1() {
     assert_eq!(part1(include_str!("test.txt")), 6032);
 }
 
-fn wrap_on_cube((row, col): Point, dir: Direction, _board: &Board, sqs: i32) -> (Point, Direction) {
-    let seg = (row / sqs, col / sqs);
-    let translate = |dst_seg: Point, dest_side: Direction, flip: bool| -> (Point, Direction) {
+fn wrap_on_cube(pos: Point, dir: Dir4, _board: &Board, sqs: i32) -> (Point, Dir4) {
+    let (row, col) = pos.to_row_col();
+    let seg: Segment = (row / sqs, col / sqs);
+    let translate = |dst_seg: Segment, dest_side: Dir4, flip: bool| -> (Point, Dir4) {
         let mut n = match dir {
-            Up => col,
-            Right => row,
-            Down => col,
-            Left => row,
+            North | South => col,
+            East | West => row,
         } % sqs;
         if flip {
             n = sqs - n - 1;
         }
-        let dest = match dest_side {
-            Up => (dst_seg.0 * sqs, dst_seg.1 * sqs + n),
-            Right => (dst_seg.0 * sqs + n, (dst_seg.1 + 1) * sqs - 1),
-            Down => ((dst_seg.0 + 1) * sqs - 1, dst_seg.1 * sqs + n),
-            Left => (dst_seg.0 * sqs + n, dst_seg.1 * sqs),
+        let (dest_row, dest_col) = match dest_side {
+            North => (dst_seg.0 * sqs, dst_seg.1 * sqs + n),
+            East => (dst_seg.0 * sqs + n, (dst_seg.1 + 1) * sqs - 1),
+            South => ((dst_seg.0 + 1) * sqs - 1, dst_seg.1 * sqs + n),
+            West => (dst_seg.0 * sqs + n, dst_seg.1 * sqs),
         };
-        (dest, dest_side.turn_around())
+        (
+            Point::from_row_col(dest_row, dest_col),
+            dest_side.opposite(),
+        )
     };
     if sqs == 50 {
         match (seg, dir) {
-            ((0, 1), Up) => translate((3, 0), Left, false),
-            ((0, 1), Left) => translate((2, 0), Left, true),
-            ((0, 2), Up) => translate((3, 0), Down, false),
-            ((0, 2), Right) => translate((2, 1), Right, true),
-            ((0, 2), Down) => translate((1, 1), Right, false),
-            ((1, 1), Left) => translate((2, 0), Up, false),
-            ((1, 1), Right) => translate((0, 2), Down, false),
-            ((2, 0), Up) => translate((1, 1), Left, false),
-            ((2, 0), Left) => translate((0, 1), Left, true),
-            ((2, 1), Right) => translate((0, 2), Right, true),
-            ((2, 1), Down) => translate((3, 0), Right, false),
-            ((3, 0), Left) => translate((0, 1), Up, false),
-            ((3, 0), Right) => translate((2, 1), Down, false),
-            ((3, 0), Down) => translate((0, 2), Up, false),
+            ((0, 1), North) => translate((3, 0), West, false),
+            ((0, 1), West) => translate((2, 0), West, true),
+            ((0, 2), North) => translate((3, 0), South, false),
+            ((0, 2), East) => translate((2, 1), East, true),
+            ((0, 2), South) => translate((1, 1), East, false),
+            ((1, 1), West) => translate((2, 0), North, false),
+            ((1, 1), East) => translate((0, 2), South, false),
+            ((2, 0), North) => translate((1, 1), West, false),
+            ((2, 0), West) => translate((0, 1), West, true),
+            ((2, 1), East) => translate((0, 2), East, true),
+            ((2, 1), South) => translate((3, 0), East, false),
+            ((3, 0), West) => translate((0, 1), North, false),
+            ((3, 0), East) => translate((2, 1), South, false),
+            ((3, 0), South) => translate((0, 2), North, false),
             _ => panic!("invalid wrap {:?} {:?}", seg, dir),
         }
     } else if sqs == 4 {
         match (seg, dir) {
-            ((0, 2), Left) => translate((1, 1), Up, false),
-            ((0, 2), Up) => translate((1, 0), Up, true),
-            ((0, 2), Right) => translate((2, 3), Right, true),
-            ((1, 0), Left) => translate((2, 3), Down, true),
-            ((1, 0), Up) => translate((0, 2), Up, true),
-            ((1, 0), Down) => translate((2, 2), Down, true),
-            ((1, 1), Up) => translate((0, 2), Left, false),
-            ((1, 1), Down) => translate((2, 2), Left, true),
-            ((1, 2), Right) => translate((2, 3), Up, true),
-            ((2, 2), Left) => translate((1, 1), Down, true),
-            ((2, 2), Down) => translate((1, 0), Down, true),
-            ((2, 3), Up) => translate((1, 2), Right, true),
-            ((2, 3), Right) => translate((0, 2), Right, true),
-            ((2, 3), Down) => translate((1, 0), Left, true),
+            ((0, 2), West) => translate((1, 1), North, false),
+            ((0, 2), North) => translate((1, 0), North, true),
+            ((0, 2), East) => translate((2, 3), East, true),
+            ((1, 0), West) => translate((2, 3), South, true),
+            ((1, 0), North) => translate((0, 2), North, true),
+            ((1, 0), South) => translate((2, 2), South, true),
+            ((1, 1), North) => translate((0, 2), West, false),
+            ((1, 1), South) => translate((2, 2), West, true),
+            ((1, 2), East) => translate((2, 3), North, true),
+            ((2, 2), West) => translate((1, 1), South, true),
+            ((2, 2), South) => translate((1, 0), South, true),
+            ((2, 3), North) => translate((1, 2), East, true),
+            ((2, 3), East) => translate((0, 2), East, true),
+            ((2, 3), South) => translate((1, 0), West, true),
             _ => panic!("invalid wrap {:?} {:?}", seg, dir),
         }
     } else {
@@ -330,8 +288,43 @@ fn part2(input: &str, square_size: i32) -> i32 {
     let board = parse_board(board);
     let instructions = parse_instructions(instructions.trim());
 
-    let state = make_moves(&board, instructions, wrap_on_cube, square_size);
-    compute_password(state)
+    let trail = make_moves(&board, instructions, wrap_on_cube, square_size);
+    if let Some(export) = Export::from_env() {
+        export_path(&export, &board, &trail).unwrap();
+    }
+    compute_password(*trail.last().unwrap())
+}
+
+/// Saves the cube net with the path walked over it, each tile showing the
+/// way it was last faced.
+fn export_path(export: &Export, board: &Board, trail: &[State]) -> std::io::Result<()> {
+    let tiles = board.0.iter().filter_map(|(p, space)| match space {
+        Space::Empty => None,
+        Space::Open => Some((p, '.')),
+        Space::Wall => Some((p, '#')),
+    });
+    let steps = trail.iter().map(|state| {
+        let arrow = match state.facing {
+            North => '^',
+            East => '>',
+            South => 'v',
+            West => '<',
+        };
+        (&state.pos, arrow)
+    });
+    let frame = Frame::from_points(
+        tiles
+            .chain(steps)
+            .map(|(p, ch)| ((p.x as i64, p.y as i64), ch.into())),
+    );
+    let palette = Palette::new([0, 0, 0], [0, 0, 0])
+        .with('.', [60, 60, 70])
+        .with('#', [150, 140, 130])
+        .with('^', [240, 200, 80])
+        .with('>', [240, 200, 80])
+        .with('v', [240, 200, 80])
+        .with('<', [240, 200, 80]);
+    export.save(&frame, &palette)
 }
 
 #[test]
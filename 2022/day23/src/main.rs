@@ -1,163 +1,125 @@
 // Problem: https://adventofcode.com/2022/day/23
 
-use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    ops::Add,
-};
+use geometry::{Direction, Point2};
+use grid::SparseGrid;
+use std::collections::{HashMap, VecDeque};
+use vis::{Cell, Color, Frame, Player, Visualise};
 use Direction::*;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point(i32, i32);
-impl Add<Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-impl Point {
-    fn row(&self) -> i32 {
-        self.0
-    }
-    fn col(&self) -> i32 {
-        self.1
-    }
-}
+type Point = Point2<i32>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    N,
-    NE,
-    E,
-    SE,
-    S,
-    SW,
-    W,
-    NW,
-}
-impl Direction {
-    fn relative(&self) -> Point {
-        match self {
-            Direction::N => Point(-1, 0),
-            Direction::NE => Point(-1, 1),
-            Direction::E => Point(0, 1),
-            Direction::SE => Point(1, 1),
-            Direction::S => Point(1, 0),
-            Direction::SW => Point(1, -1),
-            Direction::W => Point(0, -1),
-            Direction::NW => Point(-1, -1),
-        }
-    }
-}
+type Elves = SparseGrid<Point, ()>;
 
-fn parse_locations(input: &str) -> HashSet<Point> {
-    let mut result = HashSet::new();
+fn parse_locations(input: &str) -> Elves {
+    let mut result = Elves::new(());
     for (row, line) in input.lines().enumerate() {
         for (col, ch) in line.chars().enumerate() {
             if ch == '#' {
-                result.insert(Point(row as i32, col as i32));
+                result.insert(Point::from_row_col(row as i32, col as i32), ());
             }
         }
     }
     result
 }
 
-fn check_target(p: Point, dir: Direction, points: &HashSet<Point>) -> bool {
+fn check_target(p: Point, dir: Direction, points: &Elves) -> bool {
     let dirs: [Direction; 3] = match dir {
-        N => [NW, N, NE],
-        S => [SW, S, SE],
-        W => [NW, W, SW],
-        E => [NE, E, SE],
+        North => [NorthWest, North, NorthEast],
+        South => [SouthWest, South, SouthEast],
+        West => [NorthWest, West, SouthWest],
+        East => [NorthEast, East, SouthEast],
         _ => panic!(),
     };
-    dirs.iter().all(|d| !points.contains(&(p + d.relative())))
-}
-
-fn bounds(elves: &HashSet<Point>) -> ((i32, i32), (i32, i32)) {
-    let (min_row, max_row) = elves
-        .iter()
-        .map(|p| p.row())
-        .minmax()
-        .into_option()
-        .unwrap();
-    let (min_col, max_col) = elves
-        .iter()
-        .map(|p| p.col())
-        .minmax()
-        .into_option()
-        .unwrap();
-
-    ((min_row, max_row), (min_col, max_col))
+    dirs.iter().all(|d| !points.contains(p + d.unit_screen()))
 }
 
 #[allow(dead_code)]
-fn print_elves(elves: &HashSet<Point>) -> String {
-    let ((min_row, max_row), (min_col, max_col)) = bounds(elves);
-    let mut result: String = String::new();
-    for row in min_row..=max_row {
-        for col in min_col..=max_col {
-            if elves.contains(&Point(row, col)) {
-                result += "#";
-            } else {
-                result += ".";
-            }
-        }
-        result += "\n";
-    }
-    result
+fn print_elves(elves: &Elves) -> String {
+    elves.render_xy(|elf| if elf.is_some() { '#' } else { '.' })
 }
 
-fn move_elves(elves: &HashSet<Point>, target_order: &VecDeque<Direction>) -> HashSet<Point> {
+fn move_elves(elves: &Elves, target_order: &VecDeque<Direction>) -> Elves {
     let mut move_targets: HashMap<Point, usize> = HashMap::new();
     let mut moves = vec![];
-    let mut next_elves = HashSet::new();
+    let mut next_elves = Elves::new(());
 
-    for p in elves.iter() {
-        if target_order.iter().all(|d| check_target(*p, *d, elves)) {
-            next_elves.insert(*p);
+    for p in elves.points() {
+        if target_order.iter().all(|d| check_target(p, *d, elves)) {
+            next_elves.insert(p, ());
         } else {
             let mut found = false;
             for t in target_order.clone() {
-                if check_target(*p, t, elves) {
-                    *move_targets.entry(*p + t.relative()).or_default() += 1;
-                    moves.push((*p, t));
+                if check_target(p, t, elves) {
+                    *move_targets.entry(p + t.unit_screen()).or_default() += 1;
+                    moves.push((p, t));
                     found = true;
                     break;
                 }
             }
             if !found {
-                next_elves.insert(*p);
+                next_elves.insert(p, ());
             }
         }
     }
 
     for (p, t) in moves {
-        let d = p + t.relative();
+        let d = p + t.unit_screen();
         if move_targets.get(&d).unwrap() == &1 {
-            assert!(!next_elves.contains(&d));
-            next_elves.insert(d);
+            assert!(!next_elves.contains(d));
+            next_elves.insert(d, ());
         } else {
-            assert!(!next_elves.contains(&p));
-            next_elves.insert(p);
+            assert!(!next_elves.contains(p));
+            next_elves.insert(p, ());
         }
     }
 
     next_elves
 }
 
+/// The elves spreading out, round by round.
+struct Spread {
+    elves: Elves,
+    target_order: VecDeque<Direction>,
+    rounds: usize,
+}
+
+impl Spread {
+    fn new(elves: Elves) -> Self {
+        Self {
+            elves,
+            target_order: vec![North, South, West, East].into(),
+            rounds: 0,
+        }
+    }
+
+    /// Plays one round, returning whether any elf moved.
+    fn round(&mut self) -> bool {
+        let next = move_elves(&self.elves, &self.target_order);
+        self.rounds += 1;
+        self.target_order.rotate_left(1);
+        let moved = next != self.elves;
+        self.elves = next;
+        moved
+    }
+}
+
+impl Visualise for Spread {
+    fn frame(&self) -> Frame {
+        Frame::from_sparse(&self.elves, |elf| match elf {
+            Some(()) => Cell::colored('#', Color::Green),
+            None => Cell::plain('.'),
+        })
+        .with_caption(format!("round {}", self.rounds))
+    }
+}
+
 fn part1(input: &str) -> usize {
-    let mut elves = parse_locations(input);
-    let mut target_order: VecDeque<Direction> = vec![N, S, W, E].into();
+    let mut spread = Spread::new(parse_locations(input));
     for _ in 0..10 {
-        elves = move_elves(&elves, &target_order);
-
-        let tmp = target_order.pop_front().unwrap();
-        target_order.push_back(tmp);
+        spread.round();
     }
 
-    let b = bounds(&elves);
-    let ((min_row, max_row), (min_col, max_col)) = b;
-    ((max_row - min_row + 1) as usize * (max_col - min_col + 1) as usize) - elves.len()
+    spread.elves.bounds_volume() - spread.elves.len()
 }
 
 #[test]
@@ -167,13 +129,13 @@ fn test_small() {
     let small2 = parse_locations(include_str!("small2.txt"));
     let small3 = parse_locations(include_str!("small3.txt"));
 
-    let calc1 = move_elves(&small, &[N, S, W, E].into());
+    let calc1 = move_elves(&small, &[North, South, West, East].into());
     assert_eq!(print_elves(&calc1), print_elves(&small1));
 
-    let calc2 = move_elves(&small1, &[S, W, E, N].into());
+    let calc2 = move_elves(&small1, &[South, West, East, North].into());
     assert_eq!(print_elves(&calc2), print_elves(&small2));
 
-    let calc3 = move_elves(&small2, &[W, E, N, S].into());
+    let calc3 = move_elves(&small2, &[West, East, North, South].into());
     assert_eq!(print_elves(&calc3), print_elves(&small3));
 }
 
@@ -183,18 +145,12 @@ fn test_part1() {
 }
 
 fn part2(input: &str) -> usize {
-    let mut elves = parse_locations(input);
-    let mut target_order: VecDeque<Direction> = vec![N, S, W, E].into();
-    for i in 0.. {
-        let next = move_elves(&elves, &target_order);
-        if next == elves {
-            return i + 1;
-        }
-        elves = next;
-        let tmp = target_order.pop_front().unwrap();
-        target_order.push_back(tmp);
+    let mut spread = Spread::new(parse_locations(input));
+    match Player::from_env() {
+        Some(mut player) => player.play(&mut spread, Spread::round).unwrap(),
+        None => while spread.round() {},
     }
-    unreachable!()
+    spread.rounds
 }
 
 #[test]
@@ -1,78 +1,33 @@
 // Problem: https://adventofcode.com/2022/day/24
 
-use std::{
-    collections::{HashSet, VecDeque},
-    ops::Add,
-};
-use Direction::*;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct Point(i32, i32);
-impl Add<Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-impl Point {
-    fn row(&self) -> i32 {
-        self.0
-    }
-    fn col(&self) -> i32 {
-        self.1
-    }
-}
+use geometry::{Dir4, Point2};
+use numtheory::lcm;
+use search::{bfs, SearchProblem};
+use std::collections::HashSet;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-impl Direction {
-    fn from_char(c: char) -> Self {
-        match c {
-            '>' => Right,
-            '<' => Left,
-            'v' => Down,
-            '^' => Up,
-            _ => panic!("invalid dir {}", c),
-        }
-    }
-    fn go(&self, &Point(row, col): &Point) -> Point {
-        match self {
-            Up => Point(row - 1, col),
-            Right => Point(row, col + 1),
-            Down => Point(row + 1, col),
-            Left => Point(row, col - 1),
-        }
-    }
-}
+/// A tile of the valley, with `y` counting rows down from the top.
+type Point = Point2<i32>;
 
 #[derive(Debug, Clone, Copy)]
 struct Bounds(Point, Point);
 
 impl Bounds {
     fn contains(&self, p: Point) -> bool {
-        let Point(r0, c0) = self.0;
-        let Point(r1, c1) = self.1;
-        let r = p.row();
-        let c = p.col();
-        r > r0 && r < r1 - 1 && c > c0 && c < c1 - 1
+        let (min, max) = (self.0, self.1);
+        p.x > min.x && p.x < max.x - 1 && p.y > min.y && p.y < max.y - 1
     }
 
     fn width(&self) -> i32 {
-        self.1 .1 - self.0 .1
+        self.1.x - self.0.x
     }
     fn height(&self) -> i32 {
-        self.1 .0 - self.0 .0
+        self.1.y - self.0.y
     }
 }
 
 #[derive(Debug)]
 struct Map {
-    blizzards: Vec<(Point, Direction)>,
+    blizzards: Vec<(Point, Dir4)>,
     bounds: Bounds,
     entry: Point,
     exit: Point,
@@ -84,29 +39,22 @@ impl Map {
             .map(|(p, d)| blizzard_at_time(*p, *d, minute, &self.bounds))
             .collect()
     }
-}
 
-fn blizzard_at_time(start: Point, dir: Direction, minute: usize, bounds: &Bounds) -> Point {
-    match dir {
-        Right => Point(
-            start.row(),
-            (start.col() - 1 + minute as i32) % (bounds.width() - 2) + 1,
-        ),
-        Left => Point(
-            start.row(),
-            (start.col() - 1 - minute as i32).rem_euclid(bounds.width() - 2) + 1,
-        ),
-        Up => Point(
-            (start.row() - 1 - minute as i32).rem_euclid(bounds.height() - 2) + 1,
-            start.col(),
-        ),
-        Down => Point(
-            (start.row() - 1 + minute as i32) % (bounds.height() - 2) + 1,
-            start.col(),
-        ),
+    /// Blizzard positions for every minute until they repeat.
+    fn blizzard_cycle(&self) -> Vec<HashSet<Point>> {
+        let period = lcm(self.bounds.width() - 2, self.bounds.height() - 2) as usize;
+        (0..period).map(|m| self.get_blizzards_at(m)).collect()
     }
 }
 
+fn blizzard_at_time(start: Point, dir: Dir4, minute: usize, bounds: &Bounds) -> Point {
+    let inside = start - Point::new(1, 1) + dir.unit_screen() * minute as i32;
+    Point::new(
+        inside.x.rem_euclid(bounds.width() - 2) + 1,
+        inside.y.rem_euclid(bounds.height() - 2) + 1,
+    )
+}
+
 fn parse_map(input: &str) -> Map {
     let lines: Vec<&str> = input.trim().lines().collect();
     let first = lines[0];
@@ -120,57 +68,84 @@ fn parse_map(input: &str) -> Map {
         let line = lines[row];
         for (col, ch) in line.chars().enumerate() {
             if ch != '.' && ch != '#' {
-                blizzards.push((Point(row as i32, col as i32), Direction::from_char(ch)));
+                let dir = Dir4::from_char(ch).unwrap_or_else(|| panic!("invalid dir {}", ch));
+                blizzards.push((Point::from_row_col(row as i32, col as i32), dir));
             }
         }
     });
     Map {
         blizzards,
-        bounds: Bounds(Point(0, 0), Point(height as i32, width as i32)),
-        entry: Point(0, entry_col as i32),
-        exit: Point(lines.len() as i32 - 1, exit_col as i32),
+        bounds: Bounds(Point::ORIGIN, Point::new(width as i32, height as i32)),
+        entry: Point::from_row_col(0, entry_col as i32),
+        exit: Point::from_row_col(lines.len() as i32 - 1, exit_col as i32),
     }
 }
 
-fn find_best(map: &Map, start_minute: usize, start: Point, end: Point) -> usize {
-    let mut best = usize::MAX;
-    let mut queue = VecDeque::new();
-    queue.push_back((start, start_minute));
-    let mut seen = HashSet::new();
-    while !queue.is_empty() {
-        let (pos, minute) = queue.pop_front().unwrap();
-        if minute >= best {
-            continue;
-        }
-        if seen.contains(&(pos, minute)) {
-            continue;
-        }
-        seen.insert((pos, minute));
-        let next_blizzards = map.get_blizzards_at(minute);
-        for d in [Up, Down, Left, Right] {
-            let n = d.go(&pos);
-            if n == end && minute < best {
-                best = minute;
-                continue;
-            }
-            if map.bounds.contains(n) && !next_blizzards.contains(&n) {
-                queue.push_back((n, minute + 1));
+/// Walking through the valley from `start` to `end`. States are positions at
+/// a minute; reaching `end` does not take another minute.
+struct Expedition<'a> {
+    map: &'a Map,
+    blizzards: &'a [HashSet<Point>],
+    start_minute: usize,
+    start: Point,
+    end: Point,
+}
+
+impl SearchProblem for Expedition<'_> {
+    type State = (Point, usize);
+    type Cost = usize;
+
+    fn start(&self) -> Self::State {
+        (self.start, self.start_minute)
+    }
+
+    fn successors(&self, &(pos, minute): &Self::State) -> Vec<(Self::State, usize)> {
+        let next_blizzards = &self.blizzards[minute % self.blizzards.len()];
+        let mut result = vec![];
+        for n in pos.neighbours4() {
+            if n == self.end {
+                result.push(((n, minute), 1));
+            } else if self.map.bounds.contains(n) && !next_blizzards.contains(&n) {
+                result.push(((n, minute + 1), 1));
             }
         }
         if !next_blizzards.contains(&pos) {
-            queue.push_back((pos, minute + 1));
+            result.push(((pos, minute + 1), 1));
         }
+        result
+    }
+
+    fn is_goal(&self, &(pos, _): &Self::State) -> bool {
+        pos == self.end
     }
-    best
+}
+
+fn find_best(
+    map: &Map,
+    blizzards: &[HashSet<Point>],
+    start_minute: usize,
+    start: Point,
+    end: Point,
+) -> usize {
+    let expedition = Expedition {
+        map,
+        blizzards,
+        start_minute,
+        start,
+        end,
+    };
+    let (_, minute) = *bfs(&expedition).solution.unwrap().goal();
+    minute
 }
 
 fn part1(map: &Map) -> usize {
-    find_best(map, 0, map.entry, map.exit)
+    find_best(map, &map.blizzard_cycle(), 0, map.entry, map.exit)
 }
 
 fn part2(map: &Map, p1: usize) -> usize {
-    let back_at_start = find_best(map, p1, map.exit, map.entry);
-    find_best(map, back_at_start, map.entry, map.exit)
+    let blizzards = map.blizzard_cycle();
+    let back_at_start = find_best(map, &blizzards, p1, map.exit, map.entry);
+    find_best(map, &blizzards, back_at_start, map.entry, map.exit)
 }
 
 fn main() {
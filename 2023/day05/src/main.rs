@@ -1,14 +1,16 @@
 // Problem: https://adventofcode.com/2023/day/5
 
+use interval::{Interval, IntervalSet, RangeMap};
 use itertools::Itertools;
-use rayon::prelude::*;
 use sscanf::sscanf;
 
 type Int = i64;
 
 type Result = Int;
 
-type Input = (Vec<Int>, Vec<(String, String, Vec<(Int, Int, Int)>)>);
+type Map = (String, String, Vec<(Int, Int, Int)>);
+
+type Input = (Vec<Int>, Vec<Map>);
 
 fn parse_input(input: &str) -> Input {
     let (header, body) = input.trim().split_once("\n\n").unwrap();
@@ -39,53 +41,29 @@ fn parse_input(input: &str) -> Input {
     (seeds, body)
 }
 
-#[derive(Debug, Clone)]
-struct OffsetRanges(Vec<(Int, Int, Int)>);
-
-impl OffsetRanges {
-    fn from_map(map: &[(Int, Int, Int)]) -> Self {
-        Self(
+/// The maps in the order they are applied, starting from seeds.
+fn chain(maps: &[Map]) -> Vec<RangeMap<Int>> {
+    let mut chain = vec![];
+    let mut cur_type = "seed";
+    while cur_type != "location" {
+        let (_, next_type, map) = maps.iter().find(|(from, _, _)| from == cur_type).unwrap();
+        chain.push(
             map.iter()
-                .cloned()
-                .map(|(dest, source, count)| (source, source + count, dest - source))
+                .map(|&(dest, source, count)| (Interval::starting_at(source, count), dest - source))
                 .collect(),
-        )
-    }
-    fn offset_at(&self, n: Int) -> Int {
-        for (start, end, offset) in self.0.iter() {
-            if &n >= start && &n < end {
-                return *offset;
-            }
-        }
-        0
+        );
+        cur_type = next_type;
     }
+    chain
 }
 
 // ------------------------------------------
 
-fn resolve_location(
-    cur_type: &String,
-    val: Int,
-    maps: &Vec<(String, String, OffsetRanges)>,
-) -> Int {
-    let (_, next_type, ranges) = maps.iter().find(|(from, _, _)| from == cur_type).unwrap();
-    let next_offset = ranges.offset_at(val);
-    let next_val = val + next_offset;
-    if next_type == "location" {
-        next_val
-    } else {
-        resolve_location(next_type, next_val, maps)
-    }
-}
-
 fn part1((seeds, maps): &Input) -> Result {
-    let ranges = maps
-        .iter()
-        .map(|(from, to, map)| (from.clone(), to.clone(), OffsetRanges::from_map(map)))
-        .collect::<Vec<_>>();
+    let chain = chain(maps);
     seeds
         .iter()
-        .map(|seed| resolve_location(&"seed".to_string(), *seed, &ranges))
+        .map(|&seed| chain.iter().fold(seed, |val, map| map.apply(val)))
         .min()
         .unwrap()
 }
@@ -100,29 +78,15 @@ fn test_part1() {
 
 // ------------------------------------------
 
-#[inline]
-fn resolve_fast(seed: Int, ranges: &Vec<OffsetRanges>) -> Int {
-    let mut cur = seed;
-    for r in ranges {
-        cur = cur + r.offset_at(cur);
-    }
-    cur
-}
-
 fn part2((seeds, maps): &Input) -> Result {
-    let ranges = maps
+    let seeds: IntervalSet<Int> = seeds
         .iter()
-        .map(|(from, to, map)| (from.clone(), to.clone(), OffsetRanges::from_map(map)))
-        .collect::<Vec<_>>();
-    let range_list: Vec<OffsetRanges> = ranges.iter().cloned().map(|(_, _, list)| list).collect();
-    seeds
-        .iter()
-        .cloned()
         .tuples()
-        .collect::<Vec<(Int, Int)>>()
-        .par_iter()
-        .flat_map(|(a, b)| *a..(a + b))
-        .map(|seed| resolve_fast(seed, &range_list))
+        .map(|(&start, &count)| Interval::starting_at(start, count))
+        .collect();
+    chain(maps)
+        .iter()
+        .fold(seeds, |vals, map| map.apply_set(&vals))
         .min()
         .unwrap()
 }
@@ -1,5 +1,6 @@
 // Problem: https://adventofcode.com/2023/day/8
 
+use numtheory::lcm_all;
 use std::collections::HashMap;
 
 type Result = usize;
@@ -85,33 +86,13 @@ fn steps_to_end(cur: &String, dirs: &[Dir], map: &HashMap<String, Node>) -> usiz
     count
 }
 
-fn lcm(first: usize, second: usize) -> usize {
-    first * second / gcd(first, second)
-}
-
-fn gcd(first: usize, second: usize) -> usize {
-    let mut max = first;
-    let mut min = second;
-    if min > max {
-        std::mem::swap(&mut max, &mut min);
-    }
-    loop {
-        let res = max % min;
-        if res == 0 {
-            return min;
-        }
-        max = min;
-        min = res;
-    }
-}
-
 fn part2((dirs, map): &Input) -> Result {
     let starts = map.keys().filter(|k| k.ends_with('A')).collect::<Vec<_>>();
     let ends = starts
         .iter()
         .map(|start| steps_to_end(start, dirs, map))
         .collect::<Vec<_>>();
-    ends.iter().cloned().reduce(lcm).unwrap()
+    lcm_all(ends)
 }
 
 #[test]
@@ -2,42 +2,17 @@
 
 use std::collections::{HashMap, HashSet};
 
+use geometry::{Dir4, Point2};
 use itertools::Itertools;
 use lazy_static::lazy_static;
+use vis::{Export, Frame, Palette};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Dir {
-    N,
-    E,
-    W,
-    S,
-}
-use Dir::*;
-impl Dir {
-    fn all() -> [Self; 4] {
-        [N, E, S, W]
-    }
-    fn advance(&self, (l, c): &Point) -> Point {
-        match self {
-            N => (l - 1, *c),
-            E => (*l, c + 1),
-            W => (*l, c - 1),
-            S => (l + 1, *c),
-        }
-    }
-    fn invert(&self) -> Self {
-        match self {
-            N => S,
-            E => W,
-            W => E,
-            S => N,
-        }
-    }
-}
+use Dir4::*;
 
 type Result = usize;
 
-type Point = (i32, i32);
+/// A tile, with `y` counting lines down from the top.
+type Point = Point2<i32>;
 type Map = HashMap<Point, char>;
 type Input = Map;
 
@@ -49,26 +24,26 @@ fn parse_input(input: &str) -> Input {
         .flat_map(|(l, line)| {
             line.chars()
                 .enumerate()
-                .map(move |(c, ch)| ((l as i32, c as i32), ch))
+                .map(move |(c, ch)| (Point::from_row_col(l as i32, c as i32), ch))
         })
         .collect::<HashMap<Point, char>>()
 }
 
 lazy_static! {
-    static ref CONNECTORS: HashMap<char, (Dir, Dir)> = {
+    static ref CONNECTORS: HashMap<char, (Dir4, Dir4)> = {
         let mut map = HashMap::new();
-        map.insert('|', (N, S));
-        map.insert('-', (W, E));
-        map.insert('L', (N, E));
-        map.insert('J', (N, W));
-        map.insert('7', (S, W));
-        map.insert('F', (S, E));
+        map.insert('|', (North, South));
+        map.insert('-', (West, East));
+        map.insert('L', (North, East));
+        map.insert('J', (North, West));
+        map.insert('7', (South, West));
+        map.insert('F', (South, East));
         map
     };
 }
 
-fn turn(ch: char, dir: Dir) -> Option<Dir> {
-    let entry = dir.invert();
+fn turn(ch: char, dir: Dir4) -> Option<Dir4> {
+    let entry = dir.opposite();
     if let Some(&(a, b)) = CONNECTORS.get(&ch) {
         if entry == a {
             Some(b)
@@ -82,12 +57,12 @@ fn turn(ch: char, dir: Dir) -> Option<Dir> {
     }
 }
 
-fn get_loop(start: Point, dir: Dir, map: &Map) -> Option<(Vec<Point>, Dir)> {
+fn get_loop(start: Point, dir: Dir4, map: &Map) -> Option<(Vec<Point>, Dir4)> {
     let mut result = vec![start];
     let mut pos = start;
     let mut dir = dir;
     loop {
-        let next = dir.advance(&pos);
+        let next = pos + dir.unit_screen();
         if next == start {
             return Some((result, dir));
         }
@@ -107,7 +82,7 @@ fn get_loop(start: Point, dir: Dir, map: &Map) -> Option<(Vec<Point>, Dir)> {
 
 fn part1(input: &Input) -> Result {
     let (start, _) = input.iter().find(|&(_, ch)| *ch == 'S').unwrap();
-    for dir in Dir::all() {
+    for dir in Dir4::ALL {
         if let Some((result, _)) = get_loop(*start, dir, input) {
             return result.len() / 2 + result.len() % 2;
         }
@@ -126,56 +101,48 @@ fn test_part1() {
 }
 
 fn map_size(map: &Map) -> (i32, i32) {
-    let max_line = map.keys().map(|(line, _)| *line).max().unwrap();
-    let max_col = map.keys().map(|(_, col)| *col).max().unwrap();
+    let max_line = map.keys().map(|p| p.y).max().unwrap();
+    let max_col = map.keys().map(|p| p.x).max().unwrap();
     (max_line + 1, max_col + 1)
 }
 
-fn replace_start(start_dir: Dir, end_dir: Dir) -> char {
+fn replace_start(start_dir: Dir4, end_dir: Dir4) -> char {
     *CONNECTORS
         .iter()
-        .find(|&(_, v)| *v == (start_dir, end_dir.invert()) || *v == (end_dir.invert(), start_dir))
+        .find(|&(_, v)| {
+            *v == (start_dir, end_dir.opposite()) || *v == (end_dir.opposite(), start_dir)
+        })
         .map(|(k, _)| k)
         .unwrap()
 }
 
-fn expand_loop(map: &Map, start_dir: Dir, end_dir: Dir, loop_points: &HashSet<Point>) -> Map {
+fn expand_loop(map: &Map, start_dir: Dir4, end_dir: Dir4, loop_points: &HashSet<Point>) -> Map {
     let mut expanded = HashMap::new();
-    for &(l, c) in loop_points {
-        let line = l * 3;
-        let col = c * 3;
-        let ch = map[&(l, c)];
+    for &point in loop_points {
+        let centre = point * 3;
+        let ch = map[&point];
         let ch = if ch == 'S' {
             replace_start(start_dir, end_dir)
         } else {
             ch
         };
-        expanded.insert((line, col), ch);
+        expanded.insert(centre, ch);
         let (a, b) = CONNECTORS.get(&ch).unwrap();
         for dir in [a, b] {
-            expanded.insert(
-                match dir {
-                    N => (line - 1, col),
-                    E => (line, col + 1),
-                    W => (line, col - 1),
-                    S => (line + 1, col),
-                },
-                'x',
-            );
+            expanded.insert(centre + dir.unit_screen(), 'x');
         }
     }
     expanded
 }
 
 fn flood_fill_outside(map: &mut Map) {
-    let mut queue = vec![(-1, -1)];
+    let mut queue = vec![Point::new(-1, -1)];
     let (lines, cols) = map_size(map);
     let lines = &(-1..=(lines + 9));
     let cols = &(-1..=(cols + 9));
     while let Some(point) = queue.pop() {
-        for dir in Dir::all() {
-            let next = dir.advance(&point);
-            if lines.contains(&next.0) && cols.contains(&next.1) && !map.contains_key(&next) {
+        for next in point.neighbours4() {
+            if lines.contains(&next.y) && cols.contains(&next.x) && !map.contains_key(&next) {
                 map.insert(next, 'O');
                 queue.push(next);
             }
@@ -186,17 +153,41 @@ fn flood_fill_outside(map: &mut Map) {
 fn part2(input: &Input) -> Result {
     let (lines, cols) = map_size(input);
     let (start, _) = input.iter().find(|&(_, ch)| *ch == 'S').unwrap();
-    let (start_dir, (loop_points, end_dir)) = [N, E, S, W]
+    let (start_dir, (loop_points, end_dir)) = Dir4::ALL
         .iter()
         .find_map(|dir| get_loop(*start, *dir, input).map(|theloop| (dir, theloop)))
         .unwrap();
     let loop_points = loop_points.iter().cloned().collect::<HashSet<Point>>();
     let mut expanded = expand_loop(input, *start_dir, end_dir, &loop_points);
     flood_fill_outside(&mut expanded);
-    (0..lines)
+    let inside = (0..lines)
         .cartesian_product(0..cols)
-        .filter(|(line, col)| !expanded.contains_key(&(line * 3, col * 3)))
-        .count()
+        .map(|(line, col)| Point::from_row_col(line, col))
+        .filter(|&point| !expanded.contains_key(&(point * 3)))
+        .collect_vec();
+    if let Some(export) = Export::from_env() {
+        export_expanded(&export, &expanded, &inside).unwrap();
+    }
+    inside.len()
+}
+
+/// Saves the expanded loop with the outside flooded and the enclosed tiles
+/// marked.
+fn export_expanded(export: &Export, expanded: &Map, inside: &[Point]) -> std::io::Result<()> {
+    let frame = Frame::from_points(
+        expanded
+            .iter()
+            .map(|(&p, &ch)| ((p.x as i64, p.y as i64), ch.into()))
+            .chain(
+                inside
+                    .iter()
+                    .map(|&p| ((p.x as i64 * 3, p.y as i64 * 3), 'I'.into())),
+            ),
+    );
+    let palette = Palette::new([30, 60, 40], [240, 200, 80])
+        .with('O', [20, 20, 30])
+        .with('I', [90, 220, 120]);
+    export.save(&frame, &palette)
 }
 
 #[test]
@@ -1,6 +1,6 @@
 // Problem: https://adventofcode.com/2023/day/14
 
-use std::collections::HashMap;
+use cycle::fast_forward;
 
 type Result = usize;
 
@@ -14,9 +14,9 @@ fn parse_input(input: &str) -> Input {
         .collect()
 }
 
-fn tilt(input: &Vec<Vec<char>>) -> Vec<Vec<char>> {
+fn tilt(input: &[Vec<char>]) -> Vec<Vec<char>> {
     let l = input.len();
-    let mut prev = input.clone();
+    let mut prev = input.to_vec();
     loop {
         let mut input = prev.clone();
         for r in (1..l).rev() {
@@ -63,29 +63,17 @@ fn rotate(v: Vec<Vec<char>>) -> Vec<Vec<char>> {
         .collect()
 }
 
-fn part2(input: &Input) -> Result {
-    let cycles = 1000000000;
-    let mut seen = HashMap::new();
-    let mut dish = input.clone();
-    let mut i = 0;
-    let mut find_cycle = true;
-    while i < cycles {
-        for _ in 0..4 {
-            dish = tilt(&dish);
-            dish = rotate(dish);
-        }
-        if find_cycle {
-            if let Some(idx) = seen.get(&dish) {
-                let cycle_size = i - idx;
-                let remaining = cycles - i;
-                i += cycle_size * (remaining / cycle_size);
-                find_cycle = false;
-            } else {
-                seen.insert(dish.clone(), i);
-            }
-        }
-        i += 1;
+fn spin_cycle(dish: &Input) -> Input {
+    let mut dish = dish.clone();
+    for _ in 0..4 {
+        dish = tilt(&dish);
+        dish = rotate(dish);
     }
+    dish
+}
+
+fn part2(input: &Input) -> Result {
+    let dish = fast_forward(input.clone(), spin_cycle, 1000000000);
     dish.iter()
         .enumerate()
         .map(|(i, line)| line.iter().filter(|c| **c == 'O').count() * (input.len() - i))
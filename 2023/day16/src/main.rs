@@ -1,82 +1,56 @@
 // Problem: https://adventofcode.com/2023/day/16
 
+use geometry::Dir4;
+use grid::{offset, Grid, Pos};
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashSet, VecDeque};
+use Dir4::*;
 
 type Result = usize;
 
-type Point = (i32, i32);
-type Grid = HashMap<Point, char>;
-
-type Input = Grid;
+type Input = Grid<char>;
 
 fn parse_input(input: &str) -> Input {
-    input
-        .trim()
-        .lines()
-        .enumerate()
-        .flat_map(|(l, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(c, ch)| ((l as i32, c as i32), ch))
-        })
-        .collect::<Grid>()
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-enum Dir {
-    N,
-    E,
-    S,
-    W,
-}
-use Dir::*;
-
-fn step((r, c): &Point, dir: Dir) -> Point {
-    match dir {
-        N => (r - 1, *c),
-        E => (*r, c + 1),
-        S => (r + 1, *c),
-        W => (*r, c - 1),
-    }
+    Grid::parse(input, |ch| ch)
 }
 
-fn redirect(ch: char, dir: Dir) -> Vec<Dir> {
+fn redirect(ch: char, dir: Dir4) -> Vec<Dir4> {
     match ch {
         '.' => vec![dir],
         '|' => match dir {
-            N | S => vec![dir],
-            E | W => vec![N, S],
+            North | South => vec![dir],
+            East | West => vec![North, South],
         },
         '-' => match dir {
-            N | S => vec![E, W],
-            E | W => vec![dir],
+            North | South => vec![East, West],
+            East | West => vec![dir],
         },
         '\\' => vec![match dir {
-            N => W,
-            E => S,
-            S => E,
-            W => N,
+            North => West,
+            East => South,
+            South => East,
+            West => North,
         }],
         '/' => vec![match dir {
-            N => E,
-            E => N,
-            S => W,
-            W => S,
+            North => East,
+            East => North,
+            South => West,
+            West => South,
         }],
         _ => panic!("{}", ch),
     }
 }
 
-fn energized_particles(input: &Input, start: (Point, Dir)) -> usize {
+fn energized_particles(input: &Input, start: (Pos, Dir4)) -> usize {
     let mut seen = HashSet::new();
     let mut queue = VecDeque::from(vec![start]);
     while let Some((point, dir)) = queue.pop_front() {
         seen.insert((point, dir));
-        for nd in redirect(input[&point], dir) {
-            let next = step(&point, nd);
-            if input.contains_key(&next) && !seen.contains(&(next, nd)) {
-                queue.push_back((next, nd));
+        for nd in redirect(input[point], dir) {
+            if let Some(next) = input.step(point, offset(nd)) {
+                if !seen.contains(&(next, nd)) {
+                    queue.push_back((next, nd));
+                }
             }
         }
     }
@@ -84,7 +58,7 @@ fn energized_particles(input: &Input, start: (Point, Dir)) -> usize {
 }
 
 fn part1(input: &Input) -> Result {
-    energized_particles(input, ((0, 0), E))
+    energized_particles(input, ((0, 0), East))
 }
 
 #[test]
@@ -94,11 +68,11 @@ fn test_part1() {
 }
 
 fn part2(input: &Input) -> Result {
-    let max_row = *input.keys().map(|(r, _)| r).max().unwrap();
-    let max_col = *input.keys().map(|(_, c)| c).max().unwrap();
+    let max_row = input.height() - 1;
+    let max_col = input.width() - 1;
     (0..=max_col)
-        .flat_map(|c| vec![((0, c), S), ((max_row, c), N)])
-        .chain((0..=max_row).flat_map(|r| vec![((r, 0), E), ((r, max_col), W)]))
+        .flat_map(|c| vec![((0, c), South), ((max_row, c), North)])
+        .chain((0..=max_row).flat_map(|r| vec![((r, 0), East), ((r, max_col), West)]))
         .map(|point| energized_particles(input, point))
         .max()
         .unwrap()
@@ -1,92 +1,52 @@
 // Problem: https://adventofcode.com/2023/day/17
 
-use pathfinding::prelude::dijkstra;
-use std::collections::HashMap;
+use geometry::Dir4;
+use grid::{offset, Grid, Pos};
+use search::{dijkstra, SearchProblem};
+use Dir4::*;
 
 type Result = u32;
-type Point = (i32, i32);
-type Map = HashMap<Point, u32>;
-type Input = (Map, Point);
+type Map = Grid<u32>;
+type Input = (Map, Pos);
 
 fn parse_input(input: &str) -> Input {
-    let map = input
-        .trim()
-        .lines()
-        .enumerate()
-        .flat_map(|(i, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(j, ch)| ((i as i32, j as i32), ch.to_digit(10).unwrap()))
-        })
-        .collect::<Map>();
-    let target_row = *map.keys().map(|(r, _)| r).max().unwrap();
-    let target_col = *map.keys().map(|(_, c)| c).max().unwrap();
-    (map, (target_row, target_col))
+    let map = Grid::parse(input, |ch| ch.to_digit(10).unwrap());
+    let target = (map.height() - 1, map.width() - 1);
+    (map, target)
 }
 
 // ------------------------------------------
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd)]
-enum Dir {
-    N,
-    E,
-    S,
-    W,
-}
-use Dir::*;
-impl Dir {
-    fn right(&self) -> Self {
-        match self {
-            N => E,
-            E => S,
-            S => W,
-            W => N,
-        }
-    }
-    fn left(&self) -> Self {
-        self.right().right().right()
-    }
-    fn opposite(&self) -> Self {
-        self.right().right()
-    }
-}
-
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 struct Node {
-    pos: Point,
-    dir: Dir,
+    pos: Pos,
+    dir: Dir4,
     dir_count: u32,
 }
 
-fn next_node(node: &Node, dir: Dir, map: &Map) -> Option<(Node, u32)> {
-    let (r, c) = node.pos;
-    let pos = match dir {
-        N => (r - 1, c),
-        E => (r, c + 1),
-        S => (r + 1, c),
-        W => (r, c - 1),
-    };
-    map.get(&pos).map(|cost| {
-        (
-            Node {
-                pos,
-                dir,
-                dir_count: if dir == node.dir {
-                    node.dir_count + 1
-                } else {
-                    1
+fn next_node(node: &Node, dir: Dir4, map: &Map) -> Option<(Node, u32)> {
+    map.step(node.pos, offset(dir))
+        .map(|pos| {
+            (
+                Node {
+                    pos,
+                    dir,
+                    dir_count: if dir == node.dir {
+                        node.dir_count + 1
+                    } else {
+                        1
+                    },
                 },
-            },
-            *cost,
-        )
-    })
+                map[pos],
+            )
+        })
 }
 
 fn successors(node: &Node, map: &Map) -> Vec<(Node, u32)> {
     let dirs = if node.pos == (0, 0) && node.dir_count == 0 {
-        vec![next_node(node, E, map), next_node(node, S, map)]
+        vec![next_node(node, East, map), next_node(node, South, map)]
     } else {
-        [N, E, S, W]
+        Dir4::ALL
             .iter()
             .filter(|d| node.dir != d.opposite())
             .filter(|d| node.dir_count < 3 || **d != node.dir)
@@ -96,18 +56,44 @@ fn successors(node: &Node, map: &Map) -> Vec<(Node, u32)> {
     dirs.iter().filter_map(|o| o.clone()).collect()
 }
 
-fn part1((map, target): &Input) -> Result {
-    let (_, cost) = dijkstra(
-        &Node {
+struct Crucible<'a> {
+    map: &'a Map,
+    target: Pos,
+    ultra: bool,
+}
+
+impl SearchProblem for Crucible<'_> {
+    type State = Node;
+    type Cost = u32;
+
+    fn start(&self) -> Node {
+        Node {
             pos: (0, 0),
-            dir: N,
+            dir: North,
             dir_count: 0,
-        },
-        |node| successors(node, map),
-        |node| node.pos == *target,
-    )
-    .unwrap();
-    cost
+        }
+    }
+
+    fn successors(&self, node: &Node) -> Vec<(Node, u32)> {
+        if self.ultra {
+            successors2(node, self.map)
+        } else {
+            successors(node, self.map)
+        }
+    }
+
+    fn is_goal(&self, node: &Node) -> bool {
+        node.pos == self.target
+    }
+}
+
+fn part1((map, target): &Input) -> Result {
+    let crucible = Crucible {
+        map,
+        target: *target,
+        ultra: false,
+    };
+    dijkstra(&crucible).cost().unwrap()
 }
 
 #[test]
@@ -120,13 +106,13 @@ fn test_part1() {
 
 fn successors2(node: &Node, map: &Map) -> Vec<(Node, u32)> {
     let possible_dirs = if node.pos == (0, 0) && node.dir_count == 0 {
-        vec![E, S]
+        vec![East, South]
     } else if node.dir_count < 4 {
         vec![node.dir]
     } else if node.dir_count >= 10 {
-        vec![node.dir.right(), node.dir.left()]
+        vec![node.dir.turn_right(), node.dir.turn_left()]
     } else {
-        vec![node.dir, node.dir.right(), node.dir.left()]
+        vec![node.dir, node.dir.turn_right(), node.dir.turn_left()]
     };
     possible_dirs
         .iter()
@@ -135,17 +121,12 @@ fn successors2(node: &Node, map: &Map) -> Vec<(Node, u32)> {
 }
 
 fn part2((map, target): &Input) -> Result {
-    let (_, cost) = dijkstra(
-        &Node {
-            pos: (0, 0),
-            dir: N,
-            dir_count: 0,
-        },
-        |node| successors2(node, map),
-        |node| node.pos == *target,
-    )
-    .unwrap();
-    cost
+    let crucible = Crucible {
+        map,
+        target: *target,
+        ultra: true,
+    };
+    dijkstra(&crucible).cost().unwrap()
 }
 
 #[test]
@@ -1,5 +1,6 @@
 // Problem: https://adventofcode.com/2023/day/19
 
+use interval::Cuboid;
 use sscanf::sscanf;
 use std::collections::HashMap;
 
@@ -149,94 +150,41 @@ fn test_part1() {
 
 // ------------------------------------------
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-struct PossibleRange(Vec<bool>);
-impl PossibleRange {
-    fn new() -> Self {
-        Self(vec![true; 4000])
-    }
-    fn constrain(&mut self, cmp: Cmp, val: i32, inverse: bool) {
-        for v in 0..4000 {
-            if cmp.compare(v as i32 + 1, val) == inverse {
-                self.0[v] = false;
-            }
-        }
-    }
-    fn is_possible(&self) -> bool {
-        self.0.iter().any(|v| *v)
-    }
-    fn possible_values(&self) -> usize {
-        if self.is_possible() {
-            self.0.iter().filter(|v| **v).count()
-        } else {
-            0
+/// The ratings that pass a rule and the ones that go on to the next rule.
+fn split(parts: Cuboid<i32, 4>, var: Var, cmp: Cmp, val: i32) -> [Option<Cuboid<i32, 4>>; 2] {
+    match cmp {
+        Cmp::Lt => {
+            let (pass, fail) = parts.split_at(var as usize, val);
+            [pass, fail]
         }
-    }
-}
-
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-struct State {
-    step: String,
-    x: PossibleRange,
-    m: PossibleRange,
-    a: PossibleRange,
-    s: PossibleRange,
-}
-impl State {
-    fn start() -> Self {
-        State {
-            step: "in".to_string(),
-            x: PossibleRange::new(),
-            m: PossibleRange::new(),
-            a: PossibleRange::new(),
-            s: PossibleRange::new(),
+        Cmp::Gt => {
+            let (fail, pass) = parts.split_at(var as usize, val + 1);
+            [pass, fail]
         }
     }
-    fn constrain(&self, var: Var, cmp: Cmp, val: i32, inverse: bool, next_step: String) -> State {
-        let mut new_state = self.clone();
-        match var {
-            Var::X => {
-                new_state.x.constrain(cmp, val, inverse);
-            }
-            Var::M => {
-                new_state.m.constrain(cmp, val, inverse);
-            }
-            Var::A => {
-                new_state.a.constrain(cmp, val, inverse);
-            }
-            Var::S => {
-                new_state.s.constrain(cmp, val, inverse);
-            }
-        };
-        new_state.step = next_step;
-        new_state
-    }
-    fn possible_values(&self) -> usize {
-        self.x.possible_values()
-            * self.m.possible_values()
-            * self.a.possible_values()
-            * self.s.possible_values()
-    }
-    fn is_possible(&self) -> bool {
-        self.x.is_possible() && self.m.is_possible() && self.a.is_possible() && self.s.is_possible()
-    }
 }
 
-fn possible_ratings(state: State, rule_map: &HashMap<String, Ruleset>) -> usize {
-    if state.step == "R" || !state.is_possible() {
+fn possible_ratings(step: &str, parts: Cuboid<i32, 4>, rule_map: &HashMap<String, Ruleset>) -> u64 {
+    if step == "R" {
         return 0;
     }
-    if state.step == "A" {
-        return state.possible_values();
+    if step == "A" {
+        return parts.volume();
     }
     let mut total = 0;
-    let mut state = state.clone();
-    let Ruleset(_, rules, fallback) = rule_map[&state.step].clone();
+    let mut rest = Some(parts);
+    let Ruleset(_, rules, fallback) = &rule_map[step];
     for Rule(var, cmp, val, next) in rules {
-        total += possible_ratings(state.constrain(var, cmp, val, false, next), rule_map);
-        state = state.constrain(var, cmp, val, true, fallback.clone());
+        let Some(parts) = rest else {
+            return total;
+        };
+        let [pass, fail] = split(parts, *var, *cmp, *val);
+        if let Some(pass) = pass {
+            total += possible_ratings(next, pass, rule_map);
+        }
+        rest = fail;
     }
-    total + possible_ratings(state, rule_map)
+    total + rest.map_or(0, |parts| possible_ratings(fallback, parts, rule_map))
 }
 
 fn part2((workflow, _): &Input) -> Result {
@@ -245,7 +193,8 @@ fn part2((workflow, _): &Input) -> Result {
         .map(|rs| (rs.0.clone(), rs.clone()))
         .collect();
 
-    possible_ratings(State::start(), &rule_map)
+    let parts = Cuboid::inclusive([1; 4], [4000; 4]);
+    possible_ratings("in", parts, &rule_map) as usize
 }
 
 #[test]
@@ -1,81 +1,44 @@
 // Problem: https://adventofcode.com/2023/day/21
 
-use std::{
-    collections::{HashMap, HashSet},
-    ops::{Add, Range},
-};
+use cycle::{first_repeat, Cycle};
+use geometry::{Direction, Point2};
+use grid::Grid;
+use itertools::Itertools;
+use std::{collections::HashSet, ops::Range};
+use Direction::*;
 
 type Result = usize;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-pub enum Dir {
-    N,
-    E,
-    S,
-    W,
-}
-use itertools::Itertools;
-use Dir::*;
-impl Dir {
-    fn to_vec(self) -> Point {
-        match self {
-            N => Point(-1, 0),
-            E => Point(0, 1),
-            S => Point(1, 0),
-            W => Point(0, -1),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point(i64, i64);
-impl Add<Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
+/// A garden plot, with `y` counting rows down from the top of the first
+/// copy of the map.
+type Point = Point2<i64>;
 
 type Input = Map;
 
+/// The garden map, repeating infinitely in every direction.
 struct Map {
-    m: HashMap<Point, char>,
+    m: Grid<char>,
     rows: i64,
     cols: i64,
     start: Point,
 }
 impl Map {
-    fn get(&self, Point(r, c): &Point) -> char {
-        let r = r % self.rows;
-        let r = if r < 0 { r + self.rows } else { r };
-        let c = c % self.cols;
-        let c = if c < 0 { c + self.cols } else { c };
-        self.m[&Point(r, c)]
+    fn get(&self, p: &Point) -> char {
+        let r = p.y.rem_euclid(self.rows);
+        let c = p.x.rem_euclid(self.cols);
+        self.m[(r as usize, c as usize)]
     }
 }
 
 fn parse_input(input: &str) -> Input {
-    let mut m = input
-        .trim()
-        .lines()
-        .enumerate()
-        .flat_map(|(i, line)| {
-            line.chars()
-                .enumerate()
-                .map(move |(j, ch)| (Point(i as i64, j as i64), ch))
-        })
-        .collect::<HashMap<Point, char>>();
-
-    let start = m.iter().find(|(_, v)| **v == 'S').map(|(k, _)| *k).unwrap();
-
-    m.insert(start, '.');
-    let rows = m.keys().map(|Point(i, _)| *i).max().unwrap() + 1;
-    let cols = m.keys().map(|Point(_, j)| *j).max().unwrap() + 1;
+    let mut m = Grid::parse(input, |ch| ch);
+    let (start_row, start_col) = m.find(|&ch| ch == 'S').unwrap();
+    m[(start_row, start_col)] = '.';
     Map {
+        rows: m.height() as i64,
+        cols: m.width() as i64,
         m,
-        rows,
-        cols,
-        start,
+        start: Point::from_row_col(start_row as i64, start_col as i64),
     }
 }
 
@@ -94,8 +57,7 @@ fn reachable(map: &Map, steps: usize) -> HashSet<Point> {
 fn next_reachable(points: &HashSet<Point>, map: &Map) -> HashSet<Point> {
     let mut next = HashSet::new();
     for p in points {
-        for d in [N, E, S, W] {
-            let np = *p + d.to_vec();
+        for np in p.neighbours4() {
             let ch = map.get(&np);
             if ch != '#' {
                 next.insert(np);
@@ -117,55 +79,36 @@ fn test_part1() {
     assert_eq!(part1(&input, 64), 3841);
 }
 
-fn find_cycle(
-    (row_range, col_range): (Range<i64>, Range<i64>),
-    map: &Map,
-) -> (usize, Point, Vec<usize>) {
-    let mut first_point = None;
-    let mut first_step = None;
+/// Number of plots reachable in the tile spanning `row_range` and
+/// `col_range` after every step, starting with the first step that reaches
+/// the tile. Once the tile is filled, the count alternates between two
+/// values, and the sequence ends after the first two of those.
+fn tile_counts((row_range, col_range): (Range<i64>, Range<i64>), map: &Map) -> Vec<usize> {
     let mut counts = vec![];
-    let mut points: HashSet<Point> = [map.start].iter().cloned().collect();
-    let mut prev1 = None;
-    let mut prev2 = None;
-
-    for step in 0.. {
-        let contained: Vec<Point> = points
+    let steps = std::iter::successors(Some(HashSet::from([map.start])), |points| {
+        Some(next_reachable(points, map))
+    })
+    .map(|points| {
+        points
             .iter()
-            .filter(|Point(r, c)| row_range.contains(r) && col_range.contains(c))
-            .cloned()
-            .collect::<Vec<_>>();
-        let count = contained.len();
-        if count > 0 {
-            if prev2.is_some() && count == prev2.unwrap() {
-                break;
-            }
-            if first_point.is_none() {
-                first_point = Some(contained[0]);
-                first_step = Some(step);
-            }
-            counts.push(count);
-            prev2 = prev1;
-            prev1 = Some(count);
-        }
-        points = next_reachable(&points, map);
-    }
-
-    (first_step.unwrap(), first_point.unwrap(), counts)
-}
-
-fn get_full(step: usize, values: &Vec<usize>) -> usize {
-    values[values.len() - if values.len() % 2 == step % 2 { 2 } else { 1 }]
+            .filter(|p| row_range.contains(&p.y) && col_range.contains(&p.x))
+            .count()
+    })
+    .skip_while(|&count| count == 0)
+    .inspect(|&count| counts.push(count));
+    let repeat = first_repeat(steps.tuple_windows::<(usize, usize)>()).unwrap();
+    counts.truncate(repeat.second);
+    counts
 }
 
 fn precalc_tiles(map: &Input) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
-    let (_, _, center) = find_cycle((0..map.rows, 0..map.cols), map);
-    let sides = [N, E, S, W]
-        .map(|d| d.to_vec())
-        .map(|Point(dr, dc)| {
-            let rs = dr * map.rows;
-            let cs = dc * map.cols;
-            let (_, _, counts) = find_cycle(((rs)..(rs + map.rows), cs..(cs + map.cols)), map);
-            counts
+    let center = tile_counts((0..map.rows, 0..map.cols), map);
+    let sides = Direction::CARDINAL
+        .map(|d| d.unit_screen::<i64>())
+        .map(|d| {
+            let rs = d.y * map.rows;
+            let cs = d.x * map.cols;
+            tile_counts((rs..(rs + map.rows), cs..(cs + map.cols)), map)
         })
         .iter()
         .cloned()
@@ -176,12 +119,12 @@ fn precalc_tiles(map: &Input) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
                 .collect::<Vec<usize>>()
         })
         .unwrap();
-    let diags = [(-1, -1), (-1, 1), (1, 1), (1, -1)]
-        .map(|(dr, dc)| {
-            let rs = dr * map.rows;
-            let cs = dc * map.cols;
-            let (_, _, counts) = find_cycle(((rs)..(rs + map.rows), cs..(cs + map.cols)), map);
-            counts
+    let diags = [NorthWest, NorthEast, SouthEast, SouthWest]
+        .map(|d| d.unit_screen::<i64>())
+        .map(|d| {
+            let rs = d.y * map.rows;
+            let cs = d.x * map.cols;
+            tile_counts((rs..(rs + map.rows), cs..(cs + map.cols)), map)
         })
         .iter()
         .cloned()
@@ -200,9 +143,9 @@ fn zones(points: &HashSet<Point>) -> (usize, usize, usize) {
     let mut center = 0;
     let mut sides = 0;
     let mut diag = 0;
-    for Point(r, c) in points {
-        let is_center_row = (0..131).contains(r);
-        let is_center_col = (0..131).contains(c);
+    for p in points {
+        let is_center_row = (0..131).contains(&p.y);
+        let is_center_col = (0..131).contains(&p.x);
         if is_center_row && is_center_col {
             center += 1;
         } else if is_center_col || is_center_row {
@@ -239,37 +182,23 @@ fn test_calc_reachable_center() {
     }
 }
 
-fn calc_reachable_center(step: usize, center: &Vec<usize>) -> usize {
-    if step >= center.len() {
-        get_full(step, center)
-    } else {
-        center[step]
-    }
+fn calc_reachable_center(step: usize, center: &[usize]) -> usize {
+    get(center, step)
 }
 
-fn calc_reachable(
-    steps: usize,
-    center: &Vec<usize>,
-    sides: &Vec<usize>,
-    diags: &Vec<usize>,
-) -> usize {
+fn calc_reachable(steps: usize, center: &[usize], sides: &[usize], diags: &[usize]) -> usize {
     calc_reachable_center(steps, center)
         + calc_reachable_sides(steps, sides)
         + calc_reachable_diag(steps, diags)
 }
 
-fn get(list: &Vec<usize>, idx: usize) -> usize {
-    let l = list.len();
-    if idx < l {
-        list[idx]
-    } else if idx % 2 == l % 2 {
-        list[l - 2]
-    } else {
-        list[l - 1]
-    }
+/// The count of a tile `idx` steps after it was reached. Once the tile is
+/// filled, it alternates between the last two values of `tile_counts`.
+fn get(list: &[usize], idx: usize) -> usize {
+    list[Cycle::new(list.len() - 2, 2).index_at(idx)]
 }
 
-fn calc_reachable_sides(steps: usize, sides: &Vec<usize>) -> usize {
+fn calc_reachable_sides(steps: usize, sides: &[usize]) -> usize {
     let offset = 66;
     if steps < offset {
         return 0;
@@ -278,9 +207,9 @@ fn calc_reachable_sides(steps: usize, sides: &Vec<usize>) -> usize {
     let size = 131;
 
     let full = if steps >= 2 * size {
-        let f1 = get_full(steps, sides);
+        let f1 = get(sides, steps);
         let n = (steps - size) / size;
-        let f2 = get_full(steps - 1, sides);
+        let f2 = get(sides, steps - 1);
         f1 * (n / 2 + n % 2) + f2 * (n / 2)
     } else {
         0
@@ -324,14 +253,14 @@ fn even_odds_to_n(n: usize) -> (usize, usize) {
     (even, odd)
 }
 
-fn calc_reachable_diag(step: usize, diag: &Vec<usize>) -> usize {
+fn calc_reachable_diag(step: usize, diag: &[usize]) -> usize {
     let size = 131;
     let offset = 132;
     if step >= offset {
         let step = step - offset;
         let full = if step >= 2 * size {
             let (even, odd) = even_odds_to_n((step - 2 * size) / size + 1);
-            get_full(step + 1, diag) * even + get_full(step, diag) * odd
+            get(diag, step + 1) * even + get(diag, step) * odd
         } else {
             0
         };
@@ -1,51 +1,18 @@
 // Problem: https://adventofcode.com/2023/day/23
 
-use std::{
-    collections::{HashMap, HashSet},
-    ops::Add,
-};
+use geometry::{Dir4, Point2};
+use search::JunctionGraph;
+use std::collections::HashMap;
 
 type Result = usize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point(i32, i32);
-
-impl Add<Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: Point) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-impl Add<&Point> for Point {
-    type Output = Point;
-    fn add(self, rhs: &Point) -> Self::Output {
-        Point(self.0 + rhs.0, self.1 + rhs.1)
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
-    N,
-    E,
-    S,
-    W,
-}
-use Dir::*;
-impl Dir {
-    fn to_vec(self) -> Point {
-        match self {
-            N => Point(-1, 0),
-            E => Point(0, 1),
-            S => Point(1, 0),
-            W => Point(0, -1),
-        }
-    }
-}
+/// A tile of the trails, with `y` counting rows down from the top.
+type Point = Point2<i32>;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 enum Node {
     Path,
-    Slope(Dir),
+    Slope(Dir4),
 }
 type Map = HashMap<Point, Node>;
 type Input = (Map, Point, Point);
@@ -61,13 +28,10 @@ fn parse_input(input: &str) -> Input {
                 .filter(|(_, ch)| *ch != '#')
                 .map(move |(j, ch)| {
                     (
-                        Point(i as i32, j as i32),
+                        Point::from_row_col(i as i32, j as i32),
                         match ch {
                             '.' => Node::Path,
-                            '>' => Node::Slope(E),
-                            '<' => Node::Slope(W),
-                            'v' => Node::Slope(S),
-                            '^' => Node::Slope(N),
+                            '>' | '<' | 'v' | '^' => Node::Slope(Dir4::from_char(ch).unwrap()),
                             _ => panic!(),
                         },
                     )
@@ -75,84 +39,46 @@ fn parse_input(input: &str) -> Input {
         })
         .collect();
 
-    let start = map.keys().find(|Point(i, _)| *i == 0).unwrap();
-    let last_row = map.keys().map(|Point(i, _)| i).max().unwrap();
-    let end = map.keys().find(|Point(i, _)| i == last_row).unwrap();
+    let start = map.keys().find(|p| p.y == 0).unwrap();
+    let last_row = map.keys().map(|p| p.y).max().unwrap();
+    let end = map.keys().find(|p| p.y == last_row).unwrap();
 
     (map.clone(), *start, *end)
 }
 
 // ------------------------------------------
 
-fn extend_hs(hs: &HashSet<Point>, point: Point) -> HashSet<Point> {
-    let mut new = hs.clone();
-    new.insert(point);
-    new
-}
-
-fn find_longest_path(point: Point, seen: HashSet<Point>, map: &Map, target: &Point) -> usize {
-    if &point == target {
-        return 0;
-    }
+fn neighbours(point: Point, map: &Map, slippery: bool) -> Vec<Point> {
     let dirs = match map[&point] {
-        Node::Path => vec![N, E, S, W],
-        Node::Slope(d) => vec![d],
+        Node::Slope(d) if slippery => vec![d],
+        _ => Dir4::ALL.to_vec(),
     };
-
     dirs.iter()
-        .map(|d| point + d.to_vec())
-        .filter(|p| !seen.contains(p) && map.contains_key(p))
-        .map(|p| 1 + find_longest_path(p, extend_hs(&seen, p), map, target))
-        .max()
-        .unwrap_or_default()
+        .map(|d| point + d.unit_screen())
+        .filter(|p| map.contains_key(p))
+        .collect()
+}
+
+fn find_longest_path(map: &Map, start: Point, end: Point, slippery: bool) -> usize {
+    JunctionGraph::compress(start, end, |p| neighbours(p, map, slippery))
+        .longest_path()
+        .unwrap()
 }
 
 fn part1((map, start, end): &Input) -> Result {
-    find_longest_path(*start, HashSet::new(), map, end)
+    find_longest_path(map, *start, *end, true)
 }
 
 #[test]
 fn test_part1() {
     let input = parse_input(include_str!("test.txt"));
-    dbg!(&input);
     assert_eq!(part1(&input), 94);
 }
 
 // ------------------------------------------
 
-fn find_longest_path2(
-    point: Point,
-    len: usize,
-    seen: &mut HashSet<Point>,
-    map: &Map,
-    target: &Point,
-) -> Option<usize> {
-    if &point == target {
-        return Some(len);
-    }
-
-    let mut max = 0;
-    for d in [N, E, S, W] {
-        let next = point + d.to_vec();
-        if map.contains_key(&next) && !seen.contains(&next) {
-            seen.insert(next);
-            if let Some(result) = find_longest_path2(next, len + 1, seen, map, target) {
-                if result > max {
-                    max = result;
-                }
-            }
-            seen.remove(&next);
-        }
-    }
-    if max == 0 {
-        None
-    } else {
-        Some(max)
-    }
-}
-
 fn part2((map, start, end): &Input) -> Result {
-    find_longest_path2(*start, 0, &mut HashSet::new(), map, end).unwrap()
+    find_longest_path(map, *start, *end, false)
 }
 
 #[test]
@@ -1,118 +1,104 @@
 // Problem: https://adventofcode.com/2023/day/24
 
-use geo::Line;
 use itertools::Itertools;
-use std::ops::{Add, Mul};
-use z3::{ast::Ast, SatResult};
+use linalg::{BigInt, BigRational, Matrix, Trajectory};
+use num_traits::Signed;
 
 type Result = usize;
 
-type Input = Vec<((i64, i64, i64), (i64, i64, i64))>;
+type Hailstone = ((i64, i64, i64), (i64, i64, i64));
+
+type Input = Vec<Hailstone>;
 
 fn parse_input(input: &str) -> Input {
     input
         .trim()
         .lines()
         .map(|line| {
-            let mut parts = line.split_whitespace();
-            let (x, y, z) = (
-                parts
-                    .next()
-                    .unwrap()
-                    .strip_suffix(',')
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-                parts
-                    .next()
-                    .unwrap()
-                    .strip_suffix(',')
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-                parts.next().unwrap().parse::<i64>().unwrap(),
-            );
-            assert_eq!(parts.next().unwrap(), "@");
-            let (vx, vy, vz) = (
-                parts
-                    .next()
-                    .unwrap()
-                    .strip_suffix(',')
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-                parts
-                    .next()
-                    .unwrap()
-                    .strip_suffix(',')
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-                parts.next().unwrap().parse::<i64>().unwrap(),
-            );
-            ((x, y, z), (vx, vy, vz))
+            let ns = parse::ints::<i64>(line).unwrap_or_else(|e| panic!("invalid input: {}", e));
+            match ns[..] {
+                [x, y, z, vx, vy, vz] => ((x, y, z), (vx, vy, vz)),
+                _ => panic!("expected position @ velocity, got {:?}", line),
+            }
         })
         .collect()
 }
 
 // ------------------------------------------
 
-fn to_2d_line(((x, y, _), (vx, vy, _)): &((i64, i64, i64), (i64, i64, i64))) -> Line {
-    Line {
-        start: (*x as f64, *y as f64).into(),
-        end: (
-            (*x as f64 + *vx as f64 * 400000000000000.0),
-            (*y as f64 + *vy as f64 * 400000000000000.0),
-        )
-            .into(),
+/// Where the paths of two hailstones cross in x and y, if they do so ahead
+/// of both.
+fn crossing_xy(a: &Hailstone, b: &Hailstone) -> Option<(BigRational, BigRational)> {
+    let (((x1, y1, _), (vx1, vy1, _)), ((x2, y2, _), (vx2, vy2, _))) = (a, b);
+    // x1 + t vx1 = x2 + s vx2 and the same for y, for the times t and s.
+    let m = Matrix::from_rows(&[vec![*vx1, -vx2], vec![*vy1, -vy2]]);
+    let rhs = [x2 - x1, y2 - y1].map(|n| BigRational::from_integer(n.into()));
+    let ts = linalg::solve(&m, &rhs).ok()?;
+    if ts.iter().any(|t| t.is_negative()) {
+        return None;
     }
+    let at = |p: i64, v: i64| BigRational::from_integer(p.into()) + &ts[0] * BigInt::from(v);
+    Some((at(*x1, *vx1), at(*y1, *vy1)))
 }
 
-fn part1(input: &Input, min: f64, max: f64) -> Result {
-    let lines: Vec<Line> = input.iter().map(to_2d_line).collect();
-    let range = min..=max;
-    let mut count = 0;
-    for (a, b) in lines.iter().tuple_combinations() {
-        if let Some(intersection) = intersect2d::intersect(a, b) {
-            match intersection {
-                intersect2d::Intersection::Intersection(coord) => {
-                    if range.contains(&coord.x) && range.contains(&coord.y) {
-                        count += 1;
-                    }
-                }
-                intersect2d::Intersection::OverLap(_) => panic!(),
-            }
-        }
-    }
-    count
+fn part1(input: &Input, min: i64, max: i64) -> Result {
+    let range = BigRational::from_integer(min.into())..=BigRational::from_integer(max.into());
+    input
+        .iter()
+        .array_combinations()
+        .filter_map(|[a, b]| crossing_xy(a, b))
+        .filter(|(x, y)| range.contains(x) && range.contains(y))
+        .count()
 }
 
 #[test]
 fn test_part1() {
     let input = parse_input(include_str!("test.txt"));
-    assert_eq!(part1(&input, 7.0, 27.0), 2);
+    assert_eq!(part1(&input, 7, 27), 2);
 }
 
 // ------------------------------------------
 
-fn z3_solve(solver: &z3::Solver, ctx: &z3::Context, input: &Input) -> i64 {
-    let s_x = z3::ast::BV::new_const(ctx, "x", 64);
-    let s_y = z3::ast::BV::new_const(ctx, "y", 64);
-    let s_z = z3::ast::BV::new_const(ctx, "z", 64);
-    let s_vx = z3::ast::BV::new_const(ctx, "vx", 64);
-    let s_vy = z3::ast::BV::new_const(ctx, "vy", 64);
-    let s_vz = z3::ast::BV::new_const(ctx, "vz", 64);
-    let zero = z3::ast::BV::from_i64(ctx, 0, 64);
+fn part2(input: &Input) -> i64 {
+    let hail: Vec<Trajectory> = input
+        .iter()
+        .map(|&((x, y, z), (vx, vy, vz))| Trajectory {
+            position: [x, y, z],
+            velocity: [vx, vy, vz],
+        })
+        .collect();
+    let rock = linalg::intercept(&hail).unwrap();
+    let sum: BigInt = rock.position.iter().sum();
+    sum.try_into().unwrap()
+}
+
+/// The original solution, which hands the whole system to z3. Build with
+/// `--features z3` to check the linear algebra against it.
+#[cfg(feature = "z3")]
+fn part2_z3(input: &Input) -> i64 {
+    use std::ops::{Add, Mul};
+    use z3::{ast::Ast, SatResult};
+
+    let cfg = z3::Config::new();
+    let ctx = z3::Context::new(&cfg);
+    let solver = z3::Solver::new(&ctx);
+    let s_x = z3::ast::BV::new_const(&ctx, "x", 64);
+    let s_y = z3::ast::BV::new_const(&ctx, "y", 64);
+    let s_z = z3::ast::BV::new_const(&ctx, "z", 64);
+    let s_vx = z3::ast::BV::new_const(&ctx, "vx", 64);
+    let s_vy = z3::ast::BV::new_const(&ctx, "vy", 64);
+    let s_vz = z3::ast::BV::new_const(&ctx, "vz", 64);
+    let zero = z3::ast::BV::from_i64(&ctx, 0, 64);
     for (i, ((x, y, z), (vx, vy, vz))) in input.iter().enumerate() {
-        let t = z3::ast::BV::new_const(ctx, format!("t_{}", i), 64);
+        let t = z3::ast::BV::new_const(&ctx, format!("t_{}", i), 64);
         solver.assert(&t.bvuge(&zero));
 
-        let x = z3::ast::BV::from_i64(ctx, *x, 64);
-        let y = z3::ast::BV::from_i64(ctx, *y, 64);
-        let z = z3::ast::BV::from_i64(ctx, *z, 64);
-        let vx = z3::ast::BV::from_i64(ctx, *vx, 64);
-        let vy = z3::ast::BV::from_i64(ctx, *vy, 64);
-        let vz = z3::ast::BV::from_i64(ctx, *vz, 64);
+        let x = z3::ast::BV::from_i64(&ctx, *x, 64);
+        let y = z3::ast::BV::from_i64(&ctx, *y, 64);
+        let z = z3::ast::BV::from_i64(&ctx, *z, 64);
+        let vx = z3::ast::BV::from_i64(&ctx, *vx, 64);
+        let vy = z3::ast::BV::from_i64(&ctx, *vy, 64);
+        let vz = z3::ast::BV::from_i64(&ctx, *vz, 64);
         solver.assert(&(&s_x).add((&s_vx).mul(&t))._eq(&x.add(vx.mul(&t))));
         solver.assert(&(&s_y).add((&s_vy).mul(&t))._eq(&y.add(vy.mul(&t))));
         solver.assert(&(&s_z).add((&s_vz).mul(&t))._eq(&z.add(vz.mul(&t))));
@@ -123,11 +109,11 @@ fn z3_solve(solver: &z3::Solver, ctx: &z3::Context, input: &Input) -> i64 {
     model.eval(&res, false).unwrap().as_i64().unwrap()
 }
 
-fn part2(input: &Input) -> i64 {
-    let cfg = z3::Config::new();
-    let ctx = z3::Context::new(&cfg);
-    let solver = z3::Solver::new(&ctx);
-    z3_solve(&solver, &ctx, input)
+#[cfg(feature = "z3")]
+#[test]
+fn test_part2_z3() {
+    let input = parse_input(include_str!("test.txt"));
+    assert_eq!(part2_z3(&input), part2(&input));
 }
 
 #[test]
@@ -142,7 +128,9 @@ fn main() {
     let input = parse_input(include_str!("input.txt"));
     println!(
         "Part 1: {:?}",
-        part1(&input, 200000000000000f64, 400000000000000f64)
+        part1(&input, 200000000000000, 400000000000000)
     );
     println!("Part 2: {:?}", part2(&input));
+    #[cfg(feature = "z3")]
+    println!("Part 2 (z3): {:?}", part2_z3(&input));
 }
@@ -0,0 +1,84 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// Numbers distinct values from 0 in the order they are first seen, to use
+/// them as bit indices.
+#[derive(Debug, Clone)]
+pub struct Interner<T> {
+    values: Vec<T>,
+    index: HashMap<T, usize>,
+}
+
+impl<T: Clone + Eq + Hash> Interner<T> {
+    pub fn new() -> Self {
+        Self {
+            values: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    /// The index of `value`, giving it the next one if it is new.
+    pub fn intern(&mut self, value: T) -> usize {
+        if let Some(&i) = self.index.get(&value) {
+            return i;
+        }
+        self.values.push(value.clone());
+        self.index.insert(value, self.values.len() - 1);
+        self.values.len() - 1
+    }
+
+    pub fn get<Q>(&self, value: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.index.get(value).copied()
+    }
+
+    pub fn name(&self, index: usize) -> &T {
+        &self.values[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.values.iter().enumerate()
+    }
+}
+
+impl<T: Clone + Eq + Hash> Default for Interner<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Eq + Hash> FromIterator<T> for Interner<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut interner = Interner::new();
+        for value in iter {
+            interner.intern(value);
+        }
+        interner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut names: Interner<String> =
+            ["AA", "BB", "AA"].map(String::from).into_iter().collect();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.get("BB"), Some(1));
+        assert_eq!(names.intern("CC".to_string()), 2);
+        assert_eq!(names.name(0), "AA");
+        assert_eq!(names.get("DD"), None);
+    }
+}
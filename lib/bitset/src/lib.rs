@@ -0,0 +1,28 @@
+//! Small sets as bitmasks, for searches that carry a set of visited or
+//! opened things in their state.
+//!
+//! An [`Interner`] numbers the things, a [`BitSet`] holds a set of those
+//! numbers in a single `u32`, `u64` or `u128`, and a [`Memo`] caches results
+//! by node, set and time:
+//!
+//! ```
+//! use bitset::{BitSet, Interner};
+//!
+//! let mut valves = Interner::new();
+//! let (bb, cc, dd) = (valves.intern("BB"), valves.intern("CC"), valves.intern("DD"));
+//! let open = BitSet::<u64>::single(bb).with(dd);
+//! assert!(open.contains(dd) && !open.contains(cc));
+//! assert_eq!(open.iter().map(|v| *valves.name(v)).collect::<Vec<_>>(), ["BB", "DD"]);
+//! assert_eq!(open.subsets().count(), 4);
+//! ```
+//!
+//! [`subset_maxima`] turns a best value per exact set into the best value
+//! per set and all its subsets, which makes pairing up disjoint sets cheap.
+
+mod interner;
+mod memo;
+mod set;
+
+pub use interner::Interner;
+pub use memo::Memo;
+pub use set::{subset_maxima, BitSet, Subsets, Word};
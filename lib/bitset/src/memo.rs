@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use crate::{BitSet, Word};
+
+/// Results cached by node, set and time.
+///
+/// The time is whatever small counter the search needs besides its position
+/// and set, like minutes left or a flag packed into a number.
+#[derive(Clone)]
+pub struct Memo<V, W = u64> {
+    values: HashMap<(usize, BitSet<W>, u32), V>,
+    hits: usize,
+}
+
+impl<V: Clone, W: Word> Memo<V, W> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            hits: 0,
+        }
+    }
+
+    pub fn get(&mut self, node: usize, set: BitSet<W>, time: u32) -> Option<V> {
+        let value = self.values.get(&(node, set, time)).cloned();
+        self.hits += value.is_some() as usize;
+        value
+    }
+
+    pub fn insert(&mut self, node: usize, set: BitSet<W>, time: u32, value: V) -> V {
+        self.values.insert((node, set, time), value.clone());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// How many lookups found a cached result.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+}
+
+impl<V: Clone, W: Word> Default for Memo<V, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Paths through a 4x4 grid from the corner, counted by node and the set
+    /// of nodes they visited.
+    fn paths(memo: &mut Memo<u64>, node: usize, visited: BitSet, steps: u32) -> u64 {
+        if steps == 0 {
+            return 1;
+        }
+        if let Some(count) = memo.get(node, visited, steps) {
+            return count;
+        }
+        let (x, y) = (node % 4, node / 4);
+        let neighbours = [
+            (x > 0).then(|| node - 1),
+            (x < 3).then(|| node + 1),
+            (y > 0).then(|| node - 4),
+            (y < 3).then(|| node + 4),
+        ];
+        let count = neighbours
+            .into_iter()
+            .flatten()
+            .filter(|&n| !visited.contains(n))
+            .map(|n| paths(memo, n, visited.with(n), steps - 1))
+            .sum();
+        memo.insert(node, visited, steps, count)
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 0, BitSet::single(0), 3), 10);
+        assert!(!memo.is_empty());
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 0, BitSet::single(0), 15), 52);
+        assert!(memo.hits() > 0);
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fmt::{self, Debug},
+    hash::Hash,
+    ops::{BitAnd, BitOr, BitXor, Not, Shl, Sub},
+};
+
+/// The unsigned integers a [`BitSet`] can be stored in.
+pub trait Word:
+    Copy
+    + Eq
+    + Ord
+    + Hash
+    + Debug
+    + Default
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<usize, Output = Self>
+    + Sub<Output = Self>
+{
+    const BITS: usize;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+}
+
+macro_rules! impl_word {
+    ($($t:ty),*) => {
+        $(
+            impl Word for $t {
+                const BITS: usize = <$t>::BITS as usize;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+
+                fn trailing_zeros(self) -> u32 {
+                    <$t>::trailing_zeros(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u32, u64, u128);
+
+/// A set of indices below `W::BITS`, one bit per index.
+///
+/// The operators work like on `HashSet`s: `|` is the union, `&` the
+/// intersection, `-` the difference and `^` the symmetric difference.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BitSet<W = u64>(pub W);
+
+impl<W: Word> BitSet<W> {
+    pub const EMPTY: Self = BitSet(W::ZERO);
+
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// The set of all indices below `n`.
+    pub fn full(n: usize) -> Self {
+        assert!(
+            n <= W::BITS,
+            "{} elements do not fit into {} bits",
+            n,
+            W::BITS
+        );
+        if n == W::BITS {
+            BitSet(!W::ZERO)
+        } else {
+            BitSet((W::ONE << n) - W::ONE)
+        }
+    }
+
+    pub fn single(i: usize) -> Self {
+        assert!(
+            i < W::BITS,
+            "index {} does not fit into {} bits",
+            i,
+            W::BITS
+        );
+        BitSet(W::ONE << i)
+    }
+
+    pub fn contains(self, i: usize) -> bool {
+        i < W::BITS && self.0 & (W::ONE << i) != W::ZERO
+    }
+
+    pub fn insert(&mut self, i: usize) {
+        *self = self.with(i);
+    }
+
+    pub fn remove(&mut self, i: usize) {
+        *self = self.without(i);
+    }
+
+    /// The set with `i` added.
+    pub fn with(self, i: usize) -> Self {
+        self | Self::single(i)
+    }
+
+    /// The set with `i` taken out.
+    pub fn without(self, i: usize) -> Self {
+        self - Self::single(i)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == W::ZERO
+    }
+
+    pub fn is_subset(self, other: Self) -> bool {
+        self & other == self
+    }
+
+    pub fn is_disjoint(self, other: Self) -> bool {
+        (self & other).is_empty()
+    }
+
+    /// The indices in the set, smallest first.
+    pub fn iter(self) -> impl Iterator<Item = usize> {
+        let mut rest = self.0;
+        std::iter::from_fn(move || {
+            if rest == W::ZERO {
+                return None;
+            }
+            let i = rest.trailing_zeros() as usize;
+            rest = rest & (rest - W::ONE);
+            Some(i)
+        })
+    }
+
+    /// Every subset of the set, including the empty set and the set itself.
+    pub fn subsets(self) -> Subsets<W> {
+        Subsets {
+            set: self.0,
+            next: Some(self.0),
+        }
+    }
+}
+
+impl<W: Word> BitOr for BitSet<W> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        BitSet(self.0 | rhs.0)
+    }
+}
+
+impl<W: Word> BitAnd for BitSet<W> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        BitSet(self.0 & rhs.0)
+    }
+}
+
+impl<W: Word> BitXor for BitSet<W> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        BitSet(self.0 ^ rhs.0)
+    }
+}
+
+impl<W: Word> Sub for BitSet<W> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        BitSet(self.0 & !rhs.0)
+    }
+}
+
+impl<W: Word> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::EMPTY, |set, i| set.with(i))
+    }
+}
+
+impl<W: Word> Debug for BitSet<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Iterator over the subsets of a [`BitSet`], largest mask first.
+#[derive(Debug, Clone)]
+pub struct Subsets<W> {
+    set: W,
+    next: Option<W>,
+}
+
+impl<W: Word> Iterator for Subsets<W> {
+    type Item = BitSet<W>;
+
+    fn next(&mut self) -> Option<BitSet<W>> {
+        let current = self.next?;
+        self.next = (current != W::ZERO).then(|| (current - W::ONE) & self.set);
+        Some(BitSet(current))
+    }
+}
+
+/// Turns `best`, indexed by the mask of a set of `n` elements, into the
+/// largest value of the set or any of its subsets, in `O(n 2^n)`.
+pub fn subset_maxima<T: Copy + Ord>(best: &mut [T], n: usize) {
+    assert_eq!(best.len(), 1 << n, "one value per subset of {} elements", n);
+    for bit in 0..n {
+        for mask in 0..best.len() {
+            if mask & 1 << bit != 0 {
+                best[mask] = best[mask].max(best[mask ^ 1 << bit]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_ops() {
+        let a: BitSet = [1, 3, 5].into_iter().collect();
+        let b = BitSet::<u64>::single(3).with(4);
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), [1, 3, 4, 5]);
+        assert_eq!(a & b, BitSet::single(3));
+        assert_eq!((a - b).len(), 2);
+        assert!((a - b).is_disjoint(b));
+        assert!(BitSet::single(5).is_subset(a));
+        assert_eq!(format!("{:?}", a ^ b), "{1, 4, 5}");
+        assert_eq!(BitSet::<u32>::full(32).len(), 32);
+        assert_eq!(BitSet::<u128>::full(100).without(99).len(), 99);
+        assert!(BitSet::<u128>::single(127).contains(127));
+    }
+
+    #[test]
+    fn test_subsets() {
+        let set: BitSet<u32> = [0, 2, 3].into_iter().collect();
+        let subsets: Vec<u32> = set.subsets().map(|s| s.0).collect();
+        assert_eq!(
+            subsets,
+            [0b1101, 0b1100, 0b1001, 0b1000, 0b101, 0b100, 0b1, 0]
+        );
+        assert_eq!(BitSet::<u64>::EMPTY.subsets().count(), 1);
+    }
+
+    #[test]
+    fn test_subset_maxima() {
+        let mut best = [0, 5, 1, 2, 7, 0, 0, 3];
+        subset_maxima(&mut best, 3);
+        assert_eq!(best, [0, 5, 1, 5, 7, 7, 7, 7]);
+    }
+}
@@ -0,0 +1,219 @@
+use cycle::{crt, Cycle};
+
+use crate::{Circuit, CircuitError, Gate, NodeId, Signal, Simulation, LOW};
+
+/// How many times a node has to send before its period is trusted.
+const SAMPLES: usize = 4;
+
+/// For each of `nodes`, the presses at which it sends `signal`, as a cycle
+/// of press numbers starting at 1: it sends during press `n` if and only if
+/// `n >= start` and `n - start` is a multiple of `length`.
+///
+/// Each press sends a low pulse to `button` on a fresh simulation. A node
+/// gets no cycle if it does not send `signal` at least four times within
+/// `max_presses`, or not at a steady interval.
+pub fn periods(
+    circuit: &Circuit,
+    button: NodeId,
+    nodes: &[NodeId],
+    signal: Signal,
+    max_presses: usize,
+) -> Vec<Option<Cycle>> {
+    let mut sim = Simulation::new(circuit);
+    let mut seen: Vec<Vec<usize>> = vec![vec![]; nodes.len()];
+    for press in 1..=max_presses {
+        sim.send(button, LOW, |pulse| {
+            if pulse.signal != signal {
+                return;
+            }
+            let Some(from) = pulse.from else {
+                return;
+            };
+            for (i, _) in nodes.iter().enumerate().filter(|(_, &n)| n == from) {
+                if seen[i].last() != Some(&press) {
+                    seen[i].push(press);
+                }
+            }
+        });
+        if seen.iter().all(|presses| presses.len() >= SAMPLES) {
+            break;
+        }
+    }
+    seen.iter()
+        .map(|presses| {
+            if presses.len() < SAMPLES {
+                return None;
+            }
+            let length = presses[1] - presses[0];
+            let steady = presses.windows(2).all(|w| w[1] - w[0] == length);
+            steady.then(|| Cycle::new(presses[0], length))
+        })
+        .collect()
+}
+
+/// The first step at which all of the cycles are at a step they repeat.
+pub fn first_common(cycles: &[Cycle]) -> Option<usize> {
+    let congruences: Vec<_> = cycles
+        .iter()
+        .map(|c| (c.start as i64, c.length as i64))
+        .collect();
+    let (x, m) = crt(&congruences)?;
+    let (x, m) = (x as usize, m as usize);
+    let start = cycles.iter().map(|c| c.start).max().unwrap_or(0);
+    Some(if x >= start {
+        x
+    } else {
+        x + (start - x).div_ceil(m) * m
+    })
+}
+
+/// A target fed by one conjunction whose inputs are driven by parts of the
+/// circuit that share no state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decomposition {
+    /// The conjunction that drives the target.
+    pub join: NodeId,
+    /// The inputs of `join`, one per part.
+    pub counters: Vec<NodeId>,
+    /// The nodes upstream of each counter, without broadcasts, which keep
+    /// no state and may be shared.
+    pub parts: Vec<Vec<NodeId>>,
+}
+
+/// Splits what drives `target` into independent parts, if the circuit has
+/// that shape. The target sees a low pulse from the join exactly when all
+/// counters have last sent high, so if they do that periodically the
+/// periods can be combined without simulating the whole circuit.
+pub fn decompose(circuit: &Circuit, target: NodeId) -> Result<Decomposition, CircuitError> {
+    let not = |why: String| Err(CircuitError::NotDecomposable(why));
+    let &[join] = circuit.inputs(target) else {
+        return not(format!(
+            "{} does not have exactly one input",
+            circuit.name(target)
+        ));
+    };
+    if circuit.gate(join) != &Gate::Conjunction {
+        return not(format!("{} is not a conjunction", circuit.name(join)));
+    }
+    let counters = circuit.inputs(join).to_vec();
+    let mut owner = vec![None; circuit.len()];
+    let mut parts = vec![];
+    for (i, &counter) in counters.iter().enumerate() {
+        let mut part = vec![];
+        let mut stack = vec![counter];
+        while let Some(node) = stack.pop() {
+            if node == join {
+                return not(format!("{} feeds back into its inputs", circuit.name(join)));
+            }
+            if circuit.gate(node) == &Gate::Broadcast || owner[node] == Some(i) {
+                continue;
+            }
+            if let Some(other) = owner[node] {
+                return not(format!(
+                    "{} and {} share {}",
+                    circuit.name(counters[other]),
+                    circuit.name(counter),
+                    circuit.name(node)
+                ));
+            }
+            owner[node] = Some(i);
+            part.push(node);
+            stack.extend(circuit.inputs(node));
+        }
+        part.sort_unstable();
+        parts.push(part);
+    }
+    Ok(Decomposition {
+        join,
+        counters,
+        parts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CircuitBuilder;
+
+    /// Two binary counters of two and three bits, each with a conjunction
+    /// that sends high when it overflows, joined before `rx`.
+    fn counters() -> Circuit {
+        let mut builder = CircuitBuilder::new();
+        builder
+            .gate("broadcaster", Gate::Broadcast, &["a0", "b0"])
+            .gate("a0", Gate::FlipFlop, &["a1", "ac"])
+            .gate("a1", Gate::FlipFlop, &["ac"])
+            .gate("ac", Gate::Conjunction, &["ai"])
+            .gate("ai", Gate::Conjunction, &["join"])
+            .gate("b0", Gate::FlipFlop, &["b1", "bc"])
+            .gate("b1", Gate::FlipFlop, &["b2", "bc"])
+            .gate("b2", Gate::FlipFlop, &["bc"])
+            .gate("bc", Gate::Conjunction, &["bi"])
+            .gate("bi", Gate::Conjunction, &["join"])
+            .gate("join", Gate::Conjunction, &["rx"]);
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_decompose() {
+        let circuit = counters();
+        let id = |name| circuit.id(name).unwrap();
+        let d = decompose(&circuit, id("rx")).unwrap();
+        assert_eq!(d.join, id("join"));
+        assert_eq!(d.counters, [id("ai"), id("bi")]);
+        assert_eq!(d.parts[0], [id("a0"), id("a1"), id("ac"), id("ai")]);
+        assert_eq!(d.parts[1].len(), 5);
+
+        let mut builder = CircuitBuilder::new();
+        builder
+            .gate("broadcaster", Gate::Broadcast, &["x"])
+            .gate("x", Gate::FlipFlop, &["p", "q"])
+            .gate("p", Gate::Conjunction, &["join"])
+            .gate("q", Gate::Conjunction, &["join"])
+            .gate("join", Gate::Conjunction, &["rx"]);
+        let circuit = builder.build().unwrap();
+        assert_eq!(
+            decompose(&circuit, circuit.id("rx").unwrap()),
+            Err(CircuitError::NotDecomposable("p and q share x".into()))
+        );
+    }
+
+    #[test]
+    fn test_periods() {
+        let circuit = counters();
+        let id = |name| circuit.id(name).unwrap();
+        let cycles = periods(
+            &circuit,
+            id("broadcaster"),
+            &[id("ai"), id("bi"), id("a0")],
+            LOW,
+            100,
+        );
+        // the inverters send low on every press but the ones where their
+        // counter overflows, which is not periodic, and a0 sends low
+        // whenever it turns off
+        assert_eq!(cycles[0], None);
+        assert_eq!(cycles[1], None);
+        assert_eq!(cycles[2], Some(Cycle::new(2, 2)));
+        let cycles = periods(
+            &circuit,
+            id("broadcaster"),
+            &[id("ai"), id("bi")],
+            crate::HIGH,
+            100,
+        );
+        assert_eq!(cycles, [Some(Cycle::new(3, 4)), Some(Cycle::new(7, 8))]);
+        let cycles: Vec<_> = cycles.into_iter().flatten().collect();
+        assert_eq!(first_common(&cycles), Some(7));
+    }
+
+    #[test]
+    fn test_first_common() {
+        assert_eq!(
+            first_common(&[Cycle::new(3, 3), Cycle::new(5, 5)]),
+            Some(15)
+        );
+        assert_eq!(first_common(&[Cycle::new(4, 2), Cycle::new(1, 3)]), Some(4));
+        assert_eq!(first_common(&[Cycle::new(1, 2), Cycle::new(2, 4)]), None);
+    }
+}
@@ -0,0 +1,285 @@
+use std::{collections::HashMap, fmt, fmt::Write};
+
+use crate::{Gate, Operand, Signal};
+
+/// Index of a node in its [`Circuit`].
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    /// An operand names a wire that is not defined.
+    Undefined(String),
+    /// A logic gate has the wrong number of operands.
+    Arity(String),
+    /// The circuit does not have the shape an analysis needs.
+    NotDecomposable(String),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitError::Undefined(name) => write!(f, "wire {} is not defined", name),
+            CircuitError::Arity(name) => {
+                write!(f, "gate {} has the wrong number of operands", name)
+            }
+            CircuitError::NotDecomposable(why) => write!(f, "cannot decompose circuit: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+/// Where an operand of a logic gate comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    Const(Signal),
+    /// An index into the inputs of the gate.
+    Input(usize),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    pub name: String,
+    pub gate: Gate,
+    pub inputs: Vec<NodeId>,
+    pub outputs: Vec<NodeId>,
+    pub operands: Vec<Source>,
+}
+
+/// Collects named gates and their outputs, in any order.
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBuilder {
+    order: Vec<String>,
+    defs: HashMap<String, (Gate, Vec<String>)>,
+}
+
+impl CircuitBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines the node `name`, replacing any earlier definition. It sends
+    /// to `outputs` in that order, and a logic gate also receives from the
+    /// wires among its operands.
+    pub fn gate(&mut self, name: &str, gate: Gate, outputs: &[&str]) -> &mut Self {
+        let outputs = outputs.iter().map(|s| s.to_string()).collect();
+        if self
+            .defs
+            .insert(name.to_string(), (gate, outputs))
+            .is_none()
+        {
+            self.order.push(name.to_string());
+        }
+        self
+    }
+
+    /// Numbers the nodes in the order they were first defined, followed by
+    /// the sinks that are only named as outputs.
+    pub fn build(&self) -> Result<Circuit, CircuitError> {
+        let mut circuit = Circuit {
+            nodes: vec![],
+            index: HashMap::new(),
+        };
+        for name in &self.order {
+            circuit.add_node(name, self.defs[name].0.clone());
+        }
+        for name in &self.order {
+            let from = circuit.index[name];
+            for output in &self.defs[name].1 {
+                let to = match circuit.id(output) {
+                    Some(to) => to,
+                    None => circuit.add_node(output, Gate::Sink),
+                };
+                circuit.connect(from, to);
+            }
+        }
+        for name in &self.order {
+            let Gate::Logic(op, operands) = &self.defs[name].0 else {
+                continue;
+            };
+            if operands.len() != op.arity() {
+                return Err(CircuitError::Arity(name.clone()));
+            }
+            let to = circuit.index[name];
+            let mut sources = vec![];
+            for operand in operands {
+                sources.push(match operand {
+                    Operand::Const(value) => Source::Const(*value),
+                    Operand::Wire(wire) => {
+                        let from = circuit
+                            .id(wire)
+                            .ok_or_else(|| CircuitError::Undefined(wire.clone()))?;
+                        Source::Input(circuit.connect(from, to))
+                    }
+                });
+            }
+            circuit.nodes[to].operands = sources;
+        }
+        Ok(circuit)
+    }
+}
+
+/// A network of gates, see [`CircuitBuilder`] to make one and
+/// [`Simulation`](crate::Simulation) to run it.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    pub(crate) nodes: Vec<Node>,
+    index: HashMap<String, NodeId>,
+}
+
+impl Circuit {
+    fn add_node(&mut self, name: &str, gate: Gate) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            gate,
+            inputs: vec![],
+            outputs: vec![],
+            operands: vec![],
+        });
+        self.index.insert(name.to_string(), id);
+        id
+    }
+
+    /// Adds the edge unless it is there already, and returns its index
+    /// among the inputs of `to`.
+    fn connect(&mut self, from: NodeId, to: NodeId) -> usize {
+        if !self.nodes[from].outputs.contains(&to) {
+            self.nodes[from].outputs.push(to);
+        }
+        let inputs = &mut self.nodes[to].inputs;
+        match inputs.iter().position(|&i| i == from) {
+            Some(slot) => slot,
+            None => {
+                inputs.push(from);
+                inputs.len() - 1
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.index.get(name).copied()
+    }
+
+    pub fn name(&self, node: NodeId) -> &str {
+        &self.nodes[node].name
+    }
+
+    pub fn gate(&self, node: NodeId) -> &Gate {
+        &self.nodes[node].gate
+    }
+
+    pub fn inputs(&self, node: NodeId) -> &[NodeId] {
+        &self.nodes[node].inputs
+    }
+
+    pub fn outputs(&self, node: NodeId) -> &[NodeId] {
+        &self.nodes[node].outputs
+    }
+
+    /// The circuit in Graphviz's DOT language, with flip-flops as boxes,
+    /// conjunctions as diamonds and logic gates labelled with their
+    /// operation. Render it with `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for node in &self.nodes {
+            let (shape, label) = match &node.gate {
+                Gate::Broadcast => ("doubleoctagon", node.name.clone()),
+                Gate::FlipFlop => ("box", format!("%{}", node.name)),
+                Gate::Conjunction => ("diamond", format!("&{}", node.name)),
+                Gate::Logic(op, _) => ("ellipse", format!("{} = {:?}", node.name, op)),
+                Gate::Sink => ("doublecircle", node.name.clone()),
+            };
+            writeln!(
+                dot,
+                "    {} [shape={}, label={}];",
+                quote(&node.name),
+                shape,
+                quote(&label)
+            )
+            .unwrap();
+        }
+        for node in &self.nodes {
+            for &to in &node.outputs {
+                writeln!(
+                    dot,
+                    "    {} -> {};",
+                    quote(&node.name),
+                    quote(self.name(to))
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogicOp;
+
+    #[test]
+    fn test_build() {
+        let mut builder = CircuitBuilder::new();
+        builder
+            .gate("broadcaster", Gate::Broadcast, &["a"])
+            .gate("a", Gate::FlipFlop, &["inv", "out"])
+            .gate("inv", Gate::Conjunction, &["a"]);
+        let circuit = builder.build().unwrap();
+        assert_eq!(circuit.len(), 4);
+        let (a, inv, out) = (circuit.id("a").unwrap(), circuit.id("inv").unwrap(), 3);
+        assert_eq!(circuit.gate(out), &Gate::Sink);
+        assert_eq!(circuit.name(out), "out");
+        assert_eq!(circuit.outputs(a), [inv, out]);
+        assert_eq!(circuit.inputs(a), [0, inv]);
+        assert_eq!(
+            circuit.to_dot().lines().nth(2),
+            Some("    \"a\" [shape=box, label=\"%a\"];")
+        );
+        assert!(circuit.to_dot().contains("    \"a\" -> \"out\";\n"));
+    }
+
+    #[test]
+    fn test_build_logic() {
+        let mut builder = CircuitBuilder::new();
+        builder
+            .gate(
+                "d",
+                Gate::Logic(LogicOp::And, vec!["x".into(), "x".into()]),
+                &[],
+            )
+            .gate("x", Gate::Logic(LogicOp::Copy, vec!["3".into()]), &[]);
+        let circuit = builder.build().unwrap();
+        assert_eq!(circuit.inputs(0), [1]);
+        assert_eq!(
+            circuit.nodes[0].operands,
+            [Source::Input(0), Source::Input(0)]
+        );
+        assert_eq!(circuit.nodes[1].operands, [Source::Const(3)]);
+
+        builder.gate("d", Gate::Logic(LogicOp::Not, vec!["y".into()]), &[]);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CircuitError::Undefined("y".into())
+        );
+        builder.gate("d", Gate::Logic(LogicOp::Or, vec!["x".into()]), &[]);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            CircuitError::Arity("d".into())
+        );
+    }
+}
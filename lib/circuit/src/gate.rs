@@ -0,0 +1,126 @@
+use std::{fmt, str::FromStr};
+
+/// What travels along a wire: a 16-bit word, or a pulse that is [`LOW`] or
+/// [`HIGH`].
+pub type Signal = u16;
+
+pub const LOW: Signal = 0;
+pub const HIGH: Signal = 1;
+
+/// The operation of a [`Gate::Logic`] gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogicOp {
+    /// Passes its one operand on.
+    Copy,
+    Not,
+    And,
+    Or,
+    Shl,
+    Shr,
+}
+
+impl LogicOp {
+    pub fn arity(self) -> usize {
+        match self {
+            LogicOp::Copy | LogicOp::Not => 1,
+            _ => 2,
+        }
+    }
+
+    /// Applies the operation to as many operands as its arity. Bits
+    /// shifted out of the word are lost.
+    pub fn apply(self, operands: &[Signal]) -> Signal {
+        match self {
+            LogicOp::Copy => operands[0],
+            LogicOp::Not => !operands[0],
+            LogicOp::And => operands[0] & operands[1],
+            LogicOp::Or => operands[0] | operands[1],
+            LogicOp::Shl => operands[0].checked_shl(operands[1] as u32).unwrap_or(0),
+            LogicOp::Shr => operands[0].checked_shr(operands[1] as u32).unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownOp(pub String);
+
+impl fmt::Display for UnknownOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown logic op {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownOp {}
+
+impl FromStr for LogicOp {
+    type Err = UnknownOp;
+
+    /// The upper case names of the 2015 day 7 booklet.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "NOT" => LogicOp::Not,
+            "AND" => LogicOp::And,
+            "OR" => LogicOp::Or,
+            "LSHIFT" => LogicOp::Shl,
+            "RSHIFT" => LogicOp::Shr,
+            _ => return Err(UnknownOp(s.to_string())),
+        })
+    }
+}
+
+/// An operand of a [`Gate::Logic`] gate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Const(Signal),
+    /// The output of another gate, which becomes an input of this one.
+    Wire(String),
+}
+
+impl From<&str> for Operand {
+    /// A number is a constant, anything else a wire.
+    fn from(s: &str) -> Self {
+        match s.parse() {
+            Ok(value) => Operand::Const(value),
+            Err(_) => Operand::Wire(s.to_string()),
+        }
+    }
+}
+
+/// How a node reacts to the signals it receives.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gate {
+    /// Sends on every signal it receives.
+    Broadcast,
+    /// Ignores high pulses, and flips on a low pulse and sends high if it
+    /// is now on, low if it is now off. Starts off.
+    FlipFlop,
+    /// Remembers the last pulse from each input, all low to start with, and
+    /// sends low if they are all high, high otherwise.
+    Conjunction,
+    /// Sends the operation applied to its operands as soon as all of them
+    /// are known, and again whenever an input changes. Gates whose operands
+    /// are all constants send when the circuit is powered on.
+    Logic(LogicOp, Vec<Operand>),
+    /// Only receives. Nodes that are named as an output but never defined
+    /// are sinks.
+    Sink,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logic_ops() {
+        assert_eq!(LogicOp::Not.apply(&[123]), 65412);
+        assert_eq!(LogicOp::And.apply(&[123, 456]), 72);
+        assert_eq!(LogicOp::Or.apply(&[123, 456]), 507);
+        assert_eq!(LogicOp::Shl.apply(&[65535, 9]), 65024);
+        assert_eq!(LogicOp::Shr.apply(&[456, 2]), 114);
+        assert_eq!(LogicOp::Shl.apply(&[1, 16]), 0);
+        assert_eq!("RSHIFT".parse(), Ok(LogicOp::Shr));
+        assert_eq!("XOR".parse::<LogicOp>(), Err(UnknownOp("XOR".into())));
+        assert_eq!(Operand::from("12"), Operand::Const(12));
+        assert_eq!(Operand::from("lx"), Operand::Wire("lx".into()));
+    }
+}
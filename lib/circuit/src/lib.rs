@@ -0,0 +1,37 @@
+//! Circuits of gates that pass signals along wires, for the puzzles with
+//! flip-flops, conjunctions or bitwise logic.
+//!
+//! - [`CircuitBuilder`] collects named [`Gate`]s and their outputs into a
+//!   [`Circuit`], which can be exported with [`Circuit::to_dot`],
+//! - [`Simulation`] keeps the state of every gate and delivers pulses in the
+//!   order they were sent, letting a callback trace each one,
+//! - [`periods`] finds when nodes send a signal across button presses,
+//!   [`decompose`] splits what drives a node into independent parts, and
+//!   [`first_common`] combines their periods.
+//!
+//! ```
+//! use circuit::{CircuitBuilder, Gate, Simulation, LOW};
+//!
+//! let mut builder = CircuitBuilder::new();
+//! builder
+//!     .gate("broadcaster", Gate::Broadcast, &["a", "b", "c"])
+//!     .gate("a", Gate::FlipFlop, &["b"])
+//!     .gate("b", Gate::FlipFlop, &["c"])
+//!     .gate("c", Gate::FlipFlop, &["inv"])
+//!     .gate("inv", Gate::Conjunction, &["a"]);
+//! let circuit = builder.build().unwrap();
+//! let mut sim = Simulation::new(&circuit);
+//! let mut pulses = 0;
+//! sim.send(circuit.id("broadcaster").unwrap(), LOW, |_| pulses += 1);
+//! assert_eq!(pulses, 12);
+//! ```
+
+mod analysis;
+mod circuit;
+mod gate;
+mod sim;
+
+pub use analysis::{decompose, first_common, periods, Decomposition};
+pub use circuit::{Circuit, CircuitBuilder, CircuitError, NodeId};
+pub use gate::{Gate, LogicOp, Operand, Signal, UnknownOp, HIGH, LOW};
+pub use sim::{Pulse, Simulation};
@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+
+use crate::{circuit::Source, Circuit, Gate, NodeId, Signal, HIGH, LOW};
+
+/// A signal on its way along a wire. Signals from outside the circuit, like
+/// a button, come from `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pulse {
+    pub from: Option<NodeId>,
+    pub to: NodeId,
+    pub signal: Signal,
+}
+
+impl Pulse {
+    /// The pulse the way the 2023 day 20 puzzle writes them,
+    /// `a -high-> b`, with words for anything but a pulse as numbers.
+    pub fn describe(&self, circuit: &Circuit) -> String {
+        let from = self.from.map_or("button", |node| circuit.name(node));
+        let signal = match self.signal {
+            LOW => "low".to_string(),
+            HIGH => "high".to_string(),
+            word => word.to_string(),
+        };
+        format!("{} -{}-> {}", from, signal, circuit.name(self.to))
+    }
+}
+
+/// The state of every gate of a circuit, and the pulses still on their way
+/// in the order they were sent.
+#[derive(Debug, Clone)]
+pub struct Simulation<'a> {
+    pub circuit: &'a Circuit,
+    /// The last signal on each input of each node, if any.
+    memory: Vec<Vec<Option<Signal>>>,
+    on: Vec<bool>,
+    last: Vec<Option<Signal>>,
+    queue: VecDeque<Pulse>,
+}
+
+impl<'a> Simulation<'a> {
+    /// Every flip-flop off and every conjunction remembering low pulses.
+    pub fn new(circuit: &'a Circuit) -> Self {
+        let memory = circuit
+            .nodes
+            .iter()
+            .map(|node| {
+                let initial = match node.gate {
+                    Gate::Conjunction => Some(LOW),
+                    _ => None,
+                };
+                vec![initial; node.inputs.len()]
+            })
+            .collect();
+        Self {
+            circuit,
+            memory,
+            on: vec![false; circuit.len()],
+            last: vec![None; circuit.len()],
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// The last signal `node` sent, or received if it is a sink.
+    pub fn value(&self, node: NodeId) -> Option<Signal> {
+        self.last[node]
+    }
+
+    /// Whether a flip-flop is on.
+    pub fn is_on(&self, node: NodeId) -> bool {
+        self.on[node]
+    }
+
+    /// Lets the logic gates with only constant operands send, and runs
+    /// until the circuit settles.
+    pub fn power_on(&mut self, observe: impl FnMut(&Pulse)) {
+        for (id, node) in self.circuit.nodes.iter().enumerate() {
+            if matches!(node.gate, Gate::Logic(..)) && node.inputs.is_empty() {
+                self.fire(id);
+            }
+        }
+        self.run(observe);
+    }
+
+    /// Sends `signal` to `node` from outside, like pressing a button, and
+    /// runs until the circuit settles. `observe` sees every pulse as it
+    /// arrives, starting with this one.
+    pub fn send(&mut self, node: NodeId, signal: Signal, observe: impl FnMut(&Pulse)) {
+        self.queue.push_back(Pulse {
+            from: None,
+            to: node,
+            signal,
+        });
+        self.run(observe);
+    }
+
+    fn run(&mut self, mut observe: impl FnMut(&Pulse)) {
+        while let Some(pulse) = self.queue.pop_front() {
+            observe(&pulse);
+            self.receive(pulse);
+        }
+    }
+
+    fn receive(&mut self, pulse: Pulse) {
+        let node = &self.circuit.nodes[pulse.to];
+        if let Some(from) = pulse.from {
+            let slot = node.inputs.iter().position(|&i| i == from).unwrap();
+            self.memory[pulse.to][slot] = Some(pulse.signal);
+        }
+        let output = match node.gate {
+            Gate::Broadcast => Some(pulse.signal),
+            Gate::FlipFlop if pulse.signal == LOW => {
+                self.on[pulse.to] = !self.on[pulse.to];
+                Some(self.on[pulse.to] as Signal)
+            }
+            Gate::FlipFlop => None,
+            Gate::Conjunction => {
+                let all_high = self.memory[pulse.to].iter().all(|&s| s == Some(HIGH));
+                Some(if all_high { LOW } else { HIGH })
+            }
+            Gate::Logic(..) => return self.fire(pulse.to),
+            Gate::Sink => {
+                self.last[pulse.to] = Some(pulse.signal);
+                None
+            }
+        };
+        if let Some(signal) = output {
+            self.emit(pulse.to, signal);
+        }
+    }
+
+    /// Evaluates a logic gate if all its operands are known.
+    fn fire(&mut self, id: NodeId) {
+        let node = &self.circuit.nodes[id];
+        let Gate::Logic(op, _) = node.gate else {
+            unreachable!("only logic gates fire");
+        };
+        let operands: Option<Vec<Signal>> = node
+            .operands
+            .iter()
+            .map(|source| match *source {
+                Source::Const(value) => Some(value),
+                Source::Input(slot) => self.memory[id][slot],
+            })
+            .collect();
+        if let Some(operands) = operands {
+            self.emit(id, op.apply(&operands));
+        }
+    }
+
+    fn emit(&mut self, from: NodeId, signal: Signal) {
+        self.last[from] = Some(signal);
+        for &to in &self.circuit.nodes[from].outputs {
+            self.queue.push_back(Pulse {
+                from: Some(from),
+                to,
+                signal,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CircuitBuilder, LogicOp, Operand};
+
+    #[test]
+    fn test_pulses() {
+        // The second example of 2023 day 20.
+        let mut builder = CircuitBuilder::new();
+        builder
+            .gate("broadcaster", Gate::Broadcast, &["a"])
+            .gate("a", Gate::FlipFlop, &["inv", "con"])
+            .gate("inv", Gate::Conjunction, &["b"])
+            .gate("b", Gate::FlipFlop, &["con"])
+            .gate("con", Gate::Conjunction, &["output"]);
+        let circuit = builder.build().unwrap();
+        let mut sim = Simulation::new(&circuit);
+        let mut trace = vec![];
+        sim.send(0, LOW, |pulse| trace.push(pulse.describe(&circuit)));
+        assert_eq!(
+            trace,
+            [
+                "button -low-> broadcaster",
+                "broadcaster -low-> a",
+                "a -high-> inv",
+                "a -high-> con",
+                "inv -low-> b",
+                "con -high-> output",
+                "b -high-> con",
+                "con -low-> output",
+            ]
+        );
+        let output = circuit.id("output").unwrap();
+        assert_eq!(sim.value(output), Some(LOW));
+        assert!(sim.is_on(circuit.id("b").unwrap()));
+    }
+
+    #[test]
+    fn test_logic() {
+        // The example of 2015 day 7.
+        let mut builder = CircuitBuilder::new();
+        let logic = |op, operands: &[&str]| {
+            Gate::Logic(op, operands.iter().map(|&s| Operand::from(s)).collect())
+        };
+        builder
+            .gate("d", logic(LogicOp::And, &["x", "y"]), &[])
+            .gate("x", logic(LogicOp::Copy, &["123"]), &[])
+            .gate("y", logic(LogicOp::Copy, &["456"]), &[])
+            .gate("h", logic(LogicOp::Not, &["x"]), &[])
+            .gate("g", logic(LogicOp::Shr, &["y", "2"]), &[]);
+        let circuit = builder.build().unwrap();
+        let mut sim = Simulation::new(&circuit);
+        let mut pulses = 0;
+        sim.power_on(|_| pulses += 1);
+        let value = |name| sim.value(circuit.id(name).unwrap());
+        assert_eq!(value("d"), Some(72));
+        assert_eq!(value("h"), Some(65412));
+        assert_eq!(value("g"), Some(114));
+        assert_eq!(pulses, 4);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::Cycle;
+
+/// Finds the cycle in a recorded sequence, like the height of a tower after
+/// every step, whose differences repeat although the values keep growing.
+/// This helps when the state behind the values is too big to compare.
+///
+/// Returns the shortest cycle that the differences are still in at the end
+/// of the recording, covering at least half of it and at least two whole
+/// cycles. Record more values if there is none.
+pub fn find_delta_cycle(values: &[i64]) -> Option<Cycle> {
+    let deltas: Vec<i64> = values.windows(2).map(|w| w[1] - w[0]).collect();
+    let len = deltas.len();
+    (1..=len / 2).find_map(|length| {
+        // walk back from the end for as long as the differences repeat
+        let start = (0..len - length)
+            .rev()
+            .find(|&i| deltas[i] != deltas[i + length])
+            .map_or(0, |i| i + 1);
+        let covered = len - start;
+        (covered >= 2 * length && covered * 2 >= len).then(|| Cycle::new(start, length))
+    })
+}
+
+/// The value at step `n` of a sequence with `values` recorded so far, whose
+/// differences repeat in `cycle` as found by [`find_delta_cycle`].
+pub fn extrapolate(values: &[i64], cycle: Cycle, n: usize) -> i64 {
+    if n < values.len() {
+        return values[n];
+    }
+    let Cycle { start, length } = cycle;
+    let gain = values[start + length] - values[start];
+    values[cycle.index_at(n)] + cycle.cycles_until(n) as i64 * gain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extrapolate() {
+        // a tower that grows 1, 2, 3 and then 4, 0, 1 over and over
+        let deltas = [1, 2, 3].into_iter().chain([4, 0, 1].repeat(6));
+        let values: Vec<i64> = std::iter::once(0)
+            .chain(deltas.scan(0, |top, d| {
+                *top += d;
+                Some(*top)
+            }))
+            .collect();
+        let cycle = find_delta_cycle(&values).unwrap();
+        assert_eq!(cycle, Cycle::new(3, 3));
+        assert_eq!(extrapolate(&values, cycle, 5), 10);
+        assert_eq!(extrapolate(&values, cycle, 3 + 3 * 100), 6 + 5 * 100);
+        assert_eq!(extrapolate(&values, cycle, 4 + 3 * 100), 10 + 5 * 100);
+        assert_eq!(find_delta_cycle(&[0, 1, 3, 6, 10]), None);
+    }
+}
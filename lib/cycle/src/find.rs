@@ -0,0 +1,193 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::Cycle;
+
+/// Brent's algorithm: finds the cycle that repeatedly applying `step` to
+/// `initial` runs into, keeping only two states in memory. Usually needs
+/// fewer steps than [`floyd`].
+///
+/// Never returns if the states do not cycle.
+pub fn brent<T: Clone + Eq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    // with the hare a whole cycle ahead, they meet where the cycle starts
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle::new(start, length)
+}
+
+/// Floyd's tortoise and hare: finds the cycle that repeatedly applying
+/// `step` to `initial` runs into, keeping only two states in memory.
+///
+/// Never returns if the states do not cycle.
+pub fn floyd<T: Clone + Eq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let half = step(&hare);
+        hare = step(&half);
+    }
+    let mut tortoise = initial;
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle::new(start, length)
+}
+
+/// All states up to the end of the first pass through a cycle.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    pub cycle: Cycle,
+    /// The state after every step, starting with the initial state.
+    pub states: Vec<T>,
+}
+
+impl<T> History<T> {
+    /// The state after `n` steps.
+    pub fn state_at(&self, n: usize) -> &T {
+        &self.states[self.cycle.index_at(n)]
+    }
+}
+
+/// Applies `step` to `initial` until a state repeats, remembering every state
+/// on the way. Needs hashable states, but unlike [`brent`] it only steps
+/// through the cycle once and can look up any state afterwards.
+///
+/// Never returns if the states do not cycle.
+pub fn find_cycle<T: Clone + Eq + Hash>(initial: T, step: impl FnMut(&T) -> T) -> History<T> {
+    match record(initial, step, usize::MAX) {
+        Ok(history) => history,
+        Err(_) => unreachable!("no cycle within usize::MAX steps"),
+    }
+}
+
+/// The state after applying `step` to `initial` `n` times, skipping whole
+/// cycles once a state repeats.
+pub fn fast_forward<T: Clone + Eq + Hash>(initial: T, step: impl FnMut(&T) -> T, n: usize) -> T {
+    match record(initial, step, n) {
+        Ok(history) => history.state_at(n).clone(),
+        Err(state) => state,
+    }
+}
+
+/// Records states until one repeats, or returns the state after `limit`
+/// steps if none does before that.
+fn record<T: Clone + Eq + Hash>(
+    initial: T,
+    mut step: impl FnMut(&T) -> T,
+    limit: usize,
+) -> Result<History<T>, T> {
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+    for i in 0..limit {
+        if let Some(&start) = seen.get(&state) {
+            return Ok(History {
+                cycle: Cycle::new(start, i - start),
+                states,
+            });
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), i);
+        states.push(state);
+        state = next;
+    }
+    Err(state)
+}
+
+/// A value that was seen twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repeat<T> {
+    /// Positions of the first two occurrences.
+    pub first: usize,
+    pub second: usize,
+    pub value: T,
+}
+
+/// The first value that `values` yields a second time.
+pub fn first_repeat<T: Clone + Eq + Hash>(
+    values: impl IntoIterator<Item = T>,
+) -> Option<Repeat<T>> {
+    let mut seen = HashMap::new();
+    for (i, value) in values.into_iter().enumerate() {
+        if let Some(&first) = seen.get(&value) {
+            return Some(Repeat {
+                first,
+                second: i,
+                value,
+            });
+        }
+        seen.insert(value, i);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, ..., 6 and then 3, 4, 5, 6 forever.
+    fn step(&n: &u32) -> u32 {
+        if n == 6 {
+            3
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        let expected = Cycle::new(3, 4);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(floyd(0, step), expected);
+        let history = find_cycle(0, step);
+        assert_eq!(history.cycle, expected);
+        assert_eq!(history.states, vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(*history.state_at(1_000_000_004), 4);
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        assert_eq!(fast_forward(0, step, 2), 2);
+        assert_eq!(fast_forward(0, step, 7), 3);
+        assert_eq!(fast_forward(0, step, 1_000_000_000), 4);
+        // a pure cycle
+        assert_eq!(brent(0, |&n: &u32| (n + 1) % 5), Cycle::new(0, 5));
+    }
+
+    #[test]
+    fn test_first_repeat() {
+        let repeat = first_repeat([3, 1, 4, 1, 5]).unwrap();
+        assert_eq!((repeat.first, repeat.second, repeat.value), (1, 3, 1));
+        assert_eq!(first_repeat(0..10), None);
+    }
+}
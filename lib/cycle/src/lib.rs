@@ -0,0 +1,76 @@
+//! Cycle detection for puzzles that ask for the state after an absurd number
+//! of steps.
+//!
+//! - [`brent`] and [`floyd`] find the cycle of a step function using constant
+//!   memory, for states that are cheap to compare but expensive to store,
+//! - [`find_cycle`] remembers every state in a hash map and keeps them, so
+//!   [`fast_forward`] can look up the state at any step once the cycle is
+//!   found,
+//! - [`first_repeat`] finds the first value an iterator yields twice,
+//! - [`find_delta_cycle`] finds a cycle in the differences of a recorded
+//!   sequence when the state itself is too big to compare, and [`extrapolate`]
+//!   continues the sequence from there,
+//! - [`lcm_all`], [`Cycle::combine`] and [`crt`] combine the cycles of
+//!   independent components, with the number theory re-exported from
+//!   [`numtheory`].
+
+mod delta;
+mod find;
+
+pub use delta::{extrapolate, find_delta_cycle};
+pub use find::{brent, fast_forward, find_cycle, first_repeat, floyd, History, Repeat};
+pub use numtheory::{crt, gcd, lcm, lcm_all};
+
+/// A sequence that, from step `start` on, repeats itself every `length`
+/// steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// The first step that is part of the cycle.
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    pub fn new(start: usize, length: usize) -> Self {
+        assert!(length > 0, "a cycle needs a length");
+        Self { start, length }
+    }
+
+    /// The step before `start + length` that is in the same state as step
+    /// `n`.
+    pub fn index_at(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// Number of whole cycles between `start` and step `n`.
+    pub fn cycles_until(&self, n: usize) -> usize {
+        n.saturating_sub(self.start) / self.length
+    }
+
+    /// The cycle of two independent sequences run side by side: it starts
+    /// once both have entered their cycles and ends when both end at once.
+    pub fn combine(self, other: Cycle) -> Cycle {
+        Cycle::new(self.start.max(other.start), lcm(self.length, other.length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_at() {
+        let cycle = Cycle::new(3, 4);
+        assert_eq!(
+            (0..12).map(|n| cycle.index_at(n)).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5, 6, 3, 4, 5, 6, 3]
+        );
+        assert_eq!(cycle.cycles_until(2), 0);
+        assert_eq!(cycle.cycles_until(11), 2);
+        assert_eq!(cycle.combine(Cycle::new(5, 6)), Cycle::new(5, 12));
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Error, ExprId, Exprs, Op, Rational};
+
+/// A number or the name of another definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    Num(Rational),
+    Name(String),
+}
+
+impl From<&str> for Operand {
+    /// Integers become numbers, anything else a name.
+    fn from(s: &str) -> Self {
+        match s.parse::<i64>() {
+            Ok(n) => Operand::Num(n.into()),
+            Err(_) => Operand::Name(s.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Def {
+    Operand(Operand),
+    Binary(Op, Operand, Operand),
+}
+
+/// Named definitions like monkeys yelling or wires with gates, that refer
+/// to each other by name, turned into [`Exprs`] by [`Definitions::build`].
+#[derive(Debug, Clone, Default)]
+pub struct Definitions {
+    defs: HashMap<String, Def>,
+    unknowns: HashSet<String>,
+}
+
+impl Definitions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines `name`, replacing any earlier definition.
+    pub fn define(&mut self, name: &str, def: Def) {
+        self.defs.insert(name.to_string(), def);
+    }
+
+    /// Makes `name` an unknown, ignoring its definition if it has one.
+    pub fn unknown(&mut self, name: &str) {
+        self.unknowns.insert(name.to_string());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Def> {
+        self.defs.get(name)
+    }
+
+    /// The expression for `name`, with the definitions it uses substituted
+    /// and constants folded.
+    pub fn build(&self, exprs: &mut Exprs, name: &str) -> Result<ExprId, Error> {
+        self.build_name(exprs, name, &mut HashMap::new())
+    }
+
+    /// The expression for a number or a name, like the operands of another
+    /// definition.
+    pub fn build_operand(&self, exprs: &mut Exprs, operand: &Operand) -> Result<ExprId, Error> {
+        match operand {
+            Operand::Num(n) => Ok(exprs.num(*n)),
+            Operand::Name(name) => self.build(exprs, name),
+        }
+    }
+
+    /// `built` holds `None` for the names that are being built further up,
+    /// to catch definitions that refer to themselves.
+    fn build_name<'a>(
+        &'a self,
+        exprs: &mut Exprs,
+        name: &'a str,
+        built: &mut HashMap<&'a str, Option<ExprId>>,
+    ) -> Result<ExprId, Error> {
+        match built.get(name) {
+            Some(Some(id)) => return Ok(*id),
+            Some(None) => return Err(Error::Cycle(name.to_string())),
+            None => {}
+        }
+        if self.unknowns.contains(name) {
+            return Ok(exprs.var(name));
+        }
+        built.insert(name, None);
+        let mut operand = |exprs: &mut Exprs, operand: &'a Operand| match operand {
+            Operand::Num(n) => Ok(exprs.num(*n)),
+            Operand::Name(name) => self.build_name(exprs, name, built),
+        };
+        let id = match self.defs.get(name) {
+            None => return Err(Error::Undefined(name.to_string())),
+            Some(Def::Operand(a)) => operand(exprs, a)?,
+            Some(Def::Binary(op, a, b)) => {
+                let a = operand(exprs, a)?;
+                let b = operand(exprs, b)?;
+                exprs.binary(*op, a, b)?
+            }
+        };
+        built.insert(name, Some(id));
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(s: &str) -> Def {
+        match s.split(' ').collect::<Vec<_>>()[..] {
+            [a] => Def::Operand(a.into()),
+            [a, op, b] => Def::Binary(op.parse().unwrap(), a.into(), b.into()),
+            _ => panic!("{}", s),
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let mut defs = Definitions::new();
+        defs.define("a", def("b * c"));
+        defs.define("b", def("c + 2"));
+        defs.define("c", def("3"));
+        let mut exprs = Exprs::new();
+        let a = defs.build(&mut exprs, "a").unwrap();
+        assert_eq!(exprs.value(a), Some(15i64.into()));
+
+        defs.unknown("c");
+        let a = defs.build(&mut exprs, "a").unwrap();
+        assert_eq!(exprs.display(a).to_string(), "((c + 2) * c)");
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut defs = Definitions::new();
+        defs.define("a", def("b - 1"));
+        let mut exprs = Exprs::new();
+        assert_eq!(
+            defs.build(&mut exprs, "a"),
+            Err(Error::Undefined("b".to_string()))
+        );
+        defs.define("b", def("a"));
+        assert_eq!(
+            defs.build(&mut exprs, "a"),
+            Err(Error::Cycle("a".to_string()))
+        );
+    }
+}
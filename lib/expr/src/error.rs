@@ -0,0 +1,39 @@
+use std::fmt;
+
+use crate::Rational;
+
+/// Why an expression could not be built or solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A definition refers to a name that has none.
+    Undefined(String),
+    /// The name is defined in terms of itself.
+    Cycle(String),
+    DivisionByZero,
+    /// A bitwise operation on a fraction or a negative number.
+    NotBitwise(Rational),
+    UnknownOp(String),
+    /// The unknown is multiplied by itself, divided by, or used bitwise.
+    NonLinear,
+    /// The expression has an unknown besides the one solved for.
+    OtherUnknown(String),
+    /// The unknown cancels out, so any or no value solves the equation.
+    NoUniqueSolution,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Undefined(name) => write!(f, "{} is not defined", name),
+            Error::Cycle(name) => write!(f, "{} depends on itself", name),
+            Error::DivisionByZero => write!(f, "division by zero"),
+            Error::NotBitwise(n) => write!(f, "{} is not a non-negative integer", n),
+            Error::UnknownOp(op) => write!(f, "unknown operator {:?}", op),
+            Error::NonLinear => write!(f, "the equation is not linear in the unknown"),
+            Error::OtherUnknown(name) => write!(f, "{} is unknown too", name),
+            Error::NoUniqueSolution => write!(f, "the unknown cancels out"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,213 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{Error, Rational};
+
+/// The index of an expression in its [`Exprs`].
+pub type ExprId = usize;
+
+/// The binary operators. The bitwise ones only fold on non-negative
+/// integers, and [`Exprs::solve`] only inverts the arithmetic ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+}
+
+impl Op {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::And => "&",
+            Op::Or => "|",
+            Op::Xor => "^",
+            Op::Shl => "<<",
+            Op::Shr => ">>",
+        }
+    }
+
+    pub fn apply(self, a: Rational, b: Rational) -> Result<Rational, Error> {
+        let bits = |n: Rational| match n.to_integer() {
+            Some(n) if n >= 0 => Ok(n),
+            _ => Err(Error::NotBitwise(n)),
+        };
+        Ok(match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div if b.is_zero() => return Err(Error::DivisionByZero),
+            Op::Div => a / b,
+            Op::And => (bits(a)? & bits(b)?).into(),
+            Op::Or => (bits(a)? | bits(b)?).into(),
+            Op::Xor => (bits(a)? ^ bits(b)?).into(),
+            Op::Shl => (bits(a)? << bits(b)?).into(),
+            Op::Shr => (bits(a)? >> bits(b)?).into(),
+        })
+    }
+}
+
+impl FromStr for Op {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s {
+            "+" => Op::Add,
+            "-" => Op::Sub,
+            "*" => Op::Mul,
+            "/" => Op::Div,
+            "&" | "AND" => Op::And,
+            "|" | "OR" => Op::Or,
+            "^" | "XOR" => Op::Xor,
+            "<<" | "LSHIFT" => Op::Shl,
+            ">>" | "RSHIFT" => Op::Shr,
+            _ => return Err(Error::UnknownOp(s.to_string())),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Num(Rational),
+    Var(String),
+    Binary(Op, ExprId, ExprId),
+}
+
+/// Expressions stored as a DAG: every distinct expression is kept once and
+/// referred to by its [`ExprId`], so shared subexpressions are only folded,
+/// printed or solved once.
+///
+/// [`Exprs::binary`] folds constants and drops operations that do nothing
+/// while building, so an expression without unknowns always ends up as a
+/// single [`Expr::Num`].
+#[derive(Debug, Clone, Default)]
+pub struct Exprs {
+    nodes: Vec<Expr>,
+    ids: HashMap<Expr, ExprId>,
+}
+
+impl Exprs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add(&mut self, expr: Expr) -> ExprId {
+        if let Some(&id) = self.ids.get(&expr) {
+            return id;
+        }
+        self.nodes.push(expr.clone());
+        self.ids.insert(expr, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn num(&mut self, n: impl Into<Rational>) -> ExprId {
+        self.add(Expr::Num(n.into()))
+    }
+
+    pub fn var(&mut self, name: &str) -> ExprId {
+        self.add(Expr::Var(name.to_string()))
+    }
+
+    /// `a op b`, folded and simplified as far as the operands allow.
+    pub fn binary(&mut self, op: Op, a: ExprId, b: ExprId) -> Result<ExprId, Error> {
+        let zero = Some(Rational::ZERO);
+        let one = Some(Rational::ONE);
+        Ok(match (op, self.value(a), self.value(b)) {
+            (_, Some(x), Some(y)) => self.num(op.apply(x, y)?),
+            (Op::Div, _, Some(y)) if y.is_zero() => return Err(Error::DivisionByZero),
+            (Op::Add | Op::Sub | Op::Or | Op::Xor | Op::Shl | Op::Shr, _, y) if y == zero => a,
+            (Op::Add | Op::Or | Op::Xor, x, _) if x == zero => b,
+            (Op::Mul | Op::Div, _, y) if y == one => a,
+            (Op::Mul, x, _) if x == one => b,
+            (Op::Mul | Op::And, x, y) if x == zero || y == zero => self.num(Rational::ZERO),
+            (Op::Sub | Op::Xor, _, _) if a == b => self.num(Rational::ZERO),
+            _ => self.add(Expr::Binary(op, a, b)),
+        })
+    }
+
+    pub fn get(&self, id: ExprId) -> &Expr {
+        &self.nodes[id]
+    }
+
+    /// The value of the expression, if it has no unknowns.
+    pub fn value(&self, id: ExprId) -> Option<Rational> {
+        match self.nodes[id] {
+            Expr::Num(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// The expression written out with brackets around every operation.
+    pub fn display(&self, id: ExprId) -> Display<'_> {
+        Display { exprs: self, id }
+    }
+}
+
+/// Prints an expression of [`Exprs`], see [`Exprs::display`].
+pub struct Display<'a> {
+    exprs: &'a Exprs,
+    id: ExprId,
+}
+
+impl fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.exprs.get(self.id) {
+            Expr::Num(n) if n.is_integer() => write!(f, "{}", n),
+            Expr::Num(n) => write!(f, "({})", n),
+            Expr::Var(name) => write!(f, "{}", name),
+            &Expr::Binary(op, a, b) => write!(
+                f,
+                "({} {} {})",
+                self.exprs.display(a),
+                op.symbol(),
+                self.exprs.display(b)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_folding() {
+        let mut exprs = Exprs::new();
+        let (two, three) = (exprs.num(2i64), exprs.num(3i64));
+        let six = exprs.binary(Op::Mul, two, three).unwrap();
+        assert_eq!(exprs.value(six), Some(6i64.into()));
+        let x = exprs.var("x");
+        let x_plus_six = exprs.binary(Op::Add, x, six).unwrap();
+        let half = exprs.binary(Op::Div, x_plus_six, two).unwrap();
+        assert_eq!(exprs.display(half).to_string(), "((x + 6) / 2)");
+        assert_eq!(exprs.binary(Op::Add, x, six).unwrap(), x_plus_six);
+        let twelve = exprs.binary(Op::Shl, three, two).unwrap();
+        assert_eq!(exprs.value(twelve), Some(12i64.into()));
+    }
+
+    #[test]
+    fn test_simplify() {
+        let mut exprs = Exprs::new();
+        let (zero, one, x) = (exprs.num(0i64), exprs.num(1i64), exprs.var("x"));
+        assert_eq!(exprs.binary(Op::Add, zero, x).unwrap(), x);
+        assert_eq!(exprs.binary(Op::Mul, x, one).unwrap(), x);
+        assert_eq!(exprs.binary(Op::Mul, x, zero).unwrap(), zero);
+        assert_eq!(exprs.binary(Op::Sub, x, x).unwrap(), zero);
+        assert_eq!(exprs.binary(Op::Div, x, zero), Err(Error::DivisionByZero));
+        let third = exprs.num(Rational::new(1, 3));
+        let minus = exprs.num(-1i64);
+        assert_eq!(
+            exprs.binary(Op::And, third, one),
+            Err(Error::NotBitwise(Rational::new(1, 3)))
+        );
+        assert!(exprs.binary(Op::Or, minus, one).is_err());
+    }
+}
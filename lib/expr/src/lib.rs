@@ -0,0 +1,35 @@
+//! A small computer algebra system for puzzles that define values in terms
+//! of each other and then ask which input makes them come out right.
+//!
+//! [`Definitions`] collect named definitions and build them into [`Exprs`],
+//! a DAG of expressions over exact [`Rational`]s that folds constants as it
+//! goes. Whatever depends on an unknown stays symbolic, can be printed, and
+//! [`Exprs::solve`] finds the unknown when the equation is linear in it:
+//!
+//! ```
+//! use expr::{Def, Definitions, Exprs, Op};
+//!
+//! let mut defs = Definitions::new();
+//! defs.define("root", Def::Binary(Op::Div, "a".into(), "3".into()));
+//! defs.define("a", Def::Binary(Op::Sub, "x".into(), "b".into()));
+//! defs.define("b", Def::Binary(Op::Mul, "2".into(), "5".into()));
+//! defs.unknown("x");
+//!
+//! let mut exprs = Exprs::new();
+//! let root = defs.build(&mut exprs, "root").unwrap();
+//! assert_eq!(exprs.display(root).to_string(), "((x - 10) / 3)");
+//! let seven = exprs.num(7i64);
+//! assert_eq!(exprs.solve(root, seven, "x").unwrap(), 31i64.into());
+//! ```
+
+mod defs;
+mod error;
+mod expr;
+mod rational;
+mod solve;
+
+pub use defs::{Def, Definitions, Operand};
+pub use error::Error;
+pub use expr::{Display, Expr, ExprId, Exprs, Op};
+pub use rational::Rational;
+pub use solve::Linear;
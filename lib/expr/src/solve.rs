@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use crate::{Error, Expr, ExprId, Exprs, Op, Rational};
+
+/// `coef * x + constant` for the unknown `x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear {
+    pub coef: Rational,
+    pub constant: Rational,
+}
+
+impl Linear {
+    fn constant(constant: Rational) -> Self {
+        Self {
+            coef: Rational::ZERO,
+            constant,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.coef.is_zero()
+    }
+
+    fn scale(&self, factor: Rational) -> Self {
+        Self {
+            coef: self.coef * factor,
+            constant: self.constant * factor,
+        }
+    }
+}
+
+impl Exprs {
+    /// The expression as a linear function of the unknown `var`.
+    ///
+    /// Fails if the expression has other unknowns, multiplies or divides by
+    /// `var`, or uses it in a bitwise operation.
+    pub fn linear(&self, id: ExprId, var: &str) -> Result<Linear, Error> {
+        self.linear_memo(id, var, &mut HashMap::new())
+    }
+
+    fn linear_memo(
+        &self,
+        id: ExprId,
+        var: &str,
+        memo: &mut HashMap<ExprId, Linear>,
+    ) -> Result<Linear, Error> {
+        if let Some(&linear) = memo.get(&id) {
+            return Ok(linear);
+        }
+        let linear = match self.get(id) {
+            &Expr::Num(n) => Linear::constant(n),
+            Expr::Var(name) if name == var => Linear {
+                coef: Rational::ONE,
+                constant: Rational::ZERO,
+            },
+            Expr::Var(name) => return Err(Error::OtherUnknown(name.clone())),
+            &Expr::Binary(op, a, b) => {
+                let a = self.linear_memo(a, var, memo)?;
+                let b = self.linear_memo(b, var, memo)?;
+                match op {
+                    Op::Add => Linear {
+                        coef: a.coef + b.coef,
+                        constant: a.constant + b.constant,
+                    },
+                    Op::Sub => Linear {
+                        coef: a.coef - b.coef,
+                        constant: a.constant - b.constant,
+                    },
+                    Op::Mul if a.is_constant() => b.scale(a.constant),
+                    Op::Mul if b.is_constant() => a.scale(b.constant),
+                    Op::Div if b.is_constant() && !b.constant.is_zero() => {
+                        a.scale(b.constant.recip())
+                    }
+                    Op::Div if b.is_constant() => return Err(Error::DivisionByZero),
+                    _ if a.is_constant() && b.is_constant() => {
+                        Linear::constant(op.apply(a.constant, b.constant)?)
+                    }
+                    _ => return Err(Error::NonLinear),
+                }
+            }
+        };
+        memo.insert(id, linear);
+        Ok(linear)
+    }
+
+    /// The value of `var` that makes `lhs` equal to `rhs`, exactly.
+    pub fn solve(&self, lhs: ExprId, rhs: ExprId, var: &str) -> Result<Rational, Error> {
+        let l = self.linear(lhs, var)?;
+        let r = self.linear(rhs, var)?;
+        let coef = l.coef - r.coef;
+        if coef.is_zero() {
+            return Err(Error::NoUniqueSolution);
+        }
+        Ok((r.constant - l.constant) / coef)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        // (4 + 2 * (x - 3)) / 4 = 150 / 5, from the 2022 day 21 example.
+        let mut exprs = Exprs::new();
+        let x = exprs.var("humn");
+        let (two, three, four) = (exprs.num(2i64), exprs.num(3i64), exprs.num(4i64));
+        let e = exprs.binary(Op::Sub, x, three).unwrap();
+        let e = exprs.binary(Op::Mul, two, e).unwrap();
+        let e = exprs.binary(Op::Add, four, e).unwrap();
+        let lhs = exprs.binary(Op::Div, e, four).unwrap();
+        let rhs = exprs.num(30i64);
+        assert_eq!(
+            exprs.linear(lhs, "humn"),
+            Ok(Linear {
+                coef: Rational::new(1, 2),
+                constant: Rational::new(-1, 2),
+            })
+        );
+        assert_eq!(exprs.solve(lhs, rhs, "humn"), Ok(61i64.into()));
+        assert_eq!(exprs.solve(lhs, lhs, "humn"), Err(Error::NoUniqueSolution));
+        let third = exprs.binary(Op::Div, x, three).unwrap();
+        assert_eq!(exprs.solve(third, two, "humn"), Ok(6i64.into()));
+        assert_eq!(
+            exprs.solve(third, four, "y"),
+            Err(Error::OtherUnknown("humn".into()))
+        );
+    }
+
+    #[test]
+    fn test_non_linear() {
+        let mut exprs = Exprs::new();
+        let x = exprs.var("x");
+        let one = exprs.num(1i64);
+        let square = exprs.binary(Op::Mul, x, x).unwrap();
+        let inverse = exprs.binary(Op::Div, one, x).unwrap();
+        let masked = exprs.binary(Op::And, x, one).unwrap();
+        for e in [square, inverse, masked] {
+            assert_eq!(exprs.solve(e, one, "x"), Err(Error::NonLinear));
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::{Point2, Scalar};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+/// One of the eight compass directions. The four cardinal directions are
+/// enough for most puzzles; the diagonals are there for king moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// The cardinal directions, clockwise starting from north.
+    pub const CARDINAL: [Direction; 4] = [North, East, South, West];
+
+    /// All eight directions, clockwise starting from north.
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Rotates clockwise by `steps` eighths of a full turn.
+    fn rotate(self, steps: i32) -> Direction {
+        Direction::ALL[(self.index() as i32 + steps).rem_euclid(8) as usize]
+    }
+
+    /// Parses `N`/`E`/`S`/`W`, `U`/`R`/`D`/`L` and `^`/`>`/`v`/`<`, where up
+    /// is north.
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'N' | 'U' | '^' => Some(North),
+            'E' | 'R' | '>' => Some(East),
+            'S' | 'D' | 'v' => Some(South),
+            'W' | 'L' | '<' => Some(West),
+            _ => None,
+        }
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        matches!(self, North | East | South | West)
+    }
+
+    /// Turns by 90 degrees clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// Turns by 90 degrees counter-clockwise.
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn turn(self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    /// Turns clockwise by a multiple of 45 degrees, or counter-clockwise for
+    /// negative angles.
+    ///
+    /// # Panics
+    ///
+    /// If `degrees` is not a multiple of 45.
+    pub fn turn_degrees(self, degrees: i32) -> Direction {
+        assert!(degrees % 45 == 0, "cannot turn by {} degrees", degrees);
+        self.rotate(degrees / 45)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    /// Unit vector in screen coordinates, where north is towards smaller `y`
+    /// (up one row).
+    pub fn unit_screen<T: Scalar>(self) -> Point2<T> {
+        let Point2 { x, y } = self.unit_cartesian::<T>();
+        Point2::new(x, -y)
+    }
+
+    /// Unit vector in cartesian coordinates, where north is towards larger
+    /// `y`.
+    pub fn unit_cartesian<T: Scalar>(self) -> Point2<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        match self {
+            North => Point2::new(zero, one),
+            NorthEast => Point2::new(one, one),
+            East => Point2::new(one, zero),
+            SouthEast => Point2::new(one, -one),
+            South => Point2::new(zero, -one),
+            SouthWest => Point2::new(-one, -one),
+            West => Point2::new(-one, zero),
+            NorthWest => Point2::new(-one, one),
+        }
+    }
+}
+
+/// One of the four cardinal directions, for puzzles that never move
+/// diagonally and want to match on directions exhaustively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir4 {
+    /// Clockwise starting from north.
+    pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
+
+    fn rotate(self, steps: i32) -> Dir4 {
+        Dir4::ALL[(self as i32 + steps).rem_euclid(4) as usize]
+    }
+
+    /// Parses the same characters as [`Direction::from_char`].
+    pub fn from_char(c: char) -> Option<Dir4> {
+        Direction::from_char(c).map(|dir| Dir4::ALL[dir.index() / 2])
+    }
+
+    pub fn turn_right(self) -> Dir4 {
+        self.rotate(1)
+    }
+
+    pub fn turn_left(self) -> Dir4 {
+        self.rotate(-1)
+    }
+
+    pub fn turn(self, turn: Turn) -> Dir4 {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn opposite(self) -> Dir4 {
+        self.rotate(2)
+    }
+
+    /// See [`Direction::unit_screen`].
+    pub fn unit_screen<T: Scalar>(self) -> Point2<T> {
+        Direction::from(self).unit_screen()
+    }
+
+    /// See [`Direction::unit_cartesian`].
+    pub fn unit_cartesian<T: Scalar>(self) -> Point2<T> {
+        Direction::from(self).unit_cartesian()
+    }
+}
+
+impl From<Dir4> for Direction {
+    fn from(dir: Dir4) -> Direction {
+        Direction::CARDINAL[dir as usize]
+    }
+}
+
+impl TryFrom<Direction> for Dir4 {
+    type Error = Direction;
+
+    /// Fails with the direction itself for diagonals.
+    fn try_from(dir: Direction) -> Result<Dir4, Direction> {
+        if dir.is_cardinal() {
+            Ok(Dir4::ALL[dir.index() / 2])
+        } else {
+            Err(dir)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(West.turn(Turn::Right), North);
+        assert_eq!(NorthEast.opposite(), SouthWest);
+        assert_eq!(East.turn_degrees(-270), South);
+        assert_eq!(East.turn_degrees(45), SouthEast);
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.opposite().opposite(), dir);
+        }
+    }
+
+    #[test]
+    fn test_unit_vectors() {
+        assert_eq!(North.unit_screen::<i32>(), Point2::new(0, -1));
+        assert_eq!(North.unit_cartesian::<i32>(), Point2::new(0, 1));
+        assert_eq!(SouthEast.unit_screen::<i64>(), Point2::new(1, 1));
+        assert_eq!(
+            Direction::from_char('<').map(|d| d.unit_screen::<i32>()),
+            Some(Point2::new(-1, 0))
+        );
+        let sum = Direction::ALL
+            .iter()
+            .fold(Point2::new(0, 0), |p, d| p + d.unit_screen());
+        assert_eq!(sum, Point2::new(0, 0));
+    }
+
+    #[test]
+    fn test_dir4() {
+        assert_eq!(Dir4::North.turn_right(), Dir4::East);
+        assert_eq!(Dir4::North.turn(Turn::Left), Dir4::West);
+        assert_eq!(Dir4::East.opposite(), Dir4::West);
+        assert_eq!(Dir4::from_char('v'), Some(Dir4::South));
+        assert_eq!(Dir4::from_char('x'), None);
+        assert_eq!(Dir4::West.unit_screen::<i32>(), Point2::new(-1, 0));
+        assert_eq!(Dir4::North.unit_cartesian::<i32>(), Point2::new(0, 1));
+        for (dir4, dir) in Dir4::ALL.into_iter().zip(Direction::CARDINAL) {
+            assert_eq!(Direction::from(dir4), dir);
+            assert_eq!(Dir4::try_from(dir), Ok(dir4));
+            assert_eq!(Direction::from(dir4.turn_right()), dir.turn_right());
+        }
+        assert_eq!(Dir4::try_from(NorthEast), Err(NorthEast));
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::{Add, AddAssign, Sub};
+
+/// Direction on a hex grid with pointy-top tiles, so that every tile has
+/// neighbours to the east and west.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDir {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+use HexDir::*;
+
+impl HexDir {
+    pub const ALL: [HexDir; 6] = [East, SouthEast, SouthWest, West, NorthWest, NorthEast];
+
+    /// Parses a path of concatenated directions without separators, such as
+    /// `"nwwswee"`. Returns `None` on anything else.
+    pub fn parse_path(s: &str) -> Option<Vec<HexDir>> {
+        let mut dirs = vec![];
+        let mut chars = s.trim().chars();
+        while let Some(c) = chars.next() {
+            dirs.push(match c {
+                'e' => East,
+                'w' => West,
+                's' => match chars.next()? {
+                    'e' => SouthEast,
+                    'w' => SouthWest,
+                    _ => return None,
+                },
+                'n' => match chars.next()? {
+                    'e' => NorthEast,
+                    'w' => NorthWest,
+                    _ => return None,
+                },
+                _ => return None,
+            });
+        }
+        Some(dirs)
+    }
+
+    pub fn opposite(self) -> HexDir {
+        match self {
+            East => West,
+            SouthEast => NorthWest,
+            SouthWest => NorthEast,
+            West => East,
+            NorthWest => SouthEast,
+            NorthEast => SouthWest,
+        }
+    }
+
+    pub fn offset(self) -> Hex {
+        match self {
+            East => Hex::new(1, 0),
+            SouthEast => Hex::new(0, 1),
+            SouthWest => Hex::new(-1, 1),
+            West => Hex::new(-1, 0),
+            NorthWest => Hex::new(0, -1),
+            NorthEast => Hex::new(1, -1),
+        }
+    }
+}
+
+/// Axial hex coordinates. `q` grows towards the east and `r` towards the
+/// south-east.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex::new(0, 0);
+
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    pub fn step(self, dir: HexDir) -> Hex {
+        self + dir.offset()
+    }
+
+    /// Follows all directions of `path` in order.
+    pub fn walk<'a>(self, path: impl IntoIterator<Item = &'a HexDir>) -> Hex {
+        path.into_iter().fold(self, |hex, &dir| hex.step(dir))
+    }
+
+    pub fn neighbours(self) -> [Hex; 6] {
+        HexDir::ALL.map(|dir| self.step(dir))
+    }
+
+    /// Number of steps needed to get from `self` to `other`.
+    pub fn distance(self, other: Hex) -> i32 {
+        let d = self - other;
+        (d.q.abs() + d.r.abs() + (d.q + d.r).abs()) / 2
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        *self = *self + rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk() {
+        let path = HexDir::parse_path("nwwswee").unwrap();
+        assert_eq!(Hex::ORIGIN.walk(&path), Hex::ORIGIN);
+        let path = HexDir::parse_path("esew").unwrap();
+        assert_eq!(Hex::ORIGIN.walk(&path), SouthEast.offset());
+        assert_eq!(HexDir::parse_path("nx"), None);
+    }
+
+    #[test]
+    fn test_distance() {
+        for dir in HexDir::ALL {
+            assert_eq!(Hex::ORIGIN.distance(dir.offset()), 1);
+            assert_eq!(dir.offset() + dir.opposite().offset(), Hex::ORIGIN);
+        }
+        let far = Hex::ORIGIN.walk(&[East, East, SouthEast, NorthEast]);
+        assert_eq!(Hex::ORIGIN.distance(far), 3);
+    }
+}
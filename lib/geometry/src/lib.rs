@@ -0,0 +1,15 @@
+//! Points, directions and hex coordinates shared between solutions.
+//!
+//! [`Point2`] has no built-in orientation. [`Direction`] can produce unit
+//! vectors for screen coordinates, where `y` grows downwards like grid rows,
+//! or for cartesian coordinates, where `y` grows upwards. The conversions
+//! between the two are always explicit. [`Dir4`] is the same for the four
+//! cardinal directions alone.
+
+pub mod direction;
+pub mod hex;
+pub mod point;
+
+pub use direction::{Dir4, Direction, Turn};
+pub use hex::{Hex, HexDir};
+pub use point::{Point2, Point3, Scalar};
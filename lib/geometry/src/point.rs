@@ -0,0 +1,309 @@
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Signed integer types that can be used as point components.
+pub trait Scalar:
+    Copy
+    + Ord
+    + fmt::Debug
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_scalar {
+    ($($t:ty),+) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+            }
+        )+
+    };
+}
+
+impl_scalar!(i8, i16, i32, i64, i128, isize);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// Converts a screen position to a point, with the column as `x` and the
+    /// row as `y`.
+    pub fn from_row_col(row: T, col: T) -> Self {
+        Self { x: col, y: row }
+    }
+
+    /// Converts back to a screen position as `(row, col)`.
+    pub fn to_row_col(self) -> (T, T) {
+        (self.y, self.x)
+    }
+}
+
+impl<T: Scalar> Point2<T> {
+    pub const ORIGIN: Self = Self::new(T::ZERO, T::ZERO);
+
+    pub fn manhattan(self, other: Self) -> T {
+        let d = self - other;
+        d.x.abs() + d.y.abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = self - other;
+        d.x.abs().max(d.y.abs())
+    }
+
+    /// Component-wise signum, i.e. the king move from the origin towards
+    /// this point.
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Rotates by 90 degrees clockwise in cartesian coordinates, which is
+    /// counter-clockwise on screen.
+    pub fn rotate_right(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Rotates by 90 degrees counter-clockwise in cartesian coordinates,
+    /// which is clockwise on screen.
+    pub fn rotate_left(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// The four orthogonally adjacent points.
+    pub fn neighbours4(self) -> [Self; 4] {
+        let (x, y, one) = (self.x, self.y, T::ONE);
+        [
+            Self::new(x, y - one),
+            Self::new(x + one, y),
+            Self::new(x, y + one),
+            Self::new(x - one, y),
+        ]
+    }
+
+    /// The eight orthogonally and diagonally adjacent points.
+    pub fn neighbours8(self) -> [Self; 8] {
+        let (x, y, one) = (self.x, self.y, T::ONE);
+        [
+            Self::new(x - one, y - one),
+            Self::new(x, y - one),
+            Self::new(x + one, y - one),
+            Self::new(x + one, y),
+            Self::new(x + one, y + one),
+            Self::new(x, y + one),
+            Self::new(x - one, y + one),
+            Self::new(x - one, y),
+        ]
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Scalar> Point3<T> {
+    pub const ORIGIN: Self = Self::new(T::ZERO, T::ZERO, T::ZERO);
+
+    pub fn manhattan(self, other: Self) -> T {
+        let d = self - other;
+        d.x.abs() + d.y.abs() + d.z.abs()
+    }
+
+    pub fn chebyshev(self, other: Self) -> T {
+        let d = self - other;
+        d.x.abs().max(d.y.abs()).max(d.z.abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    /// The six points sharing a face with this one.
+    pub fn neighbours6(self) -> [Self; 6] {
+        let (x, y, z, one) = (self.x, self.y, self.z, T::ONE);
+        [
+            Self::new(x - one, y, z),
+            Self::new(x + one, y, z),
+            Self::new(x, y - one, z),
+            Self::new(x, y + one, z),
+            Self::new(x, y, z - one),
+            Self::new(x, y, z + one),
+        ]
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(p: Point3<T>) -> Self {
+        (p.x, p.y, p.z)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+macro_rules! impl_ops {
+    ($point:ident; $($c:ident),+) => {
+        impl<T: Scalar> Add for $point<T> {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Self { $($c: self.$c + rhs.$c),+ }
+            }
+        }
+
+        impl<T: Scalar> Sub for $point<T> {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($c: self.$c - rhs.$c),+ }
+            }
+        }
+
+        impl<T: Scalar> Neg for $point<T> {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self { $($c: -self.$c),+ }
+            }
+        }
+
+        impl<T: Scalar> Mul<T> for $point<T> {
+            type Output = Self;
+            fn mul(self, rhs: T) -> Self {
+                Self { $($c: self.$c * rhs),+ }
+            }
+        }
+
+        impl<T: Scalar> Div<T> for $point<T> {
+            type Output = Self;
+            fn div(self, rhs: T) -> Self {
+                Self { $($c: self.$c / rhs),+ }
+            }
+        }
+
+        impl<T: Scalar> AddAssign for $point<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Scalar> SubAssign for $point<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Scalar> MulAssign<T> for $point<T> {
+            fn mul_assign(&mut self, rhs: T) {
+                *self = *self * rhs;
+            }
+        }
+    };
+}
+
+impl_ops!(Point2; x, y);
+impl_ops!(Point3; x, y, z);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(3, 5);
+        assert_eq!(a + b, Point2::new(4, 3));
+        assert_eq!(a - b, Point2::new(-2, -7));
+        assert_eq!(-a, Point2::new(-1, 2));
+        assert_eq!(b * 2, Point2::new(6, 10));
+        let mut c = a;
+        c += b;
+        c -= Point2::new(1, 1);
+        assert_eq!(c, Point2::new(3, 2));
+        assert_eq!(
+            Point3::new(1i64, 2, 3) + Point3::new(1, 1, 1),
+            Point3::new(2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(4, 5);
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 7);
+        assert_eq!((b - a).signum(), Point2::new(1, 1));
+        assert_eq!(Point3::new(0, 0, 0).manhattan(Point3::new(1, -2, 3)), 6);
+    }
+
+    #[test]
+    fn test_rotation_and_conversion() {
+        let p = Point2::new(10, 4);
+        assert_eq!(p.rotate_right(), Point2::new(4, -10));
+        assert_eq!(p.rotate_left(), Point2::new(-4, 10));
+        assert_eq!(p.rotate_right().rotate_left(), p);
+        assert_eq!(Point2::from_row_col(2, 7), Point2::new(7, 2));
+        assert_eq!(Point2::new(7, 2).to_row_col(), (2, 7));
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use crate::{Grammar, RuleId, Symbol};
+
+/// A rule alternative with how much of it has matched since `origin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Item {
+    rule: RuleId,
+    alternative: usize,
+    dot: usize,
+    origin: usize,
+}
+
+impl Grammar {
+    /// Whether `rule` matches all of `input`, by Earley's algorithm, which
+    /// takes any context-free grammar including left-recursive and empty
+    /// rules.
+    ///
+    /// The chart has a set of items per byte of the input, and terminals
+    /// may be longer than one byte.
+    pub fn earley(&self, rule: RuleId, input: &str) -> bool {
+        let input = input.as_bytes();
+        let nullable = self.nullable();
+        let mut chart: Vec<Vec<Item>> = vec![vec![]; input.len() + 1];
+        let mut seen: Vec<HashSet<Item>> = vec![HashSet::new(); input.len() + 1];
+        let add = |chart: &mut Vec<Vec<Item>>, seen: &mut Vec<HashSet<Item>>, at: usize, item| {
+            if seen[at].insert(item) {
+                chart[at].push(item);
+            }
+        };
+        for alternative in 0..self.alternatives(rule).len() {
+            let item = Item {
+                rule,
+                alternative,
+                dot: 0,
+                origin: 0,
+            };
+            add(&mut chart, &mut seen, 0, item);
+        }
+        for at in 0..=input.len() {
+            let mut i = 0;
+            while i < chart[at].len() {
+                let item = chart[at][i];
+                i += 1;
+                let advanced = Item {
+                    dot: item.dot + 1,
+                    ..item
+                };
+                match self.alternatives(item.rule)[item.alternative].get(item.dot) {
+                    // complete: advance everything that was waiting for it
+                    None => {
+                        for j in 0..chart[item.origin].len() {
+                            let waiting = chart[item.origin][j];
+                            let next = self.alternatives(waiting.rule)[waiting.alternative]
+                                .get(waiting.dot);
+                            if next == Some(&Symbol::Rule(item.rule)) {
+                                let item = Item {
+                                    dot: waiting.dot + 1,
+                                    ..waiting
+                                };
+                                add(&mut chart, &mut seen, at, item);
+                            }
+                        }
+                    }
+                    // predict, and skip over rules that may match nothing
+                    // as their completion may already have happened
+                    Some(Symbol::Rule(next)) => {
+                        for alternative in 0..self.alternatives(*next).len() {
+                            let item = Item {
+                                rule: *next,
+                                alternative,
+                                dot: 0,
+                                origin: at,
+                            };
+                            add(&mut chart, &mut seen, at, item);
+                        }
+                        if nullable[*next] {
+                            add(&mut chart, &mut seen, at, advanced);
+                        }
+                    }
+                    Some(Symbol::Terminal(text)) => {
+                        if input[at..].starts_with(text.as_bytes()) {
+                            add(&mut chart, &mut seen, at + text.len(), advanced);
+                        }
+                    }
+                }
+            }
+        }
+        chart[input.len()].iter().any(|item| {
+            item.rule == rule
+                && item.origin == 0
+                && item.dot == self.alternatives(rule)[item.alternative].len()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_left_recursion() {
+        let grammar = Grammar::parse_bnf(
+            "<sum> ::= <sum> \"+\" <product> | <product>
+             <product> ::= <product> \"*\" <digit> | <digit>
+             <digit> ::= \"1\" | \"2\" | \"(\" <sum> \")\"",
+        )
+        .unwrap();
+        let sum = grammar.id("sum").unwrap();
+        assert!(grammar.earley(sum, "1+2*2+1"));
+        assert!(grammar.earley(sum, "(1+2)*(2)"));
+        assert!(!grammar.earley(sum, "1+*2"));
+        assert!(!grammar.earley(sum, ""));
+    }
+
+    #[test]
+    fn test_empty_rules() {
+        let grammar = Grammar::parse_bnf(
+            "<s> ::= <a> <a> \"x\" <a>
+             <a> ::= \"\" | \"ab\" <a>",
+        )
+        .unwrap();
+        assert!(grammar.earley(0, "x"));
+        assert!(grammar.earley(0, "ababxab"));
+        assert!(!grammar.earley(0, "abx a"));
+    }
+
+    #[test]
+    fn test_agrees_with_matcher() {
+        let grammar = Grammar::parse_rules(
+            "0: 8 11\n8: 42 | 42 8\n11: 42 31 | 42 11 31\n42: \"a\"\n31: \"b\"\n",
+        )
+        .unwrap();
+        for input in ["aab", "aaaabb", "aabb", "abab", "", "aaaaaabbb"] {
+            assert_eq!(
+                grammar.earley(0, input),
+                grammar.matches(0, input),
+                "{}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use parse::ParseError;
+
+/// Index of a rule in its [`Grammar`].
+pub type RuleId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    /// Text that has to appear as is.
+    Terminal(String),
+    Rule(RuleId),
+}
+
+/// A context-free grammar of named rules, each a list of alternatives that
+/// are sequences of symbols. An empty alternative matches the empty string.
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    names: Vec<String>,
+    index: HashMap<String, RuleId>,
+    rules: Vec<Vec<Vec<Symbol>>>,
+    defined: Vec<bool>,
+}
+
+impl Grammar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of the rule called `name`, making up an undefined one if
+    /// there is none yet. Ids are handed out in order, and the parsers
+    /// number each rule before the ones it uses.
+    pub fn rule(&mut self, name: &str) -> RuleId {
+        if let Some(&id) = self.index.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.index.insert(name.to_string(), id);
+        self.rules.push(vec![]);
+        self.defined.push(false);
+        id
+    }
+
+    /// Defines the rule `name`, replacing any earlier definition.
+    pub fn define(&mut self, name: &str, alternatives: Vec<Vec<Symbol>>) -> RuleId {
+        let id = self.rule(name);
+        self.rules[id] = alternatives;
+        self.defined[id] = true;
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<RuleId> {
+        self.index.get(name).copied()
+    }
+
+    pub fn name(&self, rule: RuleId) -> &str {
+        &self.names[rule]
+    }
+
+    pub fn alternatives(&self, rule: RuleId) -> &[Vec<Symbol>] {
+        &self.rules[rule]
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Rules that are used but never defined.
+    pub fn undefined(&self) -> Vec<&str> {
+        (0..self.len())
+            .filter(|&id| !self.defined[id])
+            .map(|id| self.name(id))
+            .collect()
+    }
+
+    /// Whether each rule can match the empty string.
+    pub fn nullable(&self) -> Vec<bool> {
+        let mut nullable = vec![false; self.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (id, alternatives) in self.rules.iter().enumerate() {
+                if nullable[id] {
+                    continue;
+                }
+                let empty = alternatives.iter().any(|alternative| {
+                    alternative
+                        .iter()
+                        .all(|symbol| matches!(symbol, Symbol::Rule(r) if nullable[*r]))
+                });
+                if empty {
+                    nullable[id] = true;
+                    changed = true;
+                }
+            }
+        }
+        nullable
+    }
+
+    /// Parses rules in the format of the 2020 day 19 puzzle, one per line:
+    ///
+    /// ```text
+    /// 0: 1 2 | 2 1
+    /// 1: "a"
+    /// ```
+    ///
+    /// Parsing stops at the first blank line.
+    pub fn parse_rules(input: &str) -> Result<Self, ParseError> {
+        let mut grammar = Self::new();
+        grammar.extend_rules(input)?;
+        Ok(grammar)
+    }
+
+    /// Adds or replaces rules in the format of [`Grammar::parse_rules`].
+    pub fn extend_rules(&mut self, input: &str) -> Result<(), ParseError> {
+        for line in input.lines().take_while(|line| !line.trim().is_empty()) {
+            let (name, body) = line
+                .split_once(':')
+                .ok_or_else(|| ParseError::at(input, line, "expected rule name"))?;
+            // numbered before the rules it uses
+            let name = name.trim();
+            self.rule(name);
+            let mut alternatives = vec![];
+            for alternative in body.split('|') {
+                let mut symbols = vec![];
+                for word in alternative.split_whitespace() {
+                    symbols.push(self.symbol(input, word)?);
+                }
+                alternatives.push(symbols);
+            }
+            self.define(name, alternatives);
+        }
+        self.check_defined(input)
+    }
+
+    /// Parses a grammar in a BNF-like notation, one rule per line:
+    ///
+    /// ```text
+    /// <list> ::= <item> | <item> "," <list>
+    /// <item> ::= "x" | ""
+    /// ```
+    ///
+    /// Rule names are in angle brackets and terminals in single or double
+    /// quotes, and an empty terminal matches the empty string.
+    pub fn parse_bnf(input: &str) -> Result<Self, ParseError> {
+        let mut grammar = Self::new();
+        for line in input.lines().filter(|line| !line.trim().is_empty()) {
+            let (name, body) = line
+                .split_once("::=")
+                .ok_or_else(|| ParseError::at(input, line, "expected ::="))?;
+            let name = name.trim();
+            let name = name
+                .strip_prefix('<')
+                .and_then(|name| name.strip_suffix('>'))
+                .ok_or_else(|| ParseError::at(input, line, "expected <rule>"))?;
+            grammar.rule(name);
+            let mut alternatives = vec![];
+            for alternative in split_unquoted(body) {
+                let mut symbols = vec![];
+                for word in words(alternative) {
+                    let symbol = grammar.bnf_symbol(input, word)?;
+                    if symbol != Symbol::Terminal(String::new()) {
+                        symbols.push(symbol);
+                    }
+                }
+                alternatives.push(symbols);
+            }
+            grammar.define(name, alternatives);
+        }
+        grammar.check_defined(input)?;
+        Ok(grammar)
+    }
+
+    /// A symbol of the 2020 day 19 format: a terminal in double quotes, or
+    /// anything else names a rule.
+    fn symbol(&mut self, input: &str, word: &str) -> Result<Symbol, ParseError> {
+        if let Some(text) = word.strip_prefix('"').and_then(|w| w.strip_suffix('"')) {
+            return Ok(Symbol::Terminal(text.to_string()));
+        }
+        if word.contains('"') {
+            return Err(ParseError::at(input, word, "unterminated string"));
+        }
+        Ok(Symbol::Rule(self.rule(word)))
+    }
+
+    fn bnf_symbol(&mut self, input: &str, word: &str) -> Result<Symbol, ParseError> {
+        if let Some(name) = word.strip_prefix('<').and_then(|w| w.strip_suffix('>')) {
+            return Ok(Symbol::Rule(self.rule(name)));
+        }
+        for quote in ['"', '\''] {
+            if let Some(text) = word.strip_prefix(quote).and_then(|w| w.strip_suffix(quote)) {
+                return Ok(Symbol::Terminal(text.to_string()));
+            }
+        }
+        Err(ParseError::at(
+            input,
+            word,
+            "expected <rule> or quoted terminal",
+        ))
+    }
+
+    fn check_defined(&self, input: &str) -> Result<(), ParseError> {
+        let Some(name) = self.undefined().first().copied() else {
+            return Ok(());
+        };
+        // point at the first use, which is all there is
+        let bracketed = format!("<{}>", name);
+        let use_ = input
+            .split_whitespace()
+            .find(|&word| word == name || word == bracketed)
+            .unwrap_or(&input[input.len()..]);
+        Err(ParseError::at(
+            input,
+            use_,
+            format!("rule {} is not defined", name),
+        ))
+    }
+}
+
+/// Splits at the `|` that are not inside quotes.
+fn split_unquoted(s: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut start, mut quote) = (0, None);
+    for (i, c) in s.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('|', None) => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Splits at whitespace that is not inside quotes.
+fn words(s: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let (mut start, mut quote) = (None, None);
+    for (i, c) in s.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                start.get_or_insert(i);
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => {
+                if let Some(s0) = start.take() {
+                    words.push(&s[s0..i]);
+                }
+            }
+            _ => {
+                start.get_or_insert(i);
+            }
+        }
+    }
+    if let Some(s0) = start {
+        words.push(&s[s0..]);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let grammar = Grammar::parse_rules("0: 1 2 | 2 1\n1: \"a\"\n2: \"b\"\n\nabab\n").unwrap();
+        assert_eq!(grammar.len(), 3);
+        let (one, two) = (grammar.id("1").unwrap(), grammar.id("2").unwrap());
+        assert_eq!(
+            grammar.alternatives(grammar.id("0").unwrap()),
+            [
+                vec![Symbol::Rule(one), Symbol::Rule(two)],
+                vec![Symbol::Rule(two), Symbol::Rule(one)]
+            ]
+        );
+        assert_eq!(
+            grammar.alternatives(one),
+            [vec![Symbol::Terminal("a".into())]]
+        );
+
+        let err = Grammar::parse_rules("0: 1 3\n1: \"a\"\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (1, 6, "rule 3 is not defined")
+        );
+        let err = Grammar::parse_rules("0: 1\n1 \"a\"\n").unwrap_err();
+        assert_eq!((err.line, err.message.as_str()), (2, "expected rule name"));
+    }
+
+    #[test]
+    fn test_parse_bnf() {
+        let grammar = Grammar::parse_bnf(
+            "<list> ::= <item> | <item> \",\" <list>\n<item> ::= 'x | y' | \"\"\n",
+        )
+        .unwrap();
+        let (list, item) = (grammar.id("list").unwrap(), grammar.id("item").unwrap());
+        assert_eq!(grammar.alternatives(list)[1].len(), 3);
+        assert_eq!(
+            grammar.alternatives(item),
+            [vec![Symbol::Terminal("x | y".into())], vec![]]
+        );
+        assert_eq!(grammar.nullable(), [true, true]);
+        let err = Grammar::parse_bnf("<a> ::= b\n").unwrap_err();
+        assert_eq!(
+            (err.column, err.message.as_str()),
+            (9, "expected <rule> or quoted terminal")
+        );
+        assert!(Grammar::parse_bnf("a ::= \"b\"").is_err());
+    }
+}
@@ -0,0 +1,33 @@
+//! Context-free grammars for the puzzles that hand out rules for valid
+//! messages.
+//!
+//! - [`Grammar`] holds named rules of alternatives, parsed from the rule
+//!   format of 2020 day 19 or a BNF-like notation,
+//! - [`Matcher`] finds every length a rule can match from a position, which
+//!   copes with right-recursive loops, and gives a parse [`Tree`] to see
+//!   how a string matched,
+//! - [`Grammar::earley`] recognises strings for any grammar, left recursion
+//!   and empty rules included,
+//! - [`Grammar::to_regex`] writes out a rule that does not recurse as a
+//!   regular expression.
+//!
+//! ```
+//! use grammar::Grammar;
+//!
+//! let mut grammar = Grammar::parse_rules("0: 1 2\n1: \"a\" | \"a\" 1\n2: \"b\"\n").unwrap();
+//! assert!(grammar.matches(0, "aaab"));
+//! assert_eq!(grammar.to_regex(0), None);
+//! grammar.extend_rules("1: \"a\"").unwrap();
+//! assert_eq!(grammar.to_regex(0).unwrap(), "ab");
+//! assert_eq!(grammar.parse_tree(0, "ab").unwrap().to_string(), "0(1(\"a\") 2(\"b\"))");
+//! ```
+
+mod earley;
+mod grammar;
+mod matcher;
+mod regex;
+mod tree;
+
+pub use grammar::{Grammar, RuleId, Symbol};
+pub use matcher::Matcher;
+pub use tree::Tree;
@@ -0,0 +1,190 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use crate::{Grammar, RuleId, Symbol, Tree};
+
+/// Matches the rules of a grammar against one input, remembering where
+/// each rule can end from each position.
+///
+/// This handles loops like `8: 42 | 42 8` without special cases, as every
+/// way through them is followed. Left recursion is cut short, so a rule
+/// that calls itself before consuming anything only gets its other
+/// alternatives; use [`Grammar::earley`] for those.
+pub struct Matcher<'a> {
+    grammar: &'a Grammar,
+    input: &'a str,
+    ends: HashMap<(RuleId, usize), Rc<Vec<usize>>>,
+}
+
+impl<'a> Matcher<'a> {
+    pub fn new(grammar: &'a Grammar, input: &'a str) -> Self {
+        Self {
+            grammar,
+            input,
+            ends: HashMap::new(),
+        }
+    }
+
+    /// Every position, in order, at which a match of `rule` starting at
+    /// `start` can end.
+    pub fn ends(&mut self, rule: RuleId, start: usize) -> Rc<Vec<usize>> {
+        if let Some(ends) = self.ends.get(&(rule, start)) {
+            return ends.clone();
+        }
+        // seen as matching nothing while it is being worked out
+        self.ends.insert((rule, start), Rc::new(vec![]));
+        let mut ends = BTreeSet::new();
+        for alternative in self.grammar.alternatives(rule) {
+            ends.extend(self.sequence_ends(alternative, start));
+        }
+        let ends = Rc::new(ends.into_iter().collect::<Vec<_>>());
+        self.ends.insert((rule, start), ends.clone());
+        ends
+    }
+
+    /// The lengths of all matches of `rule` at `start`.
+    pub fn lengths(&mut self, rule: RuleId, start: usize) -> Vec<usize> {
+        self.ends(rule, start)
+            .iter()
+            .map(|end| end - start)
+            .collect()
+    }
+
+    /// Whether `rule` matches the whole input.
+    pub fn matches(&mut self, rule: RuleId) -> bool {
+        self.ends(rule, 0).contains(&self.input.len())
+    }
+
+    fn symbol_ends(&mut self, symbol: &Symbol, start: usize) -> Vec<usize> {
+        match symbol {
+            Symbol::Terminal(text) => {
+                let rest = &self.input.as_bytes()[start..];
+                if rest.starts_with(text.as_bytes()) {
+                    vec![start + text.len()]
+                } else {
+                    vec![]
+                }
+            }
+            Symbol::Rule(rule) => self.ends(*rule, start).to_vec(),
+        }
+    }
+
+    fn sequence_ends(&mut self, symbols: &[Symbol], start: usize) -> BTreeSet<usize> {
+        let mut positions = BTreeSet::from([start]);
+        for symbol in symbols {
+            let mut next = BTreeSet::new();
+            for position in positions {
+                next.extend(self.symbol_ends(symbol, position));
+            }
+            positions = next;
+        }
+        positions
+    }
+
+    /// A parse tree of `rule` matching the whole input, if there is one.
+    pub fn parse_tree(&mut self, rule: RuleId) -> Option<Tree> {
+        self.derive(rule, 0, self.input.len())
+    }
+
+    fn derive(&mut self, rule: RuleId, start: usize, end: usize) -> Option<Tree> {
+        if !self.ends(rule, start).contains(&end) {
+            return None;
+        }
+        for alternative in self.grammar.alternatives(rule) {
+            if let Some(children) = self.derive_sequence(alternative, start, end) {
+                return Some(Tree::Node {
+                    rule: self.grammar.name(rule).to_string(),
+                    children,
+                });
+            }
+        }
+        None
+    }
+
+    fn derive_sequence(
+        &mut self,
+        symbols: &[Symbol],
+        start: usize,
+        end: usize,
+    ) -> Option<Vec<Tree>> {
+        let Some((first, rest)) = symbols.split_first() else {
+            return (start == end).then(Vec::new);
+        };
+        for middle in self.symbol_ends(first, start) {
+            if middle > end || !self.sequence_ends(rest, middle).contains(&end) {
+                continue;
+            }
+            let tree = match first {
+                Symbol::Terminal(text) => Tree::Leaf(text.clone()),
+                Symbol::Rule(rule) => match self.derive(*rule, start, middle) {
+                    Some(tree) => tree,
+                    None => continue,
+                },
+            };
+            if let Some(mut children) = self.derive_sequence(rest, middle, end) {
+                children.insert(0, tree);
+                return Some(children);
+            }
+        }
+        None
+    }
+}
+
+impl Grammar {
+    /// Whether `rule` matches all of `input`, see [`Matcher`].
+    pub fn matches(&self, rule: RuleId, input: &str) -> bool {
+        Matcher::new(self, input).matches(rule)
+    }
+
+    /// A parse tree of `rule` matching all of `input`, see [`Matcher`].
+    pub fn parse_tree(&self, rule: RuleId, input: &str) -> Option<Tree> {
+        Matcher::new(self, input).parse_tree(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lengths() {
+        let grammar = Grammar::parse_rules("0: 1 | 1 0\n1: \"ab\"\n").unwrap();
+        let mut matcher = Matcher::new(&grammar, "abababx");
+        assert_eq!(matcher.lengths(0, 0), [2, 4, 6]);
+        assert_eq!(matcher.lengths(0, 2), [2, 4]);
+        assert_eq!(matcher.lengths(0, 6), []);
+        assert!(!matcher.matches(0));
+        assert!(grammar.matches(0, "abab"));
+    }
+
+    #[test]
+    fn test_loops() {
+        // The looping rules of 2020 day 19 part 2, with 42 and 31 as letters.
+        let grammar = Grammar::parse_rules(
+            "0: 8 11\n8: 42 | 42 8\n11: 42 31 | 42 11 31\n42: \"a\"\n31: \"b\"\n",
+        )
+        .unwrap();
+        assert!(grammar.matches(0, "aab"));
+        assert!(grammar.matches(0, "aaaabb"));
+        assert!(!grammar.matches(0, "aabb"));
+        assert!(!grammar.matches(0, "abab"));
+    }
+
+    #[test]
+    fn test_parse_tree() {
+        let grammar = Grammar::parse_rules("0: 1 2\n1: \"a\" | \"a\" 1\n2: \"ab\"\n").unwrap();
+        let tree = grammar.parse_tree(0, "aaab").unwrap();
+        assert_eq!(tree.text(), "aaab");
+        assert_eq!(tree.to_string(), "0(1(\"a\" 1(\"a\")) 2(\"ab\"))");
+        assert_eq!(grammar.parse_tree(0, "ab"), None);
+    }
+
+    #[test]
+    fn test_left_recursion_is_cut() {
+        let grammar = Grammar::parse_bnf("<e> ::= <e> \"+1\" | \"1\"").unwrap();
+        assert!(grammar.matches(0, "1"));
+        assert!(!grammar.matches(0, "1+1"));
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{Grammar, RuleId, Symbol};
+
+impl Grammar {
+    /// The rule as a regular expression, with every rule it uses written
+    /// out in place, or `None` if it is recursive and so may not be
+    /// regular. Anchor it with `^(?:...)$` to match whole strings.
+    pub fn to_regex(&self, rule: RuleId) -> Option<String> {
+        let mut pattern = String::new();
+        self.write_regex(rule, &mut vec![false; self.len()], &mut pattern)?;
+        Some(pattern)
+    }
+
+    fn write_regex(&self, rule: RuleId, active: &mut [bool], pattern: &mut String) -> Option<()> {
+        if active[rule] {
+            return None;
+        }
+        active[rule] = true;
+        let alternatives = self.alternatives(rule);
+        let group = alternatives.len() > 1;
+        if group {
+            pattern.push_str("(?:");
+        }
+        for (i, alternative) in alternatives.iter().enumerate() {
+            if i > 0 {
+                pattern.push('|');
+            }
+            for symbol in alternative {
+                match symbol {
+                    Symbol::Terminal(text) => escape(text, pattern),
+                    Symbol::Rule(next) => self.write_regex(*next, active, pattern)?,
+                }
+            }
+        }
+        if group {
+            pattern.push(')');
+        }
+        active[rule] = false;
+        Some(())
+    }
+}
+
+fn escape(text: &str, pattern: &mut String) {
+    for c in text.chars() {
+        if r"\.+*?()|[]{}^$#&-~".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_regex() {
+        let grammar = Grammar::parse_rules(
+            "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            grammar.to_regex(0).unwrap(),
+            "a(?:(?:aa|bb)(?:ab|ba)|(?:ab|ba)(?:aa|bb))b"
+        );
+        let grammar = Grammar::parse_bnf("<a> ::= \"(\" <b> \")\"\n<b> ::= \"x.\" | \"\"").unwrap();
+        assert_eq!(grammar.to_regex(0).unwrap(), r"\((?:x\.|)\)");
+        let grammar = Grammar::parse_rules("0: 1 | 1 0\n1: \"a\"\n").unwrap();
+        assert_eq!(grammar.to_regex(0), None);
+        assert_eq!(grammar.to_regex(1).unwrap(), "a");
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+
+/// How a rule matched, for debugging a grammar.
+///
+/// It prints on one line as `rule(child child)` with terminals quoted, or
+/// with `{:#}` as an indented outline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tree {
+    Leaf(String),
+    Node { rule: String, children: Vec<Tree> },
+}
+
+impl Tree {
+    /// The matched text.
+    pub fn text(&self) -> String {
+        match self {
+            Tree::Leaf(text) => text.clone(),
+            Tree::Node { children, .. } => children.iter().map(Tree::text).collect(),
+        }
+    }
+
+    fn outline(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        match self {
+            Tree::Leaf(text) => writeln!(f, "{:indent$}{:?}", "", text, indent = 2 * depth),
+            Tree::Node { rule, children } => {
+                writeln!(f, "{:indent$}{}", "", rule, indent = 2 * depth)?;
+                children
+                    .iter()
+                    .try_for_each(|child| child.outline(f, depth + 1))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return self.outline(f, 0);
+        }
+        match self {
+            Tree::Leaf(text) => write!(f, "{:?}", text),
+            Tree::Node { rule, children } => {
+                write!(f, "{}(", rule)?;
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", child)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let tree = Tree::Node {
+            rule: "0".into(),
+            children: vec![
+                Tree::Leaf("a".into()),
+                Tree::Node {
+                    rule: "1".into(),
+                    children: vec![Tree::Leaf("b".into())],
+                },
+            ],
+        };
+        assert_eq!(tree.to_string(), "0(\"a\" 1(\"b\"))");
+        assert_eq!(format!("{:#}", tree), "0\n  \"a\"\n  1\n    \"b\"\n");
+        assert_eq!(tree.text(), "ab");
+    }
+}
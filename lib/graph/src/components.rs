@@ -0,0 +1,76 @@
+use crate::{Graph, NodeId};
+
+/// Union-find over node ids, with path halving and union by size.
+#[derive(Debug, Clone)]
+pub(crate) struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    pub(crate) fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Joins the sets of `a` and `b`, returning `false` if they already were
+    /// the same set.
+    pub(crate) fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+/// The connected components of the graph, ignoring edge directions. Each
+/// component lists its nodes in order, and the components are ordered by
+/// their first node.
+pub fn components(graph: &Graph) -> Vec<Vec<NodeId>> {
+    let mut sets = DisjointSet::new(graph.len());
+    for (from, to, _) in graph.edges() {
+        sets.union(from, to);
+    }
+    let mut index = vec![None; graph.len()];
+    let mut result: Vec<Vec<NodeId>> = vec![];
+    for node in graph.nodes() {
+        let root = sets.find(node);
+        let i = *index[root].get_or_insert_with(|| {
+            result.push(vec![]);
+            result.len() - 1
+        });
+        result[i].push(node);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components() {
+        let mut graph = Graph::directed();
+        graph.add_edge("a", "b");
+        graph.add_edge("c", "d");
+        graph.add_edge("e", "b");
+        graph.add_node("f");
+        assert_eq!(components(&graph), vec![vec![0, 1, 4], vec![2, 3], vec![5]]);
+    }
+}
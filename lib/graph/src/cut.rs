@@ -0,0 +1,212 @@
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::{components::DisjointSet, Graph, NodeId};
+
+/// A split of the nodes into two non-empty sides, and the total weight of the
+/// edges between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    pub weight: u64,
+    pub left: Vec<NodeId>,
+    pub right: Vec<NodeId>,
+}
+
+impl Cut {
+    fn new(graph: &Graph, on_left: impl Fn(NodeId) -> bool) -> Self {
+        let (left, right) = graph.nodes().partition(|&n| on_left(n));
+        let weight = graph
+            .edges()
+            .filter(|&(a, b, _)| on_left(a) != on_left(b))
+            .map(|(_, _, w)| w)
+            .sum();
+        Cut {
+            weight,
+            left,
+            right,
+        }
+    }
+}
+
+/// The minimum cut of an undirected graph with the Stoer-Wagner algorithm,
+/// or `None` if it has fewer than two nodes.
+pub fn stoer_wagner(graph: &Graph) -> Option<Cut> {
+    assert!(
+        !graph.is_directed(),
+        "minimum cuts need an undirected graph"
+    );
+    if graph.len() < 2 {
+        return None;
+    }
+    // nodes are merged as the algorithm goes on, every merged node keeps its
+    // original members and the summed weights to its neighbours
+    let mut adjacent: Vec<HashMap<NodeId, u64>> = vec![HashMap::new(); graph.len()];
+    for (a, b, w) in graph.edges() {
+        if a != b {
+            *adjacent[a].entry(b).or_default() += w;
+            *adjacent[b].entry(a).or_default() += w;
+        }
+    }
+    let mut members: Vec<Vec<NodeId>> = graph.nodes().map(|n| vec![n]).collect();
+    let mut active: Vec<NodeId> = graph.nodes().collect();
+    let mut best: Option<(u64, Vec<NodeId>)> = None;
+
+    while active.len() > 1 {
+        // add the most tightly connected node until all are added, the last
+        // one's connection is the cut of this phase
+        let mut added = vec![false; graph.len()];
+        let mut weight = vec![0; graph.len()];
+        let mut heap = BinaryHeap::from([(0, active[0])]);
+        let (mut prev, mut last) = (active[0], active[0]);
+        let mut count = 0;
+        while let Some((w, node)) = heap.pop() {
+            if added[node] || w != weight[node] {
+                continue;
+            }
+            added[node] = true;
+            prev = last;
+            last = node;
+            count += 1;
+            if count == active.len() {
+                break;
+            }
+            for (&next, &edge) in &adjacent[node] {
+                if !added[next] {
+                    weight[next] += edge;
+                    heap.push((weight[next], next));
+                }
+            }
+        }
+        if count < active.len() {
+            // disconnected, so nothing needs to be cut at all
+            let mut reached = vec![false; graph.len()];
+            for node in active.iter().filter(|&&n| added[n]) {
+                for &member in &members[*node] {
+                    reached[member] = true;
+                }
+            }
+            return Some(Cut::new(graph, |n| reached[n]));
+        }
+        if best.as_ref().is_none_or(|(w, _)| weight[last] < *w) {
+            best = Some((weight[last], members[last].clone()));
+        }
+
+        let merged = std::mem::take(&mut adjacent[last]);
+        for (next, w) in merged {
+            adjacent[next].remove(&last);
+            if next != prev {
+                *adjacent[prev].entry(next).or_default() += w;
+                *adjacent[next].entry(prev).or_default() += w;
+            }
+        }
+        let moved = std::mem::take(&mut members[last]);
+        members[prev].extend(moved);
+        active.retain(|&n| n != last);
+    }
+
+    let (_, side) = best?;
+    let mut on_left = vec![false; graph.len()];
+    for node in side {
+        on_left[node] = true;
+    }
+    Some(Cut::new(graph, |n| on_left[n]))
+}
+
+/// The smallest cut found by `trials` runs of Karger's random contraction,
+/// or `None` if the graph has fewer than two nodes. Every edge is equally
+/// likely to be contracted regardless of its weight, so this is best suited
+/// to unweighted graphs. The same `seed` always gives the same result.
+pub fn karger(graph: &Graph, trials: usize, seed: u64) -> Option<Cut> {
+    if graph.len() < 2 {
+        return None;
+    }
+    let mut edges: Vec<(NodeId, NodeId)> = graph
+        .edges()
+        .filter(|&(a, b, _)| a != b)
+        .map(|(a, b, _)| (a, b))
+        .collect();
+    let mut rng = XorShift(seed | 1);
+    let mut best: Option<Cut> = None;
+    for _ in 0..trials {
+        for i in (1..edges.len()).rev() {
+            edges.swap(i, rng.below(i + 1));
+        }
+        let mut sets = DisjointSet::new(graph.len());
+        let mut groups = graph.len();
+        for &(a, b) in &edges {
+            if groups == 2 {
+                break;
+            }
+            if sets.union(a, b) {
+                groups -= 1;
+            }
+        }
+        let roots: Vec<usize> = graph.nodes().map(|n| sets.find(n)).collect();
+        let cut = Cut::new(graph, |n| roots[n] == roots[0]);
+        if best.as_ref().is_none_or(|b| cut.weight < b.weight) {
+            best = Some(cut);
+        }
+    }
+    best
+}
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two complete graphs of four nodes joined by two edges.
+    fn bridged() -> Graph {
+        let mut graph = Graph::undirected();
+        for group in [["a", "b", "c", "d"], ["w", "x", "y", "z"]] {
+            for i in 0..4 {
+                for j in i + 1..4 {
+                    graph.add_edge(group[i], group[j]);
+                }
+            }
+        }
+        graph.add_edge("a", "w");
+        graph.add_edge("b", "x");
+        graph
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        let graph = bridged();
+        let cut = stoer_wagner(&graph).unwrap();
+        assert_eq!(cut.weight, 2);
+        assert_eq!(cut.left.len() * cut.right.len(), 16);
+
+        let mut weighted = Graph::undirected();
+        weighted.add_weighted_edge("a", "b", 10);
+        weighted.add_weighted_edge("b", "c", 3);
+        weighted.add_weighted_edge("c", "d", 10);
+        weighted.add_weighted_edge("d", "a", 4);
+        let cut = stoer_wagner(&weighted).unwrap();
+        assert_eq!(cut.weight, 7);
+    }
+
+    #[test]
+    fn test_disconnected() {
+        let mut graph = bridged();
+        graph.add_edge("p", "q");
+        let cut = stoer_wagner(&graph).unwrap();
+        assert_eq!(cut.weight, 0);
+        assert_eq!(karger(&graph, 10, 1).unwrap().weight, 0);
+    }
+
+    #[test]
+    fn test_karger() {
+        let cut = karger(&bridged(), 50, 7).unwrap();
+        assert_eq!(cut.weight, 2);
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt::Write;
+
+use crate::Graph;
+
+impl Graph {
+    /// The graph in Graphviz's DOT language, with weights as edge labels
+    /// unless every edge weighs 1. Render it with `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let weighted = self.edges().any(|(_, _, w)| w != 1);
+        let mut dot = format!("{} {{\n", keyword);
+        for node in self.nodes() {
+            writeln!(dot, "    {};", quote(self.name(node))).unwrap();
+        }
+        for (from, to, weight) in self.edges() {
+            write!(
+                dot,
+                "    {} {} {}",
+                quote(self.name(from)),
+                arrow,
+                quote(self.name(to))
+            )
+            .unwrap();
+            if weighted {
+                write!(dot, " [label={}]", weight).unwrap();
+            }
+            dot.push_str(";\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot() {
+        let mut graph = Graph::undirected();
+        graph.add_weighted_edge("a", "say \"b\"", 3);
+        assert_eq!(
+            graph.to_dot(),
+            "graph {\n    \"a\";\n    \"say \\\"b\\\"\";\n    \"a\" -- \"say \\\"b\\\"\" [label=3];\n}\n"
+        );
+        let graph = Graph::from_adjacency(true, [("x", ["y"])]);
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n    \"x\";\n    \"y\";\n    \"x\" -> \"y\";\n}\n"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+
+use crate::{Graph, NodeId};
+
+/// Lowest common ancestors in the tree spanned from a root, answered with
+/// binary lifting in `O(log n)` per query.
+///
+/// The tree is the breadth first tree from the root, following edge
+/// directions in directed graphs. Nodes that cannot be reached from the root
+/// are not part of it.
+#[derive(Debug, Clone)]
+pub struct Lca {
+    depth: Vec<Option<usize>>,
+    /// `up[k][node]` is the ancestor `2^k` levels above `node`, or the root.
+    up: Vec<Vec<NodeId>>,
+}
+
+impl Lca {
+    pub fn new(graph: &Graph, root: NodeId) -> Self {
+        let mut depth = vec![None; graph.len()];
+        let mut parent: Vec<NodeId> = graph.nodes().collect();
+        depth[root] = Some(0);
+        let mut queue = VecDeque::from([root]);
+        while let Some(node) = queue.pop_front() {
+            for next in graph.neighbours(node) {
+                if depth[next].is_none() {
+                    depth[next] = depth[node].map(|d| d + 1);
+                    parent[next] = node;
+                    queue.push_back(next);
+                }
+            }
+        }
+        let levels = (usize::BITS - graph.len().leading_zeros()).max(1) as usize;
+        let mut up = vec![parent];
+        for k in 1..levels {
+            let prev = &up[k - 1];
+            let next = prev.iter().map(|&p| prev[p]).collect();
+            up.push(next);
+        }
+        Self { depth, up }
+    }
+
+    /// Number of edges between the root and `node`, or `None` if it is not
+    /// in the tree.
+    pub fn depth(&self, node: NodeId) -> Option<usize> {
+        self.depth[node]
+    }
+
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        (self.depth[node]? > 0).then(|| self.up[0][node])
+    }
+
+    /// The deepest node that is an ancestor of both `a` and `b`, where every
+    /// node counts as its own ancestor.
+    pub fn lca(&self, a: NodeId, b: NodeId) -> Option<NodeId> {
+        let (mut a, mut b) = (a, b);
+        let (mut depth_a, mut depth_b) = (self.depth[a]?, self.depth[b]?);
+        if depth_a < depth_b {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut depth_a, &mut depth_b);
+        }
+        a = self.ancestor(a, depth_a - depth_b);
+        if a == b {
+            return Some(a);
+        }
+        for level in self.up.iter().rev() {
+            if level[a] != level[b] {
+                a = level[a];
+                b = level[b];
+            }
+        }
+        Some(self.up[0][a])
+    }
+
+    /// Number of edges on the path between `a` and `b` through the tree.
+    pub fn distance(&self, a: NodeId, b: NodeId) -> Option<usize> {
+        let lca = self.lca(a, b)?;
+        Some(self.depth[a]? + self.depth[b]? - 2 * self.depth[lca]?)
+    }
+
+    fn ancestor(&self, mut node: NodeId, levels: usize) -> NodeId {
+        for (k, level) in self.up.iter().enumerate() {
+            if levels >> k & 1 == 1 {
+                node = level[node];
+            }
+        }
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbits() {
+        let mut graph = Graph::directed();
+        for line in "COM)B B)C C)D D)E E)F B)G G)H D)I E)J J)K K)L K)YOU I)SAN".split(' ') {
+            let (center, orbiter) = line.split_once(')').unwrap();
+            graph.add_edge(center, orbiter);
+        }
+        graph.add_node("lost");
+        let lca = Lca::new(&graph, graph.id("COM").unwrap());
+        let id = |name| graph.id(name).unwrap();
+        let total: usize = graph.nodes().filter_map(|n| lca.depth(n)).sum();
+        assert_eq!(total, 42 + 7 + 5);
+        assert_eq!(lca.lca(id("YOU"), id("SAN")), Some(id("D")));
+        assert_eq!(lca.lca(id("H"), id("B")), Some(id("B")));
+        assert_eq!(lca.distance(id("YOU"), id("SAN")), Some(6));
+        assert_eq!(lca.parent(id("COM")), None);
+        assert_eq!(lca.parent(id("YOU")), Some(id("K")));
+        assert_eq!(lca.lca(id("lost"), id("B")), None);
+    }
+}
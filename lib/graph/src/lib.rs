@@ -0,0 +1,187 @@
+//! Graphs of named nodes and the algorithms that keep coming back for them.
+//!
+//! A [`Graph`] interns node names as it is built, so puzzles can add edges
+//! straight from their input and work with dense [`NodeId`]s afterwards:
+//!
+//! ```
+//! use graph::{toposort_by_key, Graph};
+//!
+//! let graph = Graph::from_adjacency(true, [("C", vec!["A", "F"]), ("A", vec!["B", "D"])]);
+//! let order = toposort_by_key(&graph, |id| graph.name(id).to_string()).unwrap();
+//! let names: Vec<_> = order.iter().map(|&id| graph.name(id)).collect();
+//! assert_eq!(names, ["C", "A", "B", "D", "F"]);
+//! ```
+//!
+//! On top of that there are
+//!
+//! - [`components`] for connected components,
+//! - [`toposort`], [`toposort_by_key`] and [`schedule`] for dependency
+//!   orders, optionally worked through by several workers at once,
+//! - [`Lca`] for lowest common ancestors and distances in trees,
+//! - [`bfs_distances`], [`all_pairs_bfs`] and [`floyd_warshall`] for
+//!   shortest paths,
+//! - [`stoer_wagner`] and [`karger`] for minimum cuts,
+//! - [`Graph::to_dot`] to look at a graph with Graphviz.
+
+mod components;
+mod cut;
+mod dot;
+mod lca;
+mod paths;
+mod topo;
+
+pub use components::components;
+pub use cut::{karger, stoer_wagner, Cut};
+pub use lca::Lca;
+pub use paths::{all_pairs_bfs, bfs_distances, floyd_warshall, Distances};
+pub use topo::{schedule, toposort, toposort_by_key, Schedule};
+
+use std::{collections::HashMap, ops::Range};
+
+/// Index of a node, in the order the nodes were first seen.
+pub type NodeId = usize;
+
+/// A directed or undirected graph with weighted edges between named nodes.
+///
+/// Undirected edges are stored in both directions, so [`Graph::edges_from`]
+/// sees them from either end.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    directed: bool,
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    edges: Vec<Vec<(NodeId, u64)>>,
+}
+
+impl Graph {
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            names: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+        }
+    }
+
+    /// Builds a graph from adjacency lists, with an edge of weight 1 from
+    /// every node to each node in its list.
+    pub fn from_adjacency<'a, I, J>(directed: bool, lists: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, J)>,
+        J: IntoIterator<Item = &'a str>,
+    {
+        let mut graph = Self::new(directed);
+        for (from, tos) in lists {
+            graph.add_node(from);
+            for to in tos {
+                graph.add_edge(from, to);
+            }
+        }
+        graph
+    }
+
+    /// The id of the node called `name`, adding it if it is new.
+    pub fn add_node(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: &str, to: &str, weight: u64) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+        if !self.directed && from != to {
+            self.edges[to].push((from, weight));
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn nodes(&self) -> Range<NodeId> {
+        0..self.len()
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    /// Edges leaving `id` as `(target, weight)`.
+    pub fn edges_from(&self, id: NodeId) -> &[(NodeId, u64)] {
+        &self.edges[id]
+    }
+
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id].iter().map(|&(to, _)| to)
+    }
+
+    /// Every edge as `(from, to, weight)`, undirected ones only once.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId, u64)> + '_ {
+        self.nodes().flat_map(move |from| {
+            self.edges[from]
+                .iter()
+                .filter(move |&&(to, _)| self.directed || from <= to)
+                .map(move |&(to, weight)| (from, to, weight))
+        })
+    }
+
+    /// Number of edges arriving at each node.
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.len()];
+        for targets in &self.edges {
+            for &(to, _) in targets {
+                degrees[to] += 1;
+            }
+        }
+        degrees
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning() {
+        let mut graph = Graph::undirected();
+        graph.add_edge("a", "b");
+        graph.add_weighted_edge("b", "c", 5);
+        assert_eq!(graph.add_node("a"), 0);
+        assert_eq!(graph.id("c"), Some(2));
+        assert_eq!(graph.id("d"), None);
+        assert_eq!(graph.edges_from(1), &[(0, 1), (2, 5)]);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), [(0, 1, 1), (1, 2, 5)]);
+        assert_eq!(graph.in_degrees(), [1, 2, 1]);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use crate::{Graph, NodeId};
+
+/// Shortest path lengths between every pair of nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances {
+    len: usize,
+    dist: Vec<Option<u64>>,
+}
+
+impl Distances {
+    /// `None` if `to` cannot be reached from `from`.
+    pub fn get(&self, from: NodeId, to: NodeId) -> Option<u64> {
+        self.dist[from * self.len + to]
+    }
+}
+
+/// Number of edges on the shortest path from `from` to every node, ignoring
+/// the weights.
+pub fn bfs_distances(graph: &Graph, from: NodeId) -> Vec<Option<u64>> {
+    let mut dist = vec![None; graph.len()];
+    dist[from] = Some(0);
+    let mut queue = VecDeque::from([(from, 0)]);
+    while let Some((node, d)) = queue.pop_front() {
+        for next in graph.neighbours(node) {
+            if dist[next].is_none() {
+                dist[next] = Some(d + 1);
+                queue.push_back((next, d + 1));
+            }
+        }
+    }
+    dist
+}
+
+/// Number of edges on the shortest path between every pair of nodes, with a
+/// breadth first search from each node. Faster than [`floyd_warshall`] on
+/// sparse graphs, but ignores the weights.
+pub fn all_pairs_bfs(graph: &Graph) -> Distances {
+    Distances {
+        len: graph.len(),
+        dist: graph
+            .nodes()
+            .flat_map(|from| bfs_distances(graph, from))
+            .collect(),
+    }
+}
+
+/// Weight of the lightest path between every pair of nodes.
+pub fn floyd_warshall(graph: &Graph) -> Distances {
+    let n = graph.len();
+    let mut dist = vec![None; n * n];
+    for node in graph.nodes() {
+        dist[node * n + node] = Some(0);
+    }
+    for (from, to, weight) in graph.edges() {
+        let mut relax = |a: NodeId, b: NodeId| {
+            let d = &mut dist[a * n + b];
+            *d = Some(d.map_or(weight, |d: u64| d.min(weight)));
+        };
+        relax(from, to);
+        if !graph.is_directed() {
+            relax(to, from);
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            let Some(ik) = dist[i * n + k] else {
+                continue;
+            };
+            for j in 0..n {
+                if let Some(kj) = dist[k * n + j] {
+                    let d = &mut dist[i * n + j];
+                    if d.is_none_or(|d| ik + kj < d) {
+                        *d = Some(ik + kj);
+                    }
+                }
+            }
+        }
+    }
+    Distances { len: n, dist }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortest_paths() {
+        let mut graph = Graph::directed();
+        graph.add_weighted_edge("a", "b", 4);
+        graph.add_weighted_edge("a", "c", 1);
+        graph.add_weighted_edge("c", "b", 2);
+        graph.add_weighted_edge("b", "d", 1);
+        graph.add_node("e");
+        let weighted = floyd_warshall(&graph);
+        let hops = all_pairs_bfs(&graph);
+        assert_eq!(weighted.get(0, 3), Some(4));
+        assert_eq!(hops.get(0, 3), Some(2));
+        assert_eq!(weighted.get(3, 0), None);
+        assert_eq!(weighted.get(4, 4), Some(0));
+        assert_eq!(
+            bfs_distances(&graph, 2),
+            [None, Some(1), Some(0), Some(2), None]
+        );
+    }
+}
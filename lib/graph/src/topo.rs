@@ -0,0 +1,144 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::{Graph, NodeId};
+
+/// A topological order of a directed graph, taking the lowest id whenever
+/// several nodes are ready. `None` if the graph has a cycle.
+pub fn toposort(graph: &Graph) -> Option<Vec<NodeId>> {
+    toposort_by_key(graph, |id| id)
+}
+
+/// A topological order of a directed graph, taking the ready node with the
+/// smallest key first. `None` if the graph has a cycle.
+pub fn toposort_by_key<K: Ord>(graph: &Graph, key: impl Fn(NodeId) -> K) -> Option<Vec<NodeId>> {
+    let mut waiting = graph.in_degrees();
+    let mut ready: BinaryHeap<_> = graph
+        .nodes()
+        .filter(|&id| waiting[id] == 0)
+        .map(|id| Reverse((key(id), id)))
+        .collect();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(Reverse((_, id))) = ready.pop() {
+        order.push(id);
+        for next in graph.neighbours(id) {
+            waiting[next] -= 1;
+            if waiting[next] == 0 {
+                ready.push(Reverse((key(next), next)));
+            }
+        }
+    }
+    (order.len() == graph.len()).then_some(order)
+}
+
+/// When each task of a [`schedule`] was worked on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// Tasks in the order they were started.
+    pub order: Vec<NodeId>,
+    /// Start time of every task, indexed by node.
+    pub start: Vec<u64>,
+    /// When the last task was done.
+    pub finish: u64,
+}
+
+/// Works through the tasks of a directed graph, where an edge means its
+/// target can only start once its source is done, with `workers` working in
+/// parallel. An idle worker always picks the ready task with the smallest key.
+/// `None` if the graph has a cycle.
+pub fn schedule<K: Ord>(
+    graph: &Graph,
+    workers: usize,
+    duration: impl Fn(NodeId) -> u64,
+    key: impl Fn(NodeId) -> K,
+) -> Option<Schedule> {
+    assert!(workers > 0, "nobody to do the work");
+    let mut waiting = graph.in_degrees();
+    let mut ready: BinaryHeap<_> = graph
+        .nodes()
+        .filter(|&id| waiting[id] == 0)
+        .map(|id| Reverse((key(id), id)))
+        .collect();
+    let mut busy = BinaryHeap::new();
+    let mut order = Vec::with_capacity(graph.len());
+    let mut start = vec![0; graph.len()];
+    let mut now = 0;
+    loop {
+        while busy.len() < workers {
+            let Some(Reverse((_, id))) = ready.pop() else {
+                break;
+            };
+            order.push(id);
+            start[id] = now;
+            busy.push(Reverse((now + duration(id), id)));
+        }
+        let Some(&Reverse((done, _))) = busy.peek() else {
+            break;
+        };
+        // finish everything that is done at this time before handing out
+        // new tasks, as they may have made more tasks ready
+        now = done;
+        while let Some(&Reverse((time, id))) = busy.peek() {
+            if time != now {
+                break;
+            }
+            busy.pop();
+            for next in graph.neighbours(id) {
+                waiting[next] -= 1;
+                if waiting[next] == 0 {
+                    ready.push(Reverse((key(next), next)));
+                }
+            }
+        }
+    }
+    (order.len() == graph.len()).then_some(Schedule {
+        order,
+        start,
+        finish: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps() -> Graph {
+        let mut graph = Graph::directed();
+        for (a, b) in [
+            ("C", "A"),
+            ("C", "F"),
+            ("A", "B"),
+            ("A", "D"),
+            ("B", "E"),
+            ("D", "E"),
+            ("F", "E"),
+        ] {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_toposort() {
+        let graph = steps();
+        let names = |order: Vec<NodeId>| order.iter().map(|&id| graph.name(id)).collect::<String>();
+        assert_eq!(names(toposort(&graph).unwrap()), "CAFBDE");
+        let by_name = toposort_by_key(&graph, |id| graph.name(id).to_string());
+        assert_eq!(names(by_name.unwrap()), "CABDFE");
+
+        let mut cyclic = steps();
+        cyclic.add_edge("E", "C");
+        assert_eq!(toposort(&cyclic), None);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let graph = steps();
+        let letter = |id: NodeId| graph.name(id).chars().next().unwrap();
+        let duration = |id| (letter(id) as u8 - b'A' + 1) as u64;
+        let result = schedule(&graph, 2, duration, letter).unwrap();
+        assert_eq!(result.finish, 15);
+        let order: String = result.order.iter().map(|&id| letter(id)).collect();
+        assert_eq!(order, "CAFBDE");
+        assert_eq!(result.start[graph.id("E").unwrap()], 10);
+    }
+}
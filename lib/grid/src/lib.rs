@@ -0,0 +1,474 @@
+pub mod sparse;
+
+pub use sparse::SparseGrid;
+
+use geometry::Direction;
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// Position in a grid as `(row, col)`.
+pub type Pos = (usize, usize);
+
+/// Step between two grid positions as `(row delta, col delta)`.
+pub type Offset = (isize, isize);
+
+/// The step one cell in `dir`, with north going up a row.
+pub fn offset(dir: impl Into<Direction>) -> Offset {
+    dir.into().unit_screen().to_row_col()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    /// A row has a different length than the first one.
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The char mapping rejected a character.
+    InvalidChar {
+        line: usize,
+        col: usize,
+        ch: char,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty grid"),
+            ParseError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} columns, found {}",
+                line, expected, found
+            ),
+            ParseError::InvalidChar { line, col, ch } => {
+                write!(f, "line {}, column {}: invalid char {:?}", line, col, ch)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(row, col)| {
+            self[(col, row)].clone()
+        })
+    }
+
+    /// Rotates the grid by 90° clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(row, col)| {
+            self[(self.height - 1 - col, row)].clone()
+        })
+    }
+
+    /// Rotates the grid by 90° counter-clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        Self::from_fn(self.height, self.width, |(row, col)| {
+            self[(col, self.width - 1 - row)].clone()
+        })
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(row, col)| {
+            self[(row, self.width - 1 - col)].clone()
+        })
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_fn(self.width, self.height, |(row, col)| {
+            self[(self.height - 1 - row, col)].clone()
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a grid from rows of equal length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "all rows must have the same length"
+        );
+        Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Parses a grid from text, mapping each character to a cell.
+    ///
+    /// Panics if the rows differ in length.
+    pub fn parse(input: &str, f: impl Fn(char) -> T) -> Self {
+        match Self::try_parse(input, |ch| Some(f(ch))) {
+            Ok(grid) => grid,
+            Err(e) => panic!("unable to parse grid: {}", e),
+        }
+    }
+
+    /// Parses a grid from text, where the mapping returns `None` for
+    /// characters that are not allowed.
+    ///
+    /// Blank lines after the last row are dropped, but whitespace at either
+    /// end of a row is part of the grid.
+    pub fn try_parse(input: &str, f: impl Fn(char) -> Option<T>) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        let rows = input.trim_end_matches(['\n', '\r']);
+        for (line_no, line) in rows.lines().enumerate() {
+            let len = line.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(expected) if expected != len => {
+                    return Err(ParseError::RaggedRow {
+                        line: line_no + 1,
+                        expected,
+                        found: len,
+                    })
+                }
+                _ => {}
+            }
+            for (col, ch) in line.chars().enumerate() {
+                cells.push(f(ch).ok_or(ParseError::InvalidChar {
+                    line: line_no + 1,
+                    col: col + 1,
+                    ch,
+                })?);
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(ParseError::Empty),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn in_bounds(&self, (row, col): Pos) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.in_bounds(pos) {
+            Some(&self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.in_bounds(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the cell at `pos`, returns false if it is out of bounds.
+    pub fn set(&mut self, pos: Pos, value: T) -> bool {
+        match self.get_mut(pos) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves `pos` by `offset`, or returns `None` if that leaves the grid.
+    pub fn step(&self, (row, col): Pos, (d_row, d_col): Offset) -> Option<Pos> {
+        let pos = (
+            row.checked_add_signed(d_row)?,
+            col.checked_add_signed(d_col)?,
+        );
+        if self.in_bounds(pos) {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Positions of the (up to) 4 orthogonal neighbours of `pos`.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::CARDINAL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, offset(d)))
+    }
+
+    /// Positions of the (up to) 8 orthogonal and diagonal neighbours of `pos`.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |d| self.step(pos, offset(d)))
+    }
+
+    /// Positions and values starting next to `pos` and walking in direction
+    /// `offset` until the edge of the grid.
+    pub fn ray(&self, pos: Pos, offset: Offset) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        std::iter::successors(self.step(pos, offset), move |&p| self.step(p, offset))
+            .map(move |p| (p, &self[p]))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut T)> + '_ {
+        self.positions().zip(self.cells.iter_mut())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, row: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.cells[row * self.width..(row + 1) * self.width].iter()
+    }
+
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        (0..self.height).map(move |row| &self[(row, col)])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(move |row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter().find(|(_, v)| predicate(v)).map(|(p, _)| p)
+    }
+
+    pub fn find_all<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter(move |(_, v)| predicate(v))
+            .map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Renders the grid as text, one line per row.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("position {:?} out of bounds", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("position {:?} out of bounds", pos))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Grid<char> {
+        Grid::parse("abc\ndef\n", |ch| ch)
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.to_string(), "abc\ndef");
+        let digits = Grid::parse("12\n34", |ch| ch.to_digit(10).unwrap());
+        assert_eq!(digits.values().sum::<u32>(), 10);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Grid::try_parse("ab\nabc", Some),
+            Err(ParseError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Grid::try_parse("#.\n.x", |ch| match ch {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            }),
+            Err(ParseError::InvalidChar {
+                line: 2,
+                col: 2,
+                ch: 'x'
+            })
+        );
+        assert_eq!(Grid::try_parse("\n", Some), Err(ParseError::Empty));
+        assert_eq!(
+            Grid::try_parse(" a\nb\n", Some),
+            Err(ParseError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+        let grid = Grid::try_parse("..#\n \t \n\n\n", Some).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.row(1).collect::<String>(), " \t ");
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut grid = sample();
+        assert_eq!(grid.get((2, 0)), None);
+        assert!(grid.set((0, 1), 'x'));
+        assert!(!grid.set((0, 3), 'x'));
+        assert_eq!(grid.to_string(), "axc\ndef");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_rays() {
+        let grid = Grid::parse("abc\ndef\nghi", |ch| ch);
+        let ray = |pos, dir| grid.ray(pos, dir).map(|(_, &v)| v).collect::<String>();
+        assert_eq!(ray((0, 0), offset(Direction::East)), "bc");
+        assert_eq!(ray((2, 1), offset(Direction::North)), "eb");
+        assert_eq!(ray((0, 0), (1, 1)), "ei");
+        assert_eq!(ray((0, 2), (1, -1)), "eg");
+        assert_eq!(ray((1, 1), offset(Direction::West)), "d");
+        assert_eq!(grid.col(1).collect::<String>(), "beh");
+        assert_eq!(grid.row(2).rev().collect::<String>(), "ihg");
+        assert_eq!(grid.rows().nth(1), Some(&['d', 'e', 'f'][..]));
+        let empty: Grid<char> = Grid::from_rows(vec![vec![], vec![]]);
+        assert_eq!(empty.rows().collect::<Vec<_>>(), [&[] as &[char]; 2]);
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = sample();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+    }
+
+    #[test]
+    fn test_find() {
+        let grid = Grid::parse("S..\n.#.\n..#", |ch| ch);
+        assert_eq!(grid.find(|&ch| ch == 'S'), Some((0, 0)));
+        assert_eq!(
+            grid.find_all(|&ch| ch == '#').collect::<Vec<_>>(),
+            [(1, 1), (2, 2)]
+        );
+        assert_eq!(grid.find(|&ch| ch == 'E'), None);
+    }
+}
@@ -0,0 +1,381 @@
+use geometry::{Point2, Point3};
+use std::{collections::HashMap, fmt::Debug, hash::Hash};
+
+/// A point with a fixed number of integer components that can be used as the
+/// key of a [`SparseGrid`].
+pub trait Coord: Copy + Eq + Hash + Debug {
+    const DIM: usize;
+
+    fn component(&self, axis: usize) -> i64;
+
+    fn from_fn(f: impl FnMut(usize) -> i64) -> Self;
+
+    fn min_each(self, other: Self) -> Self {
+        Self::from_fn(|axis| self.component(axis).min(other.component(axis)))
+    }
+
+    fn max_each(self, other: Self) -> Self {
+        Self::from_fn(|axis| self.component(axis).max(other.component(axis)))
+    }
+
+    /// Adds `amount` to every component.
+    fn grow(self, amount: i64) -> Self {
+        Self::from_fn(|axis| self.component(axis) + amount)
+    }
+
+    /// All points that differ by at most 1 in every component, excluding
+    /// the point itself (8 in 2D, 26 in 3D, 80 in 4D).
+    fn neighbours(self) -> Vec<Self> {
+        points_between(self.grow(-1), self.grow(1))
+            .filter(|&p| p != self)
+            .collect()
+    }
+}
+
+/// Iterates over all points in the box spanned by `min` and `max`
+/// (inclusive), varying the last component fastest.
+pub fn points_between<P: Coord>(min: P, max: P) -> impl Iterator<Item = P> {
+    let empty = (0..P::DIM).any(|axis| min.component(axis) > max.component(axis));
+    let mut next = if empty { None } else { Some(min) };
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = (0..P::DIM)
+            .rev()
+            .find(|&axis| current.component(axis) < max.component(axis))
+            .map(|axis| {
+                P::from_fn(|a| match a.cmp(&axis) {
+                    std::cmp::Ordering::Less => current.component(a),
+                    std::cmp::Ordering::Equal => current.component(a) + 1,
+                    std::cmp::Ordering::Greater => min.component(a),
+                })
+            });
+        Some(current)
+    })
+}
+
+macro_rules! impl_coord_tuple {
+    ($t:ty; $dim:expr; $($idx:tt),+) => {
+        impl Coord for ($(impl_coord_tuple!(@ty $idx $t),)+) {
+            const DIM: usize = $dim;
+
+            fn component(&self, axis: usize) -> i64 {
+                match axis {
+                    $($idx => self.$idx as i64,)+
+                    _ => panic!("axis {} out of range", axis),
+                }
+            }
+
+            fn from_fn(mut f: impl FnMut(usize) -> i64) -> Self {
+                ($(f($idx) as $t,)+)
+            }
+        }
+    };
+    (@ty $idx:tt $t:ty) => { $t };
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),+) => {
+        $(
+            impl_coord_tuple!($t; 2; 0, 1);
+            impl_coord_tuple!($t; 3; 0, 1, 2);
+            impl_coord_tuple!($t; 4; 0, 1, 2, 3);
+        )+
+    };
+}
+
+impl_coord!(i32, i64, isize);
+
+macro_rules! impl_coord_point {
+    ($($t:ty),+) => {
+        $(
+            impl Coord for Point2<$t> {
+                const DIM: usize = 2;
+
+                fn component(&self, axis: usize) -> i64 {
+                    match axis {
+                        0 => self.x as i64,
+                        1 => self.y as i64,
+                        _ => panic!("axis {} out of range", axis),
+                    }
+                }
+
+                fn from_fn(mut f: impl FnMut(usize) -> i64) -> Self {
+                    Point2::new(f(0) as $t, f(1) as $t)
+                }
+            }
+
+            impl Coord for Point3<$t> {
+                const DIM: usize = 3;
+
+                fn component(&self, axis: usize) -> i64 {
+                    match axis {
+                        0 => self.x as i64,
+                        1 => self.y as i64,
+                        2 => self.z as i64,
+                        _ => panic!("axis {} out of range", axis),
+                    }
+                }
+
+                fn from_fn(mut f: impl FnMut(usize) -> i64) -> Self {
+                    Point3::new(f(0) as $t, f(1) as $t, f(2) as $t)
+                }
+            }
+        )+
+    };
+}
+
+impl_coord_point!(i32, i64, isize);
+
+impl<const N: usize> Coord for [i64; N] {
+    const DIM: usize = N;
+
+    fn component(&self, axis: usize) -> i64 {
+        self[axis]
+    }
+
+    fn from_fn(f: impl FnMut(usize) -> i64) -> Self {
+        std::array::from_fn(f)
+    }
+}
+
+/// An unbounded grid that only stores cells which have been set. Every other
+/// cell has the default value. The bounding box of all set cells is kept up
+/// to date as cells are inserted and removed.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<P, T> {
+    cells: HashMap<P, T>,
+    default: T,
+    bounds: Option<(P, P)>,
+}
+
+impl<P: Coord, T> SparseGrid<P, T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    /// The value of every cell that has not been set.
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    pub fn set_default(&mut self, default: T) {
+        self.default = default;
+    }
+
+    /// The value at `p`, or the default value if it has not been set.
+    pub fn get(&self, p: P) -> &T {
+        self.cells.get(&p).unwrap_or(&self.default)
+    }
+
+    /// The value at `p` only if it has been set.
+    pub fn cell(&self, p: P) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn contains(&self, p: P) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn insert(&mut self, p: P, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.min_each(p), max.max_each(p)),
+            None => (p, p),
+        });
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: P) -> Option<T> {
+        let removed = self.cells.remove(&p);
+        if let (Some(_), Some((min, max))) = (&removed, self.bounds) {
+            let on_edge = (0..P::DIM).any(|axis| {
+                p.component(axis) == min.component(axis) || p.component(axis) == max.component(axis)
+            });
+            if on_edge {
+                self.recompute_bounds();
+            }
+        }
+        removed
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, &p| match bounds {
+            Some((min, max)) => Some((p.min_each(min), p.max_each(max))),
+            None => Some((p, p)),
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest and largest corner of the box containing every set cell.
+    pub fn bounds(&self) -> Option<(P, P)> {
+        self.bounds
+    }
+
+    /// Number of cells in the bounding box, set or not.
+    pub fn bounds_volume(&self) -> usize {
+        match self.bounds {
+            Some((min, max)) => (0..P::DIM)
+                .map(|axis| (max.component(axis) - min.component(axis) + 1) as usize)
+                .product(),
+            None => 0,
+        }
+    }
+
+    /// All points of the bounding box, grown by `margin` in every direction.
+    pub fn points_in_bounds(&self, margin: i64) -> impl Iterator<Item = P> {
+        let bounds = self
+            .bounds
+            .map(|(min, max)| (min.grow(-margin), max.grow(margin)));
+        bounds
+            .into_iter()
+            .flat_map(|(min, max)| points_between(min, max))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = P> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (P, &T)> + '_ {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+}
+
+impl<P: Coord, T: PartialEq> PartialEq for SparseGrid<P, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.default == other.default && self.cells == other.cells
+    }
+}
+
+impl<P: Coord, T: Eq> Eq for SparseGrid<P, T> {}
+
+impl<P: Coord, T: Default> Default for SparseGrid<P, T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<P: Coord, T: Default> FromIterator<(P, T)> for SparseGrid<P, T> {
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        let mut grid = Self::default();
+        for (p, value) in iter {
+            grid.insert(p, value);
+        }
+        grid
+    }
+}
+
+impl<P: Coord, T> SparseGrid<P, T> {
+    /// Renders a 2D grid cropped to its bounds, treating the first component
+    /// of each point as the row and the second as the column. The closure
+    /// receives `None` for cells that have not been set.
+    pub fn render(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        self.render_axes(0, 1, f)
+    }
+
+    /// Like [`SparseGrid::render`], but for `(x, y)` points where the first
+    /// component is the column and the second the row.
+    pub fn render_xy(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        self.render_axes(1, 0, f)
+    }
+
+    fn render_axes(
+        &self,
+        row_axis: usize,
+        col_axis: usize,
+        f: impl Fn(Option<&T>) -> char,
+    ) -> String {
+        assert_eq!(P::DIM, 2, "only 2D grids can be rendered");
+        let (min, max) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        (min.component(row_axis)..=max.component(row_axis))
+            .map(|row| {
+                (min.component(col_axis)..=max.component(col_axis))
+                    .map(|col| {
+                        let p = P::from_fn(|axis| if axis == row_axis { row } else { col });
+                        f(self.cell(p))
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points_between() {
+        assert_eq!(
+            points_between((0, 0), (1, 2)).collect::<Vec<(i32, i32)>>(),
+            [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
+        );
+        assert_eq!(points_between([0, 0, 0], [2, 2, 2]).count(), 27);
+        assert_eq!(points_between((1i64, 0i64), (0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!((0i64, 0i64).neighbours().len(), 8);
+        assert_eq!((0, 0, 0).neighbours().len(), 26);
+        assert_eq!((5isize, 5isize, 5isize, 5isize).neighbours().len(), 80);
+    }
+
+    #[test]
+    fn test_bounds_tracking() {
+        let mut grid = SparseGrid::new('.');
+        assert_eq!(grid.bounds(), None);
+        grid.insert((2, 3), '#');
+        grid.insert((-1, 5), '#');
+        grid.insert((0, 4), '#');
+        assert_eq!(grid.bounds(), Some(((-1, 3), (2, 5))));
+        assert_eq!(grid.bounds_volume(), 12);
+        grid.remove((0, 4));
+        assert_eq!(grid.bounds(), Some(((-1, 3), (2, 5))));
+        grid.remove((-1, 5));
+        assert_eq!(grid.bounds(), Some(((2, 3), (2, 3))));
+        assert_eq!(*grid.get((100, 100)), '.');
+        assert_eq!(grid.cell((100, 100)), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let grid = [((1, 1), 'a'), ((2, 3), 'b')]
+            .into_iter()
+            .collect::<SparseGrid<(i32, i32), char>>();
+        let render = |cell: Option<&char>| cell.copied().unwrap_or('.');
+        assert_eq!(grid.render(render), "a..\n..b");
+        assert_eq!(grid.render_xy(render), "a.\n..\n.b");
+        let grid = [(Point2::new(1, 1), 'a'), (Point2::new(2, 3), 'b')]
+            .into_iter()
+            .collect::<SparseGrid<Point2<i32>, char>>();
+        assert_eq!(grid.render_xy(render), "a.\n..\n.b");
+    }
+
+    #[test]
+    fn test_points_in_bounds() {
+        let mut grid = SparseGrid::new(false);
+        grid.insert([0, 0, 0], true);
+        grid.insert([1, 0, 0], true);
+        assert_eq!(grid.points_in_bounds(0).count(), 2);
+        assert_eq!(grid.points_in_bounds(1).count(), 4 * 3 * 3);
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+
+use crate::{IntcodeComputer, IoEvent};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsciiOutput {
+    Text(String),
+    Value(i64),
+}
+
+impl fmt::Display for AsciiOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsciiOutput::Text(text) => write!(f, "{}", text),
+            AsciiOutput::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+pub fn is_ascii(value: i64) -> bool {
+    (0..128).contains(&value)
+}
+
+/// Converts a line of text into input codes, terminated by a newline.
+pub fn encode_line(line: &str) -> Vec<i64> {
+    line.trim_end_matches('\n')
+        .chars()
+        .chain(std::iter::once('\n'))
+        .map(|ch| ch as i64)
+        .collect()
+}
+
+/// Groups output codes into runs of text, passing values outside the ASCII
+/// range through as numbers.
+pub fn decode(values: &[i64]) -> Vec<AsciiOutput> {
+    let mut result = vec![];
+    let mut text = String::new();
+    for &value in values {
+        if is_ascii(value) {
+            text.push(value as u8 as char);
+        } else {
+            if !text.is_empty() {
+                result.push(AsciiOutput::Text(std::mem::take(&mut text)));
+            }
+            result.push(AsciiOutput::Value(value));
+        }
+    }
+    if !text.is_empty() {
+        result.push(AsciiOutput::Text(text));
+    }
+    result
+}
+
+impl IntcodeComputer {
+    pub fn push_ascii_line(&mut self, line: &str) {
+        for code in encode_line(line) {
+            self.push_input(code);
+        }
+    }
+
+    /// Runs until the program halts or waits for input and returns everything
+    /// it printed in the meantime.
+    pub fn run_ascii(&mut self) -> Vec<AsciiOutput> {
+        let mut values = vec![];
+        while let IoEvent::Output(value) = self.run_until_io() {
+            values.push(value);
+        }
+        decode(&values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_line() {
+        assert_eq!(encode_line("A,B"), vec![65, 44, 66, 10]);
+        assert_eq!(encode_line("NOT A J\n"), encode_line("NOT A J"));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode(&[72, 105, 10, 19348, 33]),
+            vec![
+                AsciiOutput::Text("Hi\n".to_string()),
+                AsciiOutput::Value(19348),
+                AsciiOutput::Text("!".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_ascii() {
+        // echoes a single input character twice, then prints a large number
+        let mut computer = IntcodeComputer::from_str("3,20,4,20,4,20,104,1000,99");
+        assert_eq!(computer.run_ascii(), vec![]);
+        assert!(computer.needs_input());
+        computer.push_ascii_line("x");
+        assert_eq!(
+            computer.run_ascii(),
+            vec![
+                AsciiOutput::Text("xx".to_string()),
+                AsciiOutput::Value(1000)
+            ]
+        );
+        assert!(computer.is_halted());
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, Write},
+    process,
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+use intcode::{ascii::AsciiOutput, IntcodeComputer, IoEvent};
+
+const USAGE: &str = "\
+Usage: intcode-term <program> [--mode ascii|numeric|screen] [--poke <addr>=<value>]...
+                    [--trace <file.csv|file.json>]
+
+Modes:
+  ascii    (default) print output as text, send each line typed as ASCII input
+  numeric  print raw output values, read comma separated numbers as input
+  screen   render (x, y, tile) output triples as a live grid, like 2019 day 13.
+           Use the left/right arrows (or a/d) to move, q to quit.
+
+With --trace, an execution trace is written to the given file on exit (CSV or
+JSON depending on the extension) and a coverage report is printed to stderr.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Ascii,
+    Numeric,
+    Screen,
+}
+
+struct Options {
+    program: String,
+    mode: Mode,
+    pokes: Vec<(usize, i64)>,
+    trace: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut args = std::env::args().skip(1);
+    let mut program = None;
+    let mut mode = Mode::Ascii;
+    let mut pokes = vec![];
+    let mut trace = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            "--mode" => {
+                mode = match args.next().as_deref() {
+                    Some("ascii") => Mode::Ascii,
+                    Some("numeric") => Mode::Numeric,
+                    Some("screen") => Mode::Screen,
+                    other => return Err(format!("invalid mode {:?}\n\n{}", other, USAGE)),
+                }
+            }
+            "--poke" => {
+                let poke = args.next().unwrap_or_default();
+                let (addr, value) = poke
+                    .split_once('=')
+                    .and_then(|(a, v)| Some((a.parse().ok()?, v.parse().ok()?)))
+                    .ok_or_else(|| format!("invalid poke {:?}, expected <addr>=<value>", poke))?;
+                pokes.push((addr, value));
+            }
+            "--trace" => {
+                trace = Some(args.next().ok_or_else(|| USAGE.to_string())?);
+            }
+            _ if program.is_none() => program = Some(arg),
+            _ => return Err(format!("unexpected argument {:?}\n\n{}", arg, USAGE)),
+        }
+    }
+    let program = program.ok_or_else(|| USAGE.to_string())?;
+    Ok(Options {
+        program,
+        mode,
+        pokes,
+        trace,
+    })
+}
+
+fn read_line() -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+fn run_ascii(computer: &mut IntcodeComputer) -> io::Result<()> {
+    loop {
+        for output in computer.run_ascii() {
+            match output {
+                AsciiOutput::Text(text) => print!("{}", text),
+                AsciiOutput::Value(value) => println!("{}", value),
+            }
+        }
+        io::stdout().flush()?;
+        if computer.is_halted() {
+            return Ok(());
+        }
+        match read_line()? {
+            Some(line) => computer.push_ascii_line(&line),
+            None => return Ok(()),
+        }
+    }
+}
+
+fn run_numeric(computer: &mut IntcodeComputer) -> io::Result<()> {
+    loop {
+        match computer.run_until_io() {
+            IoEvent::Output(value) => println!("{}", value),
+            IoEvent::Halted => return Ok(()),
+            IoEvent::NeedsInput => {
+                print!("> ");
+                io::stdout().flush()?;
+                let line = match read_line()? {
+                    Some(line) => line,
+                    None => return Ok(()),
+                };
+                for value in line
+                    .split([',', ' '])
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                {
+                    match value.parse::<i64>() {
+                        Ok(value) => computer.push_input(value),
+                        Err(_) => eprintln!("ignoring invalid input {:?}", value),
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct Screen {
+    tiles: HashMap<(i64, i64), i64>,
+    score: Option<i64>,
+}
+
+impl Screen {
+    fn tile_char(tile: i64) -> char {
+        match tile {
+            0 => ' ',
+            1 => '█',
+            2 => '#',
+            3 => '=',
+            4 => 'o',
+            _ => '?',
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write, status: &str) -> io::Result<()> {
+        queue!(
+            out,
+            terminal::Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            Print(match self.score {
+                Some(score) => format!("Score: {}", score),
+                None => String::new(),
+            })
+        )?;
+        for (&(x, y), &tile) in self.tiles.iter() {
+            if x >= 0 && y >= 0 {
+                queue!(
+                    out,
+                    cursor::MoveTo(x as u16, y as u16 + 1),
+                    Print(Screen::tile_char(tile))
+                )?;
+            }
+        }
+        let max_y = self.tiles.keys().map(|(_, y)| *y).max().unwrap_or(0);
+        queue!(out, cursor::MoveTo(0, max_y as u16 + 3), Print(status))?;
+        out.flush()
+    }
+}
+
+fn next_key(timeout: Duration) -> io::Result<Option<KeyCode>> {
+    if event::poll(timeout)? {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            return Ok(Some(code));
+        }
+    }
+    Ok(None)
+}
+
+fn play_screen(computer: &mut IntcodeComputer, out: &mut impl Write) -> io::Result<()> {
+    let mut screen = Screen {
+        tiles: HashMap::new(),
+        score: None,
+    };
+    let mut pending = vec![];
+    loop {
+        match computer.run_until_io() {
+            IoEvent::Output(value) => {
+                pending.push(value);
+                if let [x, y, tile] = pending[..] {
+                    if x == -1 && y == 0 {
+                        screen.score = Some(tile);
+                    } else {
+                        screen.tiles.insert((x, y), tile);
+                    }
+                    pending.clear();
+                }
+            }
+            IoEvent::NeedsInput => {
+                screen.draw(out, "←/a left  →/d right  q quit")?;
+                let input = match next_key(Duration::from_millis(150))? {
+                    Some(KeyCode::Left | KeyCode::Char('a')) => -1,
+                    Some(KeyCode::Right | KeyCode::Char('d')) => 1,
+                    Some(KeyCode::Char('q') | KeyCode::Esc) => return Ok(()),
+                    _ => 0,
+                };
+                computer.push_input(input);
+            }
+            IoEvent::Halted => {
+                screen.draw(out, "Program halted, press any key to exit")?;
+                while next_key(Duration::from_secs(60))?.is_none() {}
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn run_screen(computer: &mut IntcodeComputer) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = play_screen(computer, &mut out);
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn write_trace(computer: &IntcodeComputer, path: &str) -> io::Result<()> {
+    let trace = computer.trace().unwrap();
+    let contents = if path.ends_with(".json") {
+        trace.to_json()
+    } else {
+        trace.to_csv()
+    };
+    fs::write(path, contents)?;
+    eprint!("{}", trace.report(computer.program_len()));
+    Ok(())
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    let source = match fs::read_to_string(&options.program) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Unable to read {}: {}", options.program, e);
+            process::exit(1);
+        }
+    };
+    let mut computer = IntcodeComputer::from_str(&source);
+    for (addr, value) in options.pokes {
+        computer.write(addr, value);
+    }
+    if options.trace.is_some() {
+        computer.enable_trace();
+    }
+    let result = match options.mode {
+        Mode::Ascii => run_ascii(&mut computer),
+        Mode::Numeric => run_numeric(&mut computer),
+        Mode::Screen => run_screen(&mut computer),
+    };
+    let result = result.and_then(|_| match &options.trace {
+        Some(path) => write_trace(&computer, path),
+        None => Ok(()),
+    });
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
+}
@@ -1,5 +1,10 @@
 use std::{cell::RefCell, collections::HashMap};
 
+pub mod ascii;
+pub mod trace;
+
+use trace::Trace;
+
 const DEBUG: bool = false;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -22,6 +27,13 @@ pub enum Instruction {
     Term,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IoEvent {
+    Output(i64),
+    NeedsInput,
+    Halted,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum InstructionEffect {
     NoEffect,
@@ -85,6 +97,7 @@ pub fn parse_instruction(instruction: i64) -> Instruction {
 
 pub fn parse_program(input: &str) -> Vec<i64> {
     input
+        .trim()
         .split(',')
         .map(|s| s.parse::<i64>().unwrap())
         .collect::<Vec<_>>()
@@ -114,9 +127,11 @@ pub struct IntcodeComputer {
     pos: usize,
     relative_base: i64,
     halted: bool,
+    trace: Option<RefCell<Trace>>,
 }
 
 impl IntcodeComputer {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(program: &str) -> Self {
         Self::create(parse_program(program), vec![])
     }
@@ -131,9 +146,11 @@ impl IntcodeComputer {
             pos: 0,
             relative_base: 0,
             halted: false,
+            trace: None,
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> IntcodeComputer {
         IntcodeComputer {
             program: RefCell::new(self.program.borrow().clone()),
@@ -144,6 +161,7 @@ impl IntcodeComputer {
             pos: self.pos,
             relative_base: self.relative_base,
             halted: self.halted,
+            trace: None,
         }
     }
 
@@ -176,8 +194,10 @@ impl IntcodeComputer {
 
     fn read_param(&self, offset: usize, mode: ParamMode) -> i64 {
         let addr = self.param_address(offset, mode);
-        let v = self.read(addr);
-        v
+        if let (Some(trace), Pos | Rel) = (&self.trace, mode) {
+            trace.borrow_mut().record_read(addr);
+        }
+        self.read(addr)
     }
 
     pub fn process_instruction(&self, instruction: Instruction) -> InstructionEffect {
@@ -185,19 +205,19 @@ impl IntcodeComputer {
             Add(a_mode, b_mode, dest_mode) => {
                 let a = self.read_param(1, a_mode);
                 let b = self.read_param(2, b_mode);
-                let dest = self.param_address(3, dest_mode) as usize;
+                let dest = self.param_address(3, dest_mode);
                 WriteMem(dest, a + b)
             }
             Mul(a_mode, b_mode, dest_mode) => {
                 let a = self.read_param(1, a_mode);
                 let b = self.read_param(2, b_mode);
-                let dest = self.param_address(3, dest_mode) as usize;
+                let dest = self.param_address(3, dest_mode);
                 WriteMem(dest, a * b)
             }
             Input(mode) => {
-                let address = self.param_address(1, mode) as usize;
+                let address = self.param_address(1, mode);
                 let mut inputs = self.inputs.borrow_mut();
-                let value = if inputs.len() == 0 {
+                let value = if inputs.is_empty() {
                     if let Some(fallback) = self.fallback_input {
                         fallback
                     } else {
@@ -232,13 +252,13 @@ impl IntcodeComputer {
                 let a = self.read_param(1, a_mode);
                 let b = self.read_param(2, b_mode);
                 let addr = self.param_address(3, dest_mode);
-                WriteMem(addr as usize, if a < b { 1 } else { 0 })
+                WriteMem(addr, if a < b { 1 } else { 0 })
             }
             Equals(a_mode, b_mode, dest_mode) => {
                 let a = self.read_param(1, a_mode);
                 let b = self.read_param(2, b_mode);
                 let addr = self.param_address(3, dest_mode);
-                WriteMem(addr as usize, if a == b { 1 } else { 0 })
+                WriteMem(addr, if a == b { 1 } else { 0 })
             }
             AdjustRelBase(mode) => {
                 let v = self.read_param(1, mode);
@@ -276,15 +296,30 @@ impl IntcodeComputer {
         if self.halted {
             panic!("program already halted");
         }
-        let instruction = parse_instruction(self.program.borrow()[self.pos]);
+        let instruction = parse_instruction(self.read(self.pos));
         if DEBUG {
             print!("[{}] {:?}", self.pos, instruction);
         }
+        let pc = self.pos;
+        let size = instruction_size(instruction);
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().record_instruction(pc..pc + size);
+        }
         let effect = self.process_instruction(instruction);
         if DEBUG {
             print!(" -> {:?}", effect);
         }
-        self.pos += instruction_size(instruction);
+        if let Some(trace) = &self.trace {
+            match effect {
+                WriteMem(address, value) => {
+                    let old = self.read(address);
+                    trace.borrow_mut().record_write(pc, address, old, value);
+                }
+                Jump(to) => trace.borrow_mut().record_jump(pc, to),
+                _ => {}
+            }
+        }
+        self.pos += size;
         self.handle_effect(effect);
         if DEBUG {
             println!();
@@ -292,19 +327,97 @@ impl IntcodeComputer {
         effect
     }
 
+    /// Starts recording which addresses get executed, read and written.
+    pub fn enable_trace(&mut self) {
+        self.trace = Some(RefCell::new(Trace::new()));
+    }
+
+    pub fn trace(&self) -> Option<std::cell::Ref<'_, Trace>> {
+        self.trace.as_ref().map(|trace| trace.borrow())
+    }
+
+    pub fn program_len(&self) -> usize {
+        self.program.borrow().len()
+    }
+
     pub fn push_input(&mut self, val: i64) {
         self.inputs.borrow_mut().push(val);
     }
 
+    pub fn set_fallback_input(&mut self, val: i64) {
+        self.fallback_input = Some(val)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// True if the next instruction reads input but there is neither a queued
+    /// input value nor a fallback to use.
+    pub fn needs_input(&self) -> bool {
+        !self.halted
+            && matches!(parse_instruction(self.read(self.pos)), Input(_))
+            && self.inputs.borrow().is_empty()
+            && self.fallback_input.is_none()
+    }
+
     pub fn run_until_next_output(&mut self) -> Option<i64> {
         while !self.halted {
-            match self.run_next_instruction() {
-                OutputValue(val) => {
-                    return Some(val);
-                }
-                _ => {}
+            if let OutputValue(val) = self.run_next_instruction() {
+                return Some(val);
             }
         }
         None
     }
+
+    /// Runs until the program produces output, halts or blocks on input,
+    /// instead of panicking when the input queue runs dry.
+    pub fn run_until_io(&mut self) -> IoEvent {
+        while !self.halted {
+            if self.needs_input() {
+                return IoEvent::NeedsInput;
+            }
+            if let OutputValue(val) = self.run_next_instruction() {
+                return IoEvent::Output(val);
+            }
+        }
+        IoEvent::Halted
+    }
+
+    pub fn run(&mut self) -> Vec<i64> {
+        while !self.halted {
+            self.run_next_instruction();
+        }
+        if DEBUG {
+            println!("Terminated.");
+        }
+        self.outputs.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let quine = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+        assert_eq!(IntcodeComputer::from_str(quine).run(), parse_program(quine));
+        assert_eq!(
+            IntcodeComputer::from_str("104,1125899906842624,99").run(),
+            vec![1125899906842624]
+        );
+    }
+
+    #[test]
+    fn test_run_until_io() {
+        let mut computer = IntcodeComputer::from_str("3,9,4,9,3,9,4,9,99,0");
+        assert_eq!(computer.run_until_io(), IoEvent::NeedsInput);
+        computer.push_input(7);
+        assert_eq!(computer.run_until_io(), IoEvent::Output(7));
+        assert_eq!(computer.run_until_io(), IoEvent::NeedsInput);
+        computer.set_fallback_input(3);
+        assert_eq!(computer.run_until_io(), IoEvent::Output(3));
+        assert_eq!(computer.run_until_io(), IoEvent::Halted);
+    }
 }
@@ -0,0 +1,254 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    ops::Range,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AddressStats {
+    /// Number of times an instruction started at this address.
+    pub executed: u64,
+    /// Number of times this address was fetched as part of an executed
+    /// instruction, either as opcode or as parameter.
+    pub fetched: u64,
+    pub reads: u64,
+    pub writes: u64,
+}
+
+impl AddressStats {
+    pub fn is_code(&self) -> bool {
+        self.fetched > 0
+    }
+}
+
+/// A write into an address that had already been executed as part of an
+/// instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfModification {
+    pub step: u64,
+    pub pc: usize,
+    pub address: usize,
+    pub old: i64,
+    pub new: i64,
+}
+
+/// A backwards jump that was taken `count` times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotLoop {
+    pub from: usize,
+    pub to: usize,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coverage {
+    pub executed: usize,
+    pub total: usize,
+}
+
+impl Coverage {
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            100.0 * self.executed as f64 / self.total as f64
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Trace {
+    pub steps: u64,
+    pub addresses: BTreeMap<usize, AddressStats>,
+    pub self_modifications: Vec<SelfModification>,
+    jumps: HashMap<(usize, usize), u64>,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn stats(&mut self, address: usize) -> &mut AddressStats {
+        self.addresses.entry(address).or_default()
+    }
+
+    pub fn record_instruction(&mut self, span: Range<usize>) {
+        self.steps += 1;
+        self.stats(span.start).executed += 1;
+        for address in span {
+            self.stats(address).fetched += 1;
+        }
+    }
+
+    pub fn record_read(&mut self, address: usize) {
+        self.stats(address).reads += 1;
+    }
+
+    pub fn record_write(&mut self, pc: usize, address: usize, old: i64, new: i64) {
+        let stats = self.stats(address);
+        stats.writes += 1;
+        if stats.is_code() {
+            let step = self.steps;
+            self.self_modifications.push(SelfModification {
+                step,
+                pc,
+                address,
+                old,
+                new,
+            });
+        }
+    }
+
+    pub fn record_jump(&mut self, from: usize, to: usize) {
+        if to <= from {
+            *self.jumps.entry((from, to)).or_insert(0) += 1;
+        }
+    }
+
+    /// Share of the first `program_len` addresses that were executed as code.
+    pub fn coverage(&self, program_len: usize) -> Coverage {
+        Coverage {
+            executed: self
+                .addresses
+                .range(0..program_len)
+                .filter(|(_, stats)| stats.is_code())
+                .count(),
+            total: program_len,
+        }
+    }
+
+    /// Backwards jumps ordered by how often they were taken.
+    pub fn hot_loops(&self) -> Vec<HotLoop> {
+        let mut loops = self
+            .jumps
+            .iter()
+            .map(|(&(from, to), &count)| HotLoop { from, to, count })
+            .collect::<Vec<_>>();
+        loops.sort_by_key(|l| (std::cmp::Reverse(l.count), l.from, l.to));
+        loops
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("address,executed,fetched,reads,writes\n");
+        for (address, stats) in self.addresses.iter() {
+            writeln!(
+                csv,
+                "{},{},{},{},{}",
+                address, stats.executed, stats.fetched, stats.reads, stats.writes
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let addresses = self
+            .addresses
+            .iter()
+            .map(|(address, stats)| {
+                format!(
+                    r#"{{"address":{},"executed":{},"fetched":{},"reads":{},"writes":{}}}"#,
+                    address, stats.executed, stats.fetched, stats.reads, stats.writes
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let self_modifications = self
+            .self_modifications
+            .iter()
+            .map(|m| {
+                format!(
+                    r#"{{"step":{},"pc":{},"address":{},"old":{},"new":{}}}"#,
+                    m.step, m.pc, m.address, m.old, m.new
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let hot_loops = self
+            .hot_loops()
+            .iter()
+            .map(|l| format!(r#"{{"from":{},"to":{},"count":{}}}"#, l.from, l.to, l.count))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            r#"{{"steps":{},"addresses":[{}],"self_modifications":[{}],"hot_loops":[{}]}}"#,
+            self.steps, addresses, self_modifications, hot_loops
+        )
+    }
+
+    pub fn report(&self, program_len: usize) -> String {
+        let coverage = self.coverage(program_len);
+        let mut report = String::new();
+        writeln!(report, "Steps executed: {}", self.steps).unwrap();
+        writeln!(
+            report,
+            "Coverage: {}/{} addresses ({:.1}%)",
+            coverage.executed,
+            coverage.total,
+            coverage.percent()
+        )
+        .unwrap();
+        writeln!(
+            report,
+            "Self-modifying writes: {}",
+            self.self_modifications.len()
+        )
+        .unwrap();
+        for m in self.self_modifications.iter().take(10) {
+            writeln!(
+                report,
+                "  step {}: [{}] wrote {} -> {} at {}",
+                m.step, m.pc, m.old, m.new, m.address
+            )
+            .unwrap();
+        }
+        writeln!(report, "Hot loops:").unwrap();
+        for l in self.hot_loops().iter().take(10) {
+            writeln!(report, "  {} -> {}: {} times", l.from, l.to, l.count).unwrap();
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IntcodeComputer;
+
+    #[test]
+    fn test_trace_counter_loop() {
+        // counts address 13 down from 3 to 0, then outputs it
+        let mut computer = IntcodeComputer::from_str("1001,13,-1,13,1005,13,0,4,13,99,0,0,0,3");
+        computer.enable_trace();
+        assert_eq!(computer.run(), vec![0]);
+        let trace = computer.trace().unwrap();
+        assert_eq!(trace.steps, 3 * 2 + 2);
+        assert_eq!(trace.addresses[&0].executed, 3);
+        assert_eq!(trace.addresses[&13].writes, 3);
+        assert_eq!(trace.addresses[&13].reads, 7);
+        assert_eq!(trace.coverage(14).executed, 10);
+        let loops = trace.hot_loops();
+        assert_eq!(loops.len(), 1);
+        assert_eq!((loops[0].from, loops[0].to, loops[0].count), (4, 0, 2));
+        assert!(trace.self_modifications.is_empty());
+    }
+
+    #[test]
+    fn test_trace_self_modification() {
+        // patches the immediate operand of the output instruction before running it
+        let mut computer = IntcodeComputer::from_str("104,1,1101,5,0,1,1105,1,0");
+        computer.enable_trace();
+        assert_eq!(computer.run_until_next_output(), Some(1));
+        assert_eq!(computer.run_until_next_output(), Some(5));
+        let trace = computer.trace().unwrap();
+        assert_eq!(trace.self_modifications.len(), 1);
+        let m = trace.self_modifications[0];
+        assert_eq!((m.pc, m.address, m.old, m.new), (2, 1, 1, 5));
+        assert!(trace
+            .to_csv()
+            .starts_with("address,executed,fetched,reads,writes\n0,2,2,0,0\n"));
+        assert!(trace
+            .to_json()
+            .contains(r#""self_modifications":[{"step":2,"pc":2,"address":1,"old":1,"new":5}]"#));
+    }
+}
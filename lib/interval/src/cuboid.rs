@@ -0,0 +1,132 @@
+use crate::{Interval, Num};
+
+/// An axis-aligned box of integer points in `N` dimensions, one
+/// [`Interval`] per axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<T, const N: usize>(pub [Interval<T>; N]);
+
+impl<T: Num, const N: usize> Cuboid<T, N> {
+    pub fn new(axes: [Interval<T>; N]) -> Self {
+        Self(axes)
+    }
+
+    /// The points from `min` to `max`, both included.
+    pub fn inclusive(min: [T; N], max: [T; N]) -> Self {
+        Self(std::array::from_fn(|i| Interval::inclusive(min[i], max[i])))
+    }
+
+    pub fn axis(&self, axis: usize) -> Interval<T> {
+        self.0[axis]
+    }
+
+    /// Number of points in the box.
+    pub fn volume(&self) -> u64 {
+        self.0.iter().map(Interval::len).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().any(Interval::is_empty)
+    }
+
+    pub fn contains(&self, point: [T; N]) -> bool {
+        self.0.iter().zip(point).all(|(iv, p)| iv.contains(p))
+    }
+
+    pub fn contains_cuboid(&self, other: &Self) -> bool {
+        other.is_empty()
+            || self
+                .0
+                .iter()
+                .zip(&other.0)
+                .all(|(a, b)| a.contains_interval(b))
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The points in both boxes, or `None` if there are none.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut axes = self.0;
+        for (axis, other) in axes.iter_mut().zip(&other.0) {
+            *axis = axis.intersection(other)?;
+        }
+        Some(Self(axes))
+    }
+
+    /// The points with a coordinate below `at` on `axis` and the ones from
+    /// `at` on, each `None` if empty.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let (below, above) = self.0[axis].split_at(at);
+        let with_axis = |iv: Interval<T>| {
+            let mut axes = self.0;
+            axes[axis] = iv;
+            Self(axes)
+        };
+        (below.map(with_axis), above.map(with_axis))
+    }
+
+    /// The points of this box that are not in `other`, as at most `2 * N`
+    /// disjoint boxes.
+    ///
+    /// Each axis in turn cuts off the slabs of the box below and above
+    /// `other`, and narrows what is left to the overlap.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if !self.intersects(other) {
+            return vec![*self];
+        }
+        let mut pieces = vec![];
+        let mut rest = *self;
+        for axis in 0..N {
+            let (below, middle) = rest.split_at(axis, other.0[axis].start);
+            let (middle, above) = middle.unwrap().split_at(axis, other.0[axis].end);
+            pieces.extend(below);
+            pieces.extend(above);
+            rest = middle.unwrap();
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cuboid() {
+        let a = Cuboid::inclusive([0, 0, 0], [9, 9, 9]);
+        let b = Cuboid::inclusive([5, -5, 2], [14, 4, 3]);
+        assert_eq!(a.volume(), 1000);
+        assert!(a.contains([9, 0, 5]) && !a.contains([10, 0, 5]));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Cuboid::inclusive([5, 0, 2], [9, 4, 3]))
+        );
+        assert!(a.contains_cuboid(&a.intersection(&b).unwrap()));
+        assert!(!a.intersects(&Cuboid::inclusive([10, 0, 0], [12, 2, 2])));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let a = Cuboid::inclusive([0, 0, 0], [9, 9, 9]);
+        let b = Cuboid::inclusive([5, -5, 2], [14, 4, 3]);
+        let pieces = a.subtract(&b);
+        assert!(pieces.len() <= 6);
+        assert_eq!(pieces.iter().map(Cuboid::volume).sum::<u64>(), 1000 - 50);
+        for (i, p) in pieces.iter().enumerate() {
+            assert!(a.contains_cuboid(p) && !p.intersects(&b));
+            assert!(pieces[i + 1..].iter().all(|q| !p.intersects(q)));
+        }
+        assert!(a.subtract(&a).is_empty());
+        assert_eq!(b.subtract(&Cuboid::inclusive([20; 3], [30; 3])), [b]);
+    }
+
+    #[test]
+    fn test_split_at() {
+        let a = Cuboid::new([Interval::new(1u32, 4001); 4]);
+        let (low, high) = a.split_at(2, 1000);
+        assert_eq!(low.unwrap().volume() + high.unwrap().volume(), a.volume());
+        assert_eq!(low.unwrap().axis(2), Interval::new(1, 1000));
+        assert_eq!(a.split_at(0, 1), (None, Some(a)));
+    }
+}
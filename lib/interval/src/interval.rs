@@ -0,0 +1,146 @@
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    ops::{Add, Range, Sub},
+};
+
+/// The integers an [`Interval`] can be made of.
+pub trait Num: Copy + Ord + Hash + Debug + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_i128(self) -> i128;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    };
+}
+
+impl_num!(i32, i64, isize, u32, u64, usize);
+
+/// The integers from `start` up to but not including `end`.
+///
+/// Puzzles tend to give inclusive ranges, which [`Interval::inclusive`]
+/// turns into this form so that lengths and splits need no `+ 1`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Num> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// The integers from `first` to `last`, both included.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Self::new(first, last + T::ONE)
+    }
+
+    /// The `len` integers from `start` on.
+    pub fn starting_at(start: T, len: T) -> Self {
+        Self::new(start, start + len)
+    }
+
+    /// The largest integer in the interval.
+    pub fn last(&self) -> T {
+        self.end - T::ONE
+    }
+
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            (self.end.to_i128() - self.start.to_i128()) as u64
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every integer of `other` is in this interval.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The integers in both intervals, or `None` if there are none.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let overlap = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!overlap.is_empty()).then_some(overlap)
+    }
+
+    /// The integers below `at` and the ones from `at` on, each `None` if
+    /// empty.
+    pub fn split_at(&self, at: T) -> (Option<Self>, Option<Self>) {
+        let at = at.clamp(self.start, self.end.max(self.start));
+        let below = Self::new(self.start, at);
+        let above = Self::new(at, self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// The interval moved by `offset`.
+    pub fn shift(&self, offset: T) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl<T: Num> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::inclusive(2, 8);
+        let b = Interval::from(6..12);
+        assert_eq!(a.len(), 7);
+        assert_eq!(a.last(), 8);
+        assert!(a.contains(8) && !a.contains(9));
+        assert_eq!(a.intersection(&b), Some(Interval::new(6, 9)));
+        assert_eq!(a.intersection(&Interval::new(9, 10)), None);
+        assert!(a.contains_interval(&Interval::new(3, 5)));
+        assert!(!a.contains_interval(&b));
+        assert_eq!(Interval::new(5, 3).len(), 0);
+        assert_eq!(b.shift(-6), Interval::new(0, 6));
+    }
+
+    #[test]
+    fn test_split_at() {
+        let a = Interval::new(0u32, 10);
+        assert_eq!(
+            a.split_at(4),
+            (Some(Interval::new(0, 4)), Some(Interval::new(4, 10)))
+        );
+        assert_eq!(a.split_at(0), (None, Some(a)));
+        assert_eq!(a.split_at(20), (Some(a), None));
+    }
+}
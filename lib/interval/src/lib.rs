@@ -0,0 +1,33 @@
+//! Arithmetic on ranges of integers, for puzzles whose ranges are far too
+//! big to look at one number at a time.
+//!
+//! An [`Interval`] is a half-open range, an [`IntervalSet`] a union of them
+//! with set operations, and a [`RangeMap`] moves parts of a set by offsets:
+//!
+//! ```
+//! use interval::{Interval, IntervalSet, RangeMap};
+//!
+//! let covered: IntervalSet<i64> = [Interval::inclusive(-2, 2), Interval::inclusive(2, 14)]
+//!     .into_iter()
+//!     .collect();
+//! assert_eq!(covered.intervals(), [Interval::new(-2, 15)]);
+//! let gaps = IntervalSet::from(Interval::new(0, 20)).difference(&covered);
+//! assert_eq!(gaps.intervals(), [Interval::new(15, 20)]);
+//!
+//! let map: RangeMap<i64> = [(Interval::new(10, 20), 100)].into_iter().collect();
+//! assert_eq!(map.apply_set(&covered).len(), covered.len());
+//! assert_eq!(map.apply_set(&covered).max(), Some(114));
+//! ```
+//!
+//! A [`Cuboid`] does the same for boxes in `N` dimensions, where cutting one
+//! box out of another leaves a handful of disjoint boxes.
+
+mod cuboid;
+mod interval;
+mod map;
+mod set;
+
+pub use cuboid::Cuboid;
+pub use interval::{Interval, Num};
+pub use map::RangeMap;
+pub use set::IntervalSet;
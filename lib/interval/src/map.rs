@@ -0,0 +1,143 @@
+use crate::{Interval, IntervalSet, Num};
+
+/// Moves the integers in some source intervals by an offset each and leaves
+/// everything else where it is, like the almanac maps of 2023 day 5.
+///
+/// Every source is kept with the integer its start moves to, so unsigned
+/// maps can move integers downwards as long as nothing goes below zero.
+///
+/// Whole [`IntervalSet`]s can be sent through the map with
+/// [`RangeMap::apply_set`], which splits their intervals at the edges of the
+/// sources instead of looking at every integer.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    /// Sources in order, with where their start goes.
+    ranges: Vec<(Interval<T>, T)>,
+}
+
+impl<T: Num> RangeMap<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// Moves the integers in `source` by `offset`. Sources must not overlap.
+    pub fn insert(&mut self, source: Interval<T>, offset: T) {
+        self.insert_to(source, source.start + offset);
+    }
+
+    /// Moves the integers in `source` to the ones from `dest` on. Sources
+    /// must not overlap.
+    pub fn insert_to(&mut self, source: Interval<T>, dest: T) {
+        let i = self
+            .ranges
+            .partition_point(|(iv, _)| iv.start < source.start);
+        assert!(
+            self.ranges[..i]
+                .last()
+                .is_none_or(|(iv, _)| iv.end <= source.start)
+                && self
+                    .ranges
+                    .get(i)
+                    .is_none_or(|(iv, _)| source.end <= iv.start),
+            "{:?} overlaps another source",
+            source
+        );
+        self.ranges.insert(i, (source, dest));
+    }
+
+    /// Where `value` ends up.
+    pub fn apply(&self, value: T) -> T {
+        let i = self.ranges.partition_point(|(iv, _)| iv.end <= value);
+        match self.ranges.get(i) {
+            Some(&(iv, dest)) if iv.contains(value) => value - iv.start + dest,
+            _ => value,
+        }
+    }
+
+    /// Where all the integers in `set` end up.
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut moved = vec![];
+        let mut rest = set.clone();
+        for &(source, dest) in &self.ranges {
+            let move_to = |iv: Interval<T>| {
+                Interval::new(iv.start - source.start + dest, iv.end - source.start + dest)
+            };
+            let source = IntervalSet::from(source);
+            moved.extend(set.intersection(&source).iter().map(move_to));
+            rest = rest.difference(&source);
+        }
+        moved.extend(rest.iter());
+        moved.into_iter().collect()
+    }
+}
+
+impl<T: Num> FromIterator<(Interval<T>, T)> for RangeMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Interval<T>, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (source, offset) in iter {
+            map.insert(source, offset);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_map() {
+        // The seed-to-soil map of the 2023 day 5 example.
+        let mut map = RangeMap::new();
+        map.insert_to(Interval::starting_at(98, 2), 50);
+        map.insert_to(Interval::starting_at(50, 48), 52);
+        assert_eq!(
+            [79, 14, 55, 13, 98, 99].map(|v| map.apply(v)),
+            [81, 14, 57, 13, 50, 51]
+        );
+
+        let seeds: IntervalSet<i64> = [Interval::starting_at(79, 14), Interval::new(95, 102)]
+            .into_iter()
+            .collect();
+        let soil = map.apply_set(&seeds);
+        assert_eq!(soil.len(), seeds.len());
+        assert_eq!(
+            soil.intervals(),
+            [
+                Interval::new(50, 52),
+                Interval::new(81, 95),
+                Interval::new(97, 102)
+            ]
+            .into_iter()
+            .collect::<IntervalSet<_>>()
+            .intervals()
+        );
+    }
+
+    #[test]
+    fn test_unsigned_map_down() {
+        let mut map: RangeMap<u64> = RangeMap::new();
+        map.insert_to(Interval::starting_at(98, 2), 50);
+        map.insert(Interval::new(10, 20), 5);
+        assert_eq!(
+            [98, 99, 100, 12, 0].map(|v| map.apply(v)),
+            [50, 51, 100, 17, 0]
+        );
+        let moved = map.apply_set(&IntervalSet::from(Interval::new(95, 100)));
+        assert_eq!(
+            moved.intervals(),
+            [Interval::new(50, 52), Interval::new(95, 98)]
+                .into_iter()
+                .collect::<IntervalSet<_>>()
+                .intervals()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overlapping_sources() {
+        let _: RangeMap<i32> = [(Interval::new(0, 5), 1), (Interval::new(4, 8), 2)]
+            .into_iter()
+            .collect();
+    }
+}
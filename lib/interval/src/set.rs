@@ -0,0 +1,174 @@
+use crate::{Interval, Num};
+
+/// A set of integers stored as sorted, disjoint and non-adjacent
+/// [`Interval`]s, so huge ranges cost as much as small ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Num> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    /// Sorts the intervals and merges the ones that overlap or touch.
+    fn normalise(mut intervals: Vec<Interval<T>>) -> Self {
+        intervals.retain(|iv| !iv.is_empty());
+        intervals.sort_unstable();
+        let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+        for iv in intervals {
+            match merged.last_mut() {
+                Some(last) if iv.start <= last.end => last.end = last.end.max(iv.end),
+                _ => merged.push(iv),
+            }
+        }
+        Self { intervals: merged }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = Self::normalise(intervals);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        *self = self.difference(&interval.into());
+    }
+
+    /// The disjoint intervals of the set, smallest first.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals.iter().copied()
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> u64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|iv| iv.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(Interval::last)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|iv| iv.end <= value);
+        self.intervals.get(i).is_some_and(|iv| iv.contains(value))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalise(self.iter().chain(other.iter()).collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut result = vec![];
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            result.extend(a.intersection(&b));
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals: result }
+    }
+
+    /// The integers in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = vec![];
+        let mut j = 0;
+        for &iv in &self.intervals {
+            let mut rest = Some(iv);
+            while j < other.intervals.len() && other.intervals[j].end <= iv.start {
+                j += 1;
+            }
+            for cut in &other.intervals[j..] {
+                let Some(current) = rest else { break };
+                if cut.start >= current.end {
+                    break;
+                }
+                let (below, _) = current.split_at(cut.start);
+                let (_, above) = current.split_at(cut.end);
+                result.extend(below);
+                rest = above;
+            }
+            result.extend(rest);
+        }
+        Self { intervals: result }
+    }
+
+    /// The integers below `at` and the ones from `at` on.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let (mut below, mut above) = (vec![], vec![]);
+        for iv in self.iter() {
+            let (b, a) = iv.split_at(at);
+            below.extend(b);
+            above.extend(a);
+        }
+        (Self { intervals: below }, Self { intervals: above })
+    }
+}
+
+impl<T: Num> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        Self::normalise(vec![interval])
+    }
+}
+
+impl<T: Num> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        Self::normalise(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> IntervalSet<i32> {
+        ranges.iter().map(|&(a, b)| Interval::new(a, b)).collect()
+    }
+
+    #[test]
+    fn test_merging() {
+        let s = set(&[(5, 8), (0, 2), (2, 3), (7, 10), (20, 20)]);
+        assert_eq!(s.intervals(), set(&[(0, 3), (5, 10)]).intervals());
+        assert_eq!(s.len(), 8);
+        assert!(s.contains(0) && s.contains(9) && !s.contains(3) && !s.contains(10));
+        assert_eq!((s.min(), s.max()), (Some(0), Some(9)));
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 29)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 29)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28), (29, 30)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20)]));
+        let mut c = a.clone();
+        c.remove(Interval::new(2, 22));
+        c.insert(Interval::new(-5, 1));
+        assert_eq!(c, set(&[(-5, 2), (22, 30)]));
+    }
+
+    #[test]
+    fn test_split_at() {
+        let (below, above) = set(&[(0, 10), (20, 30)]).split_at(25);
+        assert_eq!(below, set(&[(0, 10), (20, 25)]));
+        assert_eq!(above, set(&[(25, 30)]));
+    }
+}
@@ -0,0 +1,33 @@
+//! Exact linear algebra over arbitrary-precision integers and rationals,
+//! for puzzles whose systems of equations overflow 64 bits or lose
+//! precision in floating point.
+//!
+//! A [`Matrix`] holds [`BigRational`]s and knows its reduced row echelon
+//! form, rank and determinant. [`solve`] does Gauss-Jordan elimination on it,
+//! while [`solve_integer`] stays in the integers with fraction-free
+//! elimination and fails if the solution is not integral:
+//!
+//! ```
+//! use linalg::{solve, solve_integer, BigInt, BigRational, Matrix};
+//!
+//! let a = Matrix::from_rows(&[vec![1, 1], vec![1, -1]]);
+//! let b = [3, 1].map(|n| BigRational::from_integer(n.into()));
+//! assert_eq!(solve(&a, &b).unwrap(), [2, 1].map(|n| BigRational::from_integer(n.into())));
+//!
+//! let rows = [vec![1, 1], vec![1, -1]].map(|row| row.into_iter().map(BigInt::from).collect());
+//! let b = [3, 1].map(BigInt::from);
+//! assert_eq!(solve_integer(&rows, &b).unwrap(), [2, 1].map(BigInt::from));
+//! ```
+//!
+//! [`intercept`] turns the nonlinear problem of a trajectory that hits a
+//! set of moving points into such a linear system.
+
+mod matrix;
+mod solve;
+mod trajectory;
+
+pub use matrix::Matrix;
+pub use num_bigint::BigInt;
+pub use num_rational::BigRational;
+pub use solve::{solve, solve_integer, SolveError};
+pub use trajectory::{intercept, Trajectory};
@@ -0,0 +1,205 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+
+/// A dense matrix of exact fractions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<BigRational>,
+}
+
+impl Matrix {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![BigRational::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Self::zero(n, n);
+        for i in 0..n {
+            m[(i, i)] = BigRational::one();
+        }
+        m
+    }
+
+    /// A matrix of integers given row by row.
+    pub fn from_rows<T: Into<BigInt> + Clone>(rows: &[Vec<T>]) -> Self {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "rows of different lengths"
+        );
+        Self {
+            rows: rows.len(),
+            cols,
+            data: rows
+                .iter()
+                .flatten()
+                .map(|n| BigRational::from_integer(n.clone().into()))
+                .collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, r: usize) -> &[BigRational] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// The matrix with `column` added on the right.
+    pub fn augment(&self, column: &[BigRational]) -> Self {
+        assert_eq!(column.len(), self.rows, "one value per row");
+        let mut m = Self::zero(self.rows, self.cols + 1);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                m[(r, c)] = self[(r, c)].clone();
+            }
+            m[(r, self.cols)] = column[r].clone();
+        }
+        m
+    }
+
+    pub fn mul_vec(&self, v: &[BigRational]) -> Vec<BigRational> {
+        assert_eq!(v.len(), self.cols, "one value per column");
+        (0..self.rows)
+            .map(|r| self.row(r).iter().zip(v).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        for c in 0..self.cols {
+            self.data.swap(a * self.cols + c, b * self.cols + c);
+        }
+    }
+
+    /// Brings the matrix into reduced row echelon form with Gauss-Jordan
+    /// elimination and returns the pivot column of each non-zero row.
+    pub fn rref(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        for c in 0..self.cols {
+            let r = pivots.len();
+            let Some(p) = (r..self.rows).find(|&p| !self[(p, c)].is_zero()) else {
+                continue;
+            };
+            self.swap_rows(r, p);
+            let scale = self[(r, c)].recip();
+            for x in &mut self.data[r * self.cols..(r + 1) * self.cols] {
+                *x *= &scale;
+            }
+            for other in 0..self.rows {
+                if other == r || self[(other, c)].is_zero() {
+                    continue;
+                }
+                let factor = self[(other, c)].clone();
+                for k in c..self.cols {
+                    let delta = &factor * &self[(r, k)];
+                    self[(other, k)] -= delta;
+                }
+            }
+            pivots.push(c);
+            if pivots.len() == self.rows {
+                break;
+            }
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().rref().len()
+    }
+
+    pub fn determinant(&self) -> BigRational {
+        assert_eq!(self.rows, self.cols, "only square matrices have one");
+        let mut m = self.clone();
+        let mut det = BigRational::one();
+        for c in 0..m.cols {
+            let Some(p) = (c..m.rows).find(|&p| !m[(p, c)].is_zero()) else {
+                return BigRational::zero();
+            };
+            if p != c {
+                m.swap_rows(c, p);
+                det = -det;
+            }
+            det *= &m[(c, c)];
+            for r in c + 1..m.rows {
+                let factor = &m[(r, c)] / &m[(c, c)];
+                for k in c..m.cols {
+                    let delta = &factor * &m[(c, k)];
+                    m[(r, k)] -= delta;
+                }
+            }
+        }
+        det
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = BigRational;
+
+    fn index(&self, (r, c): (usize, usize)) -> &BigRational {
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut BigRational {
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..self.rows {
+            let row: Vec<String> = self.row(r).iter().map(|x| x.to_string()).collect();
+            writeln!(f, "[{}]", row.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(n: i64, d: i64) -> BigRational {
+        BigRational::new(n.into(), d.into())
+    }
+
+    #[test]
+    fn test_rref() {
+        let mut m = Matrix::from_rows(&[vec![2, 4, 2], vec![1, 2, 3], vec![3, 6, 5]]);
+        assert_eq!(m.rank(), 2);
+        assert_eq!(m.rref(), [0, 2]);
+        assert_eq!(m.row(0), [q(1, 1), q(2, 1), q(0, 1)]);
+        assert_eq!(m.row(1), [q(0, 1), q(0, 1), q(1, 1)]);
+        assert!(m.row(2).iter().all(Zero::is_zero));
+    }
+
+    #[test]
+    fn test_determinant() {
+        let m = Matrix::from_rows(&[vec![0, 2, 1], vec![3, 1, 0], vec![1, 1, 1]]);
+        assert_eq!(m.determinant(), q(-4, 1));
+        assert_eq!(Matrix::identity(4).determinant(), q(1, 1));
+        assert_eq!(
+            m.mul_vec(&[q(1, 2), q(1, 1), q(0, 1)]),
+            [q(2, 1), q(5, 2), q(3, 2)]
+        );
+        assert_eq!(m.to_string().lines().next(), Some("[0 2 1]"));
+    }
+}
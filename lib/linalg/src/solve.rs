@@ -0,0 +1,163 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
+
+use crate::Matrix;
+
+/// Why a linear system has no single solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The equations contradict each other.
+    Inconsistent,
+    /// The equations leave some unknowns free; `rank` of them are fixed.
+    Underdetermined { rank: usize },
+    /// The only solution has fractions in it.
+    NotIntegral,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Inconsistent => write!(f, "the system has no solution"),
+            SolveError::Underdetermined { rank } => {
+                write!(f, "the system only fixes {} unknowns", rank)
+            }
+            SolveError::NotIntegral => write!(f, "the solution is not integral"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// The `x` with `a x = b`, exactly, by Gauss-Jordan elimination over the
+/// rationals.
+pub fn solve(a: &Matrix, b: &[BigRational]) -> Result<Vec<BigRational>, SolveError> {
+    let mut m = a.augment(b);
+    let pivots = m.rref();
+    if pivots.last() == Some(&a.cols()) {
+        return Err(SolveError::Inconsistent);
+    }
+    if pivots.len() < a.cols() {
+        return Err(SolveError::Underdetermined { rank: pivots.len() });
+    }
+    Ok((0..a.cols()).map(|r| m[(r, a.cols())].clone()).collect())
+}
+
+/// The integer `x` with `a x = b`, by fraction-free (Bareiss) elimination
+/// that never leaves the integers, and back substitution that checks every
+/// division is exact.
+pub fn solve_integer(a: &[Vec<BigInt>], b: &[BigInt]) -> Result<Vec<BigInt>, SolveError> {
+    let n = a.first().map_or(0, Vec::len);
+    assert_eq!(a.len(), b.len(), "one value per equation");
+    let mut m: Vec<Vec<BigInt>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, b)| row.iter().chain([b]).cloned().collect())
+        .collect();
+
+    // Forward elimination. After step k every entry below the diagonal is
+    // divisible by the previous pivot, so the divisions are exact.
+    let mut prev = BigInt::from(1);
+    let mut pivots = vec![];
+    for c in 0..n {
+        let r = pivots.len();
+        let Some(p) = (r..m.len()).find(|&p| !m[p][c].is_zero()) else {
+            continue;
+        };
+        m.swap(r, p);
+        for i in r + 1..m.len() {
+            for k in c + 1..=n {
+                let value = (&m[r][c] * &m[i][k] - &m[i][c] * &m[r][k]) / &prev;
+                m[i][k] = value;
+            }
+            m[i][c] = BigInt::zero();
+        }
+        prev = m[r][c].clone();
+        pivots.push(c);
+    }
+    if m[pivots.len()..].iter().any(|row| !row[n].is_zero()) {
+        return Err(SolveError::Inconsistent);
+    }
+    if pivots.len() < n {
+        return Err(SolveError::Underdetermined { rank: pivots.len() });
+    }
+
+    let mut x = vec![BigInt::zero(); n];
+    for r in (0..n).rev() {
+        let rest: BigInt = (r + 1..n).map(|k| &m[r][k] * &x[k]).sum();
+        let num = &m[r][n] - rest;
+        if !(&num % &m[r][r]).is_zero() {
+            return Err(SolveError::NotIntegral);
+        }
+        x[r] = num / &m[r][r];
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(ns: &[i64]) -> Vec<BigInt> {
+        ns.iter().map(|&n| n.into()).collect()
+    }
+
+    fn rationals(ns: &[i64]) -> Vec<BigRational> {
+        ns.iter()
+            .map(|&n| BigRational::from_integer(n.into()))
+            .collect()
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = Matrix::from_rows(&[vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]]);
+        let x = solve(&a, &rationals(&[8, -11, -3])).unwrap();
+        assert_eq!(x, rationals(&[2, 3, -1]));
+        assert_eq!(a.mul_vec(&x), rationals(&[8, -11, -3]));
+
+        let half = solve(&Matrix::from_rows(&[vec![2]]), &rationals(&[1])).unwrap();
+        assert_eq!(half, [BigRational::new(1.into(), 2.into())]);
+    }
+
+    #[test]
+    fn test_singular() {
+        let a = Matrix::from_rows(&[vec![1, 2], vec![2, 4]]);
+        assert_eq!(
+            solve(&a, &rationals(&[1, 3])),
+            Err(SolveError::Inconsistent)
+        );
+        assert_eq!(
+            solve(&a, &rationals(&[1, 2])),
+            Err(SolveError::Underdetermined { rank: 1 })
+        );
+        let rows = [ints(&[1, 2]), ints(&[2, 4])];
+        assert_eq!(
+            solve_integer(&rows, &ints(&[1, 3])),
+            Err(SolveError::Inconsistent)
+        );
+    }
+
+    #[test]
+    fn test_solve_integer() {
+        let rows = [ints(&[2, 1, -1]), ints(&[-3, -1, 2]), ints(&[-2, 1, 2])];
+        assert_eq!(
+            solve_integer(&rows, &ints(&[8, -11, -3])),
+            Ok(ints(&[2, 3, -1]))
+        );
+        let rows = [ints(&[0, 3]), ints(&[2, 0])];
+        assert_eq!(solve_integer(&rows, &ints(&[9, 4])), Ok(ints(&[2, 3])));
+        assert_eq!(
+            solve_integer(&rows, &ints(&[9, 3])),
+            Err(SolveError::NotIntegral)
+        );
+        // Big enough to overflow 64 bits along the way.
+        let big = 1_000_000_000_000_000i64;
+        let rows = [ints(&[big, big - 1]), ints(&[big + 1, big])];
+        assert_eq!(
+            solve_integer(&rows, &ints(&[1, 0])),
+            Ok(ints(&[big, -(big + 1)]))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use num_bigint::BigInt;
+
+use crate::{solve_integer, SolveError};
+
+/// A point that is at `position + t * velocity` at time `t`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Trajectory<T = i64> {
+    pub position: [T; 3],
+    pub velocity: [T; 3],
+}
+
+fn cross(a: [i64; 3], b: [i64; 3]) -> [i128; 3] {
+    let [a0, a1, a2] = a.map(i128::from);
+    let [b0, b1, b2] = b.map(i128::from);
+    [a1 * b2 - a2 * b1, a2 * b0 - a0 * b2, a0 * b1 - a1 * b0]
+}
+
+fn unit(k: usize) -> [i64; 3] {
+    let mut e = [0; 3];
+    e[k] = 1;
+    e
+}
+
+/// The integer trajectory that meets every one of `targets` at the same
+/// place at the same time, whatever those times are.
+///
+/// Meeting target `i` at time `t_i` means `P + t_i V = p_i + t_i v_i`, so
+/// `P - p_i` and `V - v_i` point the same way and their cross product is 0:
+///
+/// ```text
+/// P × V - P × v_i - p_i × V + p_i × v_i = 0
+/// ```
+///
+/// The only nonlinear term `P × V` is the same for every target, so
+/// subtracting the equations of two targets leaves three linear ones in the
+/// six unknowns of `P` and `V`. Two pairs out of three targets give a square
+/// system; if the first three targets happen to be degenerate the next ones
+/// are tried.
+pub fn intercept(targets: &[Trajectory]) -> Result<Trajectory<BigInt>, SolveError> {
+    let mut error = SolveError::Underdetermined { rank: 0 };
+    for three in targets.windows(3) {
+        let (rows, rhs) = pairwise_equations(&three[0], &three[1..]);
+        match solve_integer(&rows, &rhs) {
+            Ok(x) => {
+                let [p0, p1, p2, v0, v1, v2] = <[BigInt; 6]>::try_from(x).unwrap();
+                return Ok(Trajectory {
+                    position: [p0, p1, p2],
+                    velocity: [v0, v1, v2],
+                });
+            }
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+/// For `i = first` and each `j` in `others` the three linear equations
+///
+/// ```text
+/// P × (v_j - v_i) + (p_j - p_i) × V = p_j × v_j - p_i × v_i
+/// ```
+///
+/// with the unknowns ordered as `P` then `V`.
+fn pairwise_equations(
+    first: &Trajectory,
+    others: &[Trajectory],
+) -> (Vec<Vec<BigInt>>, Vec<BigInt>) {
+    let (mut rows, mut rhs) = (vec![], vec![]);
+    let (pi, vi) = (first.position, first.velocity);
+    for other in others {
+        let (pj, vj) = (other.position, other.velocity);
+        let dv = [0, 1, 2].map(|k| vj[k] - vi[k]);
+        let dp = [0, 1, 2].map(|k| pj[k] - pi[k]);
+        let (cj, ci) = (cross(pj, vj), cross(pi, vi));
+        for comp in 0..3 {
+            let p_coefs = (0..3).map(|k| cross(unit(k), dv)[comp]);
+            let v_coefs = (0..3).map(|k| cross(dp, unit(k))[comp]);
+            rows.push(p_coefs.chain(v_coefs).map(BigInt::from).collect());
+            rhs.push(BigInt::from(cj[comp] - ci[comp]));
+        }
+    }
+    (rows, rhs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intercept() {
+        // The hailstones of the 2023 day 24 example.
+        let hail = [
+            ([19, 13, 30], [-2, 1, -2]),
+            ([18, 19, 22], [-1, -1, -2]),
+            ([20, 25, 34], [-2, -2, -4]),
+            ([12, 31, 28], [-1, -2, -1]),
+            ([20, 19, 15], [1, -5, -3]),
+        ]
+        .map(|(position, velocity)| Trajectory { position, velocity });
+        let rock = intercept(&hail).unwrap();
+        assert_eq!(rock.position, [24, 13, 10].map(BigInt::from));
+        assert_eq!(rock.velocity, [-3, 1, 2].map(BigInt::from));
+        assert!(intercept(&hail[..2]).is_err());
+    }
+}
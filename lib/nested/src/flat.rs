@@ -0,0 +1,201 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut, Range},
+};
+
+use crate::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+    Open,
+    Close,
+    Int(i64),
+}
+
+/// Nested lists of integers written out as one run of tokens, as they are
+/// printed. Changes are splices into a single vector rather than rebuilt
+/// trees, and neighbouring integers are a scan away, which is what makes
+/// snailfish arithmetic quick.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Flat {
+    tokens: Vec<Token>,
+}
+
+impl Flat {
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// A list of the given values.
+    pub fn list(items: &[Flat]) -> Flat {
+        let mut tokens = vec![Token::Open];
+        for item in items {
+            tokens.extend_from_slice(&item.tokens);
+        }
+        tokens.push(Token::Close);
+        Flat { tokens }
+    }
+
+    /// The index of the nearest integer before `i`.
+    pub fn int_before(&self, i: usize) -> Option<usize> {
+        (0..i)
+            .rev()
+            .find(|&j| matches!(self.tokens[j], Token::Int(_)))
+    }
+
+    /// The index of the nearest integer after `i`.
+    pub fn int_after(&self, i: usize) -> Option<usize> {
+        (i + 1..self.len()).find(|&j| matches!(self.tokens[j], Token::Int(_)))
+    }
+
+    /// The index just past the value that starts at `i`.
+    pub fn end_of(&self, i: usize) -> usize {
+        let mut depth = 0;
+        for (j, token) in self.tokens.iter().enumerate().skip(i) {
+            match token {
+                Token::Open => depth += 1,
+                Token::Close => depth -= 1,
+                Token::Int(_) => {}
+            }
+            if depth == 0 {
+                return j + 1;
+            }
+        }
+        self.len()
+    }
+
+    /// Puts `with` in place of the tokens in `range`.
+    pub fn replace(&mut self, range: Range<usize>, with: &[Token]) {
+        self.tokens.splice(range, with.iter().copied());
+    }
+
+    /// Each token with how many lists are open around it, a list's own
+    /// brackets counting as inside it.
+    pub fn depths(&self) -> impl Iterator<Item = (usize, Token)> + '_ {
+        let mut depth = 0;
+        self.tokens.iter().map(move |&token| {
+            if token == Token::Open {
+                depth += 1;
+            }
+            let at = depth;
+            if token == Token::Close {
+                depth -= 1;
+            }
+            (at, token)
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut stack = vec![vec![]];
+        for &token in &self.tokens {
+            match token {
+                Token::Open => stack.push(vec![]),
+                Token::Close => {
+                    let items = stack.pop().unwrap();
+                    stack.last_mut().unwrap().push(Value::List(items));
+                }
+                Token::Int(n) => stack.last_mut().unwrap().push(Value::Int(n)),
+            }
+        }
+        stack.pop().unwrap().pop().unwrap_or(Value::Null)
+    }
+}
+
+impl Value {
+    /// The value as tokens, if it is made of nothing but lists and
+    /// integers.
+    pub fn flatten(&self) -> Option<Flat> {
+        let mut flat = Flat::default();
+        self.flatten_into(&mut flat.tokens)?;
+        Some(flat)
+    }
+
+    fn flatten_into(&self, tokens: &mut Vec<Token>) -> Option<()> {
+        match self {
+            &Value::Int(n) => tokens.push(Token::Int(n)),
+            Value::List(items) => {
+                tokens.push(Token::Open);
+                for item in items {
+                    item.flatten_into(tokens)?;
+                }
+                tokens.push(Token::Close);
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+impl Index<usize> for Flat {
+    type Output = Token;
+
+    fn index(&self, i: usize) -> &Token {
+        &self.tokens[i]
+    }
+}
+
+impl IndexMut<usize> for Flat {
+    fn index_mut(&mut self, i: usize) -> &mut Token {
+        &mut self.tokens[i]
+    }
+}
+
+impl fmt::Display for Flat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, token) in self.tokens.iter().enumerate() {
+            let after_value = i > 0 && self.tokens[i - 1] != Token::Open;
+            if after_value && *token != Token::Close {
+                write!(f, ",")?;
+            }
+            match token {
+                Token::Open => write!(f, "[")?,
+                Token::Close => write!(f, "]")?,
+                Token::Int(n) => write!(f, "{}", n)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat(s: &str) -> Flat {
+        s.parse::<Value>().unwrap().flatten().unwrap()
+    }
+
+    #[test]
+    fn test_flatten() {
+        let f = flat("[[1,[]],2]");
+        assert_eq!(f.len(), 8);
+        assert_eq!(f.to_string(), "[[1,[]],2]");
+        assert_eq!(f.to_value().to_string(), "[[1,[]],2]");
+        assert_eq!(f.end_of(1), 6);
+        assert_eq!(f.end_of(6), 7);
+        assert_eq!(f.int_before(6), Some(2));
+        assert_eq!(f.int_after(2), Some(6));
+        assert_eq!(f.int_after(6), None);
+        let depths: Vec<_> = f.depths().map(|(depth, _)| depth).collect();
+        assert_eq!(depths, [1, 2, 2, 3, 3, 2, 1, 1]);
+        assert_eq!(r#"[1,"a"]"#.parse::<Value>().unwrap().flatten(), None);
+        assert_eq!(flat("7").to_value(), Value::Int(7));
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut f = Flat::list(&[flat("[1,2]"), flat("3")]);
+        assert_eq!(f.to_string(), "[[1,2],3]");
+        f.replace(1..5, &[Token::Int(0)]);
+        f[2] = Token::Int(5);
+        assert_eq!(f.to_string(), "[0,5]");
+    }
+}
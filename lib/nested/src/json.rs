@@ -0,0 +1,203 @@
+use std::str::FromStr;
+
+use parse::ParseError;
+
+use crate::Value;
+
+/// A recursive descent parser over what is left of the input, which the
+/// errors point into.
+struct Parser<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::at(self.input, self.rest, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        for (word, value) in [
+            ("null", Value::Null),
+            ("true", Value::Bool(true)),
+            ("false", Value::Bool(false)),
+        ] {
+            if let Some(rest) = self.rest.strip_prefix(word) {
+                self.rest = rest;
+                return Ok(value);
+            }
+        }
+        match self.rest.chars().next() {
+            Some('[') => self.list(),
+            Some('{') => self.map(),
+            Some('"') => self.string().map(Value::Str),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.int(),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Value, ParseError> {
+        self.eat('[');
+        let mut items = vec![];
+        if self.eat(']') {
+            return Ok(Value::List(items));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.eat(']') {
+                return Ok(Value::List(items));
+            }
+            if !self.eat(',') {
+                return Err(self.error("expected , or ]"));
+            }
+        }
+    }
+
+    fn map(&mut self) -> Result<Value, ParseError> {
+        self.eat('{');
+        let mut entries = vec![];
+        if self.eat('}') {
+            return Ok(Value::Map(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            if !self.rest.starts_with('"') {
+                return Err(self.error("expected a key"));
+            }
+            let key = self.string()?;
+            if !self.eat(':') {
+                return Err(self.error("expected :"));
+            }
+            entries.push((key, self.value()?));
+            if self.eat('}') {
+                return Ok(Value::Map(entries));
+            }
+            if !self.eat(',') {
+                return Err(self.error("expected , or }"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.rest;
+        let mut s = String::new();
+        let mut chars = self.rest[1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 2..];
+                    return Ok(s);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| {
+                                    ParseError::at(
+                                        self.input,
+                                        &start[i + 1..],
+                                        "invalid \\u escape",
+                                    )
+                                })?
+                        }
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        _ => {
+                            let message = "invalid escape";
+                            return Err(ParseError::at(self.input, &start[i + 1..], message));
+                        }
+                    };
+                    s.push(escaped);
+                }
+                c => s.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn int(&mut self) -> Result<Value, ParseError> {
+        let sign = usize::from(self.rest.starts_with('-'));
+        let len = self.rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len() - sign);
+        let n = self.rest[..sign + len]
+            .parse()
+            .map_err(|_| self.error("expected an integer"))?;
+        self.rest = &self.rest[sign + len..];
+        Ok(Value::Int(n))
+    }
+}
+
+/// Parses JSON with integers for numbers, which covers the nested lists of
+/// the puzzles.
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser { input, rest: input };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if !parser.rest.is_empty() {
+            return Err(parser.error("expected the end"));
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let value: Value = r#" { "a" : [1, -2, []], "b\"é": null, "c": [true,false] } "#
+            .parse()
+            .unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{"a":[1,-2,[]],"b\"é":null,"c":[true,false]}"#
+        );
+        assert_eq!(
+            "[[1],[2,3,4]]".parse::<Value>().unwrap().to_string(),
+            "[[1],[2,3,4]]"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |input: &str| {
+            let err = input.parse::<Value>().unwrap_err();
+            (err.column, err.message)
+        };
+        assert_eq!(error("[1,2"), (5, "expected , or ]".to_string()));
+        assert_eq!(error("[1,]"), (4, "expected a value".to_string()));
+        assert_eq!(error("{1:2}"), (2, "expected a key".to_string()));
+        assert_eq!(error("\"ab"), (1, "unterminated string".to_string()));
+        assert_eq!(error("\"a\\qb\""), (3, "invalid escape".to_string()));
+        assert_eq!(error("[1] 2"), (5, "expected the end".to_string()));
+        assert_eq!(error("-"), (1, "expected an integer".to_string()));
+    }
+}
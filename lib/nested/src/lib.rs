@@ -0,0 +1,36 @@
+//! Nested lists and maps as the puzzles write them, which is JSON.
+//!
+//! - [`Value`] parses and prints compact JSON with integer numbers, with
+//!   serde support behind the `serde` feature,
+//! - [`Value::packet_cmp`] and `Ord` compare the way the packets of 2022
+//!   day 13 do, an integer against a list counting as a list of one,
+//! - a [`Path`] finds a value inside another, and
+//!   [`Value::leaf_before`] and [`Value::leaf_after`] the leaves either
+//!   side of it, as snailfish explosions need,
+//! - [`Flat`] keeps lists of integers as one run of tokens to edit in
+//!   place, for when the tree is rewritten over and over.
+//!
+//! ```
+//! use nested::{Token, Value};
+//!
+//! let value: Value = "[[1,[2,3]],4]".parse().unwrap();
+//! let pair = value.find(|path, v| path.len() == 2 && !v.is_leaf()).unwrap();
+//! assert_eq!(pair, [0, 1]);
+//! assert_eq!(value.leaf_before(&pair), Some(vec![0, 0]));
+//! assert_eq!(value.leaf_after(&pair), Some(vec![1]));
+//! assert!(value < "[[1,[2,3]],[5]]".parse().unwrap());
+//!
+//! let mut flat = value.flatten().unwrap();
+//! flat.replace(3..7, &[Token::Int(0)]);
+//! assert_eq!(flat.to_string(), "[[1,0],4]");
+//! ```
+
+mod flat;
+mod json;
+mod order;
+#[cfg(feature = "serde")]
+mod ser;
+mod value;
+
+pub use flat::{Flat, Token};
+pub use value::{Path, Value};
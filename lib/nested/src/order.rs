@@ -0,0 +1,136 @@
+use std::cmp::Ordering;
+
+use crate::Value;
+
+impl Value {
+    /// The order of the packets of 2022 day 13: integers by value, lists
+    /// item by item and then by length, and an integer against a list as
+    /// if it were a list of just that integer.
+    ///
+    /// Other kinds come before (null, booleans) or after (strings, maps)
+    /// integers and lists. This calls `1` and `[[1]]` equal, which
+    /// [`Ord`] breaks ties on.
+    pub fn packet_cmp(&self, other: &Value) -> Ordering {
+        use Value::*;
+        match (self, other) {
+            (Int(a), Int(b)) => a.cmp(b),
+            (List(a), List(b)) => Self::list_cmp(a, b),
+            (Int(_), List(b)) => Self::list_cmp(std::slice::from_ref(self), b),
+            (List(a), Int(_)) => Self::list_cmp(a, std::slice::from_ref(other)),
+            (Bool(a), Bool(b)) => a.cmp(b),
+            (Str(a), Str(b)) => a.cmp(b),
+            (Map(a), Map(b)) => {
+                for ((ka, va), (kb, vb)) in a.iter().zip(b) {
+                    let order = ka.cmp(kb).then_with(|| va.packet_cmp(vb));
+                    if order != Ordering::Equal {
+                        return order;
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    fn list_cmp(a: &[Value], b: &[Value]) -> Ordering {
+        for (x, y) in a.iter().zip(b) {
+            let order = x.packet_cmp(y);
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        a.len().cmp(&b.len())
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Int(_) | Value::List(_) => 2,
+            Value::Str(_) => 3,
+            Value::Map(_) => 4,
+        }
+    }
+
+    /// Tells apart values that [`Value::packet_cmp`] calls equal, by
+    /// putting an integer before a list where they first differ.
+    fn tie_cmp(&self, other: &Value) -> Ordering {
+        use Value::*;
+        match (self, other) {
+            (Int(_), List(_)) => Ordering::Less,
+            (List(_), Int(_)) => Ordering::Greater,
+            _ => {
+                let (a, b) = (self.children(), other.children());
+                for (x, y) in a.iter().zip(&b) {
+                    let order = x.tie_cmp(y);
+                    if order != Ordering::Equal {
+                        return order;
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+        }
+    }
+}
+
+/// [`Value::packet_cmp`], with the ties it leaves between an integer and
+/// a list of it broken so that the order agrees with `==`.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.packet_cmp(other).then_with(|| self.tie_cmp(other))
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(s: &str) -> Value {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_packet_order() {
+        // the pairs of the 2022 day 13 example, with whether they are in order
+        for (left, right, in_order) in [
+            ("[1,1,3,1,1]", "[1,1,5,1,1]", true),
+            ("[[1],[2,3,4]]", "[[1],4]", true),
+            ("[9]", "[[8,7,6]]", false),
+            ("[[4,4],4,4]", "[[4,4],4,4,4]", true),
+            ("[7,7,7,7]", "[7,7,7]", false),
+            ("[]", "[3]", true),
+            ("[[[]]]", "[[]]", false),
+            (
+                "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+                "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+                false,
+            ),
+        ] {
+            assert_eq!(value(left) < value(right), in_order, "{} {}", left, right);
+            assert_eq!(value(left).packet_cmp(&value(right)).is_lt(), in_order);
+        }
+    }
+
+    #[test]
+    fn test_ties() {
+        let (one, wrapped) = (value("1"), value("[[1]]"));
+        assert_eq!(one.packet_cmp(&wrapped), Ordering::Equal);
+        assert!(one < wrapped);
+        assert_eq!(value("[1,[2]]").cmp(&value("[[1],2]")), Ordering::Less);
+        // a later difference still wins over an earlier tie
+        assert!(value("[[1],2]") < value("[1,3]"));
+        assert!(value("null") < value("false") && value("[]") < value("\"a\""));
+        let mut sorted = [value("[[2]]"), value("2"), value("[2]"), value("[1,9]")];
+        sorted.sort();
+        assert_eq!(
+            sorted.iter().map(Value::to_string).collect::<Vec<_>>(),
+            ["[1,9]", "2", "[2]", "[[2]]"]
+        );
+    }
+}
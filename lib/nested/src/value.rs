@@ -0,0 +1,321 @@
+use std::fmt;
+
+/// Where a value sits inside another: the index of the child to take at
+/// each level, map entries counting in order.
+pub type Path = Vec<usize>;
+
+/// A JSON-like value: lists and maps of integers, strings, booleans and
+/// null. Map entries keep the order they were given in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    List(Vec<Value>),
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// A list of two, such as a snailfish number.
+    pub fn pair(a: Value, b: Value) -> Self {
+        Value::List(vec![a, b])
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Value::Int(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The entry called `key`, if this is a map that has one.
+    pub fn get_key(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Whether this is a single value rather than a list or map. Empty
+    /// lists and maps are not leaves, as they hold no value.
+    pub fn is_leaf(&self) -> bool {
+        !matches!(self, Value::List(_) | Value::Map(_))
+    }
+
+    pub fn children(&self) -> Vec<&Value> {
+        match self {
+            Value::List(items) => items.iter().collect(),
+            Value::Map(entries) => entries.iter().map(|(_, v)| v).collect(),
+            _ => vec![],
+        }
+    }
+
+    fn child(&self, i: usize) -> Option<&Value> {
+        match self {
+            Value::List(items) => items.get(i),
+            Value::Map(entries) => entries.get(i).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn child_mut(&mut self, i: usize) -> Option<&mut Value> {
+        match self {
+            Value::List(items) => items.get_mut(i),
+            Value::Map(entries) => entries.get_mut(i).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Value::List(items) => items.len(),
+            Value::Map(entries) => entries.len(),
+            _ => 0,
+        }
+    }
+
+    pub fn get(&self, path: &[usize]) -> Option<&Value> {
+        path.iter().try_fold(self, |value, &i| value.child(i))
+    }
+
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut Value> {
+        path.iter().try_fold(self, |value, &i| value.child_mut(i))
+    }
+
+    /// The path of the first value, in order from the outside in and left
+    /// to right, for which `pred` holds.
+    pub fn find(&self, mut pred: impl FnMut(&[usize], &Value) -> bool) -> Option<Path> {
+        let mut path = vec![];
+        self.find_from(&mut path, &mut pred).then_some(path)
+    }
+
+    fn find_from(&self, path: &mut Path, pred: &mut impl FnMut(&[usize], &Value) -> bool) -> bool {
+        if pred(path, self) {
+            return true;
+        }
+        for (i, child) in self.children().into_iter().enumerate() {
+            path.push(i);
+            if child.find_from(path, pred) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// The paths of all leaves from left to right.
+    pub fn leaves(&self) -> Vec<Path> {
+        let mut leaves = vec![];
+        self.find(|path, value| {
+            if value.is_leaf() {
+                leaves.push(path.to_vec());
+            }
+            false
+        });
+        leaves
+    }
+
+    /// The path of the nearest leaf left of the value at `path`, outside
+    /// it: where the left half of an exploding snailfish pair goes.
+    pub fn leaf_before(&self, path: &[usize]) -> Option<Path> {
+        self.neighbour(path, false)
+    }
+
+    /// The path of the nearest leaf right of the value at `path`.
+    pub fn leaf_after(&self, path: &[usize]) -> Option<Path> {
+        self.neighbour(path, true)
+    }
+
+    fn neighbour(&self, path: &[usize], right: bool) -> Option<Path> {
+        for level in (0..path.len()).rev() {
+            let parent = self.get(&path[..level])?;
+            let siblings: Box<dyn Iterator<Item = usize>> = if right {
+                Box::new(path[level] + 1..parent.len())
+            } else {
+                Box::new((0..path[level]).rev())
+            };
+            for i in siblings {
+                let mut found = path[..level].to_vec();
+                found.push(i);
+                if parent.child(i)?.edge_leaf(&mut found, !right) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Extends `path` to the first or last leaf inside this value, if it
+    /// has one.
+    fn edge_leaf(&self, path: &mut Path, first: bool) -> bool {
+        if self.is_leaf() {
+            return true;
+        }
+        let mut order: Box<dyn Iterator<Item = usize>> = if first {
+            Box::new(0..self.len())
+        } else {
+            Box::new((0..self.len()).rev())
+        };
+        order.any(|i| {
+            path.push(i);
+            let found = self
+                .child(i)
+                .is_some_and(|child| child.edge_leaf(path, first));
+            if !found {
+                path.pop();
+            }
+            found
+        })
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Int(n)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.to_string())
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(items: Vec<Value>) -> Self {
+        Value::List(items)
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Compact JSON, which is also how the puzzles write their lists.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Str(s) => write_str(f, s),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_str(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(s: &str) -> Value {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_paths() {
+        let v = value(r#"[1,[2,{"a":3,"b":[]}],[[4]]]"#);
+        assert_eq!(v.get(&[1, 1, 0]), Some(&Value::Int(3)));
+        assert_eq!(
+            v.get(&[1, 1]).unwrap().get_key("b"),
+            Some(&Value::List(vec![]))
+        );
+        assert_eq!(v.get(&[3]), None);
+        assert_eq!(
+            v.leaves(),
+            [vec![0], vec![1, 0], vec![1, 1, 0], vec![2, 0, 0]]
+        );
+        assert_eq!(v.find(|_, v| v.as_int() == Some(4)), Some(vec![2, 0, 0]));
+        let mut w = v.clone();
+        *w.get_mut(&[1, 1, 1]).unwrap() = Value::Null;
+        assert_eq!(w.to_string(), r#"[1,[2,{"a":3,"b":null}],[[4]]]"#);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let v = value("[[1,[]],[[2,3],[[],[4]]]]");
+        assert_eq!(v.leaf_before(&[1, 0]), Some(vec![0, 0]));
+        assert_eq!(v.leaf_before(&[1, 0, 0]), Some(vec![0, 0]));
+        assert_eq!(v.leaf_after(&[1, 0]), Some(vec![1, 1, 1, 0]));
+        assert_eq!(v.leaf_after(&[1, 1, 0]), Some(vec![1, 1, 1, 0]));
+        assert_eq!(v.leaf_before(&[0, 0]), None);
+        assert_eq!(v.leaf_after(&[1]), None);
+    }
+
+    #[test]
+    fn test_snailfish_explode() {
+        // the pair at depth 4 goes to the leaves either side of it
+        let mut v = value("[[6,[5,[4,[3,2]]]],1]");
+        let path = v.find(|path, v| path.len() == 4 && !v.is_leaf()).unwrap();
+        let pair = v.get(&path).unwrap().clone();
+        for (side, neighbour) in [(0, v.leaf_before(&path)), (1, v.leaf_after(&path))] {
+            if let Some(Value::Int(n)) = neighbour.and_then(|p| v.get_mut(&p)) {
+                *n += pair.children()[side].as_int().unwrap();
+            }
+        }
+        *v.get_mut(&path).unwrap() = Value::Int(0);
+        assert_eq!(v.to_string(), "[[6,[5,[7,0]]],3]");
+    }
+
+    #[test]
+    fn test_display() {
+        let v = Value::Map(vec![
+            ("a\"b".into(), Value::pair(1.into(), Value::Null)),
+            ("c".into(), Value::List(vec![true.into(), "x\n".into()])),
+        ]);
+        assert_eq!(v.to_string(), r#"{"a\"b":[1,null],"c":[true,"x\n"]}"#);
+        assert_eq!(value(&v.to_string()), v);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::egcd;
+
+/// Chinese remainder theorem: the smallest `x >= 0` with
+/// `x % modulus == residue` for every `(residue, modulus)` pair, together with
+/// the modulus of all solutions. The moduli do not need to be coprime, and
+/// there is no solution if they contradict each other.
+///
+/// Also `None` if a modulus is not positive or the modulus of all solutions
+/// does not fit an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut m) = (0i128, 1i128);
+    for &(residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (residue, modulus) = (residue as i128, modulus as i128);
+        let (g, p, _) = egcd(m, modulus);
+        let diff = residue - x;
+        if diff % g != 0 {
+            return None;
+        }
+        // x + m * k solves the new congruence for k = diff / g * p
+        let step = modulus / g;
+        let k = (diff / g % step * p % step).rem_euclid(step);
+        x += m * k;
+        // both fit an i64 before, so the product fits an i128
+        m = i64::try_from(m * step).ok()? as i128;
+        x = x.rem_euclid(m);
+    }
+    Some((x as i64, m as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 5), (0, 3)]), Some((9, 15)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(1, 0)]), None);
+        assert_eq!(crt(&[(1, 4), (2, -3)]), None);
+        let big = (1 << 40) + 15;
+        assert_eq!(crt(&[(1, big), (2, big + 2)]), None);
+        assert_eq!(crt(&[(1, big), (1, big)]), Some((1, big)));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+/// The primitive integers, for the functions that work on any of them.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+/// The signed primitive integers, which [`egcd`] needs for its
+/// coefficients.
+pub trait Signed: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+fn abs<T: Integer>(a: T) -> T {
+    if a < T::ZERO {
+        T::ZERO - a
+    } else {
+        a
+    }
+}
+
+/// The greatest common divisor, never negative.
+pub fn gcd<T: Integer>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    abs(a)
+}
+
+/// The least common multiple, never negative, and 0 if either is 0.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        T::ZERO
+    } else {
+        abs(a / gcd(a, b) * b)
+    }
+}
+
+/// The least common multiple of all the numbers, 1 if there are none.
+pub fn lcm_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ONE, lcm)
+}
+
+/// The greatest common divisor of all the numbers, 0 if there are none.
+pub fn gcd_all<T: Integer>(numbers: impl IntoIterator<Item = T>) -> T {
+    numbers.into_iter().fold(T::ZERO, gcd)
+}
+
+/// Extended Euclid: `(g, p, q)` with `g = gcd(a, b) = a * p + b * q` and
+/// `g >= 0`.
+pub fn egcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut p0, mut p1) = (T::ONE, T::ZERO);
+    let (mut q0, mut q1) = (T::ZERO, T::ONE);
+    while r1 != T::ZERO {
+        let k = r0 / r1;
+        (r0, r1) = (r1, r0 - k * r1);
+        (p0, p1) = (p1, p0 - k * p1);
+        (q0, q1) = (q1, q0 - k * q1);
+    }
+    if r0 < T::ZERO {
+        (-r0, -p0, -q0)
+    } else {
+        (r0, p0, q0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u8, 7), 7);
+        assert_eq!(lcm(4usize, 6), 12);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(lcm_all([18u64, 28, 44]), 2772);
+        assert_eq!(lcm_all(Vec::<u64>::new()), 1);
+        assert_eq!(gcd_all([12, 18, 27]), 3);
+    }
+
+    #[test]
+    fn test_egcd() {
+        for (a, b) in [
+            (240i64, 46),
+            (46, 240),
+            (-7, 3),
+            (7, -3),
+            (0, 5),
+            (5, 0),
+            (0, 0),
+        ] {
+            let (g, p, q) = egcd(a, b);
+            assert_eq!(g, gcd(a, b), "gcd of {} and {}", a, b);
+            assert_eq!(a * p + b * q, g, "coefficients for {} and {}", a, b);
+        }
+        assert_eq!(egcd(240i128, 46).0, 2);
+    }
+}
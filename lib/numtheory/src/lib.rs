@@ -0,0 +1,25 @@
+//! Number theory for the puzzles that are secretly about modular
+//! arithmetic.
+//!
+//! - [`gcd`], [`lcm`], [`lcm_all`] and [`gcd_all`] on any primitive integer,
+//!   and [`egcd`] for the Bézout coefficients on signed ones,
+//! - [`modpow`], [`mulmod`] and [`modinv`] for arithmetic modulo `m`,
+//! - [`crt`] for systems of congruences whose moduli need not be coprime,
+//! - [`discrete_log`] to undo a `modpow` with baby-step giant-step.
+//!
+//! ```
+//! use numtheory::{crt, discrete_log, lcm_all, modpow};
+//!
+//! assert_eq!(lcm_all([4u64, 6, 10]), 60);
+//! assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+//! let loop_size = discrete_log(7, 5764801, 20201227).unwrap();
+//! assert_eq!(modpow(17807724, loop_size, 20201227), 14897079);
+//! ```
+
+mod crt;
+mod gcd;
+mod modular;
+
+pub use crt::crt;
+pub use gcd::{egcd, gcd, gcd_all, lcm, lcm_all, Integer, Signed};
+pub use modular::{discrete_log, modinv, modpow, mulmod};
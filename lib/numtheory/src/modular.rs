@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::egcd;
+
+/// `a * b % m` without overflowing.
+pub fn mulmod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `base ^ exp % m` by repeated squaring.
+pub fn modpow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mulmod(result, base, m);
+        }
+        base = mulmod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..m` with `a * x % m == 1`, if `a` and `m` are coprime.
+pub fn modinv(a: i64, m: i64) -> Option<i64> {
+    let (g, p, _) = egcd((a as i128).rem_euclid(m as i128), m as i128);
+    (g == 1).then(|| p.rem_euclid(m as i128) as i64)
+}
+
+/// The smallest `x >= 0` with `base ^ x % m == target`, by baby-step
+/// giant-step in `O(sqrt(m))` time and memory.
+///
+/// `base` must be coprime to `m`, which it always is when `m` is prime.
+pub fn discrete_log(base: u64, target: u64, m: u64) -> Option<u64> {
+    let target = target % m;
+    let n = (m as f64).sqrt() as u64 + 1;
+
+    // Baby steps: base ^ j for j < n, keeping the smallest j per value.
+    let mut baby = HashMap::with_capacity(n as usize);
+    let mut value = 1 % m;
+    for j in 0..n {
+        baby.entry(value).or_insert(j);
+        value = mulmod(value, base, m);
+    }
+
+    // Giant steps: target * base ^ (-n i) for i < n.
+    let factor = modpow(modinv(base as i64, m as i64)? as u64, n, m);
+    let mut gamma = target;
+    for i in 0..n {
+        if let Some(j) = baby.get(&gamma) {
+            return Some(i * n + j);
+        }
+        gamma = mulmod(gamma, factor, m);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modpow() {
+        assert_eq!(modpow(2, 10, 1000), 24);
+        assert_eq!(modpow(7, 0, 13), 1);
+        assert_eq!(modpow(5, 3, 1), 0);
+        assert_eq!(modpow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn test_modinv() {
+        assert_eq!(modinv(3, 11), Some(4));
+        assert_eq!(modinv(-3, 11), Some(7));
+        assert_eq!(modinv(6, 9), None);
+        let m = 20201227;
+        assert_eq!(mulmod(modinv(7, m).unwrap() as u64, 7, m as u64), 1);
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        // The loop sizes of the 2020 day 25 example.
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(3, 1, 7), Some(0));
+        assert_eq!(discrete_log(2, 3, 7), None);
+        let x = discrete_log(5, 123456, 1_000_000_007).unwrap();
+        assert_eq!(modpow(5, x, 1_000_000_007), 123456);
+    }
+}
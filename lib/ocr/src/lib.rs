@@ -0,0 +1,292 @@
+//! Recognises the block letters that some puzzles draw as their answer, in
+//! the standard 4x6 font or the larger 6x10 one.
+//!
+//! Build an [`Image`] from lit points, rows of pixels or a rendered string
+//! and call [`Image::recognize`]:
+//!
+//! ```
+//! let image = ocr::Image::parse(
+//!     "\
+//! █..█.███.
+//! █..█..█..
+//! ████..█..
+//! █..█..█..
+//! █..█..█..
+//! █..█.███.",
+//! );
+//! assert_eq!(image.recognize(), Ok("HI".to_string()));
+//! ```
+
+mod font;
+
+use std::{fmt, fmt::Debug};
+
+use font::{Font, FONTS};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// No pixel is lit.
+    Empty,
+    /// The lit pixels span a number of rows that no font has.
+    UnsupportedHeight(usize),
+    /// Pixels that do not start any letter of the font.
+    UnknownGlyph {
+        /// Number of letters recognised before it.
+        position: usize,
+        /// Column of the image where it starts.
+        column: usize,
+        /// The pixels up to the next dark column, drawn with `#` and `.`.
+        glyph: String,
+    },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "no lit pixels"),
+            OcrError::UnsupportedHeight(height) => {
+                write!(f, "letters are {} pixels high, expected 6 or 10", height)
+            }
+            OcrError::UnknownGlyph {
+                position,
+                column,
+                glyph,
+            } => write!(
+                f,
+                "unknown glyph for letter {} at column {}:\n{}",
+                position + 1,
+                column,
+                glyph
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// A monochrome image, stored as rows of lit or dark pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    rows: Vec<Vec<bool>>,
+}
+
+impl Image {
+    /// Rows may have different lengths, missing pixels are dark.
+    pub fn from_rows(rows: Vec<Vec<bool>>) -> Self {
+        Self { rows }
+    }
+
+    /// An image with the given `(x, y)` points lit, where y grows downwards.
+    /// Only the area around the lit points is kept.
+    pub fn from_points<T>(points: impl IntoIterator<Item = (T, T)>) -> Self
+    where
+        T: TryInto<i64>,
+        T::Error: Debug,
+    {
+        let points: Vec<(i64, i64)> = points
+            .into_iter()
+            .map(|(x, y)| (x.try_into().unwrap(), y.try_into().unwrap()))
+            .collect();
+        let (Some(min_x), Some(min_y)) = (
+            points.iter().map(|p| p.0).min(),
+            points.iter().map(|p| p.1).min(),
+        ) else {
+            return Self { rows: vec![] };
+        };
+        let mut rows: Vec<Vec<bool>> = vec![];
+        for (x, y) in points {
+            let (x, y) = ((x - min_x) as usize, (y - min_y) as usize);
+            if rows.len() <= y {
+                rows.resize(y + 1, vec![]);
+            }
+            if rows[y].len() <= x {
+                rows[y].resize(x + 1, false);
+            }
+            rows[y][x] = true;
+        }
+        Self { rows }
+    }
+
+    /// Parses a rendered image. `#` and `█` are lit, anything else is dark.
+    pub fn parse(s: &str) -> Self {
+        Self::from_rows(
+            s.lines()
+                .map(|line| line.chars().map(|ch| matches!(ch, '#' | '█')).collect())
+                .collect(),
+        )
+    }
+
+    fn is_lit(&self, row: usize, col: usize) -> bool {
+        self.rows
+            .get(row)
+            .and_then(|r| r.get(col))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Reads the letters from left to right.
+    pub fn recognize(&self) -> Result<String, OcrError> {
+        let width = self.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        let lit_rows: Vec<usize> = (0..self.rows.len())
+            .filter(|&r| self.rows[r].contains(&true))
+            .collect();
+        let (Some(&top), Some(&bottom)) = (lit_rows.first(), lit_rows.last()) else {
+            return Err(OcrError::Empty);
+        };
+        let height = bottom - top + 1;
+        let font = FONTS
+            .iter()
+            .find(|font| font.height == height)
+            .ok_or(OcrError::UnsupportedHeight(height))?;
+        // every column as a bit mask of its lit rows
+        let columns: Vec<u16> = (0..width)
+            .map(|c| {
+                (0..height)
+                    .filter(|&r| self.is_lit(top + r, c))
+                    .fold(0, |mask, r| mask | 1 << r)
+            })
+            .collect();
+        let glyphs = glyph_columns(font);
+
+        let mut letters = String::new();
+        let mut col = 0;
+        while col < width {
+            if columns[col] == 0 {
+                col += 1;
+                continue;
+            }
+            let rest = &columns[col..];
+            // prefer a letter that is followed by a gap, otherwise the widest
+            // one, for letters touching their neighbour
+            let letter = glyphs
+                .iter()
+                .filter(|(_, glyph)| rest.starts_with(glyph))
+                .max_by_key(|(_, glyph)| {
+                    (rest.get(glyph.len()).is_none_or(|&c| c == 0), glyph.len())
+                });
+            match letter {
+                Some((ch, glyph)) => {
+                    letters.push(*ch);
+                    col += glyph.len();
+                }
+                None => {
+                    let end = rest.iter().position(|&c| c == 0).unwrap_or(rest.len());
+                    return Err(OcrError::UnknownGlyph {
+                        position: letters.len(),
+                        column: col,
+                        glyph: draw(&rest[..end], height),
+                    });
+                }
+            }
+        }
+        Ok(letters)
+    }
+}
+
+/// The glyphs of a font as column bit masks, without dark columns on
+/// either side.
+fn glyph_columns(font: &Font) -> Vec<(char, Vec<u16>)> {
+    font.glyphs
+        .iter()
+        .map(|&(ch, art)| {
+            let rows: Vec<&[u8]> = art.lines().map(str::as_bytes).collect();
+            let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+            let mut columns: Vec<u16> = (0..width)
+                .map(|c| {
+                    (0..rows.len())
+                        .filter(|&r| rows[r].get(c) == Some(&b'#'))
+                        .fold(0, |mask, r| mask | 1 << r)
+                })
+                .collect();
+            while columns.last() == Some(&0) {
+                columns.pop();
+            }
+            let leading = columns.iter().take_while(|&&c| c == 0).count();
+            (ch, columns.split_off(leading))
+        })
+        .collect()
+}
+
+fn draw(columns: &[u16], height: usize) -> String {
+    (0..height)
+        .map(|r| {
+            columns
+                .iter()
+                .map(|c| if c & (1 << r) != 0 { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders `text` in `font` with a dark column between letters.
+    fn render(font: &Font, text: &str) -> String {
+        let glyphs: Vec<Vec<&str>> = text
+            .chars()
+            .map(|ch| {
+                let (_, art) = font.glyphs.iter().find(|(c, _)| *c == ch).unwrap();
+                art.lines().collect()
+            })
+            .collect();
+        (0..font.height)
+            .map(|r| {
+                glyphs
+                    .iter()
+                    .map(|g| format!("{}.", g[r]))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_every_letter() {
+        for font in &FONTS {
+            let alphabet: String = font.glyphs.iter().map(|(ch, _)| ch).collect();
+            let image = Image::parse(&render(font, &alphabet));
+            assert_eq!(image.recognize(), Ok(alphabet));
+        }
+    }
+
+    #[test]
+    fn test_touching_and_points() {
+        // in the 4x6 font, Y fills the gap to the next letter
+        let art = "\
+#...##..#
+#...##..#
+.#.#.####
+..#..#..#
+..#..#..#
+..#..#..#";
+        assert_eq!(Image::parse(art).recognize(), Ok("YH".to_string()));
+        let points = art.lines().enumerate().flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, ch)| *ch == '#')
+                .map(move |(x, _)| (x as i32 + 10, y as i32 - 3))
+        });
+        assert_eq!(Image::from_points(points).recognize(), Ok("YH".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Image::parse("....").recognize(), Err(OcrError::Empty));
+        assert_eq!(
+            Image::parse("#\n#").recognize(),
+            Err(OcrError::UnsupportedHeight(2))
+        );
+        let art = "#..#..#.#\n#..#..#.#\n####..###\n#..#....#\n#..#....#\n#..#....#";
+        assert_eq!(
+            Image::parse(art).recognize(),
+            Err(OcrError::UnknownGlyph {
+                position: 1,
+                column: 6,
+                glyph: "#.#\n#.#\n###\n..#\n..#\n..#".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, digit1, line_ending, not_line_ending, one_of, space0},
+    combinator::{map_res, opt, recognize, value},
+    error::context,
+    multi::separated_list1,
+    sequence::{pair, separated_pair, terminated, tuple},
+};
+
+use crate::IResult;
+
+/// An integer with an optional sign, like `-12` or `+3`.
+pub fn int<T: FromStr>(input: &str) -> IResult<'_, T> {
+    context(
+        "integer",
+        map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse),
+    )(input)
+}
+
+/// An integer without a sign.
+pub fn uint<T: FromStr>(input: &str) -> IResult<'_, T> {
+    context("unsigned integer", map_res(digit1, str::parse))(input)
+}
+
+/// A run of letters, digits and underscores.
+pub fn word(input: &str) -> IResult<'_, &str> {
+    context(
+        "word",
+        take_while1(|c: char| c.is_alphanumeric() || c == '_'),
+    )(input)
+}
+
+/// `key: value`, where the value is the rest of the line.
+pub fn key_value(input: &str) -> IResult<'_, (&str, &str)> {
+    context(
+        "key: value",
+        separated_pair(
+            take_while1(|c| c != ':' && c != '\n'),
+            terminated(char(':'), space0),
+            not_line_ending,
+        ),
+    )(input)
+}
+
+/// A graph edge list like `a -> b, c`. The source may carry a prefix such as
+/// `%a`, the targets are [`word`]s.
+pub fn edge(input: &str) -> IResult<'_, (&str, Vec<&str>)> {
+    context(
+        "edge",
+        separated_pair(
+            take_while1(|c: char| !c.is_whitespace()),
+            tag(" -> "),
+            separated_list1(tag(", "), word),
+        ),
+    )(input)
+}
+
+/// The empty line between two blocks, including the line ending before it.
+pub fn blank_line(input: &str) -> IResult<'_, ()> {
+    context(
+        "blank line",
+        value((), tuple((line_ending, space0, line_ending))),
+    )(input)
+}
+
+/// `parser` applied to consecutive lines, stopping at the first line that
+/// does not match.
+pub fn lines_of<'a, T>(
+    parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    separated_list1(line_ending, parser)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finish;
+
+    #[test]
+    fn test_combinators() {
+        assert_eq!(int::<i64>("-42 rest"), Ok((" rest", -42)));
+        assert_eq!(int::<u8>("+7"), Ok(("", 7)));
+        assert!(uint::<u32>("-1").is_err());
+        assert_eq!(key_value("Time:   7  15"), Ok(("", ("Time", "7  15"))));
+        assert_eq!(edge("%a -> b, inv"), Ok(("", ("%a", vec!["b", "inv"]))));
+        let blocks = finish(
+            "1\n2\n\n3\n",
+            separated_pair(lines_of(uint::<u32>), blank_line, lines_of(uint)),
+        );
+        assert_eq!(blocks, Ok((vec![1, 2], vec![3])));
+    }
+}
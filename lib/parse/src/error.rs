@@ -0,0 +1,133 @@
+use std::fmt;
+
+use nom::{
+    character::complete::multispace0,
+    error::{VerboseError, VerboseErrorKind},
+    Finish,
+};
+
+use crate::IResult;
+
+/// Where and why parsing failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the input, starting at 1.
+    pub line: usize,
+    /// Character in that line, starting at 1.
+    pub column: usize,
+    pub message: String,
+    /// The line that failed.
+    pub text: String,
+}
+
+impl ParseError {
+    /// An error at the start of `rest`, which must be a suffix of a line of
+    /// `input` or of `input` itself.
+    pub fn at(input: &str, rest: &str, message: impl Into<String>) -> Self {
+        let offset = rest.as_ptr() as usize - input.as_ptr() as usize;
+        assert!(offset <= input.len(), "rest is not part of the input");
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let text = input[line_start..].lines().next().unwrap_or("");
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+            text: text.to_string(),
+        }
+    }
+
+    fn from_nom(input: &str, error: VerboseError<&str>) -> Self {
+        // the innermost error tells where, the innermost context what
+        let (rest, kind) = &error.errors[0];
+        let message = error
+            .errors
+            .iter()
+            .find_map(|(_, kind)| match kind {
+                VerboseErrorKind::Context(context) => Some(format!("expected {}", context)),
+                _ => None,
+            })
+            .unwrap_or_else(|| match kind {
+                VerboseErrorKind::Char(c) => format!("expected {:?}", c),
+                VerboseErrorKind::Nom(kind) => format!("expected {}", kind.description()),
+                VerboseErrorKind::Context(context) => format!("expected {}", context),
+            });
+        Self::at(input, rest, message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        writeln!(f, "  {}", self.text)?;
+        write!(f, "  {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Runs `parser` on the whole input, which may only be followed by
+/// whitespace.
+pub fn finish<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> Result<T, ParseError> {
+    let (rest, result) = parser(input)
+        .finish()
+        .map_err(|e| ParseError::from_nom(input, e))?;
+    let (rest, _) = multispace0::<_, ()>(rest).unwrap();
+    if !rest.is_empty() {
+        return Err(ParseError::at(input, rest, "unexpected input"));
+    }
+    Ok(result)
+}
+
+/// Runs `parser` on every non-empty line.
+pub fn lines<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<'a, T>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (rest, result) = parser(line)
+                .finish()
+                .map_err(|e| ParseError::from_nom(input, e))?;
+            if !rest.trim_end().is_empty() {
+                return Err(ParseError::at(input, rest, "unexpected input"));
+            }
+            Ok(result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{int, key_value};
+
+    #[test]
+    fn test_error_position() {
+        let input = "a: 1\nb: 2\nc 3\n";
+        let err = lines(input, key_value).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.text, "c 3");
+        let err = lines("1\n2\n3x\n", int::<i32>).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 2));
+        assert_eq!(err.message, "unexpected input");
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 2: unexpected input\n  3x\n   ^"
+        );
+        let err = lines("1\n-\n", int::<i32>).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.message.as_str()),
+            (2, 2, "expected integer")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use std::{fmt::Display, str::FromStr};
+
+use grid::Grid;
+
+use crate::ParseError;
+
+/// All integers in `s`, in order. A `-` right before a digit is a sign,
+/// every other character separates numbers. Fails on a number that does
+/// not fit in `T`.
+pub fn ints<T>(s: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    numbers(s, true)
+}
+
+/// All unsigned integers in `s`, in order. Every character that is not a
+/// digit separates numbers, so `2-4` is `[2, 4]`.
+pub fn uints<T>(s: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    numbers(s, false)
+}
+
+fn numbers<T>(s: &str, signed: bool) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = s.as_bytes();
+    let mut result = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let is_sign =
+            signed && bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if !bytes[i].is_ascii_digit() && !is_sign {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let number = s[start..i]
+            .parse()
+            .map_err(|e| ParseError::at(s, &s[start..], format!("{}", e)))?;
+        result.push(number);
+    }
+    Ok(result)
+}
+
+/// The blocks of lines in `input` that are separated by blank lines.
+pub fn blocks(input: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    let mut start = None;
+    let mut end = 0;
+    for line in input.split_inclusive('\n') {
+        let offset = line.as_ptr() as usize - input.as_ptr() as usize;
+        if line.trim().is_empty() {
+            if let Some(start) = start.take() {
+                blocks.push(input[start..end].trim_end());
+            }
+        } else {
+            start.get_or_insert(offset);
+            end = offset + line.len();
+        }
+    }
+    if let Some(start) = start {
+        blocks.push(input[start..end].trim_end());
+    }
+    blocks
+}
+
+/// Parses a character grid, where `f` returns `None` for characters that are
+/// not allowed.
+pub fn grid<T>(input: &str, f: impl Fn(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+    // blank lines before the grid are skipped, but errors count lines from
+    // the top
+    let first_line = input
+        .lines()
+        .take_while(|line| line.trim().is_empty())
+        .count();
+    let start: usize = input
+        .split_inclusive('\n')
+        .take(first_line)
+        .map(str::len)
+        .sum();
+    let text = |line: usize| {
+        input
+            .lines()
+            .nth(first_line + line - 1)
+            .unwrap_or("")
+            .to_string()
+    };
+    Grid::try_parse(&input[start..], f).map_err(|e| match e {
+        grid::ParseError::Empty => ParseError {
+            line: 1,
+            column: 1,
+            message: "empty grid".to_string(),
+            text: String::new(),
+        },
+        grid::ParseError::RaggedRow {
+            line,
+            expected,
+            found,
+        } => ParseError {
+            line: first_line + line,
+            column: expected.min(found) + 1,
+            message: format!("expected {} columns, found {}", expected, found),
+            text: text(line),
+        },
+        grid::ParseError::InvalidChar { line, col, ch } => ParseError {
+            line: first_line + line,
+            column: col,
+            message: format!("invalid character {:?}", ch),
+            text: text(line),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        let line = "p=<-3,10,0>, v=1-2 x-y";
+        assert_eq!(ints::<i32>(line), Ok(vec![-3, 10, 0, 1, -2]));
+        assert_eq!(uints::<u32>(line), Ok(vec![3, 10, 0, 1, 2]));
+        assert_eq!(ints::<i64>("no numbers"), Ok(vec![]));
+        let err = ints::<i8>("1 2\n3, -300").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "3, -300"));
+        assert_eq!(err.message, "number too small to fit in target type");
+        let err = uints::<u8>("x=256").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(blocks("a\nb\n\n\nc\n \nd"), vec!["a\nb", "c", "d"]);
+        assert_eq!(blocks("\n\n"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_grid() {
+        let g = grid("\n#.\n.#\n", |c| matches!(c, '#' | '.').then_some(c == '#')).unwrap();
+        assert_eq!((g.width(), g.height()), (2, 2));
+        let err = grid("\n#.\n.x\n", |c| matches!(c, '#' | '.').then_some(c)).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 2, ".x"));
+        let err = grid("#.\n.", Some).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+}
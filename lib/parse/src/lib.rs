@@ -0,0 +1,36 @@
+//! Puzzle input parsing on top of [`nom`].
+//!
+//! The combinators in this crate parse the pieces that keep coming back:
+//! signed and unsigned integers ([`int`], [`uint`]), `key: value` pairs
+//! ([`key_value`]), graph edges like `a -> b, c` ([`edge`]) and blocks
+//! separated by blank lines ([`blank_line`]). Run a parser with [`finish`] or
+//! [`lines`] to get a [`ParseError`] pointing at the line and column that
+//! failed instead of a panic:
+//!
+//! ```
+//! use parse::{finish, int, nom::sequence::separated_pair, nom::bytes::complete::tag};
+//!
+//! let pair = finish("3,-4", separated_pair(int::<i32>, tag(","), int::<i32>));
+//! assert_eq!(pair, Ok((3, -4)));
+//! let err = finish("3;-4", separated_pair(int::<i32>, tag(","), int::<i32>)).unwrap_err();
+//! assert_eq!((err.line, err.column), (1, 2));
+//! ```
+//!
+//! When a line has a fixed format that is not worth a parser, [`ints`] pulls
+//! out all of its integers, failing on any that overflow. Character grids are parsed into the shared
+//! [`grid::Grid`] with [`grid`].
+
+mod combinators;
+mod error;
+mod extract;
+
+pub use combinators::{blank_line, edge, int, key_value, lines_of, uint, word};
+pub use error::{finish, lines, ParseError};
+pub use extract::{blocks, grid, ints, uints};
+pub use nom;
+
+/// The result of the parsers in this crate, which collect [`context`]s for
+/// the error messages.
+///
+/// [`context`]: nom::error::context
+pub type IResult<'a, T> = nom::IResult<&'a str, T, nom::error::VerboseError<&'a str>>;
@@ -0,0 +1,73 @@
+use std::fmt;
+
+/// A range of byte offsets into the parsed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The smallest span covering both.
+    pub fn join(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Num(i64),
+    Var(String),
+    Prefix(String, Box<Ast>),
+    Binary(String, Box<Ast>, Box<Ast>),
+}
+
+/// A parsed expression, with the span of input each part came from.
+///
+/// Parentheses leave no trace in the tree: they only decide its shape, and
+/// [`Operators::display`](crate::Operators::display) puts back those that
+/// are needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ast {
+    pub node: Node,
+    pub span: Span,
+}
+
+impl Ast {
+    pub fn new(node: Node, span: Span) -> Self {
+        Self { node, span }
+    }
+
+    /// The names used in the expression, in order of first use.
+    pub fn vars(&self) -> Vec<&str> {
+        let mut vars = vec![];
+        self.collect_vars(&mut vars);
+        vars
+    }
+
+    fn collect_vars<'a>(&'a self, vars: &mut Vec<&'a str>) {
+        match &self.node {
+            Node::Num(_) => {}
+            Node::Var(name) => {
+                if !vars.contains(&name.as_str()) {
+                    vars.push(name);
+                }
+            }
+            Node::Prefix(_, operand) => operand.collect_vars(vars),
+            Node::Binary(_, a, b) => {
+                a.collect_vars(vars);
+                b.collect_vars(vars);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnexpectedChar(char),
+    /// A number that does not fit an `i64`.
+    NumberTooLarge,
+    /// An operator or `)` where an operand should start.
+    ExpectedOperand(String),
+    /// An operand or `(` where an operator or the end should be.
+    ExpectedOperator(String),
+    UnexpectedEnd,
+    UnclosedParen,
+    UnmatchedParen,
+    /// An operator the table has no entry for, in a tree parsed with
+    /// another table.
+    UnknownOperator(String),
+    UnknownVariable(String),
+    /// The operator has no value for its operands, such as on overflow or
+    /// division by zero.
+    Undefined(String),
+    /// The tree could not be built into an expression.
+    Expr(expr::Error),
+}
+
+/// What went wrong, and which part of the input it is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub span: Span,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::NumberTooLarge => write!(f, "number too large"),
+            ErrorKind::ExpectedOperand(found) => write!(f, "expected an operand, found {}", found),
+            ErrorKind::ExpectedOperator(found) => {
+                write!(f, "expected an operator, found {}", found)
+            }
+            ErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ErrorKind::UnclosedParen => write!(f, "unclosed parenthesis"),
+            ErrorKind::UnmatchedParen => write!(f, "unmatched closing parenthesis"),
+            ErrorKind::UnknownOperator(op) => write!(f, "unknown operator {}", op),
+            ErrorKind::UnknownVariable(name) => write!(f, "{} has no value", name),
+            ErrorKind::Undefined(expr) => write!(f, "{} is undefined", expr),
+            ErrorKind::Expr(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.kind, self.span)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,252 @@
+use std::fmt;
+
+use expr::{ExprId, Exprs, Op};
+
+use crate::{Ast, Error, ErrorKind, Node, Operators};
+
+impl Operators {
+    /// The value of the tree, with the operators computing what the table
+    /// says and `vars` giving the value of each name.
+    pub fn eval(&self, ast: &Ast, vars: &impl Fn(&str) -> Option<i64>) -> Result<i64, Error> {
+        let error = |kind| Error::new(kind, ast.span);
+        match &ast.node {
+            &Node::Num(n) => Ok(n),
+            Node::Var(name) => {
+                vars(name).ok_or_else(|| error(ErrorKind::UnknownVariable(name.clone())))
+            }
+            Node::Prefix(symbol, operand) => {
+                let op = self
+                    .get_prefix(symbol)
+                    .ok_or_else(|| error(ErrorKind::UnknownOperator(symbol.clone())))?;
+                let a = self.eval(operand, vars)?;
+                (op.apply)(a).ok_or_else(|| error(ErrorKind::Undefined(format!("{}{}", symbol, a))))
+            }
+            Node::Binary(symbol, lhs, rhs) => {
+                let op = self
+                    .get_binary(symbol)
+                    .ok_or_else(|| error(ErrorKind::UnknownOperator(symbol.clone())))?;
+                let (a, b) = (self.eval(lhs, vars)?, self.eval(rhs, vars)?);
+                (op.apply)(a, b)
+                    .ok_or_else(|| error(ErrorKind::Undefined(format!("{} {} {}", a, symbol, b))))
+            }
+        }
+    }
+
+    /// The tree written out with only the parentheses this table needs to
+    /// parse it back the same.
+    pub fn display<'a>(&'a self, ast: &'a Ast) -> Display<'a> {
+        Display { ops: self, ast }
+    }
+}
+
+impl Ast {
+    /// Builds the tree into `exprs`, so it can be folded and solved for an
+    /// unknown. Operators are taken by their symbol as [`expr::Op`]s, and a
+    /// prefix `-` subtracts from zero.
+    pub fn build(&self, exprs: &mut Exprs) -> Result<ExprId, Error> {
+        let error = |kind| Error::new(kind, self.span);
+        match &self.node {
+            &Node::Num(n) => Ok(exprs.num(n)),
+            Node::Var(name) => Ok(exprs.var(name)),
+            Node::Prefix(symbol, operand) if symbol == "-" => {
+                let zero = exprs.num(0i64);
+                let a = operand.build(exprs)?;
+                exprs
+                    .binary(Op::Sub, zero, a)
+                    .map_err(|err| error(ErrorKind::Expr(err)))
+            }
+            Node::Prefix(symbol, _) => Err(error(ErrorKind::UnknownOperator(symbol.clone()))),
+            Node::Binary(symbol, lhs, rhs) => {
+                let op: Op = symbol.parse().map_err(|err| error(ErrorKind::Expr(err)))?;
+                let (a, b) = (lhs.build(exprs)?, rhs.build(exprs)?);
+                exprs
+                    .binary(op, a, b)
+                    .map_err(|err| error(ErrorKind::Expr(err)))
+            }
+        }
+    }
+}
+
+/// Prints a tree with as few parentheses as its operator table allows, see
+/// [`Operators::display`].
+pub struct Display<'a> {
+    ops: &'a Operators,
+    ast: &'a Ast,
+}
+
+impl Display<'_> {
+    /// Writes `ast` where the operator before it binds its right side with
+    /// `min_bp` and the one after it binds its left side with `next_bp`, or
+    /// zero if there is none. Parentheses go in when the operator at the
+    /// top would not win against the one before, or an operator at the end
+    /// would take the operand after it.
+    fn write(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        ast: &Ast,
+        min_bp: u16,
+        next_bp: u16,
+    ) -> fmt::Result {
+        let (left, right) = match &ast.node {
+            Node::Num(_) | Node::Var(_) => (u16::MAX, u16::MAX),
+            Node::Prefix(symbol, _) => {
+                let op = self.ops.get_prefix(symbol).ok_or(fmt::Error)?;
+                (u16::MAX, op.right_bp())
+            }
+            Node::Binary(symbol, _, _) => {
+                let op = self.ops.get_binary(symbol).ok_or(fmt::Error)?;
+                (op.left_bp(), op.right_bp())
+            }
+        };
+        if left < min_bp || next_bp >= right {
+            write!(f, "(")?;
+            self.write_bare(f, ast, 0, 0)?;
+            return write!(f, ")");
+        }
+        self.write_bare(f, ast, min_bp, next_bp)
+    }
+
+    fn write_bare(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        ast: &Ast,
+        min_bp: u16,
+        next_bp: u16,
+    ) -> fmt::Result {
+        match &ast.node {
+            Node::Num(n) => write!(f, "{}", n),
+            Node::Var(name) => write!(f, "{}", name),
+            Node::Prefix(symbol, operand) => {
+                let op = self.ops.get_prefix(symbol).ok_or(fmt::Error)?;
+                write!(f, "{}", symbol)?;
+                if symbol.ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                    write!(f, " ")?;
+                }
+                self.write(f, operand, op.right_bp(), next_bp)
+            }
+            Node::Binary(symbol, lhs, rhs) => {
+                let op = self.ops.get_binary(symbol).ok_or(fmt::Error)?;
+                self.write(f, lhs, min_bp, op.left_bp())?;
+                write!(f, " {} ", symbol)?;
+                self.write(f, rhs, op.right_bp(), next_bp)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, self.ast, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assoc;
+
+    fn no_vars(_: &str) -> Option<i64> {
+        None
+    }
+
+    fn reprint(ops: &Operators, input: &str) -> String {
+        ops.display(&ops.parse(input).unwrap()).to_string()
+    }
+
+    #[test]
+    fn test_eval() {
+        let ops = Operators::arithmetic().binary("^", 3, Assoc::Right, |a, b| {
+            a.checked_pow(b.try_into().ok()?)
+        });
+        let eval = |input| {
+            ops.eval(&ops.parse(input).unwrap(), &|name| {
+                (name == "x").then_some(3)
+            })
+        };
+        assert_eq!(eval("1 + 2 * x"), Ok(7));
+        assert_eq!(eval("10 - 4 - 3"), Ok(3));
+        assert_eq!(eval("2 ^ 3 ^ 2"), Ok(512));
+        assert_eq!(eval("-x ^ 2"), Ok(-9));
+        assert_eq!(eval("(1 - x) * -(2)"), Ok(4));
+        let err = eval("1 + 4 / (x - 3)").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Undefined("4 / 0".into()));
+        assert_eq!((err.span.start, err.span.end), (4, 15));
+        assert_eq!(
+            eval("y").unwrap_err().kind,
+            ErrorKind::UnknownVariable("y".into())
+        );
+        let ast = Operators::arithmetic().parse("7 % 2").unwrap_err();
+        assert_eq!(ast.kind, ErrorKind::UnexpectedChar('%'));
+        let err = ops.eval(&ops.parse("9223372036854775807 + 1").unwrap(), &no_vars);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let ops = Operators::arithmetic().binary("^", 3, Assoc::Right, |_, _| None);
+        assert_eq!(reprint(&ops, "((1 + 2)) * (3) * 4"), "(1 + 2) * 3 * 4");
+        assert_eq!(reprint(&ops, "(1 + 2) * (3 * 4)"), "(1 + 2) * (3 * 4)");
+        assert_eq!(
+            reprint(&ops, "1 - (2 - 3) - (4 + 5)"),
+            "1 - (2 - 3) - (4 + 5)"
+        );
+        assert_eq!(reprint(&ops, "(1 - 2) + (3 * x)"), "1 - 2 + 3 * x");
+        assert_eq!(reprint(&ops, "(2 ^ 3) ^ (4 ^ 5)"), "(2 ^ 3) ^ 4 ^ 5");
+        assert_eq!(reprint(&ops, "-(x) ^ 2 * -(1 + 2)"), "-x ^ 2 * -(1 + 2)");
+        assert_eq!(reprint(&ops, "(-x) ^ 2"), "(-x) ^ 2");
+        // a loose prefix operator at the end of an operand has to be
+        // closed off before the next operator
+        let ops = Operators::arithmetic().prefix("-", 0, i64::checked_neg);
+        assert_eq!(reprint(&ops, "(1 * -2) * 3"), "1 * (-2) * 3");
+        assert_eq!(reprint(&ops, "1 * -(2 * 3)"), "1 * -2 * 3");
+    }
+
+    #[test]
+    fn test_display_parses_back() {
+        let same = Operators::new()
+            .binary("+", 0, Assoc::Left, i64::checked_add)
+            .binary("*", 0, Assoc::Left, i64::checked_mul);
+        let plus_first = same.clone().binary("+", 1, Assoc::Left, i64::checked_add);
+        for input in [
+            "1 + (2 * 3) + 4",
+            "(1 + 2) * (3 + 4) * 5",
+            "2 * (3 + (4 * 5))",
+        ] {
+            for ops in [&same, &plus_first] {
+                let ast = ops.parse(input).unwrap();
+                let printed = ops.display(&ast).to_string();
+                let reparsed = ops.parse(&printed).unwrap();
+                assert_eq!(
+                    ops.eval(&reparsed, &no_vars),
+                    ops.eval(&ast, &no_vars),
+                    "{}",
+                    printed
+                );
+                assert_eq!(ops.display(&reparsed).to_string(), printed);
+            }
+        }
+        let ast = same.parse("2 * 3 + 4").unwrap();
+        assert_eq!(plus_first.display(&ast).to_string(), "(2 * 3) + 4");
+        assert_eq!(same.display(&ast).to_string(), "2 * 3 + 4");
+    }
+
+    #[test]
+    fn test_build() {
+        let ops = Operators::arithmetic();
+        let mut exprs = Exprs::new();
+        let lhs = ops
+            .parse("(x - 2 * 5) / 3")
+            .unwrap()
+            .build(&mut exprs)
+            .unwrap();
+        let rhs = ops.parse("-(1 - 8)").unwrap().build(&mut exprs).unwrap();
+        assert_eq!(exprs.value(rhs), Some(7i64.into()));
+        assert_eq!(exprs.solve(lhs, rhs, "x").unwrap(), 31i64.into());
+        let ops = ops.binary("%", 2, Assoc::Left, i64::checked_rem);
+        let err = ops.parse("x % 2").unwrap().build(&mut exprs).unwrap_err();
+        assert_eq!(
+            err.kind,
+            ErrorKind::Expr(expr::Error::UnknownOp("%".into()))
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Expressions parsed by precedence climbing from an operator table, for
+//! puzzles that change what the operators mean.
+//!
+//! - [`Operators`] is the table: binary and prefix operators with their
+//!   precedence, associativity and what they compute, punctuation or words,
+//! - [`Operators::parse`] gives an [`Ast`] that knows the [`Span`] of input
+//!   each part came from, and [`Error`]s point at where parsing stopped,
+//! - [`Operators::eval`] computes the value with checked operations,
+//! - [`Operators::display`] prints the tree with only the parentheses the
+//!   table needs,
+//! - [`Ast::build`] turns the tree into [`expr::Exprs`] to solve for an
+//!   unknown.
+//!
+//! ```
+//! use pratt::{Assoc, Operators};
+//!
+//! // 2020 day 18 part 2: addition before multiplication
+//! let ops = Operators::new()
+//!     .binary("+", 2, Assoc::Left, i64::checked_add)
+//!     .binary("*", 1, Assoc::Left, i64::checked_mul);
+//! let ast = ops.parse("2 * 3 + (4 * 5)").unwrap();
+//! assert_eq!(ops.eval(&ast, &|_| None), Ok(46));
+//! assert_eq!(ops.display(&ast).to_string(), "2 * 3 + (4 * 5)");
+//! // the same tree with the usual precedence
+//! let usual = Operators::arithmetic();
+//! assert_eq!(usual.display(&ast).to_string(), "2 * (3 + 4 * 5)");
+//!
+//! let err = ops.parse("1 + (2 *").unwrap_err();
+//! assert_eq!(err.to_string(), "unexpected end of input at 8..8");
+//! ```
+
+mod ast;
+mod error;
+mod eval;
+mod operators;
+mod parser;
+
+pub use ast::{Ast, Node, Span};
+pub use error::{Error, ErrorKind};
+pub use eval::Display;
+pub use operators::{Assoc, BinaryFn, Operators, PrefixFn};
@@ -0,0 +1,118 @@
+/// Which way a chain of operators of the same precedence groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+    Right,
+}
+
+/// The value of `a op b`, or `None` where there is none.
+pub type BinaryFn = fn(i64, i64) -> Option<i64>;
+/// The value of `op a`, or `None` where there is none.
+pub type PrefixFn = fn(i64) -> Option<i64>;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Binary {
+    pub symbol: String,
+    pub precedence: u8,
+    pub assoc: Assoc,
+    pub apply: BinaryFn,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Prefix {
+    pub symbol: String,
+    pub precedence: u8,
+    pub apply: PrefixFn,
+}
+
+// Binding powers: how strongly an operator holds on to the operand on
+// either side. Zero is left for "nothing follows".
+impl Binary {
+    pub fn left_bp(&self) -> u16 {
+        match self.assoc {
+            Assoc::Left => 2 * self.precedence as u16 + 2,
+            Assoc::Right => 2 * self.precedence as u16 + 3,
+        }
+    }
+
+    pub fn right_bp(&self) -> u16 {
+        match self.assoc {
+            Assoc::Left => 2 * self.precedence as u16 + 3,
+            Assoc::Right => 2 * self.precedence as u16 + 2,
+        }
+    }
+}
+
+impl Prefix {
+    pub fn right_bp(&self) -> u16 {
+        2 * self.precedence as u16 + 2
+    }
+}
+
+/// The operator table a parser works from: every operator with its
+/// symbol, precedence (higher binds tighter), associativity and what it
+/// computes.
+///
+/// Symbols are either punctuation, of which the longest that fits is
+/// taken, or words like `AND` that stand apart from names. A symbol can be
+/// both a prefix and a binary operator, as `-` usually is.
+#[derive(Debug, Clone, Default)]
+pub struct Operators {
+    pub(crate) binary: Vec<Binary>,
+    pub(crate) prefix: Vec<Prefix>,
+}
+
+impl Operators {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `+ - * /` with the usual precedence and a prefix `-`, on checked
+    /// integers.
+    pub fn arithmetic() -> Self {
+        Self::new()
+            .binary("+", 1, Assoc::Left, i64::checked_add)
+            .binary("-", 1, Assoc::Left, i64::checked_sub)
+            .binary("*", 2, Assoc::Left, i64::checked_mul)
+            .binary("/", 2, Assoc::Left, i64::checked_div)
+            .prefix("-", 3, i64::checked_neg)
+    }
+
+    /// Adds a binary operator, replacing one with the same symbol.
+    pub fn binary(mut self, symbol: &str, precedence: u8, assoc: Assoc, apply: BinaryFn) -> Self {
+        self.binary.retain(|op| op.symbol != symbol);
+        self.binary.push(Binary {
+            symbol: symbol.to_string(),
+            precedence,
+            assoc,
+            apply,
+        });
+        self
+    }
+
+    /// Adds a prefix operator, replacing one with the same symbol.
+    pub fn prefix(mut self, symbol: &str, precedence: u8, apply: PrefixFn) -> Self {
+        self.prefix.retain(|op| op.symbol != symbol);
+        self.prefix.push(Prefix {
+            symbol: symbol.to_string(),
+            precedence,
+            apply,
+        });
+        self
+    }
+
+    pub(crate) fn get_binary(&self, symbol: &str) -> Option<&Binary> {
+        self.binary.iter().find(|op| op.symbol == symbol)
+    }
+
+    pub(crate) fn get_prefix(&self, symbol: &str) -> Option<&Prefix> {
+        self.prefix.iter().find(|op| op.symbol == symbol)
+    }
+
+    pub(crate) fn symbols(&self) -> impl Iterator<Item = &str> {
+        let binary = self.binary.iter().map(|op| op.symbol.as_str());
+        binary.chain(self.prefix.iter().map(|op| op.symbol.as_str()))
+    }
+}
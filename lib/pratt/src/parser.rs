@@ -0,0 +1,256 @@
+use std::iter::Peekable;
+
+use crate::{Ast, Error, ErrorKind, Node, Operators, Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Num(i64),
+    Name(String),
+    Op(String),
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Num(n) => n.to_string(),
+            Token::Name(name) => name.clone(),
+            Token::Op(op) => op.clone(),
+            Token::Open => "(".to_string(),
+            Token::Close => ")".to_string(),
+        }
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Splits the input into tokens. A word is an operator if the table has
+/// it as a symbol and a name otherwise, and punctuation is the longest
+/// operator symbol that fits.
+fn tokenize(ops: &Operators, input: &str) -> Result<Vec<(Token, Span)>, Error> {
+    let mut tokens = vec![];
+    let mut rest = input.char_indices().peekable();
+    while let Some(&(start, c)) = rest.peek() {
+        if c.is_whitespace() {
+            rest.next();
+            continue;
+        }
+        let (token, end) = if is_word(c) {
+            let mut end = start;
+            while let Some(&(i, c)) = rest.peek() {
+                if !is_word(c) {
+                    break;
+                }
+                end = i + c.len_utf8();
+                rest.next();
+            }
+            let word = &input[start..end];
+            let token = if ops.symbols().any(|symbol| symbol == word) {
+                Token::Op(word.to_string())
+            } else if c.is_ascii_digit() {
+                let n = word.parse().map_err(|_| {
+                    match word.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+                        Some((i, c)) => {
+                            let span = Span::new(start + i, start + i + c.len_utf8());
+                            Error::new(ErrorKind::UnexpectedChar(c), span)
+                        }
+                        None => Error::new(ErrorKind::NumberTooLarge, Span::new(start, end)),
+                    }
+                })?;
+                Token::Num(n)
+            } else {
+                Token::Name(word.to_string())
+            };
+            (token, end)
+        } else if c == '(' || c == ')' {
+            rest.next();
+            let token = if c == '(' { Token::Open } else { Token::Close };
+            (token, start + 1)
+        } else {
+            let symbol = ops
+                .symbols()
+                .filter(|symbol| !symbol.starts_with(is_word))
+                .filter(|symbol| input[start..].starts_with(symbol))
+                .max_by_key(|symbol| symbol.len())
+                .ok_or_else(|| {
+                    let span = Span::new(start, start + c.len_utf8());
+                    Error::new(ErrorKind::UnexpectedChar(c), span)
+                })?;
+            let end = start + symbol.len();
+            while rest.next_if(|&(i, _)| i < end).is_some() {}
+            (Token::Op(symbol.to_string()), end)
+        };
+        tokens.push((token, Span::new(start, end)));
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a, I: Iterator<Item = (Token, Span)>> {
+    ops: &'a Operators,
+    tokens: Peekable<I>,
+    end: Span,
+}
+
+impl<I: Iterator<Item = (Token, Span)>> Parser<'_, I> {
+    /// An expression whose operators all bind at least `min_bp`, which is
+    /// where precedence and associativity come in.
+    fn expr(&mut self, min_bp: u16) -> Result<Ast, Error> {
+        let mut lhs = self.operand()?;
+        loop {
+            let (symbol, span) = match self.tokens.peek() {
+                None | Some((Token::Close, _)) => break,
+                Some((Token::Op(symbol), span)) => (symbol.clone(), *span),
+                Some((token, span)) => {
+                    let kind = ErrorKind::ExpectedOperator(token.describe());
+                    return Err(Error::new(kind, *span));
+                }
+            };
+            let op = self
+                .ops
+                .get_binary(&symbol)
+                .ok_or_else(|| Error::new(ErrorKind::ExpectedOperator(symbol.clone()), span))?;
+            if op.left_bp() < min_bp {
+                break;
+            }
+            self.tokens.next();
+            let rhs = self.expr(op.right_bp())?;
+            let span = lhs.span.join(rhs.span);
+            lhs = Ast::new(Node::Binary(symbol, Box::new(lhs), Box::new(rhs)), span);
+        }
+        Ok(lhs)
+    }
+
+    fn operand(&mut self) -> Result<Ast, Error> {
+        let Some((token, span)) = self.tokens.next() else {
+            return Err(Error::new(ErrorKind::UnexpectedEnd, self.end));
+        };
+        match token {
+            Token::Num(n) => Ok(Ast::new(Node::Num(n), span)),
+            Token::Name(name) => Ok(Ast::new(Node::Var(name), span)),
+            Token::Open => {
+                let inner = self.expr(0)?;
+                match self.tokens.next() {
+                    Some((Token::Close, close)) => Ok(Ast::new(inner.node, span.join(close))),
+                    _ => Err(Error::new(ErrorKind::UnclosedParen, span)),
+                }
+            }
+            Token::Op(symbol) => {
+                let op = self
+                    .ops
+                    .get_prefix(&symbol)
+                    .ok_or_else(|| Error::new(ErrorKind::ExpectedOperand(symbol.clone()), span))?;
+                let operand = self.expr(op.right_bp())?;
+                let span = span.join(operand.span);
+                Ok(Ast::new(Node::Prefix(symbol, Box::new(operand)), span))
+            }
+            Token::Close => Err(Error::new(
+                ErrorKind::ExpectedOperand(token.describe()),
+                span,
+            )),
+        }
+    }
+}
+
+impl Operators {
+    /// Parses a whole expression of numbers, names, parentheses and the
+    /// operators in the table.
+    pub fn parse(&self, input: &str) -> Result<Ast, Error> {
+        let tokens = tokenize(self, input)?;
+        let mut parser = Parser {
+            ops: self,
+            tokens: tokens.into_iter().peekable(),
+            end: Span::new(input.len(), input.len()),
+        };
+        let ast = parser.expr(0)?;
+        match parser.tokens.next() {
+            None => Ok(ast),
+            Some((_, span)) => Err(Error::new(ErrorKind::UnmatchedParen, span)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Assoc;
+
+    fn kind(ops: &Operators, input: &str) -> (ErrorKind, Span) {
+        let err = ops.parse(input).unwrap_err();
+        (err.kind, err.span)
+    }
+
+    #[test]
+    fn test_precedence() {
+        let ops = Operators::arithmetic();
+        let ast = ops.parse("1 + 2 * x").unwrap();
+        let Node::Binary(op, a, b) = &ast.node else {
+            panic!("{:?}", ast);
+        };
+        assert_eq!(op, "+");
+        assert_eq!(a.node, Node::Num(1));
+        assert_eq!(b.span, Span::new(4, 9));
+        assert_eq!(ast.span, Span::new(0, 9));
+        assert_eq!(ast.vars(), ["x"]);
+    }
+
+    #[test]
+    fn test_symbols() {
+        let ops = Operators::new()
+            .binary("<", 1, Assoc::Left, |a, b| Some((a < b) as i64))
+            .binary("<<", 2, Assoc::Left, |a, b| a.checked_shl(b as u32))
+            .binary("AND", 0, Assoc::Left, |a, b| Some(a & b))
+            .prefix("NOT", 3, |a| Some(!a));
+        let ast = ops.parse("x<<1 AND NOT y").unwrap();
+        let Node::Binary(op, a, b) = &ast.node else {
+            panic!("{:?}", ast);
+        };
+        assert_eq!(op, "AND");
+        assert!(matches!(&a.node, Node::Binary(op, _, _) if op == "<<"));
+        assert!(matches!(&b.node, Node::Prefix(op, _) if op == "NOT"));
+        assert_eq!(ops.parse("ANDY").unwrap().node, Node::Var("ANDY".into()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let ops = Operators::arithmetic();
+        assert_eq!(
+            kind(&ops, "1 + $"),
+            (ErrorKind::UnexpectedChar('$'), Span::new(4, 5))
+        );
+        assert_eq!(
+            kind(&ops, "1 + * 2"),
+            (ErrorKind::ExpectedOperand("*".into()), Span::new(4, 5))
+        );
+        assert_eq!(
+            kind(&ops, "1 2"),
+            (ErrorKind::ExpectedOperator("2".into()), Span::new(2, 3))
+        );
+        assert_eq!(
+            kind(&ops, "1 +"),
+            (ErrorKind::UnexpectedEnd, Span::new(3, 3))
+        );
+        assert_eq!(
+            kind(&ops, "(1 + 2"),
+            (ErrorKind::UnclosedParen, Span::new(0, 1))
+        );
+        assert_eq!(
+            kind(&ops, "1)"),
+            (ErrorKind::UnmatchedParen, Span::new(1, 2))
+        );
+        assert_eq!(
+            kind(&ops, "()"),
+            (ErrorKind::ExpectedOperand(")".into()), Span::new(1, 2))
+        );
+        assert_eq!(
+            kind(&ops, "99999999999999999999"),
+            (ErrorKind::NumberTooLarge, Span::new(0, 20))
+        );
+        assert_eq!(
+            ops.parse("1 + 2a").unwrap_err().to_string(),
+            "unexpected character 'a' at 5..6"
+        );
+    }
+}
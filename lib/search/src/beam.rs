@@ -0,0 +1,91 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::{solution_at, stats::StatsTimer, Node, SearchProblem, SearchResult};
+
+/// Searches layer by layer like [`bfs`](crate::bfs), but only keeps the
+/// `width` states with the lowest cost plus [`SearchProblem::heuristic`] in
+/// every layer. Returns the cheapest goal of the first layer that contains
+/// one.
+///
+/// This is not guaranteed to find the cheapest path, or any path at all, but
+/// bounds the work per layer. Widen the beam until the answer stops changing.
+pub fn beam_search<P: SearchProblem>(problem: &P, width: usize) -> SearchResult<P::State, P::Cost> {
+    let mut timer = StatsTimer::start();
+    let start = problem.start();
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![Node {
+        state: start,
+        parent: None,
+        cost: P::Cost::default(),
+        depth: 0,
+    }];
+    let mut layer = vec![0];
+    while !layer.is_empty() {
+        let goal = layer
+            .iter()
+            .copied()
+            .filter(|&idx| problem.is_goal(&nodes[idx].state))
+            .min_by_key(|&idx| nodes[idx].cost);
+        if let Some(idx) = goal {
+            return SearchResult {
+                solution: Some(solution_at(&nodes, idx)),
+                stats: timer.finish(),
+            };
+        }
+        let mut next_layer = vec![];
+        for idx in layer {
+            timer.stats.expanded += 1;
+            for (next, step) in problem.successors(&nodes[idx].state) {
+                timer.stats.generated += 1;
+                let cost = nodes[idx].cost + step;
+                let depth = nodes[idx].depth + 1;
+                match index.entry(next.clone()) {
+                    Entry::Occupied(entry) => {
+                        let node = &mut nodes[*entry.get()];
+                        if node.depth == depth && cost < node.cost {
+                            node.cost = cost;
+                            node.parent = Some(idx);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(nodes.len());
+                        next_layer.push(nodes.len());
+                        nodes.push(Node {
+                            state: next,
+                            parent: Some(idx),
+                            cost,
+                            depth,
+                        });
+                    }
+                }
+            }
+        }
+        timer.stats.track_frontier(next_layer.len());
+        if next_layer.len() > width {
+            let estimate = |idx: usize| nodes[idx].cost + problem.heuristic(&nodes[idx].state);
+            next_layer.select_nth_unstable_by_key(width, |&idx| estimate(idx));
+            next_layer.truncate(width);
+        }
+        layer = next_layer;
+    }
+    SearchResult {
+        solution: None,
+        stats: timer.finish(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{beam_search, bfs, tests::*};
+
+    #[test]
+    fn test_beam_width() {
+        let maze = Maze::new(MAZE);
+        // a beam wide enough to never drop a state behaves like bfs
+        let wide = beam_search(&maze, 100);
+        assert_eq!(wide.steps(), bfs(&maze).steps());
+        assert_eq!(wide.cost(), Some(15));
+        let narrow = beam_search(&maze, 1);
+        assert!(narrow.stats.expanded <= wide.stats.expanded);
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+};
+
+use crate::{solution_at, stats::StatsTimer, Cost, Node, SearchProblem, SearchResult};
+
+/// Dijkstra's algorithm: finds the cheapest path, expanding states in order of
+/// their cost from the start.
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    best_first(problem, |_| P::Cost::default())
+}
+
+/// A* search: like [`dijkstra`], but expands states in order of their cost
+/// plus [`SearchProblem::heuristic`].
+pub fn astar<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    best_first(problem, |state| problem.heuristic(state))
+}
+
+/// An entry in the open list. Ordered so that the binary heap pops the lowest
+/// estimate first, preferring deeper states on ties.
+struct Open<C> {
+    estimate: C,
+    cost: C,
+    idx: usize,
+}
+
+impl<C: Cost> PartialEq for Open<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: Cost> Eq for Open<C> {}
+
+impl<C: Cost> PartialOrd for Open<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Cost> Ord for Open<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+fn best_first<P: SearchProblem>(
+    problem: &P,
+    heuristic: impl Fn(&P::State) -> P::Cost,
+) -> SearchResult<P::State, P::Cost> {
+    let mut timer = StatsTimer::start();
+    let start = problem.start();
+    let mut open = BinaryHeap::from([Open {
+        estimate: heuristic(&start),
+        cost: P::Cost::default(),
+        idx: 0,
+    }]);
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![Node {
+        state: start,
+        parent: None,
+        cost: P::Cost::default(),
+        depth: 0,
+    }];
+    while let Some(Open { cost, idx, .. }) = open.pop() {
+        if cost > nodes[idx].cost {
+            // a cheaper way to this state was found after it was queued
+            continue;
+        }
+        if problem.is_goal(&nodes[idx].state) {
+            return SearchResult {
+                solution: Some(solution_at(&nodes, idx)),
+                stats: timer.finish(),
+            };
+        }
+        timer.stats.expanded += 1;
+        for (next, step) in problem.successors(&nodes[idx].state) {
+            timer.stats.generated += 1;
+            let next_cost = cost + step;
+            let depth = nodes[idx].depth + 1;
+            let next_idx = match index.entry(next.clone()) {
+                Entry::Occupied(entry) => {
+                    let next_idx = *entry.get();
+                    let node = &mut nodes[next_idx];
+                    if node.cost <= next_cost {
+                        continue;
+                    }
+                    node.cost = next_cost;
+                    node.parent = Some(idx);
+                    node.depth = depth;
+                    next_idx
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(nodes.len());
+                    nodes.push(Node {
+                        state: next.clone(),
+                        parent: Some(idx),
+                        cost: next_cost,
+                        depth,
+                    });
+                    nodes.len() - 1
+                }
+            };
+            open.push(Open {
+                estimate: next_cost + heuristic(&next),
+                cost: next_cost,
+                idx: next_idx,
+            });
+        }
+        timer.stats.track_frontier(open.len());
+    }
+    SearchResult {
+        solution: None,
+        stats: timer.finish(),
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::{
+    path_to, solution_at, stats::StatsTimer, Node, ReversibleProblem, SearchProblem, SearchResult,
+    Solution,
+};
+
+/// Breadth-first search for the path with the fewest steps. Step costs are
+/// ignored while searching, the cost of the solution is the sum of the steps
+/// on the path that was found.
+pub fn bfs<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    let mut timer = StatsTimer::start();
+    let start = problem.start();
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut nodes = vec![Node {
+        state: start,
+        parent: None,
+        cost: P::Cost::default(),
+        depth: 0,
+    }];
+    let mut queue = VecDeque::from([0]);
+    while let Some(idx) = queue.pop_front() {
+        if problem.is_goal(&nodes[idx].state) {
+            return SearchResult {
+                solution: Some(solution_at(&nodes, idx)),
+                stats: timer.finish(),
+            };
+        }
+        timer.stats.expanded += 1;
+        for (next, cost) in problem.successors(&nodes[idx].state) {
+            timer.stats.generated += 1;
+            if let Entry::Vacant(entry) = index.entry(next.clone()) {
+                entry.insert(nodes.len());
+                queue.push_back(nodes.len());
+                nodes.push(Node {
+                    state: next,
+                    parent: Some(idx),
+                    cost: nodes[idx].cost + cost,
+                    depth: nodes[idx].depth + 1,
+                });
+            }
+        }
+        timer.stats.track_frontier(queue.len());
+    }
+    SearchResult {
+        solution: None,
+        stats: timer.finish(),
+    }
+}
+
+/// One direction of a bidirectional search.
+struct Side<S, C> {
+    nodes: Vec<Node<S, C>>,
+    index: HashMap<S, usize>,
+    layer: Vec<usize>,
+}
+
+impl<S: Clone + Eq + std::hash::Hash, C: crate::Cost> Side<S, C> {
+    fn new(root: S) -> Self {
+        Self {
+            index: HashMap::from([(root.clone(), 0)]),
+            nodes: vec![Node {
+                state: root,
+                parent: None,
+                cost: C::default(),
+                depth: 0,
+            }],
+            layer: vec![0],
+        }
+    }
+}
+
+/// Breadth-first search from the start and the goal at the same time, always
+/// growing the side with the smaller frontier. Finds a path with the fewest
+/// steps, like [`bfs`], while usually visiting far fewer states.
+pub fn bidirectional_bfs<P: ReversibleProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    let mut timer = StatsTimer::start();
+    let mut forward = Side::new(problem.start());
+    let mut backward = Side::new(problem.goal());
+    if forward.nodes[0].state == backward.nodes[0].state {
+        return SearchResult {
+            solution: Some(solution_at(&forward.nodes, 0)),
+            stats: timer.finish(),
+        };
+    }
+    while !forward.layer.is_empty() && !backward.layer.is_empty() {
+        let is_forward = forward.layer.len() <= backward.layer.len();
+        let (side, other) = if is_forward {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+        // the best meeting point of this layer, as (total steps, this side, other side)
+        let mut meeting: Option<(usize, usize, usize)> = None;
+        let mut next_layer = vec![];
+        for &idx in std::mem::take(&mut side.layer).iter() {
+            timer.stats.expanded += 1;
+            let state = side.nodes[idx].state.clone();
+            let neighbours = if is_forward {
+                problem.successors(&state)
+            } else {
+                problem.predecessors(&state)
+            };
+            for (next, cost) in neighbours {
+                timer.stats.generated += 1;
+                if side.index.contains_key(&next) {
+                    continue;
+                }
+                let new_idx = side.nodes.len();
+                side.index.insert(next.clone(), new_idx);
+                side.nodes.push(Node {
+                    state: next.clone(),
+                    parent: Some(idx),
+                    cost: side.nodes[idx].cost + cost,
+                    depth: side.nodes[idx].depth + 1,
+                });
+                next_layer.push(new_idx);
+                if let Some(&other_idx) = other.index.get(&next) {
+                    let steps = side.nodes[new_idx].depth + other.nodes[other_idx].depth;
+                    if meeting.is_none_or(|(best, _, _)| steps < best) {
+                        meeting = Some((steps, new_idx, other_idx));
+                    }
+                }
+            }
+        }
+        side.layer = next_layer;
+        timer
+            .stats
+            .track_frontier(forward.layer.len() + backward.layer.len());
+        if let Some((_, side_idx, other_idx)) = meeting {
+            let (forward_idx, backward_idx) = if is_forward {
+                (side_idx, other_idx)
+            } else {
+                (other_idx, side_idx)
+            };
+            let mut path = path_to(&forward.nodes, forward_idx);
+            let mut rest = path_to(&backward.nodes, backward_idx);
+            rest.reverse();
+            path.extend(rest.into_iter().skip(1));
+            return SearchResult {
+                solution: Some(Solution {
+                    path,
+                    cost: forward.nodes[forward_idx].cost + backward.nodes[backward_idx].cost,
+                }),
+                stats: timer.finish(),
+            };
+        }
+    }
+    SearchResult {
+        solution: None,
+        stats: timer.finish(),
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use crate::{stats::StatsTimer, SearchProblem, SearchResult, Solution};
+
+/// Iterative deepening A*: depth-first searches that give up on states whose
+/// cost plus [`SearchProblem::heuristic`] exceeds a bound, starting from the
+/// estimate of the start and raising the bound to the smallest estimate that
+/// went over it until a goal is reached. Finds the cheapest path when the
+/// heuristic never overestimates, like [`astar`](crate::astar).
+///
+/// Only the current path is kept in memory, so this suits deep state spaces
+/// whose open list would not fit. States on the current path are skipped,
+/// but nothing else is remembered, so a state that can be reached in many
+/// ways is expanded again for each of them. The frontier in the stats is the
+/// longest path that was held.
+pub fn ida_star<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    let start = problem.start();
+    let mut search = Ida {
+        problem,
+        path: vec![start.clone()],
+        on_path: HashSet::from([start.clone()]),
+        timer: StatsTimer::start(),
+    };
+    let mut bound = problem.heuristic(&start);
+    loop {
+        match search.dfs(P::Cost::default(), bound) {
+            Ok(cost) => {
+                return SearchResult {
+                    solution: Some(Solution {
+                        path: search.path,
+                        cost,
+                    }),
+                    stats: search.timer.finish(),
+                }
+            }
+            Err(Some(next)) => bound = next,
+            Err(None) => {
+                return SearchResult {
+                    solution: None,
+                    stats: search.timer.finish(),
+                }
+            }
+        }
+    }
+}
+
+struct Ida<'a, P: SearchProblem> {
+    problem: &'a P,
+    path: Vec<P::State>,
+    on_path: HashSet<P::State>,
+    timer: StatsTimer,
+}
+
+impl<P: SearchProblem> Ida<'_, P> {
+    /// Searches below the last state of the path, reached at `cost`. Returns
+    /// the cost of the goal, with the path leading to it, or else the
+    /// smallest estimate over `bound`, if there was one.
+    fn dfs(&mut self, cost: P::Cost, bound: P::Cost) -> Result<P::Cost, Option<P::Cost>> {
+        let state = self.path.last().unwrap().clone();
+        let estimate = cost + self.problem.heuristic(&state);
+        if estimate > bound {
+            return Err(Some(estimate));
+        }
+        if self.problem.is_goal(&state) {
+            return Ok(cost);
+        }
+        self.timer.stats.expanded += 1;
+        let mut next_bound: Option<P::Cost> = None;
+        for (next, step) in self.problem.successors(&state) {
+            self.timer.stats.generated += 1;
+            if !self.on_path.insert(next.clone()) {
+                continue;
+            }
+            self.path.push(next);
+            self.timer.stats.track_frontier(self.path.len());
+            match self.dfs(cost + step, bound) {
+                Ok(goal_cost) => return Ok(goal_cost),
+                Err(Some(over)) => {
+                    next_bound = Some(next_bound.map_or(over, |b| b.min(over)));
+                }
+                Err(None) => {}
+            }
+            let next = self.path.pop().unwrap();
+            self.on_path.remove(&next);
+        }
+        Err(next_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{astar, ida_star, tests::*};
+
+    #[test]
+    fn test_ida_star() {
+        let maze = Maze::new(MAZE);
+        let ida = ida_star(&maze);
+        assert_eq!(ida.cost(), astar(&maze).cost());
+        assert_eq!(ida.steps(), Some(11));
+        let path = ida.solution.unwrap().path;
+        assert_eq!((path.first(), path.last()), (Some(&(0, 0)), Some(&(0, 7))));
+        assert_eq!(ida.stats.max_frontier, 12);
+    }
+}
@@ -0,0 +1,296 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A maze compressed to its junctions: the start, the end and every cell
+/// with more than two successors. Edges are the corridors between them,
+/// weighted by their length. Corridors that can only be walked in one
+/// direction, like slopes, become one-way edges.
+#[derive(Debug, Clone)]
+pub struct JunctionGraph<N> {
+    pub junctions: Vec<N>,
+    /// Outgoing edges of every junction as `(junction index, length)`.
+    pub edges: Vec<Vec<(usize, usize)>>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl<N: Copy + Eq + Hash> JunctionGraph<N> {
+    /// Walks the maze from `start`, using `successors` to find the cells that
+    /// can be entered from a cell.
+    pub fn compress(start: N, end: N, successors: impl Fn(N) -> Vec<N>) -> Self {
+        let mut index = HashMap::from([(start, 0), (end, 1)]);
+        let mut junctions = vec![start, end];
+        let mut edges: Vec<HashMap<usize, usize>> = vec![HashMap::new(), HashMap::new()];
+        let mut todo = vec![0];
+        let mut expanded = HashSet::new();
+        while let Some(from) = todo.pop() {
+            if !expanded.insert(from) {
+                continue;
+            }
+            for first in successors(junctions[from]) {
+                let Some((to, len)) = walk_corridor(junctions[from], first, end, &successors)
+                else {
+                    continue;
+                };
+                let to = *index.entry(to).or_insert_with(|| {
+                    junctions.push(to);
+                    edges.push(HashMap::new());
+                    junctions.len() - 1
+                });
+                if to == from {
+                    continue;
+                }
+                let best = edges[from].entry(to).or_default();
+                *best = (*best).max(len);
+                todo.push(to);
+            }
+        }
+        let edges = edges
+            .into_iter()
+            .map(|e| {
+                let mut e = e.into_iter().collect::<Vec<_>>();
+                e.sort_unstable();
+                e
+            })
+            .collect();
+        Self {
+            junctions,
+            edges,
+            start: 0,
+            end: 1,
+        }
+    }
+
+    /// Length of the longest path from start to end that visits no junction,
+    /// and therefore no cell, twice.
+    ///
+    /// # Panics
+    ///
+    /// If there are more than 64 junctions.
+    pub fn longest_path(&self) -> Option<usize> {
+        let n = self.junctions.len();
+        assert!(n <= 64, "{} junctions do not fit into a u64 bitset", n);
+        // the longest edge into every junction, to bound what is still reachable
+        let mut max_in = vec![0; n];
+        let mut predecessors = vec![vec![]; n];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, len) in edges {
+                max_in[to] = max_in[to].max(len);
+                predecessors[to].push((from, len));
+            }
+        }
+        // with a single way into the end, arriving at its predecessor means
+        // having to go to the end right away
+        let gate = match predecessors[self.end][..] {
+            [(gate, len)] => Some((gate, len)),
+            _ => None,
+        };
+        let mut search = LongestPath {
+            graph: self,
+            max_in,
+            gate,
+            best: None,
+            seen: HashMap::new(),
+        };
+        let visited = 1 << self.start;
+        let remaining = search.bound(visited);
+        search.dfs(self.start, visited, 0, remaining);
+        search.best
+    }
+}
+
+/// Follows the corridor entered by stepping from `junction` to `first` until
+/// the next junction. Returns that junction and the corridor length, or
+/// `None` for dead ends. A corridor that leads back to `junction` returns
+/// it, and one that goes round a loop without reaching any junction is a
+/// dead end.
+fn walk_corridor<N: Copy + Eq + Hash>(
+    junction: N,
+    first: N,
+    end: N,
+    successors: &impl Fn(N) -> Vec<N>,
+) -> Option<(N, usize)> {
+    let (mut prev, mut cur, mut len) = (junction, first, 1);
+    let mut walked = HashSet::new();
+    loop {
+        if cur == end || cur == junction {
+            return Some((cur, len));
+        }
+        if !walked.insert(cur) {
+            return None;
+        }
+        let next = successors(cur);
+        if next.len() > 2 {
+            return Some((cur, len));
+        }
+        let mut forward = next.into_iter().filter(|&n| n != prev);
+        let step = forward.next()?;
+        if forward.next().is_some() {
+            return Some((cur, len));
+        }
+        (prev, cur, len) = (cur, step, len + 1);
+    }
+}
+
+/// Only paths through at most this many junctions are memoised. Deeper
+/// down, arriving at the same junction with the same visited set again is
+/// too rare to be worth the lookups.
+const MEMO_DEPTH: u32 = 8;
+
+struct LongestPath<'a, N> {
+    graph: &'a JunctionGraph<N>,
+    max_in: Vec<usize>,
+    gate: Option<(usize, usize)>,
+    best: Option<usize>,
+    /// The longest way found so far into each junction with each set of
+    /// visited junctions. Arriving again with the same set but a shorter
+    /// length can never do better.
+    seen: HashMap<(usize, u64), usize>,
+}
+
+impl<N> LongestPath<'_, N> {
+    /// Upper bound for the length still to come: every unvisited junction
+    /// entered by its longest edge.
+    fn bound(&self, visited: u64) -> usize {
+        (0..self.max_in.len())
+            .filter(|&i| visited & (1 << i) == 0)
+            .map(|i| self.max_in[i])
+            .sum()
+    }
+
+    fn dfs(&mut self, node: usize, visited: u64, len: usize, remaining: usize) {
+        if node == self.graph.end {
+            self.best = Some(self.best.map_or(len, |best| best.max(len)));
+            return;
+        }
+        if let Some((gate, gate_len)) = self.gate {
+            if node == gate {
+                self.dfs(
+                    self.graph.end,
+                    visited | (1 << self.graph.end),
+                    len + gate_len,
+                    0,
+                );
+                return;
+            }
+        }
+        if self.best.is_some_and(|best| len + remaining <= best) {
+            return;
+        }
+        if visited.count_ones() <= MEMO_DEPTH {
+            match self.seen.get(&(node, visited)) {
+                Some(&seen_len) if seen_len >= len => return,
+                _ => {
+                    self.seen.insert((node, visited), len);
+                }
+            }
+        }
+        for &(next, edge_len) in &self.graph.edges[node] {
+            if visited & (1 << next) == 0 {
+                self.dfs(
+                    next,
+                    visited | (1 << next),
+                    len + edge_len,
+                    remaining - self.max_in[next],
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_cells(maze: &str) -> HashSet<(i32, i32)> {
+        maze.lines()
+            .enumerate()
+            .flat_map(|(r, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, ch)| *ch != '#')
+                    .map(move |(c, _)| (r as i32, c as i32))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_two_loops() {
+        let maze = "\
+#.#######
+#.......#
+#.#.###.#
+#.....#.#
+#####...#
+#######.#";
+        let open = open_cells(maze);
+        let graph = JunctionGraph::compress((0, 1), (5, 7), |(r, c)| {
+            [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)]
+                .into_iter()
+                .filter(|p| open.contains(p))
+                .collect()
+        });
+        assert_eq!(graph.junctions.len(), 6);
+        assert_eq!(graph.longest_path(), Some(15));
+    }
+
+    #[test]
+    fn test_one_way() {
+        // a line from 0 to 5 where the step from 2 to 3 cannot be undone
+        let successors = |i: i32| -> Vec<i32> {
+            match i {
+                2 => vec![3],
+                _ => [i - 1, i + 1]
+                    .into_iter()
+                    .filter(|n| (0..=5).contains(n))
+                    .collect(),
+            }
+        };
+        let forwards = JunctionGraph::compress(0, 5, successors);
+        assert_eq!(forwards.edges[forwards.start], vec![(forwards.end, 5)]);
+        assert_eq!(forwards.longest_path(), Some(5));
+        let backwards = JunctionGraph::compress(5, 0, successors);
+        assert_eq!(backwards.longest_path(), None);
+    }
+
+    #[test]
+    fn test_start_on_ring() {
+        let maze = "\
+....
+.##.
+....
+####
+#..#";
+        let open = open_cells(maze);
+        let graph = JunctionGraph::compress((0, 0), (4, 1), |(r, c)| {
+            [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)]
+                .into_iter()
+                .filter(|p| open.contains(p))
+                .collect()
+        });
+        assert_eq!(graph.junctions.len(), 2);
+        assert!(graph.edges[graph.start].is_empty());
+        assert_eq!(graph.longest_path(), None);
+    }
+
+    #[test]
+    fn test_one_way_cycle() {
+        // 0 leads into the cycle 1 -> 2 -> 3 -> 1, and 4 leads into 5 -> 6 -> 4
+        let successors = |i: i32| -> Vec<i32> {
+            match i {
+                0 => vec![1],
+                3 => vec![1],
+                6 => vec![4],
+                _ => vec![i + 1],
+            }
+        };
+        let into_cycle = JunctionGraph::compress(0, 9, successors);
+        assert!(into_cycle.edges[into_cycle.start].is_empty());
+        assert_eq!(into_cycle.longest_path(), None);
+        let through_start = JunctionGraph::compress(4, 9, successors);
+        assert_eq!(through_start.junctions.len(), 2);
+        assert_eq!(through_start.longest_path(), None);
+    }
+}
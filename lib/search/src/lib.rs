@@ -0,0 +1,253 @@
+//! Generic state-space search.
+//!
+//! A puzzle describes its state space by implementing [`SearchProblem`], and
+//! can then be solved by any of the strategies in this crate:
+//!
+//! - [`bfs`] finds the path with the fewest steps,
+//! - [`dijkstra`] finds the cheapest path,
+//! - [`astar`] finds the cheapest path guided by [`SearchProblem::heuristic`],
+//! - [`ida_star`] does the same holding only the current path in memory,
+//! - [`bidirectional_bfs`] searches from both ends of a [`ReversibleProblem`],
+//! - [`beam_search`] keeps only the most promising states of every layer and
+//!   trades optimality for speed.
+//!
+//! For longest simple paths through mazes, [`JunctionGraph`] compresses the
+//! maze into the corridors between its junctions first.
+//!
+//! Every strategy returns a [`SearchResult`] with the solution, if any, and
+//! [`SearchStats`] about the work that was done, so strategies can be
+//! compared on the same problem.
+
+mod beam;
+mod best_first;
+mod bfs;
+mod ida;
+mod junction;
+mod stats;
+
+pub use beam::beam_search;
+pub use best_first::{astar, dijkstra};
+pub use bfs::{bfs, bidirectional_bfs};
+pub use ida::ida_star;
+pub use junction::JunctionGraph;
+pub use stats::SearchStats;
+
+use std::{fmt::Debug, hash::Hash, ops::Add};
+
+/// Step costs. Implemented for every type that has a zero (its default) and
+/// can be added up and compared, which covers all unsigned integers.
+pub trait Cost: Copy + Ord + Debug + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Debug + Default + Add<Output = T>> Cost for T {}
+
+pub trait SearchProblem {
+    type State: Clone + Eq + Hash;
+    type Cost: Cost;
+
+    fn start(&self) -> Self::State;
+
+    /// States reachable from `state` in one step, with the cost of that step.
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, Self::Cost)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Estimate of the remaining cost from `state` to the nearest goal. It must
+    /// never overestimate for [`astar`] to find the cheapest path.
+    fn heuristic(&self, _state: &Self::State) -> Self::Cost {
+        Self::Cost::default()
+    }
+}
+
+/// A problem with a single known goal state, whose steps can be followed
+/// backwards.
+pub trait ReversibleProblem: SearchProblem {
+    fn goal(&self) -> Self::State;
+
+    /// States from which `state` can be reached in one step, with the cost of
+    /// that step.
+    fn predecessors(&self, state: &Self::State) -> Vec<(Self::State, Self::Cost)>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<S, C> {
+    /// All states from the start to the goal, both included.
+    pub path: Vec<S>,
+    pub cost: C,
+}
+
+impl<S, C> Solution<S, C> {
+    /// Number of steps taken, which is one less than the length of the path.
+    pub fn steps(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    pub fn goal(&self) -> &S {
+        self.path.last().unwrap()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    pub solution: Option<Solution<S, C>>,
+    pub stats: SearchStats,
+}
+
+impl<S, C: Copy> SearchResult<S, C> {
+    pub fn cost(&self) -> Option<C> {
+        self.solution.as_ref().map(|s| s.cost)
+    }
+
+    pub fn steps(&self) -> Option<usize> {
+        self.solution.as_ref().map(|s| s.steps())
+    }
+}
+
+/// A visited state with the cheapest known way of reaching it.
+#[derive(Debug, Clone)]
+struct Node<S, C> {
+    state: S,
+    parent: Option<usize>,
+    cost: C,
+    depth: usize,
+}
+
+/// Follows parent links from `idx` back to the root and returns the states in
+/// order from the root to `idx`.
+fn path_to<S: Clone, C>(nodes: &[Node<S, C>], idx: usize) -> Vec<S> {
+    let mut path = vec![];
+    let mut cur = Some(idx);
+    while let Some(i) = cur {
+        path.push(nodes[i].state.clone());
+        cur = nodes[i].parent;
+    }
+    path.reverse();
+    path
+}
+
+fn solution_at<S: Clone, C: Copy>(nodes: &[Node<S, C>], idx: usize) -> Solution<S, C> {
+    Solution {
+        path: path_to(nodes, idx),
+        cost: nodes[idx].cost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2D maze where `#` is a wall and every step into a cell costs the
+    /// digit in that cell, or 1 for `.`.
+    pub(crate) struct Maze {
+        cells: Vec<Vec<char>>,
+    }
+
+    impl Maze {
+        pub(crate) fn new(s: &str) -> Self {
+            Self {
+                cells: s.lines().map(|l| l.chars().collect()).collect(),
+            }
+        }
+
+        fn find(&self, c: char) -> (usize, usize) {
+            (0..self.cells.len())
+                .flat_map(|r| (0..self.cells[r].len()).map(move |c| (r, c)))
+                .find(|&(r, col)| self.cells[r][col] == c)
+                .unwrap()
+        }
+
+        fn neighbours(&self, (r, c): (usize, usize)) -> Vec<((usize, usize), u32)> {
+            [
+                (r.wrapping_sub(1), c),
+                (r + 1, c),
+                (r, c.wrapping_sub(1)),
+                (r, c + 1),
+            ]
+            .into_iter()
+            .filter_map(|(r, c)| {
+                let ch = *self.cells.get(r)?.get(c)?;
+                match ch {
+                    '#' => None,
+                    '1'..='9' => Some(((r, c), ch.to_digit(10).unwrap())),
+                    _ => Some(((r, c), 1)),
+                }
+            })
+            .collect()
+        }
+    }
+
+    impl SearchProblem for Maze {
+        type State = (usize, usize);
+        type Cost = u32;
+
+        fn start(&self) -> Self::State {
+            self.find('S')
+        }
+
+        fn successors(&self, &state: &Self::State) -> Vec<(Self::State, u32)> {
+            self.neighbours(state)
+        }
+
+        fn is_goal(&self, state: &Self::State) -> bool {
+            *state == self.find('E')
+        }
+
+        fn heuristic(&self, &(r, c): &Self::State) -> u32 {
+            let (gr, gc) = self.find('E');
+            (r.abs_diff(gr) + c.abs_diff(gc)) as u32
+        }
+    }
+
+    impl ReversibleProblem for Maze {
+        fn goal(&self) -> Self::State {
+            self.find('E')
+        }
+
+        fn predecessors(&self, &state: &Self::State) -> Vec<(Self::State, u32)> {
+            let (r, c) = state;
+            let cost = self.cells[r][c].to_digit(10).unwrap_or(1);
+            self.neighbours(state)
+                .into_iter()
+                .map(|(p, _)| (p, cost))
+                .collect()
+        }
+    }
+
+    pub(crate) const MAZE: &str = "\
+S.9....E
+.######.
+........";
+
+    #[test]
+    fn test_strategies_agree() {
+        let maze = Maze::new(MAZE);
+        let bfs = bfs(&maze);
+        let bidirectional = bidirectional_bfs(&maze);
+        let dijkstra = dijkstra(&maze);
+        let astar = astar(&maze);
+        assert_eq!((bfs.steps(), bfs.cost()), (Some(7), Some(15)));
+        assert_eq!(
+            (bidirectional.steps(), bidirectional.cost()),
+            (Some(7), Some(15))
+        );
+        assert_eq!((dijkstra.steps(), dijkstra.cost()), (Some(11), Some(11)));
+        assert_eq!(astar.cost(), Some(11));
+        assert!(astar.stats.expanded <= dijkstra.stats.expanded);
+        let path = astar.solution.unwrap().path;
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(0, 7)));
+        assert!(path
+            .windows(2)
+            .all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1));
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let maze = Maze::new("S#E");
+        assert!(bfs(&maze).solution.is_none());
+        assert!(bidirectional_bfs(&maze).solution.is_none());
+        assert!(dijkstra(&maze).solution.is_none());
+        assert!(astar(&maze).solution.is_none());
+        assert!(ida_star(&maze).solution.is_none());
+        assert!(beam_search(&maze, 10).solution.is_none());
+    }
+}
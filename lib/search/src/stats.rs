@@ -0,0 +1,54 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+/// How much work a search did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SearchStats {
+    /// States whose successors were generated.
+    pub expanded: usize,
+    /// Successor states that were generated, including ones that had already
+    /// been seen.
+    pub generated: usize,
+    /// Largest number of states waiting to be expanded at any one time.
+    pub max_frontier: usize,
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    pub(crate) fn track_frontier(&mut self, len: usize) {
+        self.max_frontier = self.max_frontier.max(len);
+    }
+}
+
+impl fmt::Display for SearchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expanded {}, generated {}, max frontier {} in {:?}",
+            self.expanded, self.generated, self.max_frontier, self.elapsed
+        )
+    }
+}
+
+/// Collects [`SearchStats`] and measures the elapsed time until it is
+/// finished.
+pub(crate) struct StatsTimer {
+    pub stats: SearchStats,
+    started: Instant,
+}
+
+impl StatsTimer {
+    pub fn start() -> Self {
+        Self {
+            stats: SearchStats::default(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn finish(mut self) -> SearchStats {
+        self.stats.elapsed = self.started.elapsed();
+        self.stats
+    }
+}
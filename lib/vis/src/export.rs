@@ -0,0 +1,425 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crossterm::style::Color;
+
+use crate::{Cell, Frame};
+
+/// A colour as red, green and blue.
+pub type Rgb = [u8; 3];
+
+/// Maps frame cells to pixel colours.
+///
+/// A colour set for the cell's character wins, then the cell's own terminal
+/// colour, then the background for blank cells and the foreground for the
+/// rest.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub background: Rgb,
+    pub foreground: Rgb,
+    chars: HashMap<char, Rgb>,
+}
+
+impl Palette {
+    pub fn new(background: Rgb, foreground: Rgb) -> Self {
+        Self {
+            background,
+            foreground,
+            chars: HashMap::new(),
+        }
+    }
+
+    /// Draws every cell showing `ch` in `color`.
+    pub fn with(mut self, ch: char, color: Rgb) -> Self {
+        self.chars.insert(ch, color);
+        self
+    }
+
+    pub fn color(&self, cell: Cell) -> Rgb {
+        if let Some(&color) = self.chars.get(&cell.ch) {
+            return color;
+        }
+        match cell.color.and_then(terminal_rgb) {
+            Some(color) => color,
+            None if cell.ch == ' ' => self.background,
+            None => self.foreground,
+        }
+    }
+}
+
+/// White on black, like a terminal.
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::new([0, 0, 0], [204, 204, 204])
+    }
+}
+
+/// The usual RGB values of the terminal colours.
+fn terminal_rgb(color: Color) -> Option<Rgb> {
+    Some(match color {
+        Color::Black => [0, 0, 0],
+        Color::DarkGrey => [128, 128, 128],
+        Color::Red => [255, 85, 85],
+        Color::DarkRed => [170, 0, 0],
+        Color::Green => [85, 255, 85],
+        Color::DarkGreen => [0, 170, 0],
+        Color::Yellow => [255, 255, 85],
+        Color::DarkYellow => [170, 85, 0],
+        Color::Blue => [85, 85, 255],
+        Color::DarkBlue => [0, 0, 170],
+        Color::Magenta => [255, 85, 255],
+        Color::DarkMagenta => [170, 0, 170],
+        Color::Cyan => [85, 255, 255],
+        Color::DarkCyan => [0, 170, 170],
+        Color::White => [255, 255, 255],
+        Color::Grey => [170, 170, 170],
+        Color::Rgb { r, g, b } => [r, g, b],
+        Color::Reset | Color::AnsiValue(_) => return None,
+    })
+}
+
+/// A picture with one pixel per cell, written scaled up so every cell is a
+/// `scale` by `scale` square.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+    /// Position of the top left pixel, taken from the frame it was drawn
+    /// from, so animation frames of different sizes line up.
+    origin: (i64, i64),
+}
+
+impl Raster {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+            origin: (0, 0),
+        }
+    }
+
+    pub fn from_frame(frame: &Frame, palette: &Palette) -> Self {
+        let mut raster = Raster::new(frame.width(), frame.height(), palette.background);
+        for y in 0..frame.height() {
+            for x in 0..frame.width() {
+                raster.set(x, y, palette.color(frame.get(x, y)));
+            }
+        }
+        raster.origin = frame.origin();
+        raster
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    fn scaled_rgb(&self, scale: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.pixels.len() * scale * scale * 3);
+        for row in self.pixels.chunks(self.width.max(1)) {
+            for _ in 0..scale {
+                for pixel in row {
+                    for _ in 0..scale {
+                        data.extend_from_slice(pixel);
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    pub fn write_png(&self, out: impl Write, scale: usize) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(
+            out,
+            (self.width * scale) as u32,
+            (self.height * scale) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.scaled_rgb(scale))?;
+        Ok(writer.finish()?)
+    }
+
+    /// Writes the picture as rectangles, one per run of equal pixels in a
+    /// row, on top of the most common colour.
+    pub fn write_svg(&self, mut out: impl Write, scale: usize) -> io::Result<()> {
+        let mut counts: HashMap<Rgb, usize> = HashMap::new();
+        for &pixel in &self.pixels {
+            *counts.entry(pixel).or_default() += 1;
+        }
+        let background = counts
+            .into_iter()
+            .max_by_key(|&(color, count)| (count, color))
+            .map_or([0, 0, 0], |(color, _)| color);
+        let (width, height) = (self.width * scale, self.height * scale);
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+            width, height, width, height
+        )?;
+        writeln!(
+            out,
+            r#"<rect width="{}" height="{}" fill="{}"/>"#,
+            width,
+            height,
+            hex(background)
+        )?;
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let color = self.get(x, y);
+                let start = x;
+                while x < self.width && self.get(x, y) == color {
+                    x += 1;
+                }
+                if color != background {
+                    writeln!(
+                        out,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                        start * scale,
+                        y * scale,
+                        (x - start) * scale,
+                        scale,
+                        hex(color)
+                    )?;
+                }
+            }
+        }
+        writeln!(out, "</svg>")
+    }
+}
+
+fn hex([r, g, b]: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Frames collected for an animated GIF.
+///
+/// Frames are lined up by the coordinates they were drawn from and padded to
+/// the area covered by all of them, so a growing picture stays in place.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<Raster>,
+    delay: Duration,
+    background: Rgb,
+}
+
+impl Animation {
+    pub fn new(delay: Duration, background: Rgb) -> Self {
+        Self {
+            frames: vec![],
+            delay,
+            background,
+        }
+    }
+
+    pub fn push(&mut self, frame: Raster) {
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Writes the frames as a looping GIF. Fails if they use more than 256
+    /// colours between them or, once scaled, are wider or taller than 65535
+    /// pixels.
+    pub fn write_gif(&self, out: impl Write, scale: usize) -> io::Result<()> {
+        let min_x = self.frames.iter().map(|f| f.origin.0).min().unwrap_or(0);
+        let min_y = self.frames.iter().map(|f| f.origin.1).min().unwrap_or(0);
+        let max_x = self.frames.iter().map(|f| f.origin.0 + f.width as i64);
+        let max_y = self.frames.iter().map(|f| f.origin.1 + f.height as i64);
+        let width = (max_x.max().unwrap_or(0) - min_x) as usize;
+        let height = (max_y.max().unwrap_or(0) - min_y) as usize;
+
+        let mut colors = vec![self.background];
+        for frame in &self.frames {
+            for pixel in &frame.pixels {
+                if !colors.contains(pixel) {
+                    colors.push(*pixel);
+                }
+            }
+        }
+        if colors.len() > 256 {
+            return Err(invalid("a GIF can only have 256 colours"));
+        }
+        let (Ok(gif_width), Ok(gif_height)) =
+            (u16::try_from(width * scale), u16::try_from(height * scale))
+        else {
+            return Err(invalid("the animation is too large for a GIF"));
+        };
+        let index: HashMap<Rgb, u8> = colors
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (c, i as u8))
+            .collect();
+        let palette: Vec<u8> = colors.concat();
+
+        let mut encoder =
+            gif::Encoder::new(out, gif_width, gif_height, &palette).map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        for frame in &self.frames {
+            let mut padded = Raster::new(width, height, self.background);
+            let (dx, dy) = (
+                (frame.origin.0 - min_x) as usize,
+                (frame.origin.1 - min_y) as usize,
+            );
+            for y in 0..frame.height {
+                for x in 0..frame.width {
+                    padded.set(x + dx, y + dy, frame.get(x, y));
+                }
+            }
+            let pixels: Vec<u8> = padded
+                .scaled_rgb(scale)
+                .chunks(3)
+                .map(|rgb| index[&[rgb[0], rgb[1], rgb[2]]])
+                .collect();
+            let mut gif_frame =
+                gif::Frame::from_indexed_pixels(gif_width, gif_height, pixels, None);
+            gif_frame.delay = (self.delay.as_millis() / 10) as u16;
+            encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+/// Where to write pictures of a solution, taken from the `EXPORT`
+/// environment variable. The extension of the file picks the format: `.png`,
+/// `.svg` or `.gif`, where solutions that can record an animation do so.
+/// `EXPORT_SCALE` sets the size of a cell in pixels, 4 by default.
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub path: PathBuf,
+    pub scale: usize,
+}
+
+impl Export {
+    pub fn new(path: impl Into<PathBuf>, scale: usize) -> Self {
+        Self {
+            path: path.into(),
+            scale,
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var_os("EXPORT")?;
+        let scale = std::env::var("EXPORT_SCALE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(4);
+        Some(Self::new(path, scale))
+    }
+
+    /// Whether the file is a GIF, so solutions should record every step.
+    pub fn is_animation(&self) -> bool {
+        extension(&self.path) == "gif"
+    }
+
+    /// Writes a single picture, as a one frame animation for GIFs.
+    pub fn save(&self, frame: &Frame, palette: &Palette) -> io::Result<()> {
+        let raster = Raster::from_frame(frame, palette);
+        let out = BufWriter::new(File::create(&self.path)?);
+        match extension(&self.path).as_str() {
+            "png" => raster.write_png(out, self.scale),
+            "svg" => raster.write_svg(out, self.scale),
+            "gif" => {
+                let mut animation = Animation::new(Duration::ZERO, palette.background);
+                animation.push(raster);
+                animation.write_gif(out, self.scale)
+            }
+            _ => Err(invalid("export to .png, .svg or .gif")),
+        }
+    }
+
+    pub fn save_animation(&self, animation: &Animation) -> io::Result<()> {
+        animation.write_gif(BufWriter::new(File::create(&self.path)?), self.scale)
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Raster {
+        let frame = Frame::from_points([
+            ((0, 0), Cell::plain('#')),
+            ((1, 0), Cell::plain('#')),
+            ((2, 1), Cell::colored('o', Color::Rgb { r: 1, g: 2, b: 3 })),
+        ]);
+        Raster::from_frame(&frame, &Palette::default().with('#', [255, 0, 0]))
+    }
+
+    #[test]
+    fn test_palette() {
+        let raster = sample();
+        assert_eq!(raster.get(0, 0), [255, 0, 0]);
+        assert_eq!(raster.get(2, 1), [1, 2, 3]);
+        assert_eq!(raster.get(0, 1), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_svg_merges_runs() {
+        let mut svg = vec![];
+        sample().write_svg(&mut svg, 2).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains(r##"<rect width="6" height="4" fill="#000000"/>"##));
+        assert!(svg.contains(r##"<rect x="0" y="0" width="4" height="2" fill="#ff0000"/>"##));
+        assert!(svg.contains(r##"<rect x="4" y="2" width="2" height="2" fill="#010203"/>"##));
+        assert_eq!(svg.matches("<rect").count(), 3);
+    }
+
+    #[test]
+    fn test_png_and_gif_headers() {
+        let mut png = vec![];
+        sample().write_png(&mut png, 3).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        // width and height of the IHDR chunk
+        assert_eq!(&png[16..24], &[0, 0, 0, 9, 0, 0, 0, 6]);
+
+        let mut animation = Animation::new(Duration::from_millis(100), [0, 0, 0]);
+        animation.push(sample());
+        let mut shifted = sample();
+        shifted.origin = (-1, 0);
+        animation.push(shifted);
+        let mut gif = vec![];
+        animation.write_gif(&mut gif, 1).unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        // the frames together cover 4 by 2 cells
+        assert_eq!(&gif[6..10], &[4, 0, 2, 0]);
+    }
+}
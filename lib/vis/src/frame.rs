@@ -0,0 +1,276 @@
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use crossterm::{
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+};
+use grid::{sparse::Coord, Grid, SparseGrid};
+
+/// A character drawn in a colour, or in the terminal's default colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Color>,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell::plain(' ');
+
+    pub const fn plain(ch: char) -> Self {
+        Self { ch, color: None }
+    }
+
+    pub const fn colored(ch: char, color: Color) -> Self {
+        Self {
+            ch,
+            color: Some(color),
+        }
+    }
+}
+
+impl From<char> for Cell {
+    fn from(ch: char) -> Self {
+        Cell::plain(ch)
+    }
+}
+
+/// One picture of a simulation: a rectangle of cells with a caption below.
+///
+/// Frames built from points remember where their top left corner is, so
+/// [`Frame::focus_on`] can take the same coordinates as the points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    /// Position of the top left cell in the coordinates of the points the
+    /// frame was built from.
+    origin: (i64, i64),
+    /// The cell to keep in view when the frame is cropped to fit the screen.
+    pub focus: Option<(usize, usize)>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::BLANK; width * height],
+            origin: (0, 0),
+            focus: None,
+            caption: String::new(),
+        }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, f: impl Fn(&T) -> Cell) -> Self {
+        let mut frame = Frame::new(grid.width(), grid.height());
+        for ((row, col), value) in grid.iter() {
+            frame.set(col, row, f(value));
+        }
+        frame
+    }
+
+    /// Draws the bounding box of a 2D sparse grid, with x to the right and y
+    /// downwards. `f` gets `None` for cells that were never set.
+    pub fn from_sparse<P: Coord, T>(
+        grid: &SparseGrid<P, T>,
+        f: impl Fn(Option<&T>) -> Cell,
+    ) -> Self {
+        assert_eq!(P::DIM, 2, "only 2D grids can be drawn");
+        let Some((min, max)) = grid.bounds() else {
+            return Frame::new(0, 0);
+        };
+        let mut frame = Frame::new(
+            (max.component(0) - min.component(0) + 1) as usize,
+            (max.component(1) - min.component(1) + 1) as usize,
+        );
+        frame.origin = (min.component(0), min.component(1));
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                let p = P::from_fn(|axis| {
+                    [min.component(0) + x as i64, min.component(1) + y as i64][axis]
+                });
+                frame.set(x, y, f(grid.cell(p)));
+            }
+        }
+        frame
+    }
+
+    /// Draws the bounding box of `(x, y)` points, with y downwards. Cells
+    /// without a point are blank.
+    pub fn from_points(points: impl IntoIterator<Item = ((i64, i64), Cell)>) -> Self {
+        let points: Vec<_> = points.into_iter().collect();
+        let xs = points.iter().map(|((x, _), _)| *x);
+        let ys = points.iter().map(|((_, y), _)| *y);
+        let (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) =
+            (xs.clone().min(), xs.max(), ys.clone().min(), ys.max())
+        else {
+            return Frame::new(0, 0);
+        };
+        let mut frame = Frame::new((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+        frame.origin = (min_x, min_y);
+        for ((x, y), cell) in points {
+            frame.set((x - min_x) as usize, (y - min_y) as usize, cell);
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Position of the top left cell in the coordinates the frame was built
+    /// from.
+    pub fn origin(&self) -> (i64, i64) {
+        self.origin
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Cell {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: impl Into<Cell>) {
+        self.cells[y * self.width + x] = cell.into();
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    /// Keeps the cell at `(x, y)`, in the coordinates the frame was built
+    /// from, in view when cropping. Ignored if it is outside the frame.
+    pub fn focus_on(mut self, x: i64, y: i64) -> Self {
+        let (x, y) = (x - self.origin.0, y - self.origin.1);
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            self.focus = Some((x as usize, y as usize));
+        }
+        self
+    }
+
+    /// The part of the frame that is `width` by `height` cells starting at
+    /// `(x, y)`, clipped to the frame.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Frame {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+        let mut frame = Frame::new(width, height);
+        for row in 0..height {
+            for col in 0..width {
+                frame.set(col, row, self.get(x + col, y + row));
+            }
+        }
+        frame.origin = (self.origin.0 + x as i64, self.origin.1 + y as i64);
+        frame.focus = self
+            .focus
+            .and_then(|(fx, fy)| Some((fx.checked_sub(x)?, fy.checked_sub(y)?)))
+            .filter(|&(fx, fy)| fx < width && fy < height);
+        frame.caption = self.caption.clone();
+        frame
+    }
+
+    /// Crops the frame to at most `width` by `height` cells, keeping the focus
+    /// centred if there is one and the top left corner otherwise.
+    pub fn fit(&self, width: usize, height: usize) -> Frame {
+        let start = |focus: usize, size: usize, len: usize| {
+            focus.saturating_sub(size / 2).min(len.saturating_sub(size))
+        };
+        let (x, y) = match self.focus {
+            Some((fx, fy)) => (start(fx, width, self.width), start(fy, height, self.height)),
+            None => (0, 0),
+        };
+        self.crop(x, y, width, height)
+    }
+
+    /// Writes the frame with colours as terminal commands, starting at the
+    /// cursor position.
+    pub fn queue_ansi(&self, out: &mut impl Write) -> io::Result<()> {
+        for y in 0..self.height {
+            let mut run = String::new();
+            let mut color = None;
+            for x in 0..self.width {
+                let cell = self.get(x, y);
+                if cell.color != color && !run.is_empty() {
+                    queue_run(out, &run, color)?;
+                    run.clear();
+                }
+                color = cell.color;
+                run.push(cell.ch);
+            }
+            queue_run(out, &run, color)?;
+            queue!(out, Print("\r\n"))?;
+        }
+        if !self.caption.is_empty() {
+            queue!(out, Print(&self.caption), Print("\r\n"))?;
+        }
+        Ok(())
+    }
+}
+
+fn queue_run(out: &mut impl Write, run: &str, color: Option<Color>) -> io::Result<()> {
+    match color {
+        Some(color) => queue!(out, SetForegroundColor(color), Print(run), ResetColor),
+        None => queue!(out, Print(run)),
+    }
+}
+
+/// The frame without colours.
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            let row: String = (0..self.width).map(|x| self.get(x, y).ch).collect();
+            writeln!(f, "{}", row)?;
+        }
+        if !self.caption.is_empty() {
+            writeln!(f, "{}", self.caption)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points_and_crop() {
+        let frame = Frame::from_points([
+            ((-1, 5), Cell::plain('a')),
+            ((2, 6), Cell::colored('b', Color::Red)),
+        ])
+        .with_caption("step 1")
+        .focus_on(2, 6);
+        assert_eq!(frame.to_string(), "a   \n   b\nstep 1\n");
+        assert_eq!(frame.focus, Some((3, 1)));
+        let cropped = frame.fit(2, 1);
+        assert_eq!(cropped.to_string(), " b\nstep 1\n");
+        assert_eq!(cropped.focus, Some((1, 0)));
+        assert_eq!(frame.crop(0, 0, 2, 1).focus, None);
+        let cropped = frame.fit(2, 2);
+        assert_eq!(cropped.to_string(), "  \n b\nstep 1\n");
+        assert_eq!(cropped.focus, Some((1, 1)));
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut grid = SparseGrid::new('.');
+        grid.insert((3, -1), '#');
+        grid.insert((4, 0), 'o');
+        let frame = Frame::from_sparse(&grid, |ch| Cell::plain(*ch.unwrap_or(&' ')));
+        assert_eq!(frame.to_string(), "# \n o\n");
+        let grid = Grid::parse("ab\ncd", |ch| ch);
+        assert_eq!(
+            Frame::from_grid(&grid, |&ch| ch.into()).to_string(),
+            "ab\ncd\n"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Terminal visualisation for simulations.
+//!
+//! A simulation implements [`Visualise`] by drawing its current state into a
+//! [`Frame`], built from a [`Grid`](grid::Grid), a
+//! [`SparseGrid`](grid::SparseGrid) or plain points. A [`Player`] then steps
+//! the simulation in the terminal, with pausing, single steps and speed
+//! control, cropping frames that do not fit around their focus:
+//!
+//! ```no_run
+//! use vis::{Frame, Player, Visualise};
+//!
+//! struct Counter(i64);
+//!
+//! impl Visualise for Counter {
+//!     fn frame(&self) -> Frame {
+//!         Frame::from_points((0..self.0).map(|x| ((x, 0), '#'.into())))
+//!     }
+//! }
+//!
+//! Player::new(10.0)
+//!     .play(&mut Counter(0), |c| {
+//!         c.0 += 1;
+//!         c.0 < 40
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! Solutions only animate when the `VISUALISE` environment variable is set,
+//! see [`Player::from_env`].
+//!
+//! Frames can also be saved as PNG or SVG pictures and animated GIFs with
+//! [`Export`], coloured by a [`Palette`] and scaled up to a number of pixels
+//! per cell.
+
+mod export;
+mod frame;
+mod player;
+
+pub use crossterm::style::Color;
+pub use export::{Animation, Export, Palette, Raster, Rgb};
+pub use frame::{Cell, Frame};
+pub use player::Player;
+
+/// A simulation that can draw its current state.
+pub trait Visualise {
+    fn frame(&self) -> Frame;
+}
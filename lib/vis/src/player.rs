@@ -0,0 +1,176 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute, queue,
+    style::Print,
+    terminal::{self, ClearType},
+};
+
+use crate::Visualise;
+
+const KEYS: &str = "space pause  n next  +/- speed  q skip to end";
+
+const DEFAULT_FPS: f64 = 10.0;
+
+/// Steps a simulation in the terminal, drawing a frame after every step.
+///
+/// While playing, the simulation advances at the chosen number of frames per
+/// second. Space pauses and resumes, `n` or → advances a single step while
+/// paused, `+` and `-` double or halve the speed and `q` runs the rest of the
+/// simulation without drawing it. When standard output is not a terminal,
+/// the simulation runs to the end and only the last frame is printed.
+#[derive(Debug, Clone)]
+pub struct Player {
+    fps: f64,
+    paused: bool,
+}
+
+impl Player {
+    /// Plays at `fps` frames per second, or at 10 if that is not a positive
+    /// number.
+    pub fn new(fps: f64) -> Self {
+        let fps = if fps.is_finite() && fps > 0.0 {
+            fps
+        } else {
+            DEFAULT_FPS
+        };
+        Self { fps, paused: false }
+    }
+
+    /// A player if the `VISUALISE` environment variable is set, running at
+    /// the frames per second it is set to, or 10 if that is not a positive
+    /// number.
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var("VISUALISE").ok()?;
+        Some(Self::new(value.parse().unwrap_or(DEFAULT_FPS)))
+    }
+
+    /// Starts paused, waiting for a key before the first step.
+    pub fn paused(mut self) -> Self {
+        self.paused = true;
+        self
+    }
+
+    /// Calls `step` until it returns `false`, showing `sim` before the first
+    /// and after every step.
+    pub fn play<S: Visualise>(
+        &mut self,
+        sim: &mut S,
+        mut step: impl FnMut(&mut S) -> bool,
+    ) -> io::Result<()> {
+        let mut out = io::stdout();
+        if !out.is_terminal() {
+            while step(sim) {}
+            return write!(out, "{}", sim.frame());
+        }
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+        let result = self.run(sim, &mut step, &mut out);
+        execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result?;
+        // leave the final picture on the normal screen
+        write!(out, "{}", sim.frame())
+    }
+
+    /// How long a frame is shown. Halving the speed often enough waits
+    /// forever rather than overflowing.
+    fn frame_time(&self) -> Duration {
+        Duration::try_from_secs_f64(1.0 / self.fps).unwrap_or(Duration::MAX)
+    }
+
+    fn run<S: Visualise>(
+        &mut self,
+        sim: &mut S,
+        step: &mut impl FnMut(&mut S) -> bool,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut steps = 0;
+        loop {
+            let state = if self.paused { "paused" } else { "playing" };
+            let status = format!("step {}, {} at {} fps  {}", steps, state, self.fps, KEYS);
+            draw(out, &sim.frame(), &status)?;
+            let timeout = if self.paused {
+                Duration::MAX
+            } else {
+                self.frame_time()
+            };
+            match next_key(timeout)? {
+                Some(KeyCode::Char(' ')) => {
+                    self.paused = !self.paused;
+                    continue;
+                }
+                Some(KeyCode::Char('+')) => {
+                    self.fps *= 2.0;
+                    continue;
+                }
+                Some(KeyCode::Char('-')) => {
+                    self.fps /= 2.0;
+                    continue;
+                }
+                Some(KeyCode::Char('q') | KeyCode::Esc) => {
+                    while step(sim) {}
+                    return Ok(());
+                }
+                Some(KeyCode::Char('n') | KeyCode::Right) if self.paused => {}
+                _ if self.paused => continue,
+                _ => {}
+            }
+            if !step(sim) {
+                let status = format!("finished after {} steps, press any key", steps);
+                draw(out, &sim.frame(), &status)?;
+                while next_key(Duration::MAX)?.is_none() {}
+                return Ok(());
+            }
+            steps += 1;
+        }
+    }
+}
+
+fn draw(out: &mut impl Write, frame: &crate::Frame, status: &str) -> io::Result<()> {
+    let (cols, rows) = terminal::size()?;
+    // keep a line for the caption and one for the status
+    let frame = frame.fit(cols as usize, (rows as usize).saturating_sub(2));
+    queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+    frame.queue_ansi(out)?;
+    queue!(out, Print(status))?;
+    out.flush()
+}
+
+fn next_key(timeout: Duration) -> io::Result<Option<KeyCode>> {
+    if event::poll(timeout)? {
+        if let Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            return Ok(Some(code));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fps() {
+        for fps in ["0", "-2", "nan", "inf", "fast"] {
+            let player = Player::new(fps.parse().unwrap_or(DEFAULT_FPS));
+            assert_eq!(player.fps, DEFAULT_FPS, "{}", fps);
+        }
+        assert_eq!(Player::new(4.0).frame_time(), Duration::from_millis(250));
+        let mut player = Player::new(1.0);
+        player.fps /= 2f64.powi(1100);
+        assert_eq!(player.frame_time(), Duration::MAX);
+        player.fps = f64::INFINITY;
+        assert_eq!(player.frame_time(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt;
+
+use parse::ParseError;
+
+use crate::State;
+
+/// The most operands an instruction can take.
+pub const MAX_ARGS: usize = 3;
+
+/// What an operand of an op may be written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arg {
+    /// A register name.
+    Reg,
+    /// An integer literal, with an optional sign.
+    Imm,
+    /// Either of them.
+    Val,
+}
+
+/// A parsed operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Reg(usize),
+    Imm(i64),
+}
+
+impl Operand {
+    /// The value of an immediate, for ops that only take [`Arg::Imm`].
+    /// Panics on a register.
+    #[inline]
+    pub fn imm(self) -> i64 {
+        match self {
+            Operand::Imm(n) => n,
+            Operand::Reg(_) => panic!("expected an immediate"),
+        }
+    }
+}
+
+/// Where execution goes after an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    /// On to the next instruction.
+    Next,
+    /// Relative to this instruction.
+    Jump(i64),
+    /// Stop, and stay on this instruction.
+    Halt,
+    /// The instruction needs input that is not there yet, run it again once
+    /// there is.
+    Wait,
+}
+
+/// The semantics of an op, given the machine state and the operands.
+pub type Exec = fn(&mut State, &[Operand]) -> Flow;
+
+/// One row of an [`InstructionSet`].
+#[derive(Clone)]
+pub struct Op {
+    pub name: &'static str,
+    pub args: &'static [Arg],
+    /// How many cycles the instruction takes to complete.
+    pub cycles: u64,
+    pub exec: Exec,
+}
+
+/// An op and its operands, of which only the first `args.len()` of the op
+/// are used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    pub op: usize,
+    operands: [Operand; MAX_ARGS],
+    len: u8,
+}
+
+impl Instruction {
+    /// Panics if there are more than [`MAX_ARGS`] operands.
+    pub fn new(op: usize, args: &[Operand]) -> Self {
+        assert!(args.len() <= MAX_ARGS, "too many operands");
+        let mut operands = [Operand::Imm(0); MAX_ARGS];
+        operands[..args.len()].copy_from_slice(args);
+        Self {
+            op,
+            operands,
+            len: args.len() as u8,
+        }
+    }
+
+    #[inline]
+    pub fn args(&self) -> &[Operand] {
+        &self.operands[..self.len as usize]
+    }
+
+    /// The same operands under another op.
+    pub fn with_op(self, op: usize) -> Self {
+        Self { op, ..self }
+    }
+}
+
+pub type Program = Vec<Instruction>;
+
+/// A table of named registers and ops that parses and prints programs.
+///
+/// ```
+/// use vm::{Arg, Flow, InstructionSet};
+///
+/// let isa = InstructionSet::new(&["a"]).op("inc", &[Arg::Reg], 1, |state, args| {
+///     state.set(args[0], state.get(args[0]) + 1);
+///     Flow::Next
+/// });
+/// let program = isa.parse("inc a\ninc a\n").unwrap();
+/// assert_eq!(isa.display(&program[1]).to_string(), "inc a");
+/// ```
+#[derive(Clone)]
+pub struct InstructionSet {
+    registers: Vec<&'static str>,
+    ops: Vec<Op>,
+}
+
+impl InstructionSet {
+    pub fn new(registers: &[&'static str]) -> Self {
+        Self {
+            registers: registers.to_vec(),
+            ops: vec![],
+        }
+    }
+
+    /// Adds an op, panicking if it takes more than [`MAX_ARGS`] operands.
+    pub fn op(mut self, name: &'static str, args: &'static [Arg], cycles: u64, exec: Exec) -> Self {
+        assert!(args.len() <= MAX_ARGS, "{} takes too many operands", name);
+        self.ops.push(Op {
+            name,
+            args,
+            cycles,
+            exec,
+        });
+        self
+    }
+
+    pub fn registers(&self) -> &[&'static str] {
+        &self.registers
+    }
+
+    pub fn register(&self, name: &str) -> Option<usize> {
+        self.registers.iter().position(|&r| r == name)
+    }
+
+    /// The index of the op called `name`, for building or patching
+    /// instructions. Panics if there is none.
+    pub fn opcode(&self, name: &str) -> usize {
+        self.ops
+            .iter()
+            .position(|op| op.name == name)
+            .unwrap_or_else(|| panic!("no op called {}", name))
+    }
+
+    #[inline]
+    pub fn get(&self, op: usize) -> &Op {
+        &self.ops[op]
+    }
+
+    /// Parses one instruction per non-empty line, as the op name followed by
+    /// its operands separated by whitespace.
+    pub fn parse(&self, source: &str) -> Result<Program, ParseError> {
+        source
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| self.parse_line(source, line))
+            .collect()
+    }
+
+    fn parse_line(&self, source: &str, line: &str) -> Result<Instruction, ParseError> {
+        let mut words = line.split_whitespace();
+        let name = words.next().unwrap();
+        let at = |word: &str, message: String| ParseError::at(source, word, message);
+        let op = self
+            .ops
+            .iter()
+            .position(|op| op.name == name)
+            .ok_or_else(|| at(name, format!("unknown op {}", name)))?;
+        let mut args = vec![];
+        for &kind in self.ops[op].args {
+            let word = words
+                .next()
+                .ok_or_else(|| at(line.trim_end(), format!("{} is missing an operand", name)))?;
+            let register = self.register(word).map(Operand::Reg);
+            let literal = word.parse().ok().map(Operand::Imm);
+            let operand = match kind {
+                Arg::Reg => register.ok_or_else(|| at(word, "expected register".into())),
+                Arg::Imm => literal.ok_or_else(|| at(word, "expected integer".into())),
+                Arg::Val => register
+                    .or(literal)
+                    .ok_or_else(|| at(word, "expected register or integer".into())),
+            }?;
+            args.push(operand);
+        }
+        if let Some(word) = words.next() {
+            return Err(at(word, "unexpected input".into()));
+        }
+        Ok(Instruction::new(op, &args))
+    }
+
+    /// Prints an instruction the way it is parsed.
+    pub fn display<'a>(&'a self, instruction: &'a Instruction) -> Display<'a> {
+        Display {
+            isa: self,
+            instruction,
+        }
+    }
+}
+
+pub struct Display<'a> {
+    isa: &'a InstructionSet,
+    instruction: &'a Instruction,
+}
+
+impl fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.isa.ops[self.instruction.op].name)?;
+        for arg in self.instruction.args() {
+            match arg {
+                Operand::Reg(r) => write!(f, " {}", self.isa.registers[*r])?,
+                Operand::Imm(n) => write!(f, " {}", n)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn isa() -> InstructionSet {
+        InstructionSet::new(&["x", "y"])
+            .op("add", &[Arg::Reg, Arg::Val], 1, |_, _| Flow::Next)
+            .op("jmp", &[Arg::Imm], 1, |_, _| Flow::Next)
+            .op("noop", &[], 1, |_, _| Flow::Next)
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let isa = isa();
+        let program = isa.parse("add x y\nadd y -3\n\njmp +2\nnoop\n").unwrap();
+        assert_eq!(program.len(), 4);
+        assert_eq!(program[0].args(), [Operand::Reg(0), Operand::Reg(1)]);
+        assert_eq!(program[2].args(), [Operand::Imm(2)]);
+        assert_eq!(program[3].op, isa.opcode("noop"));
+        let printed: Vec<_> = program.iter().map(|i| isa.display(i).to_string()).collect();
+        assert_eq!(printed, ["add x y", "add y -3", "jmp 2", "noop"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let isa = isa();
+        let err = isa.parse("noop\nmul x 2\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.message, "unknown op mul");
+        let err = isa.parse("add 2 x").unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (5, "expected register"));
+        let err = isa.parse("jmp x").unwrap_err();
+        assert_eq!(err.message, "expected integer");
+        let err = isa.parse("add x").unwrap_err();
+        assert_eq!(err.message, "add is missing an operand");
+        let err = isa.parse("noop 1").unwrap_err();
+        assert_eq!((err.column, err.message.as_str()), (6, "unexpected input"));
+    }
+}
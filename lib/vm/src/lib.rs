@@ -0,0 +1,34 @@
+//! A register machine for the puzzles that come with their own assembly
+//! language.
+//!
+//! - [`InstructionSet`] is the declarative part: register names and a table
+//!   of ops with their operand kinds, cycle counts and semantics, which
+//!   parses and prints programs,
+//! - [`Machine`] runs a program on a [`State`] of registers, program counter,
+//!   cycle count and input and output queues, with a hook on every cycle and
+//!   detection of loops by program counter or by program counter and
+//!   registers,
+//! - [`repair`] patches one instruction at a time until the program halts.
+//!
+//! ```
+//! use vm::{Arg, Exit, Flow, InstructionSet, LoopCheck, Machine};
+//!
+//! let isa = InstructionSet::new(&["acc"])
+//!     .op("acc", &[Arg::Imm], 1, |state, args| {
+//!         state.regs[0] += args[0].imm();
+//!         Flow::Next
+//!     })
+//!     .op("jmp", &[Arg::Imm], 1, |_, args| Flow::Jump(args[0].imm()));
+//! let program = isa.parse("acc +2\nacc -5\njmp -1\n").unwrap();
+//! let mut machine = Machine::new(&isa, &program);
+//! assert_eq!(machine.run_until_loop(LoopCheck::Pc), Exit::Loop);
+//! assert_eq!(machine.state.regs, [-3]);
+//! ```
+
+mod isa;
+mod machine;
+
+pub use isa::{
+    Arg, Display, Exec, Flow, Instruction, InstructionSet, Op, Operand, Program, MAX_ARGS,
+};
+pub use machine::{repair, Exit, LoopCheck, Machine, State};
@@ -0,0 +1,327 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::{Flow, Instruction, InstructionSet, Operand};
+
+/// The register file and everything else an op may touch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub regs: Vec<i64>,
+    pub pc: usize,
+    /// Cycles completed so far. While a hook runs it is the number of the
+    /// current cycle, starting at 1.
+    pub cycle: u64,
+    pub input: VecDeque<i64>,
+    pub output: Vec<i64>,
+}
+
+impl State {
+    /// All registers 0 and nothing to read.
+    pub fn new(registers: usize) -> Self {
+        Self {
+            regs: vec![0; registers],
+            pc: 0,
+            cycle: 0,
+            input: VecDeque::new(),
+            output: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Reg(r) => self.regs[r],
+            Operand::Imm(n) => n,
+        }
+    }
+
+    /// Panics if `operand` is not a register.
+    #[inline]
+    pub fn set(&mut self, operand: Operand, value: i64) {
+        match operand {
+            Operand::Reg(r) => self.regs[r] = value,
+            Operand::Imm(_) => panic!("cannot write to an immediate"),
+        }
+    }
+}
+
+/// Why a run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// The program counter went just past the last instruction.
+    Halted,
+    /// An op returned [`Flow::Halt`].
+    Stopped,
+    /// An op returned [`Flow::Wait`]; push to the input and run again.
+    NeedsInput,
+    /// The next instruction would repeat a state seen before.
+    Loop,
+    /// A jump went somewhere other than the program or just past its end.
+    OutOfBounds(i64),
+}
+
+/// What counts as having been here before for [`Machine::run_until_loop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopCheck {
+    /// The program counter alone, which is enough when nothing branches on
+    /// the registers.
+    Pc,
+    /// The program counter and all registers.
+    State,
+}
+
+/// Runs a program of an [`InstructionSet`].
+///
+/// ```
+/// use vm::{Arg, Exit, Flow, InstructionSet, Machine};
+///
+/// let isa = InstructionSet::new(&["a"])
+///     .op("inc", &[], 1, |state, _| {
+///         state.regs[0] += 1;
+///         Flow::Next
+///     })
+///     .op("jmp", &[Arg::Imm], 1, |_, args| Flow::Jump(args[0].imm()));
+/// let program = isa.parse("inc\ninc\njmp +1\n").unwrap();
+/// let mut machine = Machine::new(&isa, &program);
+/// assert_eq!(machine.run(), Exit::Halted);
+/// assert_eq!(machine.state.regs, [2]);
+/// ```
+#[derive(Clone)]
+pub struct Machine<'a> {
+    pub isa: &'a InstructionSet,
+    pub program: &'a [Instruction],
+    pub state: State,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(isa: &'a InstructionSet, program: &'a [Instruction]) -> Self {
+        Self::with_state(isa, program, State::new(isa.registers().len()))
+    }
+
+    pub fn with_state(isa: &'a InstructionSet, program: &'a [Instruction], state: State) -> Self {
+        Self {
+            isa,
+            program,
+            state,
+        }
+    }
+
+    /// Queues values for ops to read.
+    pub fn push_input(&mut self, input: impl IntoIterator<Item = i64>) {
+        self.state.input.extend(input);
+    }
+
+    /// Executes one instruction, calling `hook` once for each of its cycles
+    /// before its effect, and returns why the machine cannot go on, if it
+    /// cannot.
+    ///
+    /// When the op waits for input the cycle count is rolled back, so the
+    /// hook will see the same cycles again when the instruction is retried.
+    pub fn step(&mut self, hook: &mut impl FnMut(&State)) -> Option<Exit> {
+        let Some(instruction) = self.program.get(self.state.pc) else {
+            return Some(Exit::Halted);
+        };
+        let op = self.isa.get(instruction.op);
+        for _ in 0..op.cycles {
+            self.state.cycle += 1;
+            hook(&self.state);
+        }
+        match (op.exec)(&mut self.state, instruction.args()) {
+            Flow::Next => self.state.pc += 1,
+            Flow::Jump(offset) => {
+                let target = self.state.pc as i64 + offset;
+                if target < 0 || target > self.program.len() as i64 {
+                    return Some(Exit::OutOfBounds(target));
+                }
+                self.state.pc = target as usize;
+            }
+            Flow::Halt => return Some(Exit::Stopped),
+            Flow::Wait => {
+                self.state.cycle -= op.cycles;
+                return Some(Exit::NeedsInput);
+            }
+        }
+        (self.state.pc == self.program.len()).then_some(Exit::Halted)
+    }
+
+    pub fn run(&mut self) -> Exit {
+        self.run_with(|_| {})
+    }
+
+    /// Runs with `hook` called on every cycle, see [`Machine::step`].
+    pub fn run_with(&mut self, mut hook: impl FnMut(&State)) -> Exit {
+        loop {
+            if let Some(exit) = self.step(&mut hook) {
+                return exit;
+            }
+        }
+    }
+
+    /// Runs until the program exits or is about to execute an instruction
+    /// in a state it has been in before, which it would then repeat forever.
+    /// On [`Exit::Loop`] the state is the one before the repetition.
+    pub fn run_until_loop(&mut self, check: LoopCheck) -> Exit {
+        let mut pcs = HashSet::new();
+        let mut states = HashSet::new();
+        loop {
+            let new = match check {
+                LoopCheck::Pc => pcs.insert(self.state.pc),
+                LoopCheck::State => states.insert((self.state.pc, self.state.regs.clone())),
+            };
+            if !new {
+                return Exit::Loop;
+            }
+            if let Some(exit) = self.step(&mut |_| {}) {
+                return exit;
+            }
+        }
+    }
+}
+
+/// Patches one instruction at a time and reruns from `init` until a patched
+/// program halts by running off its end, returning the index of the
+/// instruction that was patched and the final state.
+///
+/// `patch` returns the replacement for an instruction, or `None` to leave it
+/// alone.
+pub fn repair(
+    isa: &InstructionSet,
+    program: &[Instruction],
+    init: &State,
+    check: LoopCheck,
+    mut patch: impl FnMut(&Instruction) -> Option<Instruction>,
+) -> Option<(usize, State)> {
+    let mut patched = program.to_vec();
+    for (i, instruction) in program.iter().enumerate() {
+        let Some(replacement) = patch(instruction) else {
+            continue;
+        };
+        patched[i] = replacement;
+        let mut machine = Machine::with_state(isa, &patched, init.clone());
+        if machine.run_until_loop(check) == Exit::Halted {
+            return Some((i, machine.state));
+        }
+        patched[i] = *instruction;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Arg;
+
+    // The handheld console of 2020 day 8.
+    fn console() -> InstructionSet {
+        InstructionSet::new(&["acc"])
+            .op("acc", &[Arg::Imm], 1, |state, args| {
+                state.regs[0] += args[0].imm();
+                Flow::Next
+            })
+            .op("jmp", &[Arg::Imm], 1, |_, args| Flow::Jump(args[0].imm()))
+            .op("nop", &[Arg::Imm], 1, |_, _| Flow::Next)
+    }
+
+    const BOOT: &str = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+
+    #[test]
+    fn test_loop_and_repair() {
+        let isa = console();
+        let program = isa.parse(BOOT).unwrap();
+        let mut machine = Machine::new(&isa, &program);
+        assert_eq!(machine.run_until_loop(LoopCheck::Pc), Exit::Loop);
+        assert_eq!((machine.state.pc, machine.state.regs[0]), (1, 5));
+
+        let (jmp, nop) = (isa.opcode("jmp"), isa.opcode("nop"));
+        let init = State::new(1);
+        let (i, state) =
+            repair(
+                &isa,
+                &program,
+                &init,
+                LoopCheck::Pc,
+                |instruction| match instruction.op {
+                    op if op == jmp => Some(instruction.with_op(nop)),
+                    op if op == nop => Some(instruction.with_op(jmp)),
+                    _ => None,
+                },
+            )
+            .unwrap();
+        assert_eq!((i, state.regs[0]), (7, 8));
+    }
+
+    #[test]
+    fn test_state_loop() {
+        // Counts down from 3 and then spins with a constant register.
+        let isa = InstructionSet::new(&["a"])
+            .op("dec", &[], 1, |state, _| {
+                state.regs[0] = (state.regs[0] - 1).max(0);
+                Flow::Next
+            })
+            .op("jmp", &[Arg::Imm], 1, |_, args| Flow::Jump(args[0].imm()));
+        let program = isa.parse("dec\njmp -1").unwrap();
+        let mut state = State::new(1);
+        state.regs[0] = 3;
+        let mut machine = Machine::with_state(&isa, &program, state.clone());
+        assert_eq!(machine.run_until_loop(LoopCheck::Pc), Exit::Loop);
+        assert_eq!(machine.state.regs[0], 2);
+        let mut machine = Machine::with_state(&isa, &program, state);
+        assert_eq!(machine.run_until_loop(LoopCheck::State), Exit::Loop);
+        assert_eq!((machine.state.cycle, machine.state.regs[0]), (7, 0));
+    }
+
+    #[test]
+    fn test_cycles_and_input() {
+        let isa = InstructionSet::new(&["x"])
+            .op("addx", &[Arg::Imm], 2, |state, args| {
+                state.regs[0] += args[0].imm();
+                Flow::Next
+            })
+            .op("inp", &[Arg::Reg], 1, |state, args| {
+                match state.input.pop_front() {
+                    Some(value) => {
+                        state.set(args[0], value);
+                        Flow::Next
+                    }
+                    None => Flow::Wait,
+                }
+            })
+            .op("out", &[Arg::Val], 1, |state, args| {
+                state.output.push(state.get(args[0]));
+                Flow::Next
+            })
+            .op("hlt", &[], 1, |_, _| Flow::Halt)
+            .op("jmp", &[Arg::Imm], 1, |_, args| Flow::Jump(args[0].imm()));
+        let program = isa
+            .parse("addx 3\ninp x\naddx -1\nout x\nhlt\njmp -10")
+            .unwrap();
+        let mut machine = Machine::new(&isa, &program);
+        let mut seen = vec![];
+        assert_eq!(
+            machine.run_with(|s| seen.push((s.cycle, s.regs[0]))),
+            Exit::NeedsInput
+        );
+        assert_eq!(seen, [(1, 0), (2, 0), (3, 3)]);
+        machine.push_input([10]);
+        assert_eq!(
+            machine.run_with(|s| seen.push((s.cycle, s.regs[0]))),
+            Exit::Stopped
+        );
+        // the third cycle is seen again when the input is there
+        assert_eq!(
+            seen,
+            [
+                (1, 0),
+                (2, 0),
+                (3, 3),
+                (3, 3),
+                (4, 10),
+                (5, 10),
+                (6, 9),
+                (7, 9)
+            ]
+        );
+        assert_eq!(machine.state.output, [9]);
+        machine.state.pc += 1;
+        assert_eq!(machine.run(), Exit::OutOfBounds(-5));
+    }
+}